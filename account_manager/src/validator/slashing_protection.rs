@@ -1,17 +1,20 @@
+use account_utils::validator_definitions::{ValidatorDefinitions, CONFIG_FILENAME};
 use clap::{App, Arg, ArgMatches};
 use environment::Environment;
 use slashing_protection::{
     interchange::Interchange, InterchangeError, InterchangeImportOutcome, SlashingDatabase,
     SLASHING_PROTECTION_FILENAME,
 };
-use std::fs::File;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 
 pub const CMD: &str = "slashing-protection";
 pub const IMPORT_CMD: &str = "import";
 pub const EXPORT_CMD: &str = "export";
+pub const PRUNE_CMD: &str = "prune";
+pub const AUDIT_CMD: &str = "audit";
 
 pub const IMPORT_FILE_ARG: &str = "IMPORT-FILE";
 pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
@@ -51,6 +54,20 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         ),
                 )
         )
+        .subcommand(
+            App::new(PRUNE_CMD).about(
+                "Delete all blocks and attestations which are not required to protect against \
+                 slashing, keeping only the latest block and attestation of each validator, \
+                 and compact the database. The validator client must not be running",
+            ),
+        )
+        .subcommand(
+            App::new(AUDIT_CMD).about(
+                "Check the consistency of the database, reporting slashable or invalid records \
+                 and validators from the validator definitions which are not registered. The \
+                 validator client must not be running",
+            ),
+        )
 }
 
 pub fn cli_run<T: EthSpec>(
@@ -92,19 +109,6 @@ pub fn cli_run<T: EthSpec>(
                     )
                 })?;
 
-            let display_slot = |slot: Option<Slot>| {
-                slot.map_or("none".to_string(), |slot| format!("slot {}", slot.as_u64()))
-            };
-            let display_epoch = |epoch: Option<Epoch>| {
-                epoch.map_or("?".to_string(), |epoch| format!("epoch {}", epoch.as_u64()))
-            };
-            let display_attestation = |source, target| match (source, target) {
-                (None, None) => "none".to_string(),
-                (source, target) => {
-                    format!("{} => {}", display_epoch(source), display_epoch(target))
-                }
-            };
-
             match slashing_protection_database
                 .import_interchange_info(interchange, genesis_validators_root)
             {
@@ -184,21 +188,8 @@ pub fn cli_run<T: EthSpec>(
                 None
             };
 
-            if !slashing_protection_db_path.exists() {
-                return Err(format!(
-                    "No slashing protection database exists at: {}",
-                    slashing_protection_db_path.display()
-                ));
-            }
-
-            let slashing_protection_database = SlashingDatabase::open(&slashing_protection_db_path)
-                .map_err(|e| {
-                    format!(
-                        "Unable to open database at {}: {:?}",
-                        slashing_protection_db_path.display(),
-                        e
-                    )
-                })?;
+            let slashing_protection_database =
+                open_existing_database(&slashing_protection_db_path)?;
 
             let interchange = slashing_protection_database
                 .export_interchange_info(genesis_validators_root, selected_pubkeys.as_deref())
@@ -215,7 +206,116 @@ pub fn cli_run<T: EthSpec>(
 
             Ok(())
         }
+        (PRUNE_CMD, Some(_)) => {
+            let slashing_protection_database =
+                open_existing_database(&slashing_protection_db_path)?;
+
+            let size_before = file_size(&slashing_protection_db_path);
+
+            let summaries = slashing_protection_database
+                .prune_to_watermarks(genesis_validators_root)
+                .map_err(|e| {
+                    format!(
+                        "Error during pruning: {:?}\n\
+                         No data has been deleted. Run the `{}` command for details.",
+                        e, AUDIT_CMD
+                    )
+                })?;
+
+            eprintln!(
+                "Retained the latest records of {} validators:",
+                summaries.len()
+            );
+            for (pubkey, summary) in &summaries {
+                eprintln!("- {:?}", pubkey);
+                eprintln!(
+                    "    - latest proposed block: {}",
+                    display_slot(summary.max_block_slot)
+                );
+                eprintln!(
+                    "    - latest attestation: {}",
+                    display_attestation(
+                        summary.max_attestation_source,
+                        summary.max_attestation_target
+                    )
+                );
+            }
+
+            eprintln!(
+                "Pruning completed successfully, database size reduced from {} to {} bytes",
+                size_before,
+                file_size(&slashing_protection_db_path)
+            );
+
+            Ok(())
+        }
+        (AUDIT_CMD, Some(_)) => {
+            let slashing_protection_database =
+                open_existing_database(&slashing_protection_db_path)?;
+
+            // Every validator known to the validator client should be registered.
+            let expected_pubkeys = if validator_base_dir.join(CONFIG_FILENAME).exists() {
+                ValidatorDefinitions::open(&validator_base_dir)
+                    .map_err(|e| format!("Unable to open validator definitions: {:?}", e))?
+                    .as_slice()
+                    .iter()
+                    .map(|def| def.voting_public_key.compress())
+                    .collect()
+            } else {
+                vec![]
+            };
+
+            let anomalies = slashing_protection_database
+                .audit(&expected_pubkeys)
+                .map_err(|e| format!("Error during audit: {:?}", e))?;
+
+            if anomalies.is_empty() {
+                eprintln!("No anomalies found");
+                Ok(())
+            } else {
+                eprintln!("Found {} anomalies:", anomalies.len());
+                for anomaly in &anomalies {
+                    eprintln!("- {}", anomaly);
+                }
+                Err(
+                    "ERROR: the slashing protection database is inconsistent, see above.\n\
+                     Do not start validating until the cause has been investigated."
+                        .to_string(),
+                )
+            }
+        }
         ("", _) => Err("No subcommand provided, see --help for options".to_string()),
         (command, _) => Err(format!("No such subcommand `{}`", command)),
     }
 }
+
+fn open_existing_database(path: &Path) -> Result<SlashingDatabase, String> {
+    if !path.exists() {
+        return Err(format!(
+            "No slashing protection database exists at: {}",
+            path.display()
+        ));
+    }
+
+    SlashingDatabase::open(path)
+        .map_err(|e| format!("Unable to open database at {}: {:?}", path.display(), e))
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
+
+fn display_slot(slot: Option<Slot>) -> String {
+    slot.map_or("none".to_string(), |slot| format!("slot {}", slot.as_u64()))
+}
+
+fn display_epoch(epoch: Option<Epoch>) -> String {
+    epoch.map_or("?".to_string(), |epoch| format!("epoch {}", epoch.as_u64()))
+}
+
+fn display_attestation(source: Option<Epoch>, target: Option<Epoch>) -> String {
+    match (source, target) {
+        (None, None) => "none".to_string(),
+        (source, target) => format!("{} => {}", display_epoch(source), display_epoch(target)),
+    }
+}
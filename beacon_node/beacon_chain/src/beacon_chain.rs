@@ -56,6 +56,7 @@ use crate::observed_slashable::ObservedSlashable;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
+use crate::proposal_rewards::ProducedBlockValuesCache;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{BlockProductionPreState, SnapshotCache};
 use crate::sync_committee_verification::{
@@ -466,6 +467,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
+    /// A cache of the values of recently produced blocks, for recording proposal rewards.
+    pub produced_block_values_cache: ProducedBlockValuesCache,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
     /// continue they can request that everything shuts down.
     pub shutdown_sender: Sender<ShutdownReason>,
@@ -2921,6 +2924,33 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.remove_notified(&block_root, r)
     }
 
+    /// Process blobs fetched from the execution layer's blob pool for `block`.
+    ///
+    /// See `DataAvailabilityChecker::put_engine_blobs` for how the blobs are verified.
+    pub async fn process_engine_blobs(
+        self: &Arc<Self>,
+        block_root: Hash256,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        blobs: Vec<(usize, Blob<T::EthSpec>, KzgProof)>,
+    ) -> Result<AvailabilityProcessingStatus, BlockError<T::EthSpec>> {
+        // If this block has already been imported to forkchoice it must have been available, so
+        // we don't need to process its blobs again.
+        if self
+            .canonical_head
+            .fork_choice_read_lock()
+            .contains_block(&block_root)
+        {
+            return Err(BlockError::BlockIsAlreadyKnown);
+        }
+
+        let availability = self
+            .data_availability_checker
+            .put_engine_blobs(block_root, &block, blobs)?;
+
+        let r = self.process_availability(block.slot(), availability).await;
+        self.remove_notified(&block_root, r)
+    }
+
     /// Remove any block components from the *processing cache* if we no longer require them. If the
     /// block was imported full or erred, we no longer require them.
     fn remove_notified(
@@ -4720,16 +4750,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         //
         // Wait for the execution layer to return an execution payload (if one is required).
         let prepare_payload_handle = partial_beacon_block.prepare_payload_handle.take();
-        let block_contents_type_option =
+        let (block_contents_type_option, fee_recipient) =
             if let Some(prepare_payload_handle) = prepare_payload_handle {
-                Some(
-                    prepare_payload_handle
-                        .await
-                        .map_err(BlockProductionError::TokioJoin)?
-                        .ok_or(BlockProductionError::ShuttingDown)??,
-                )
+                let (block_contents_type, fee_recipient) = prepare_payload_handle
+                    .await
+                    .map_err(BlockProductionError::TokioJoin)?
+                    .ok_or(BlockProductionError::ShuttingDown)??;
+                (Some(block_contents_type), fee_recipient)
             } else {
-                None
+                (None, None)
             };
         // Part 3/3 (blocking)
         if let Some(block_contents_type) = block_contents_type_option {
@@ -4743,6 +4772,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                                 chain.complete_partial_beacon_block(
                                     partial_beacon_block,
                                     Some(block_contents),
+                                    fee_recipient,
                                     verification,
                                 )
                             },
@@ -4763,6 +4793,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                                 chain.complete_partial_beacon_block(
                                     partial_beacon_block,
                                     Some(block_contents),
+                                    fee_recipient,
                                     verification,
                                 )
                             },
@@ -4784,6 +4815,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         chain.complete_partial_beacon_block(
                             partial_beacon_block,
                             None,
+                            fee_recipient,
                             verification,
                         )
                     },
@@ -5051,6 +5083,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         partial_beacon_block: PartialBeaconBlock<T::EthSpec>,
         block_contents: Option<BlockProposalContents<T::EthSpec, Payload>>,
+        fee_recipient: Option<Address>,
         verification: ProduceBlockVerification,
     ) -> Result<BeaconBlockResponse<T::EthSpec, Payload>, BlockProductionError> {
         let PartialBeaconBlock {
@@ -5317,6 +5350,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
+        self.produced_block_values_cache.insert(
+            block.canonical_root(),
+            execution_payload_value,
+            consensus_block_value,
+            fee_recipient,
+        );
+
         trace!(
             self.log,
             "Produced beacon block";
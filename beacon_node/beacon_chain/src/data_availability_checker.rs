@@ -22,7 +22,9 @@ use std::sync::Arc;
 use task_executor::TaskExecutor;
 use types::beacon_block_body::{KzgCommitmentOpts, KzgCommitments};
 use types::blob_sidecar::{BlobIdentifier, BlobSidecar, FixedBlobSidecarList};
-use types::{BlobSidecarList, ChainSpec, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+use types::{
+    Blob, BlobSidecarList, ChainSpec, Epoch, EthSpec, Hash256, KzgProof, SignedBeaconBlock, Slot,
+};
 
 mod availability_view;
 mod child_components;
@@ -210,6 +212,48 @@ impl<T: BeaconChainTypes> DataAvailabilityChecker<T> {
             .put_kzg_verified_blobs(block_root, verified_blobs)
     }
 
+    /// Put a list of blobs fetched from the execution layer's blob pool into the availability
+    /// cache.
+    ///
+    /// The execution layer only returns blobs and proofs, so the sidecars are built locally from
+    /// `block`. The inclusion proof of each sidecar is checked and KZG verification is performed
+    /// before any blob is cached.
+    pub fn put_engine_blobs(
+        &self,
+        block_root: Hash256,
+        block: &SignedBeaconBlock<T::EthSpec>,
+        blobs: Vec<(usize, Blob<T::EthSpec>, KzgProof)>,
+    ) -> Result<Availability<T::EthSpec>, AvailabilityCheckError> {
+        let Some(kzg) = self.kzg.as_ref() else {
+            return Err(AvailabilityCheckError::KzgNotInitialized);
+        };
+
+        let mut sidecars = Vec::with_capacity(blobs.len());
+        for (index, blob, kzg_proof) in blobs {
+            let sidecar = BlobSidecar::new(index, blob, block, kzg_proof)
+                .map_err(AvailabilityCheckError::BlobSidecar)?;
+            if !sidecar
+                .verify_blob_sidecar_inclusion_proof()
+                .unwrap_or(false)
+            {
+                return Err(AvailabilityCheckError::InvalidInclusionProof(sidecar.index));
+            }
+            sidecars.push(Arc::new(sidecar));
+        }
+
+        let verified_blobs =
+            KzgVerifiedBlobList::new(sidecars, kzg).map_err(AvailabilityCheckError::Kzg)?;
+
+        self.availability_cache
+            .put_kzg_verified_blobs(block_root, verified_blobs)
+    }
+
+    /// Returns the indices of the blobs for `block_root` that are already held in memory by the
+    /// availability cache.
+    pub fn cached_blob_indices(&self, block_root: &Hash256) -> Vec<u64> {
+        self.availability_cache.cached_blob_indices(block_root)
+    }
+
     /// Check if we've cached other blobs for this block. If it completes a set and we also
     /// have a block cached, return the `Availability` variant triggering block import.
     /// Otherwise cache the blob sidecar.
@@ -1,4 +1,5 @@
 use kzg::{Error as KzgError, KzgCommitment};
+use types::blob_sidecar::BlobSidecarError;
 use types::{BeaconStateError, Hash256};
 
 #[derive(Debug)]
@@ -19,6 +20,8 @@ pub enum Error {
     ParentStateMissing(Hash256),
     BlockReplayError(state_processing::BlockReplayError),
     RebuildingStateCaches(BeaconStateError),
+    BlobSidecar(BlobSidecarError),
+    InvalidInclusionProof(u64),
 }
 
 pub enum ErrorCategory {
@@ -39,7 +42,9 @@ impl Error {
             | Error::Unexpected
             | Error::ParentStateMissing(_)
             | Error::BlockReplayError(_)
-            | Error::RebuildingStateCaches(_) => ErrorCategory::Internal,
+            | Error::RebuildingStateCaches(_)
+            | Error::BlobSidecar(_)
+            | Error::InvalidInclusionProof(_) => ErrorCategory::Internal,
             Error::Kzg(_)
             | Error::BlobIndexInvalid(_)
             | Error::KzgCommitmentMismatch { .. }
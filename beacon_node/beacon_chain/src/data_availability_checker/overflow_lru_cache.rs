@@ -415,6 +415,24 @@ impl<T: BeaconChainTypes> OverflowLRUCache<T> {
         }
     }
 
+    /// Returns the indices of the blobs held in memory for `block_root`. Blobs that have been
+    /// written to the overflow store are not included.
+    pub fn cached_blob_indices(&self, block_root: &Hash256) -> Vec<u64> {
+        self.critical
+            .read()
+            .in_memory
+            .peek(block_root)
+            .map(|pending_components| {
+                pending_components
+                    .verified_blobs
+                    .iter()
+                    .flatten()
+                    .map(|blob| blob.blob_index())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn put_kzg_verified_blobs<I: IntoIterator<Item = KzgVerifiedBlob<T::EthSpec>>>(
         &self,
         block_root: Hash256,
@@ -20,6 +20,7 @@ pub struct ServerSentEventHandler<T: EthSpec> {
     light_client_finality_update_tx: Sender<EventKind<T>>,
     light_client_optimistic_update_tx: Sender<EventKind<T>>,
     block_reward_tx: Sender<EventKind<T>>,
+    engine_disagreement_tx: Sender<EventKind<T>>,
     log: Logger,
 }
 
@@ -45,6 +46,7 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         let (light_client_finality_update_tx, _) = broadcast::channel(capacity);
         let (light_client_optimistic_update_tx, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
+        let (engine_disagreement_tx, _) = broadcast::channel(capacity);
 
         Self {
             attestation_tx,
@@ -60,6 +62,7 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
             light_client_finality_update_tx,
             light_client_optimistic_update_tx,
             block_reward_tx,
+            engine_disagreement_tx,
             log,
         }
     }
@@ -126,6 +129,10 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
                 .block_reward_tx
                 .send(kind)
                 .map(|count| log_count("block reward", count)),
+            EventKind::EngineDisagreement(_) => self
+                .engine_disagreement_tx
+                .send(kind)
+                .map(|count| log_count("engine disagreement", count)),
         };
         if let Err(SendError(event)) = result {
             trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
@@ -184,6 +191,10 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.block_reward_tx.subscribe()
     }
 
+    pub fn subscribe_engine_disagreement(&self) -> Receiver<EventKind<T>> {
+        self.engine_disagreement_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -227,4 +238,8 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
     pub fn has_block_reward_subscribers(&self) -> bool {
         self.block_reward_tx.receiver_count() > 0
     }
+
+    pub fn has_engine_disagreement_subscribers(&self) -> bool {
+        self.engine_disagreement_tx.receiver_count() > 0
+    }
 }
@@ -30,7 +30,9 @@ use tree_hash::TreeHash;
 use types::payload::BlockProductionVersion;
 use types::*;
 
-pub type PreparePayloadResult<E> = Result<BlockProposalContentsType<E>, BlockProductionError>;
+/// The prepared payload and the fee recipient suggested to the execution layer or builder, if any.
+pub type PreparePayloadResult<E> =
+    Result<(BlockProposalContentsType<E>, Option<Address>), BlockProductionError>;
 pub type PreparePayloadHandle<E> = JoinHandle<Option<PreparePayloadResult<E>>>;
 
 #[derive(PartialEq)]
@@ -489,7 +491,7 @@ pub async fn prepare_execution_payload<T>(
     parent_beacon_block_root: Option<Hash256>,
     builder_boost_factor: Option<u64>,
     block_production_version: BlockProductionVersion,
-) -> Result<BlockProposalContentsType<T::EthSpec>, BlockProductionError>
+) -> PreparePayloadResult<T::EthSpec>
 where
     T: BeaconChainTypes,
 {
@@ -509,11 +511,12 @@ where
         if is_terminal_block_hash_set && !is_activation_epoch_reached {
             // Use the "empty" payload if there's a terminal block hash, but we haven't reached the
             // terminal block epoch yet.
-            return Ok(BlockProposalContentsType::Full(
-                BlockProposalContents::Payload {
+            return Ok((
+                BlockProposalContentsType::Full(BlockProposalContents::Payload {
                     payload: FullPayload::default_at_fork(fork)?,
                     block_value: Uint256::zero(),
-                },
+                }),
+                None,
             ));
         }
 
@@ -527,11 +530,12 @@ where
         } else {
             // If the merge transition hasn't occurred yet and the EL hasn't found the terminal
             // block, return an "empty" payload.
-            return Ok(BlockProposalContentsType::Full(
-                BlockProposalContents::Payload {
+            return Ok((
+                BlockProposalContentsType::Full(BlockProposalContents::Payload {
                     payload: FullPayload::default_at_fork(fork)?,
                     block_value: Uint256::zero(),
-                },
+                }),
+                None,
             ));
         }
     } else {
@@ -584,5 +588,5 @@ where
         .await
         .map_err(BlockProductionError::GetPayloadFailed)?;
 
-    Ok(block_contents)
+    Ok((block_contents, Some(suggested_fee_recipient)))
 }
@@ -0,0 +1,92 @@
+//! Fetches the blobs of a block from the execution layer's blob pool.
+//!
+//! A block is often received over gossip before its blob sidecars. The blobs it commits to have
+//! usually already been seen by the execution layer as blob transactions, so they can be fetched
+//! with `engine_getBlobsV1` instead of waiting for gossip or requesting them from peers. The
+//! sidecars are built locally from the block and are verified by the `DataAvailabilityChecker`.
+use crate::{
+    metrics, AvailabilityProcessingStatus, BeaconChain, BeaconChainError, BeaconChainTypes,
+    BlockError,
+};
+use execution_layer::Error as ExecutionLayerError;
+use slog::debug;
+use std::sync::Arc;
+use types::{Hash256, SignedBeaconBlock};
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Fetch any blobs of `block` that are not already in the availability cache from the
+    /// execution layer, and process them.
+    ///
+    /// Returns `Ok(None)` if there was nothing to fetch, the execution layer does not support
+    /// `engine_getBlobsV1` or none of the blobs were found in its blob pool.
+    pub async fn fetch_blobs_from_execution_layer(
+        self: &Arc<Self>,
+        block_root: Hash256,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    ) -> Result<Option<AvailabilityProcessingStatus>, BlockError<T::EthSpec>> {
+        let Some(execution_layer) = self.execution_layer.as_ref() else {
+            return Ok(None);
+        };
+        let Ok(commitments) = block.message().body().blob_kzg_commitments() else {
+            return Ok(None);
+        };
+
+        let cached_indices = self
+            .data_availability_checker
+            .cached_blob_indices(&block_root);
+        let (indices, versioned_hashes): (Vec<_>, Vec<_>) = commitments
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !cached_indices.contains(&(*index as u64)))
+            .map(|(index, commitment)| (index, commitment.calculate_versioned_hash()))
+            .unzip();
+
+        if versioned_hashes.is_empty() {
+            return Ok(None);
+        }
+
+        let _timer = metrics::start_timer(&metrics::BLOBS_FROM_EL_FETCH_TIMES);
+
+        let response = match execution_layer.get_blobs(versioned_hashes).await {
+            Ok(response) => response,
+            Err(ExecutionLayerError::GetBlobsNotSupported) => return Ok(None),
+            Err(e) => {
+                return Err(BlockError::BeaconChainError(
+                    BeaconChainError::ExecutionLayerGetBlobsFailed(Box::new(e)),
+                ))
+            }
+        };
+
+        let requested = indices.len();
+        let blobs = indices
+            .into_iter()
+            .zip(response)
+            .filter_map(|(index, blob_and_proof)| {
+                blob_and_proof
+                    .map(|blob_and_proof| (index, blob_and_proof.blob, blob_and_proof.proof))
+            })
+            .collect::<Vec<_>>();
+
+        metrics::inc_counter_by(&metrics::BLOBS_FROM_EL_HIT_TOTAL, blobs.len() as u64);
+        metrics::inc_counter_by(
+            &metrics::BLOBS_FROM_EL_MISS_TOTAL,
+            requested.saturating_sub(blobs.len()) as u64,
+        );
+
+        debug!(
+            self.log,
+            "Fetched blobs from execution layer";
+            "block_root" => ?block_root,
+            "requested" => requested,
+            "received" => blobs.len(),
+        );
+
+        if blobs.is_empty() {
+            return Ok(None);
+        }
+
+        self.process_engine_blobs(block_root, block, blobs)
+            .await
+            .map(Some)
+    }
+}
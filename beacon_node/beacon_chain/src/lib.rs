@@ -25,6 +25,7 @@ pub mod eth1_chain;
 mod eth1_finalization_cache;
 pub mod events;
 pub mod execution_payload;
+pub mod fetch_blobs;
 pub mod fork_choice_signal;
 pub mod fork_revert;
 mod head_tracker;
@@ -46,6 +47,7 @@ pub mod otb_verification_service;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
 mod pre_finalization_cache;
+pub mod proposal_rewards;
 pub mod proposer_prep_service;
 pub mod schema_change;
 pub mod shuffling_cache;
@@ -1030,6 +1030,18 @@ lazy_static! {
         "blob_sidecar_inclusion_proof_computation_seconds",
         "Time taken to compute blob sidecar inclusion proof"
     );
+    pub static ref BLOBS_FROM_EL_HIT_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_blobs_from_el_hit_total",
+        "Number of blobs fetched from the execution layer blob pool"
+    );
+    pub static ref BLOBS_FROM_EL_MISS_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_blobs_from_el_miss_total",
+        "Number of blobs requested from the execution layer blob pool that were not found"
+    );
+    pub static ref BLOBS_FROM_EL_FETCH_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_blobs_from_el_fetch_seconds",
+        "Time taken to fetch and verify blobs from the execution layer blob pool"
+    );
 }
 
 // Fifth lazy-static block is used to account for macro recursion limit.
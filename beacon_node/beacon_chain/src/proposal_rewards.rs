@@ -0,0 +1,221 @@
+//! Records the rewards earned by blocks proposed via this beacon node.
+//!
+//! The value of a block is known to the beacon node when it is produced, but the value actually
+//! paid to the proposer's fee recipient is only known once the payload has been executed. A
+//! builder may pay less than its bid, so the paid value is measured from the execution node's
+//! balance of the fee recipient before and after the payload.
+//!
+//! The fee recipient is taken from the proposal itself rather than the current configuration of
+//! the beacon node, which may have changed since the block was produced.
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{PayloadSource, ProposalReward};
+use execution_layer::FeeRecipientBalanceChange;
+use lru::LruCache;
+use parking_lot::Mutex;
+use slog::{debug, warn};
+use ssz::{Decode, Encode};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use store::{DBColumn, KeyValueStore};
+use types::non_zero_usize::new_non_zero_usize;
+use types::{Address, ExecPayload, Hash256, SignedBeaconBlock, Slot, Uint256};
+
+const PRODUCED_BLOCK_CACHE_LIMIT: NonZeroUsize = new_non_zero_usize(16);
+
+/// Cache of the values of recently produced blocks, keyed by block root.
+///
+/// The values are computed during block production and are consumed when the block is published.
+/// The block root is the same for full and blinded blocks, so blocks from a builder are matched
+/// once they have been unblinded.
+pub struct ProducedBlockValuesCache {
+    cache: Mutex<LruCache<Hash256, ProducedBlockValues>>,
+}
+
+#[derive(Clone, Copy)]
+struct ProducedBlockValues {
+    /// The execution payload value in wei.
+    execution_payload_value: Uint256,
+    /// The consensus block value in gwei.
+    consensus_block_value: u64,
+    /// The fee recipient suggested to the execution layer or builder, if a payload was requested.
+    fee_recipient: Option<Address>,
+}
+
+impl Default for ProducedBlockValuesCache {
+    fn default() -> Self {
+        ProducedBlockValuesCache {
+            cache: Mutex::new(LruCache::new(PRODUCED_BLOCK_CACHE_LIMIT)),
+        }
+    }
+}
+
+impl ProducedBlockValuesCache {
+    pub fn insert(
+        &self,
+        block_root: Hash256,
+        execution_payload_value: Uint256,
+        consensus_block_value: u64,
+        fee_recipient: Option<Address>,
+    ) {
+        self.cache.lock().put(
+            block_root,
+            ProducedBlockValues {
+                execution_payload_value,
+                consensus_block_value,
+                fee_recipient,
+            },
+        );
+    }
+
+    fn get(&self, block_root: &Hash256) -> Option<ProducedBlockValues> {
+        self.cache.lock().get(block_root).copied()
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Record the rewards of `block`, which was published via this beacon node and imported.
+    ///
+    /// Blocks without an execution payload are ignored, as are builder payloads for blocks which
+    /// were not produced by this beacon node, since the fee recipient registered with the builder
+    /// is unknown. Failure to determine the value paid to the fee recipient is logged and results
+    /// in a record without a `paid_value`.
+    pub async fn record_proposal_reward(
+        self: &Arc<Self>,
+        block_root: Hash256,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        payload_source: PayloadSource,
+    ) -> Result<(), BeaconChainError> {
+        let execution_layer = self
+            .execution_layer
+            .as_ref()
+            .ok_or(BeaconChainError::ExecutionLayerMissing)?;
+
+        let Ok(payload) = block.message().execution_payload() else {
+            return Ok(());
+        };
+        let execution_block_hash = payload.block_hash();
+        let parent_hash = payload.parent_hash();
+        if execution_block_hash == <_>::default() {
+            return Ok(());
+        }
+
+        let slot = block.slot();
+        let proposer_index = block.message().proposer_index();
+        let produced = self.produced_block_values_cache.get(&block_root);
+        let fee_recipient = match payload_source {
+            PayloadSource::Local => payload.fee_recipient(),
+            PayloadSource::Builder => {
+                let Some(fee_recipient) = produced.and_then(|values| values.fee_recipient) else {
+                    warn!(
+                        self.log,
+                        "Unable to record proposal reward";
+                        "msg" => "the fee recipient registered with the builder is unknown",
+                        "slot" => slot,
+                        "block_root" => ?block_root,
+                    );
+                    return Ok(());
+                };
+                fee_recipient
+            }
+        };
+
+        let (paid_value, balance_decrease) = match execution_layer
+            .get_fee_recipient_balance_change(fee_recipient, execution_block_hash, parent_hash)
+            .await
+        {
+            Ok(FeeRecipientBalanceChange::Increased(value)) => (Some(value), None),
+            Ok(FeeRecipientBalanceChange::Decreased(value)) => {
+                warn!(
+                    self.log,
+                    "Unable to determine proposal payment";
+                    "msg" => "the fee recipient transacted in the block",
+                    "balance_decrease_wei" => %value,
+                    "slot" => slot,
+                    "block_root" => ?block_root,
+                );
+                (None, Some(value))
+            }
+            Err(e) => {
+                warn!(
+                    self.log,
+                    "Unable to determine proposal payment";
+                    "error" => ?e,
+                    "slot" => slot,
+                    "block_root" => ?block_root,
+                );
+                (None, None)
+            }
+        };
+
+        let reward = ProposalReward {
+            slot,
+            block_root,
+            proposer_index,
+            payload_source,
+            fee_recipient,
+            execution_block_hash,
+            bid_value: produced.map(|values| values.execution_payload_value),
+            paid_value,
+            consensus_reward: produced.map(|values| values.consensus_block_value),
+            balance_decrease,
+        };
+
+        if let (Some(bid_value), Some(paid_value)) = (reward.bid_value, reward.paid_value) {
+            if paid_value < bid_value {
+                warn!(
+                    self.log,
+                    "Proposal paid less than its bid";
+                    "paid_wei" => %paid_value,
+                    "bid_wei" => %bid_value,
+                    "payload_source" => ?payload_source,
+                    "slot" => slot,
+                );
+                if payload_source == PayloadSource::Builder {
+                    execution_layer.record_builder_value_shortfall(&execution_block_hash);
+                }
+            }
+        }
+
+        debug!(
+            self.log,
+            "Recording proposal reward";
+            "paid_wei" => ?reward.paid_value,
+            "bid_wei" => ?reward.bid_value,
+            "payload_source" => ?payload_source,
+            "slot" => slot,
+        );
+
+        self.store.hot_db.put_bytes(
+            DBColumn::BeaconProposalRewards.as_str(),
+            &proposal_reward_key(slot),
+            &reward.as_ssz_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    /// Return the recorded rewards of proposals between `from` and `to` (inclusive).
+    pub fn get_proposal_rewards(
+        &self,
+        from: Slot,
+        to: Slot,
+    ) -> Result<Vec<ProposalReward>, BeaconChainError> {
+        let mut rewards = vec![];
+        for res in self.store.hot_db.iter_column_from::<Vec<u8>>(
+            DBColumn::BeaconProposalRewards,
+            &proposal_reward_key(from),
+        ) {
+            let (_, bytes) = res?;
+            let reward = ProposalReward::from_ssz_bytes(&bytes).map_err(store::Error::from)?;
+            if reward.slot > to {
+                break;
+            }
+            rewards.push(reward);
+        }
+        Ok(rewards)
+    }
+}
+
+fn proposal_reward_key(slot: Slot) -> [u8; 8] {
+    slot.as_u64().to_be_bytes()
+}
@@ -565,6 +565,7 @@ where
             runtime: self.runtime,
             mock_execution_layer: self.mock_execution_layer,
             mock_builder: None,
+            mock_builders: vec![],
             rng: make_rng(),
         }
     }
@@ -617,6 +618,8 @@ pub struct BeaconChainHarness<T: BeaconChainTypes> {
 
     pub mock_execution_layer: Option<MockExecutionLayer<T::EthSpec>>,
     pub mock_builder: Option<Arc<MockBuilder<T::EthSpec>>>,
+    /// Every mock builder used as a relay, including `mock_builder`.
+    pub mock_builders: Vec<Arc<MockBuilder<T::EthSpec>>>,
 
     pub rng: Mutex<StdRng>,
 }
@@ -655,6 +658,17 @@ where
     pub fn set_mock_builder(
         &mut self,
         beacon_url: SensitiveUrl,
+    ) -> impl futures::Future<Output = ()> {
+        self.set_mock_builders(beacon_url, 1)
+    }
+
+    /// Create `count` mock builders and use all of them as relays for the execution layer.
+    ///
+    /// The first builder is also available as `self.mock_builder`.
+    pub fn set_mock_builders(
+        &mut self,
+        beacon_url: SensitiveUrl,
+        count: usize,
     ) -> impl futures::Future<Output = ()> {
         let mock_el = self
             .mock_execution_layer
@@ -663,25 +677,32 @@ where
 
         let mock_el_url = SensitiveUrl::parse(mock_el.server.url().as_str()).unwrap();
 
-        // Create the builder, listening on a free port.
-        let (mock_builder, (addr, mock_builder_server)) = MockBuilder::new_for_testing(
-            mock_el_url,
-            beacon_url,
-            self.spec.clone(),
-            self.runtime.task_executor.clone(),
-        );
+        let mut mock_builders = vec![];
+        let mut builder_urls = vec![];
+        let mut mock_builder_servers = vec![];
+        for _ in 0..count {
+            // Create the builder, listening on a free port.
+            let (mock_builder, (addr, mock_builder_server)) = MockBuilder::new_for_testing(
+                mock_el_url.clone(),
+                beacon_url.clone(),
+                self.spec.clone(),
+                self.runtime.task_executor.clone(),
+            );
+            let port = addr.port();
+            mock_builders.push(Arc::new(mock_builder));
+            builder_urls
+                .push(SensitiveUrl::parse(format!("http://127.0.0.1:{port}").as_str()).unwrap());
+            mock_builder_servers.push(mock_builder_server);
+        }
 
-        // Set the builder URL in the execution layer now that its port is known.
-        let port = addr.port();
+        // Set the builder URLs in the execution layer now that their ports are known.
         mock_el
             .el
-            .set_builder_url(
-                SensitiveUrl::parse(format!("http://127.0.0.1:{port}").as_str()).unwrap(),
-                None,
-            )
+            .set_builder_urls(builder_urls, None, None)
             .unwrap();
 
-        self.mock_builder = Some(Arc::new(mock_builder));
+        self.mock_builder = mock_builders.first().cloned();
+        self.mock_builders = mock_builders;
 
         // Sanity check.
         let el_builder = self
@@ -694,7 +715,9 @@ where
         let mock_el_builder = mock_el.el.builder().unwrap();
         assert!(Arc::ptr_eq(&el_builder, &mock_el_builder));
 
-        mock_builder_server
+        async move {
+            futures::future::join_all(mock_builder_servers).await;
+        }
     }
 
     pub fn get_head_block(&self) -> RpcBlock<E> {
@@ -1607,6 +1607,125 @@ async fn import_duplicate_block_unrealized_justification() {
     );
 }
 
+/// Produce and import blocks on a Deneb harness until the mock EL includes blobs in a block, and
+/// return that block without importing it.
+async fn make_block_with_blobs(
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+) -> Arc<SignedBeaconBlock<E>> {
+    loop {
+        let slot = harness.get_current_slot();
+        let (block_contents, _) = harness.make_block(harness.get_current_state(), slot).await;
+        let block = block_contents.0.clone();
+        if block.num_expected_blobs() > 0 {
+            return block;
+        }
+        harness
+            .process_block(slot, block.canonical_root(), block_contents)
+            .await
+            .unwrap();
+        harness.advance_slot();
+    }
+}
+
+fn get_deneb_harness() -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(ForkName::Deneb.make_genesis_spec(E::default_spec()))
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    harness.advance_slot();
+
+    harness
+}
+
+#[tokio::test]
+async fn blobs_from_execution_layer_complete_gossip_block() {
+    let harness = get_deneb_harness();
+    let block = make_block_with_blobs(&harness).await;
+    let block_root = block.canonical_root();
+
+    let verified_block = harness
+        .chain
+        .clone()
+        .verify_block_for_gossip(block.clone())
+        .await
+        .unwrap();
+    let status = harness
+        .chain
+        .process_block(
+            block_root,
+            verified_block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        status,
+        AvailabilityProcessingStatus::MissingComponents(block.slot(), block_root)
+    );
+
+    let status = harness
+        .chain
+        .fetch_blobs_from_execution_layer(block_root, block.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        status,
+        Some(AvailabilityProcessingStatus::Imported(block_root))
+    );
+    assert_eq!(
+        harness.chain.get_blobs(&block_root).unwrap().len(),
+        block.num_expected_blobs()
+    );
+}
+
+#[tokio::test]
+async fn blobs_missing_from_execution_layer() {
+    let harness = get_deneb_harness();
+    let block = make_block_with_blobs(&harness).await;
+    let block_root = block.canonical_root();
+
+    harness
+        .mock_execution_layer
+        .as_ref()
+        .unwrap()
+        .server
+        .execution_block_generator()
+        .clear_blob_pool();
+
+    let verified_block = harness
+        .chain
+        .clone()
+        .verify_block_for_gossip(block.clone())
+        .await
+        .unwrap();
+    harness
+        .chain
+        .process_block(
+            block_root,
+            verified_block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap();
+
+    let status = harness
+        .chain
+        .fetch_blobs_from_execution_layer(block_root, block)
+        .await
+        .unwrap();
+    assert_eq!(status, None);
+    assert!(!harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+}
+
 async fn import_execution_pending_block<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     execution_pending_block: ExecutionPendingBlock<T>,
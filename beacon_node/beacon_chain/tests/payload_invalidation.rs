@@ -411,6 +411,53 @@ async fn valid_invalid_syncing() {
     rig.import_block(Payload::Syncing).await;
 }
 
+/// Ensure that faults injected by a declarative scenario are applied to block import.
+#[tokio::test]
+async fn scenario_rejects_payload_once() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+    let parent_root = rig.import_block(Payload::Valid).await;
+
+    let scenario = serde_json::from_value(serde_json::json!({
+        "rules": [{
+            "method": "engine_newPayload*",
+            "times": 1,
+            "action": {
+                "type": "payload_status",
+                "status": "INVALID",
+                "latest_valid_hash": rig.block_hash(parent_root),
+            },
+        }],
+    }))
+    .unwrap();
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer.server.set_scenario(scenario);
+
+    let head = rig.harness.chain.head_snapshot();
+    let state = head.beacon_state.clone_with_only_committee_caches();
+    let slot = state.slot() + 1;
+    let ((block, blobs), _) = rig.harness.make_block(state, slot).await;
+    let block_root = block.canonical_root();
+    let error = rig
+        .harness
+        .process_block(slot, block_root, (block, blobs))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        BlockError::ExecutionPayloadError(ExecutionPayloadError::RejectedByExecutionEngine { .. })
+    ));
+    assert!(rig
+        .canonical_head()
+        .fork_choice_read_lock()
+        .get_block(&block_root)
+        .is_none());
+
+    // The rule only applies once, so the next block is verified as usual.
+    let root = rig.import_block(Payload::Valid).await;
+    assert!(rig.execution_status(root).is_valid_and_post_bellatrix());
+}
+
 /// Ensure that an invalid payload can invalidate its parent too (given the right
 /// `latest_valid_hash`.
 #[tokio::test]
@@ -63,6 +63,12 @@ impl BuilderHttpClient {
         &self.user_agent
     }
 
+    /// Set the timeout for `GET /eth/v1/builder/header` requests.
+    pub fn with_get_header_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.get_header = timeout;
+        self
+    }
+
     async fn get_with_timeout<T: DeserializeOwned, U: IntoUrl>(
         &self,
         url: U,
@@ -1,5 +1,5 @@
 use crate::address_change_broadcast::broadcast_address_changes_at_capella;
-use crate::config::{ClientGenesis, Config as ClientConfig};
+use crate::config::{ClientGenesis, Config as ClientConfig, DepositSnapshotSource};
 use crate::notifier::spawn_notifier;
 use crate::Client;
 use beacon_chain::attestation_simulator::start_attestation_simulator_service;
@@ -18,11 +18,12 @@ use beacon_chain::{
 use beacon_processor::BeaconProcessorConfig;
 use beacon_processor::{BeaconProcessor, BeaconProcessorChannels};
 use environment::RuntimeContext;
-use eth1::{Config as Eth1Config, Service as Eth1Service};
+use eth1::{Config as Eth1Config, Error as Eth1Error, Service as Eth1Service};
 use eth2::{
-    types::{BlockId, StateId},
+    types::{BlockId, EventKind, StateId},
     BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
+use execution_layer::test_utils::generate_genesis_header;
 use execution_layer::ExecutionLayer;
 use genesis::{interop_genesis_state, Eth1GenesisService, DEFAULT_ETH1_BLOCK_HASH};
 use lighthouse_network::{prometheus_client::registry::Registry, NetworkGlobals};
@@ -31,16 +32,17 @@ use network::{NetworkConfig, NetworkSenders, NetworkService};
 use slasher::Slasher;
 use slasher_service::SlasherService;
 use slog::{debug, info, warn, Logger};
+use ssz::Decode;
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 use timer::spawn_timer;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast::error::RecvError, oneshot};
 use types::{
-    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
-    ExecutionBlockHash, Hash256, SignedBeaconBlock,
+    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, DepositTreeSnapshot,
+    EthSpec, ExecutionBlockHash, Hash256, SignedBeaconBlock,
 };
 
 /// Interval between polling the eth1 node for genesis information.
@@ -51,6 +53,9 @@ pub const ETH1_GENESIS_UPDATE_INTERVAL_MILLIS: u64 = 7_000;
 /// have been pruned before they can manage to sync the chain.
 const BLOB_AVAILABILITY_REDUCTION_EPOCHS: u64 = 2;
 
+/// Timeout for downloading a deposit snapshot from a trusted beacon node.
+const DEPOSIT_SNAPSHOT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// Builds a `Client` instance.
 ///
 /// ## Notes
@@ -222,6 +227,7 @@ where
         } else if chain_exists {
             if matches!(client_genesis, ClientGenesis::WeakSubjSszBytes { .. })
                 || matches!(client_genesis, ClientGenesis::CheckpointSyncUrl { .. })
+                || matches!(client_genesis, ClientGenesis::CheckpointSyncPeers { .. })
             {
                 info!(
                     context.log(),
@@ -250,6 +256,21 @@ where
                 )?;
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
+            ClientGenesis::InteropMerge {
+                validator_count,
+                genesis_time,
+            } => {
+                let execution_payload_header = generate_genesis_header(&spec, true);
+                let keypairs = generate_deterministic_keypairs(validator_count);
+                let genesis_state = interop_genesis_state(
+                    &keypairs,
+                    genesis_time,
+                    Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+                    execution_payload_header,
+                    &spec,
+                )?;
+                builder.genesis_state(genesis_state).map(|v| (v, None))?
+            }
             ClientGenesis::GenesisState => {
                 info!(
                     context.log(),
@@ -454,6 +475,48 @@ where
                     .weak_subjectivity_state(state, block, genesis_state)
                     .map(|v| (v, service))?
             }
+            ClientGenesis::CheckpointSyncPeers { peers } => {
+                info!(
+                    context.log(),
+                    "Starting checkpoint sync from trusted peers";
+                    "peers" => peers.len(),
+                );
+                if config.chain.genesis_backfill {
+                    info!(
+                        context.log(),
+                        "Blocks will be downloaded all the way back to genesis"
+                    );
+                }
+
+                let checkpoint = config
+                    .chain
+                    .weak_subjectivity_checkpoint
+                    .ok_or("Checkpoint sync from peers requires a weak subjectivity checkpoint")?;
+                let genesis_state = genesis_state(&runtime_context, &config, log).await?;
+
+                let (state, block) = network::checkpoint_sync::fetch_checkpoint_from_peers(
+                    context.executor.clone(),
+                    &config.network,
+                    &peers,
+                    checkpoint,
+                    &genesis_state,
+                    &spec,
+                    context.log(),
+                )
+                .await?;
+
+                info!(
+                    context.log(),
+                    "Loaded checkpoint block and state";
+                    "block_slot" => block.slot(),
+                    "state_slot" => state.slot(),
+                    "block_root" => ?checkpoint.root,
+                );
+
+                builder
+                    .weak_subjectivity_state(state, block, genesis_state)
+                    .map(|v| (v, None))?
+            }
             ClientGenesis::DepositContract => {
                 info!(
                     context.log(),
@@ -853,6 +916,29 @@ where
                     // Spawn a routine that tracks the status of the execution engines.
                     execution_layer.spawn_watchdog_routine(beacon_chain.slot_clock.clone());
 
+                    // Forward payload status disagreements between execution engines to the
+                    // event stream.
+                    let mut disagreements = execution_layer.subscribe_engine_disagreements();
+                    let inner_chain = beacon_chain.clone();
+                    runtime_context.executor.spawn(
+                        async move {
+                            loop {
+                                let disagreement = match disagreements.recv().await {
+                                    Ok(disagreement) => disagreement,
+                                    Err(RecvError::Lagged(_)) => continue,
+                                    Err(RecvError::Closed) => break,
+                                };
+                                if let Some(event_handler) = inner_chain.event_handler.as_ref() {
+                                    if event_handler.has_engine_disagreement_subscribers() {
+                                        event_handler
+                                            .register(EventKind::EngineDisagreement(disagreement));
+                                    }
+                                }
+                            }
+                        },
+                        "el_disagreement_events",
+                    );
+
                     // Spawn a routine that removes expired proposer preparations.
                     execution_layer.spawn_clean_proposer_caches_routine::<TSlotClock>(
                         beacon_chain.slot_clock.clone(),
@@ -1069,6 +1155,62 @@ where
         Ok(self)
     }
 
+    /// Imports a finalized deposit tree snapshot into the eth1 service, so that deposit logs are
+    /// only downloaded from the snapshot's execution block onwards.
+    ///
+    /// The snapshot may be imported into an existing database, as long as it does not conflict
+    /// with the deposits which have already been downloaded. A snapshot which is not newer than
+    /// the finalized deposits is ignored with a warning.
+    pub async fn import_deposit_snapshot(
+        self,
+        source: &DepositSnapshotSource,
+    ) -> Result<Self, String> {
+        let eth1_service = self
+            .eth1_service
+            .as_ref()
+            .ok_or("import_deposit_snapshot requires an eth1 service")?;
+        let log = self
+            .runtime_context
+            .as_ref()
+            .ok_or("import_deposit_snapshot requires a runtime_context")?
+            .log();
+
+        let snapshot = match source {
+            DepositSnapshotSource::SszBytes(bytes) => DepositTreeSnapshot::from_ssz_bytes(bytes)
+                .map_err(|e| format!("Unable to parse deposit snapshot SSZ: {:?}", e))?,
+            DepositSnapshotSource::Url(url) => {
+                let remote = BeaconNodeHttpClient::new(
+                    url.clone(),
+                    Timeouts::set_all(DEPOSIT_SNAPSHOT_DOWNLOAD_TIMEOUT),
+                );
+                remote
+                    .get_deposit_snapshot()
+                    .await
+                    .map_err(|e| format!("Error fetching deposit snapshot from remote: {:?}", e))?
+                    .ok_or("Remote beacon node does not have a deposit snapshot")?
+            }
+        };
+
+        match eth1_service.import_deposit_snapshot(&snapshot) {
+            Ok(()) => (),
+            // The snapshot is supplied on every start, so it is expected to be stale once the
+            // deposit cache has caught up with it.
+            Err(Eth1Error::SnapshotNotNewer {
+                snapshot_deposit_count,
+                finalized_deposit_count,
+            }) => warn!(
+                log,
+                "Ignoring deposit snapshot";
+                "reason" => "deposit cache is already finalized beyond the snapshot",
+                "snapshot_deposit_count" => snapshot_deposit_count,
+                "finalized_deposit_count" => finalized_deposit_count,
+            ),
+            Err(e) => return Err(format!("Unable to import deposit snapshot: {:?}", e)),
+        }
+
+        Ok(self)
+    }
+
     /// Do not use any eth1 backend. The client will not be able to produce beacon blocks.
     pub fn no_eth1_backend(mut self) -> Result<Self, String> {
         let beacon_chain_builder = self
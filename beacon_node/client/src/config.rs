@@ -3,6 +3,7 @@ use beacon_chain::TrustedSetup;
 use beacon_processor::BeaconProcessorConfig;
 use directory::DEFAULT_ROOT_DIR;
 use environment::LoggerConfig;
+use lighthouse_network::Multiaddr;
 use network::NetworkConfig;
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
@@ -24,6 +25,12 @@ pub enum ClientGenesis {
         validator_count: usize,
         genesis_time: u64,
     },
+    /// Creates an interop genesis state which is post-merge, with the genesis execution payload
+    /// header of the mock execution layer.
+    InteropMerge {
+        validator_count: usize,
+        genesis_time: u64,
+    },
     /// Reads the genesis state and other persisted data from the `Store`.
     FromStore,
     /// Connects to an eth1 node and waits until it can create the genesis state from the deposit
@@ -39,6 +46,20 @@ pub enum ClientGenesis {
     CheckpointSyncUrl {
         url: SensitiveUrl,
     },
+    /// Downloads the finalized state and block from trusted libp2p peers and verifies them
+    /// against the weak subjectivity checkpoint.
+    CheckpointSyncPeers {
+        peers: Vec<Multiaddr>,
+    },
+}
+
+/// A trusted source of a finalized EIP-4881 deposit tree snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DepositSnapshotSource {
+    /// The SSZ bytes of a snapshot read from disk.
+    SszBytes(Vec<u8>),
+    /// A beacon node which serves the snapshot at `GET /eth/v1/beacon/deposit_snapshot`.
+    Url(SensitiveUrl),
 }
 
 /// The core configuration of a Lighthouse beacon node.
@@ -64,6 +85,12 @@ pub struct Config {
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
     /// via the CLI at runtime, instead of from a configuration file saved to disk.
     pub genesis: ClientGenesis,
+    #[serde(skip)]
+    /// A deposit tree snapshot to import on startup, so that only deposit logs after the snapshot
+    /// need to be downloaded.
+    ///
+    /// Like `genesis`, this is only defined via the CLI at runtime.
+    pub deposit_snapshot: Option<DepositSnapshotSource>,
     pub store: store::StoreConfig,
     pub network: network::NetworkConfig,
     pub chain: beacon_chain::ChainConfig,
@@ -90,6 +117,7 @@ impl Default for Config {
             blobs_db_path: None,
             log_file: PathBuf::from(""),
             genesis: <_>::default(),
+            deposit_snapshot: None,
             store: <_>::default(),
             network: NetworkConfig::default(),
             chain: <_>::default(),
@@ -15,7 +15,7 @@ use std::sync::Arc;
 
 pub use beacon_chain::{BeaconChainTypes, Eth1ChainBackend};
 pub use builder::ClientBuilder;
-pub use config::{ClientGenesis, Config as ClientConfig};
+pub use config::{ClientGenesis, Config as ClientConfig, DepositSnapshotSource};
 pub use eth2_config::Eth2Config;
 
 /// The core "beacon node" client.
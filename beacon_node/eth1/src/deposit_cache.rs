@@ -502,7 +502,7 @@ pub mod tests {
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     ];
 
-    fn example_log() -> DepositLog {
+    pub fn example_log() -> DepositLog {
         let spec = MainnetEthSpec::default_spec();
 
         let log = Log {
@@ -512,7 +512,7 @@ pub mod tests {
         log.to_deposit_log(&spec).expect("should decode log")
     }
 
-    fn get_cache_with_deposits(n: u64) -> DepositCache {
+    pub fn get_cache_with_deposits(n: u64) -> DepositCache {
         let mut deposit_cache = DepositCache::default();
         for i in 0..n {
             let mut log = example_log();
@@ -661,7 +661,10 @@ pub mod tests {
 
     // returns an eth1 block that can be used to finalize the cache at `deposit_index`
     // this will ensure the `deposit_root` on the `Eth1Block` is correct
-    fn fake_eth1_block(deposit_cache: &DepositCache, deposit_index: usize) -> Option<Eth1Block> {
+    pub fn fake_eth1_block(
+        deposit_cache: &DepositCache,
+        deposit_index: usize,
+    ) -> Option<Eth1Block> {
         let deposit_log = deposit_cache.get_log(deposit_index)?;
         Some(Eth1Block {
             hash: Hash256::from_low_u64_be(deposit_log.block_number),
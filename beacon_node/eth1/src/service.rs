@@ -20,7 +20,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Unsigned};
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Hash256, Unsigned};
 
 /// Indicates the default eth1 chain id we use for the deposit contract.
 pub const DEFAULT_CHAIN_ID: Eth1Id = Eth1Id::Goerli;
@@ -223,6 +223,13 @@ pub enum Error {
     FailedToFinalizeDeposit(String),
     /// There was a problem Initializing from deposit snapshot
     FailedToInitializeFromSnapshot(String),
+    /// A deposit snapshot could not be imported into an existing deposit cache.
+    FailedToImportSnapshot(String),
+    /// A deposit snapshot was not imported since it is not newer than the finalized deposits.
+    SnapshotNotNewer {
+        snapshot_deposit_count: u64,
+        finalized_deposit_count: u64,
+    },
 }
 
 /// The success message for an Eth1Data cache update.
@@ -832,6 +839,70 @@ impl Service {
         self.inner.deposit_cache.read().cache.get_deposit_snapshot()
     }
 
+    /// Replaces the deposit cache with a finalized deposit tree `snapshot`, so that deposit logs
+    /// are only downloaded from the snapshot's execution block onwards.
+    ///
+    /// The snapshot is rejected if it conflicts with the deposit root we computed from the deposit
+    /// logs. A snapshot which is not newer than the deposits we have already finalized is rejected
+    /// with `Error::SnapshotNotNewer`, leaving the deposit cache untouched.
+    pub fn import_deposit_snapshot(&self, snapshot: &DepositTreeSnapshot) -> Result<(), Error> {
+        if !snapshot.is_valid() {
+            return Err(Error::FailedToImportSnapshot(
+                "snapshot deposit root does not match its finalized hashes".to_string(),
+            ));
+        }
+
+        let deposit_contract_deploy_block = self.config().deposit_contract_deploy_block;
+        let mut deposits = self.inner.deposit_cache.write();
+
+        if let Some(deposit_root) = deposits.cache.get_root(snapshot.deposit_count as usize) {
+            if *deposit_root != snapshot.deposit_root {
+                return Err(Error::FailedToImportSnapshot(format!(
+                    "snapshot deposit root {:?} conflicts with the local deposit root {:?}",
+                    snapshot.deposit_root, deposit_root
+                )));
+            }
+        }
+        let finalized_deposit_count = deposits.cache.finalized_deposit_count();
+        if snapshot.deposit_count <= finalized_deposit_count {
+            return Err(Error::SnapshotNotNewer {
+                snapshot_deposit_count: snapshot.deposit_count,
+                finalized_deposit_count,
+            });
+        }
+
+        *deposits = DepositUpdater::from_snapshot(deposit_contract_deploy_block, snapshot)
+            .map_err(Error::FailedToImportSnapshot)?;
+
+        metrics::set_gauge(&metrics::DEPOSIT_CACHE_LEN, deposits.cache.len() as i64);
+        metrics::set_gauge(
+            &metrics::HIGHEST_PROCESSED_DEPOSIT_BLOCK,
+            snapshot.execution_block_height as i64,
+        );
+
+        info!(
+            self.log,
+            "Imported deposit tree snapshot";
+            "deposit_count" => snapshot.deposit_count,
+            "execution_block_height" => snapshot.execution_block_height,
+        );
+
+        Ok(())
+    }
+
+    /// Returns the root of the deposit tree when it contained `deposit_count` deposits.
+    ///
+    /// Returns `None` if `deposit_count` is below the finalized deposit count, or if we have not
+    /// yet downloaded that many deposit logs.
+    pub fn deposit_root_at_count(&self, deposit_count: u64) -> Option<Hash256> {
+        self.inner
+            .deposit_cache
+            .read()
+            .cache
+            .get_root(deposit_count as usize)
+            .copied()
+    }
+
     /// Contacts the remote eth1 node and attempts to import deposit logs up to the configured
     /// follow-distance block.
     ///
@@ -913,6 +984,21 @@ impl Service {
              * Step 2. Import logs to cache.
              */
             let mut cache = self.deposits().write();
+            // The deposit cache may have been replaced by a snapshot whilst the logs were being
+            // downloaded, in which case they have already been accounted for.
+            if cache
+                .last_processed_block
+                .map_or(false, |block| block >= block_range.start)
+            {
+                debug!(
+                    self.log,
+                    "Skipping deposit logs before snapshot";
+                    "last_processed_block" => cache.last_processed_block,
+                    "block_range" => ?block_range,
+                );
+                break;
+            }
+
             logs.iter()
                 .map(|raw_log| {
                     raw_log.to_deposit_log(self.inner.spec()).map_err(|error| {
@@ -1249,8 +1335,39 @@ async fn download_eth1_block(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::deposit_cache::{
+        tests::{example_log, fake_eth1_block, get_cache_with_deposits},
+        DepositCache,
+    };
+    use sloggers::{null::NullLoggerBuilder, Build};
     use types::MainnetEthSpec;
 
+    fn service_with_deposits(n: u64) -> Service {
+        let log = NullLoggerBuilder.build().expect("should build logger");
+        let service = Service::new(Config::default(), log, MainnetEthSpec::default_spec())
+            .expect("should create service");
+        *service.deposits().write() = DepositUpdater {
+            cache: get_cache_with_deposits(n),
+            last_processed_block: Some(n - 1),
+        };
+        service
+    }
+
+    /// Returns a snapshot of `deposit_cache` with its first `deposit_count` deposits finalized.
+    fn finalized_snapshot(
+        mut deposit_cache: DepositCache,
+        deposit_count: usize,
+    ) -> DepositTreeSnapshot {
+        let eth1_block =
+            fake_eth1_block(&deposit_cache, deposit_count - 1).expect("should create eth1 block");
+        deposit_cache
+            .finalize(eth1_block)
+            .expect("should finalize deposits");
+        deposit_cache
+            .get_deposit_snapshot()
+            .expect("should have snapshot")
+    }
+
     #[test]
     // Ensures the default config does not panic.
     fn default_config() {
@@ -1283,4 +1400,87 @@ mod tests {
 
         assert!(len > minimum_len as usize);
     }
+
+    #[test]
+    fn import_deposit_snapshot() {
+        let service = service_with_deposits(16);
+
+        let snapshot = finalized_snapshot(get_cache_with_deposits(16), 8);
+        service
+            .import_deposit_snapshot(&snapshot)
+            .expect("should import snapshot");
+
+        let deposits = service.deposits().read();
+        assert_eq!(deposits.cache.finalized_deposit_count(), 8);
+        assert_eq!(
+            deposits.last_processed_block,
+            Some(snapshot.execution_block_height),
+            "logs should be downloaded from the snapshot block onwards"
+        );
+        drop(deposits);
+        assert_eq!(
+            service.deposit_root_at_count(8),
+            Some(snapshot.deposit_root)
+        );
+
+        let older_snapshot = finalized_snapshot(get_cache_with_deposits(16), 4);
+        assert!(
+            matches!(
+                service.import_deposit_snapshot(&older_snapshot),
+                Err(Error::SnapshotNotNewer {
+                    snapshot_deposit_count: 4,
+                    finalized_deposit_count: 8,
+                })
+            ),
+            "should not import a snapshot older than the finalized deposits"
+        );
+    }
+
+    #[test]
+    fn reimport_deposit_snapshot() {
+        let service = service_with_deposits(16);
+
+        let snapshot = finalized_snapshot(get_cache_with_deposits(16), 8);
+        service
+            .import_deposit_snapshot(&snapshot)
+            .expect("should import snapshot");
+        let deposit_cache_len = service.deposits().read().cache.len();
+
+        // The same snapshot is supplied again on the next start.
+        assert!(matches!(
+            service.import_deposit_snapshot(&snapshot),
+            Err(Error::SnapshotNotNewer {
+                snapshot_deposit_count: 8,
+                finalized_deposit_count: 8,
+            })
+        ));
+        let deposits = service.deposits().read();
+        assert_eq!(deposits.cache.finalized_deposit_count(), 8);
+        assert_eq!(deposits.cache.len(), deposit_cache_len);
+    }
+
+    #[test]
+    fn import_conflicting_deposit_snapshot() {
+        let service = service_with_deposits(16);
+        let local_root = service.deposit_root_at_count(8);
+
+        let mut conflicting_cache = DepositCache::default();
+        for i in 0..16 {
+            let mut log = example_log();
+            log.index = i;
+            log.block_number = i;
+            log.deposit_data.withdrawal_credentials = Hash256::repeat_byte(0x42);
+            conflicting_cache
+                .insert_log(log)
+                .expect("should add consecutive logs");
+        }
+        let snapshot = finalized_snapshot(conflicting_cache, 8);
+
+        assert!(
+            service.import_deposit_snapshot(&snapshot).is_err(),
+            "should not import a snapshot which conflicts with the deposit logs"
+        );
+        assert_eq!(service.deposits().read().cache.finalized_deposit_count(), 0);
+        assert_eq!(service.deposit_root_at_count(8), local_root);
+    }
 }
@@ -1,9 +1,9 @@
 use crate::engines::ForkchoiceState;
 use crate::http::{
     ENGINE_FORKCHOICE_UPDATED_V1, ENGINE_FORKCHOICE_UPDATED_V2, ENGINE_FORKCHOICE_UPDATED_V3,
-    ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1, ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
-    ENGINE_GET_PAYLOAD_V1, ENGINE_GET_PAYLOAD_V2, ENGINE_GET_PAYLOAD_V3, ENGINE_NEW_PAYLOAD_V1,
-    ENGINE_NEW_PAYLOAD_V2, ENGINE_NEW_PAYLOAD_V3,
+    ENGINE_GET_BLOBS_V1, ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1,
+    ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1, ENGINE_GET_PAYLOAD_V1, ENGINE_GET_PAYLOAD_V2,
+    ENGINE_GET_PAYLOAD_V3, ENGINE_NEW_PAYLOAD_V1, ENGINE_NEW_PAYLOAD_V2, ENGINE_NEW_PAYLOAD_V3,
 };
 use eth2::types::{
     BlobsBundle, SsePayloadAttributes, SsePayloadAttributesV1, SsePayloadAttributesV2,
@@ -27,13 +27,15 @@ pub use types::{
     Withdrawal, Withdrawals,
 };
 use types::{
-    BeaconStateError, ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadMerge,
-    KzgProofs, VersionedHash,
+    BeaconStateError, Blob, ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadMerge,
+    KzgProof, KzgProofs, VersionedHash,
 };
 
 pub mod auth;
 pub mod http;
+pub mod ipc;
 pub mod json_structures;
+pub mod recorder;
 
 pub const LATEST_TAG: &str = "latest";
 
@@ -42,6 +44,7 @@ pub type PayloadId = [u8; 8];
 #[derive(Debug)]
 pub enum Error {
     HttpClient(PrettyReqwestError),
+    Ipc(String),
     Auth(auth::Error),
     BadResponse(String),
     RequestFailed(String),
@@ -675,6 +678,13 @@ impl<E: EthSpec> TryFrom<ExecutionPayload<E>> for NewPayloadRequest<E> {
     }
 }
 
+/// A blob from the execution engine's blob pool, with the KZG proof for its commitment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlobAndProof<E: EthSpec> {
+    pub blob: Blob<E>,
+    pub proof: KzgProof,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct EngineCapabilities {
     pub new_payload_v1: bool,
@@ -688,6 +698,7 @@ pub struct EngineCapabilities {
     pub get_payload_v1: bool,
     pub get_payload_v2: bool,
     pub get_payload_v3: bool,
+    pub get_blobs_v1: bool,
 }
 
 impl EngineCapabilities {
@@ -726,6 +737,9 @@ impl EngineCapabilities {
         if self.get_payload_v3 {
             response.push(ENGINE_GET_PAYLOAD_V3);
         }
+        if self.get_blobs_v1 {
+            response.push(ENGINE_GET_BLOBS_V1);
+        }
 
         response
     }
@@ -1,13 +1,16 @@
-//! Contains an implementation of `EngineAPI` using the JSON-RPC API via HTTP.
+//! Contains an implementation of `EngineAPI` using the JSON-RPC API via HTTP or IPC.
 
 use super::*;
 use crate::auth::Auth;
 use crate::json_structures::*;
+use crate::recorder::{timestamp_ms, RecordedExchange, TrafficRecorder};
 use reqwest::header::CONTENT_TYPE;
 use sensitive_url::SensitiveUrl;
 use serde::de::DeserializeOwned;
 use serde_json::json;
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use std::time::{Duration, Instant};
@@ -30,6 +33,9 @@ pub const ETH_GET_BLOCK_BY_HASH_TIMEOUT: Duration = Duration::from_secs(1);
 pub const ETH_SYNCING: &str = "eth_syncing";
 pub const ETH_SYNCING_TIMEOUT: Duration = Duration::from_secs(1);
 
+pub const ETH_GET_BALANCE: &str = "eth_getBalance";
+pub const ETH_GET_BALANCE_TIMEOUT: Duration = Duration::from_secs(1);
+
 pub const ENGINE_NEW_PAYLOAD_V1: &str = "engine_newPayloadV1";
 pub const ENGINE_NEW_PAYLOAD_V2: &str = "engine_newPayloadV2";
 pub const ENGINE_NEW_PAYLOAD_V3: &str = "engine_newPayloadV3";
@@ -49,6 +55,9 @@ pub const ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1: &str = "engine_getPayloadBodiesB
 pub const ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1: &str = "engine_getPayloadBodiesByRangeV1";
 pub const ENGINE_GET_PAYLOAD_BODIES_TIMEOUT: Duration = Duration::from_secs(10);
 
+pub const ENGINE_GET_BLOBS_V1: &str = "engine_getBlobsV1";
+pub const ENGINE_GET_BLOBS_TIMEOUT: Duration = Duration::from_secs(1);
+
 pub const ENGINE_EXCHANGE_CAPABILITIES: &str = "engine_exchangeCapabilities";
 pub const ENGINE_EXCHANGE_CAPABILITIES_TIMEOUT: Duration = Duration::from_secs(1);
 
@@ -70,6 +79,7 @@ pub static LIGHTHOUSE_CAPABILITIES: &[&str] = &[
     ENGINE_FORKCHOICE_UPDATED_V3,
     ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1,
     ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
+    ENGINE_GET_BLOBS_V1,
 ];
 
 /// This is necessary because a user might run a capella-enabled version of
@@ -87,6 +97,7 @@ pub static PRE_CAPELLA_ENGINE_CAPABILITIES: EngineCapabilities = EngineCapabilit
     get_payload_v1: true,
     get_payload_v2: false,
     get_payload_v3: false,
+    get_blobs_v1: false,
 };
 
 /// Contains methods to convert arbitrary bytes to an ETH2 deposit contract object.
@@ -598,6 +609,10 @@ pub struct HttpJsonRpc {
     pub execution_timeout_multiplier: u32,
     pub engine_capabilities_cache: Mutex<Option<CapabilitiesCacheEntry>>,
     auth: Option<Auth>,
+    /// The socket path if `url` is an IPC endpoint, in which case requests are not sent via HTTP.
+    ipc_path: Option<PathBuf>,
+    /// Records every request and response, if enabled.
+    recorder: Option<Arc<TrafficRecorder>>,
 }
 
 impl HttpJsonRpc {
@@ -607,10 +622,12 @@ impl HttpJsonRpc {
     ) -> Result<Self, Error> {
         Ok(Self {
             client: Client::builder().build()?,
+            ipc_path: ipc::ipc_path(&url),
             url,
             execution_timeout_multiplier: execution_timeout_multiplier.unwrap_or(1),
             engine_capabilities_cache: Mutex::new(None),
             auth: None,
+            recorder: None,
         })
     }
 
@@ -621,13 +638,21 @@ impl HttpJsonRpc {
     ) -> Result<Self, Error> {
         Ok(Self {
             client: Client::builder().build()?,
+            ipc_path: ipc::ipc_path(&url),
             url,
             execution_timeout_multiplier: execution_timeout_multiplier.unwrap_or(1),
             engine_capabilities_cache: Mutex::new(None),
             auth: Some(auth),
+            recorder: None,
         })
     }
 
+    /// Record all requests and responses to `recorder`.
+    pub fn with_recorder(mut self, recorder: Arc<TrafficRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     pub async fn rpc_request<D: DeserializeOwned>(
         &self,
         method: &str,
@@ -641,20 +666,53 @@ impl HttpJsonRpc {
             id: json!(STATIC_ID),
         };
 
+        let timestamp_ms = timestamp_ms();
+        let start = Instant::now();
+        let response = self.send_request(&body, timeout).await;
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record(RecordedExchange {
+                timestamp_ms,
+                endpoint: self.url.to_string(),
+                method: method.to_string(),
+                params: body.params,
+                duration_ms: start.elapsed().as_millis() as u64,
+                response: response.as_ref().ok().cloned(),
+                error: response.as_ref().err().map(|e| format!("{:?}", e)),
+            });
+        }
+
+        Self::parse_response(response?)
+    }
+
+    async fn send_request(
+        &self,
+        body: &JsonRequestBody<'_>,
+        timeout: Duration,
+    ) -> Result<JsonResponseBody, Error> {
+        // IPC sockets are protected by file permissions rather than JWT auth.
+        if let Some(path) = &self.ipc_path {
+            return ipc::request(path, body, timeout).await;
+        }
+
         let mut request = self
             .client
             .post(self.url.full.clone())
             .timeout(timeout)
             .header(CONTENT_TYPE, "application/json")
-            .json(&body);
+            .json(body);
 
         // Generate and add a jwt token to the header if auth is defined.
         if let Some(auth) = &self.auth {
             request = request.bearer_auth(auth.generate_token()?);
         };
 
-        let body: JsonResponseBody = request.send().await?.error_for_status()?.json().await?;
+        let body = request.send().await?.error_for_status()?.json().await?;
 
+        Ok(body)
+    }
+
+    fn parse_response<D: DeserializeOwned>(body: JsonResponseBody) -> Result<D, Error> {
         match (body.result, body.error) {
             (result, None) => serde_json::from_value(result).map_err(Into::into),
             (_, Some(error)) => {
@@ -727,6 +785,25 @@ impl HttpJsonRpc {
         .await
     }
 
+    /// Returns the balance of `address` in the state after the execution block `block_hash`.
+    pub async fn get_balance(
+        &self,
+        address: Address,
+        block_hash: ExecutionBlockHash,
+    ) -> Result<Uint256, Error> {
+        let params = json!([address, { "blockHash": block_hash }]);
+
+        let balance: JsonBalance = self
+            .rpc_request(
+                ETH_GET_BALANCE,
+                params,
+                ETH_GET_BALANCE_TIMEOUT * self.execution_timeout_multiplier,
+            )
+            .await?;
+
+        Ok(balance.0)
+    }
+
     pub async fn get_block_by_hash_with_txns<T: EthSpec>(
         &self,
         block_hash: ExecutionBlockHash,
@@ -1010,6 +1087,26 @@ impl HttpJsonRpc {
             .collect())
     }
 
+    pub async fn get_blobs_v1<E: EthSpec>(
+        &self,
+        versioned_hashes: Vec<VersionedHash>,
+    ) -> Result<Vec<Option<BlobAndProof<E>>>, Error> {
+        let params = json!([versioned_hashes]);
+
+        let response: Vec<Option<JsonBlobAndProofV1<E>>> = self
+            .rpc_request(
+                ENGINE_GET_BLOBS_V1,
+                params,
+                ENGINE_GET_BLOBS_TIMEOUT * self.execution_timeout_multiplier,
+            )
+            .await?;
+
+        Ok(response
+            .into_iter()
+            .map(|opt_json| opt_json.map(From::from))
+            .collect())
+    }
+
     pub async fn exchange_capabilities(&self) -> Result<EngineCapabilities, Error> {
         let params = json!([LIGHTHOUSE_CAPABILITIES]);
 
@@ -1043,6 +1140,7 @@ impl HttpJsonRpc {
                 get_payload_v1: capabilities.contains(ENGINE_GET_PAYLOAD_V1),
                 get_payload_v2: capabilities.contains(ENGINE_GET_PAYLOAD_V2),
                 get_payload_v3: capabilities.contains(ENGINE_GET_PAYLOAD_V3),
+                get_blobs_v1: capabilities.contains(ENGINE_GET_BLOBS_V1),
             }),
         }
     }
@@ -1187,21 +1285,42 @@ impl HttpJsonRpc {
 mod test {
     use super::auth::JwtKey;
     use super::*;
-    use crate::test_utils::{MockServer, DEFAULT_JWT_SECRET};
+    use crate::test_utils::{Config, MockExecutionConfig, MockServer, DEFAULT_JWT_SECRET};
     use std::future::Future;
     use std::str::FromStr;
     use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::runtime;
     use types::{ExecutionPayloadMerge, MainnetEthSpec, Transactions, Unsigned, VariableList};
 
-    struct Tester {
-        server: MockServer<MainnetEthSpec>,
+    /// Clients for a single transport.
+    struct Clients {
         rpc_client: Arc<HttpJsonRpc>,
         echo_client: Arc<HttpJsonRpc>,
     }
 
+    struct Tester {
+        server: MockServer<MainnetEthSpec>,
+        /// HTTP clients, followed by IPC clients on platforms which support IPC.
+        transports: Vec<Clients>,
+        _ipc_dir: TempDir,
+    }
+
     impl Tester {
         pub fn new(with_auth: bool) -> Self {
-            let server = MockServer::unit_testing();
+            let ipc_dir = TempDir::new().unwrap();
+            let server = MockServer::new_with_config(
+                &runtime::Handle::current(),
+                MockExecutionConfig {
+                    server_config: Config {
+                        ipc_path: cfg!(unix).then(|| ipc_dir.path().join("engine.ipc")),
+                        ..Config::default()
+                    },
+                    jwt_key: JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap(),
+                    ..MockExecutionConfig::default()
+                },
+                None,
+            );
 
             let rpc_url = SensitiveUrl::parse(&server.url()).unwrap();
             let echo_url = SensitiveUrl::parse(&format!("{}/echo", server.url())).unwrap();
@@ -1221,11 +1340,25 @@ mod test {
                     Arc::new(HttpJsonRpc::new(echo_url, None).unwrap()),
                 )
             };
+            let mut transports = vec![Clients {
+                rpc_client,
+                echo_client,
+            }];
+
+            // IPC does not use auth, so the same clients are used regardless of `with_auth`.
+            if let (Some(ipc_url), Some(ipc_echo_url)) = (server.ipc_url(), server.ipc_echo_url()) {
+                let rpc_url = SensitiveUrl::parse(&ipc_url).unwrap();
+                let echo_url = SensitiveUrl::parse(&ipc_echo_url).unwrap();
+                transports.push(Clients {
+                    rpc_client: Arc::new(HttpJsonRpc::new(rpc_url, None).unwrap()),
+                    echo_client: Arc::new(HttpJsonRpc::new(echo_url, None).unwrap()),
+                });
+            }
 
             Self {
                 server,
-                rpc_client,
-                echo_client,
+                transports,
+                _ipc_dir: ipc_dir,
             }
         }
 
@@ -1238,15 +1371,17 @@ mod test {
             R: Fn(Arc<HttpJsonRpc>) -> F,
             F: Future<Output = ()>,
         {
-            request_func(self.echo_client.clone()).await;
-            let request_bytes = self.server.last_echo_request();
-            let request_json: serde_json::Value =
-                serde_json::from_slice(&request_bytes).expect("request was not valid json");
-            if request_json != expected_json {
-                panic!(
-                    "json mismatch!\n\nobserved: {}\n\nexpected: {}\n\n",
-                    request_json, expected_json,
-                )
+            for clients in &self.transports {
+                request_func(clients.echo_client.clone()).await;
+                let request_bytes = self.server.last_echo_request();
+                let request_json: serde_json::Value =
+                    serde_json::from_slice(&request_bytes).expect("request was not valid json");
+                if request_json != expected_json {
+                    panic!(
+                        "json mismatch for {}!\n\nobserved: {}\n\nexpected: {}\n\n",
+                        clients.echo_client, request_json, expected_json,
+                    )
+                }
             }
             self
         }
@@ -1257,7 +1392,8 @@ mod test {
             F: Future<Output = Result<T, Error>>,
             T: std::fmt::Debug,
         {
-            let res = request_func(self.echo_client.clone()).await;
+            // Only HTTP uses auth.
+            let res = request_func(self.transports[0].echo_client.clone()).await;
             if !matches!(res, Err(Error::Auth(_))) {
                 panic!(
                     "No authentication provided, rpc call should have failed.\nResult: {:?}",
@@ -1276,10 +1412,12 @@ mod test {
             R: Fn(Arc<HttpJsonRpc>) -> F,
             F: Future<Output = ()>,
         {
-            for response in preloaded_responses {
-                self.server.push_preloaded_response(response);
+            for clients in &self.transports {
+                for response in preloaded_responses.clone() {
+                    self.server.push_preloaded_response(response);
+                }
+                request_func(clients.rpc_client.clone()).await;
             }
-            request_func(self.rpc_client.clone()).await;
             self
         }
     }
@@ -1467,6 +1605,68 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn get_blobs_v1_request() {
+        Tester::new(true)
+            .assert_request_equals(
+                |client| async move {
+                    let _ = client
+                        .get_blobs_v1::<MainnetEthSpec>(vec![
+                            Hash256::repeat_byte(0),
+                            Hash256::repeat_byte(1),
+                        ])
+                        .await;
+                },
+                json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "method": ENGINE_GET_BLOBS_V1,
+                    "params": [[HASH_00, HASH_01]]
+                }),
+            )
+            .await;
+
+        Tester::new(false)
+            .assert_auth_failure(|client| async move {
+                client
+                    .get_blobs_v1::<MainnetEthSpec>(vec![Hash256::repeat_byte(1)])
+                    .await
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn get_blobs_v1_response() {
+        let blob_and_proof = BlobAndProof::<MainnetEthSpec> {
+            blob: Blob::<MainnetEthSpec>::default(),
+            proof: KzgProof::empty(),
+        };
+        let expected = vec![Some(blob_and_proof.clone()), None];
+
+        Tester::new(true)
+            .with_preloaded_responses(
+                vec![json!({
+                    "id": STATIC_ID,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "result": [JsonBlobAndProofV1::from(blob_and_proof), JSON_NULL]
+                })],
+                |client| {
+                    let expected = expected.clone();
+                    async move {
+                        let response = client
+                            .get_blobs_v1::<MainnetEthSpec>(vec![
+                                Hash256::repeat_byte(0),
+                                Hash256::repeat_byte(1),
+                            ])
+                            .await
+                            .unwrap();
+                        assert_eq!(response, expected);
+                    }
+                },
+            )
+            .await;
+    }
+
     #[tokio::test]
     async fn forkchoice_updated_v1_with_payload_attributes_request() {
         Tester::new(true)
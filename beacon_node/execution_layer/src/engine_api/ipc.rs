@@ -0,0 +1,243 @@
+//! Contains the transport used to send JSON-RPC requests to an execution engine via IPC (a Unix
+//! domain socket).
+//!
+//! IPC endpoints are specified with the `ipc` URL scheme, e.g. `ipc:///path/to/engine.ipc`. Access
+//! to the socket is controlled by its file permissions, so no JWT is sent.
+
+use super::Error;
+use crate::json_structures::{JsonRequestBody, JsonResponseBody};
+use sensitive_url::SensitiveUrl;
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// The URL scheme which indicates an IPC endpoint.
+pub const IPC_SCHEME: &str = "ipc";
+
+/// The minimum number of bytes read from the socket at a time.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Returns the path of the socket if `url` is an IPC endpoint.
+pub fn ipc_path(url: &SensitiveUrl) -> Option<PathBuf> {
+    (url.full.scheme() == IPC_SCHEME).then(|| PathBuf::from(url.full.path()))
+}
+
+/// Sends `body` to the engine listening on the socket at `path` and reads a single response.
+#[cfg(unix)]
+pub async fn request(
+    path: &Path,
+    body: &JsonRequestBody<'_>,
+    timeout: Duration,
+) -> Result<JsonResponseBody, Error> {
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::UnixStream;
+
+    let exchange = async {
+        let mut stream = UnixStream::connect(path)
+            .await
+            .map_err(|e| Error::Ipc(format!("Unable to connect to {}: {}", path.display(), e)))?;
+        stream
+            .write_all(&serde_json::to_vec(body)?)
+            .await
+            .map_err(|e| Error::Ipc(format!("Unable to write request: {}", e)))?;
+
+        JsonMessageReader::new(stream)
+            .next()
+            .await?
+            .ok_or_else(|| Error::Ipc("Connection closed without a response".to_string()))
+    };
+
+    tokio::time::timeout(timeout, exchange)
+        .await
+        .map_err(|_| Error::Ipc(format!("Request to {} timed out", path.display())))?
+}
+
+#[cfg(not(unix))]
+pub async fn request(
+    _path: &Path,
+    _body: &JsonRequestBody<'_>,
+    _timeout: Duration,
+) -> Result<JsonResponseBody, Error> {
+    Err(Error::Ipc(
+        "IPC endpoints are only supported on Unix platforms".to_string(),
+    ))
+}
+
+/// Reads consecutive JSON values from a byte stream.
+///
+/// IPC messages are not delimited, so the end of each message is found by tracking the nesting
+/// of objects and arrays as bytes arrive. Messages must be JSON objects or arrays, as JSON-RPC
+/// responses are.
+pub struct JsonMessageReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    scanner: MessageScanner,
+}
+
+impl<R: AsyncRead + Unpin> JsonMessageReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: vec![],
+            scanner: MessageScanner::default(),
+        }
+    }
+
+    /// Returns the next message, or `None` if the stream was closed between messages.
+    pub async fn next<T: DeserializeOwned>(&mut self) -> Result<Option<T>, Error> {
+        loop {
+            if let Some(len) = self.scanner.scan(&self.buf)? {
+                let value = serde_json::from_slice(&self.buf[..len])?;
+                self.buf.drain(..len);
+                return Ok(Some(value));
+            }
+
+            self.buf.reserve(READ_CHUNK_SIZE);
+            let read = self
+                .reader
+                .read_buf(&mut self.buf)
+                .await
+                .map_err(|e| Error::Ipc(format!("Unable to read from socket: {}", e)))?;
+            if read == 0 {
+                return if self.buf.iter().all(u8::is_ascii_whitespace) {
+                    Ok(None)
+                } else {
+                    Err(Error::Ipc("Connection closed mid-message".to_string()))
+                };
+            }
+        }
+    }
+}
+
+/// Finds the end of the first message in a buffer, resuming from where the previous scan of the
+/// same buffer stopped so that each byte is only scanned once.
+#[derive(Default)]
+struct MessageScanner {
+    /// The number of bytes of the buffer which have been scanned.
+    offset: usize,
+    /// The number of objects and arrays which are open at `offset`.
+    depth: usize,
+    in_string: bool,
+    /// Whether the previous byte in a string was an unescaped backslash.
+    escaped: bool,
+}
+
+impl MessageScanner {
+    /// Returns the length of the first message in `buf` once it is complete.
+    ///
+    /// The scanner is reset once a message is found, so the message must then be removed from
+    /// the front of `buf`.
+    fn scan(&mut self, buf: &[u8]) -> Result<Option<usize>, Error> {
+        while let Some(&byte) = buf.get(self.offset) {
+            self.offset += 1;
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' if self.depth > 0 => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' if self.depth > 0 => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        let len = self.offset;
+                        *self = Self::default();
+                        return Ok(Some(len));
+                    }
+                }
+                byte if self.depth == 0 && !byte.is_ascii_whitespace() => {
+                    return Err(Error::Ipc(format!(
+                        "Expected a JSON object or array, found {:?}",
+                        char::from(byte)
+                    )));
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn reads_concatenated_and_split_messages() {
+        // Split the second message across two reads to check that partial messages are buffered.
+        let first = &br#"{"id":1}  {"id":[2"#[..];
+        let second = &br#",3]}
+{"id":"four"}"#[..];
+        let mut reader = JsonMessageReader::new(first.chain(second));
+
+        assert_eq!(
+            reader.next::<Value>().await.unwrap(),
+            Some(json!({"id": 1}))
+        );
+        assert_eq!(
+            reader.next::<Value>().await.unwrap(),
+            Some(json!({"id": [2, 3]}))
+        );
+        assert_eq!(
+            reader.next::<Value>().await.unwrap(),
+            Some(json!({"id": "four"}))
+        );
+        assert_eq!(reader.next::<Value>().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn ignores_brackets_in_strings() {
+        let messages = &br#"{"result":"}]\"{[","id":1}{"result":["\\"]}"#[..];
+        let mut reader = JsonMessageReader::new(messages);
+
+        assert_eq!(
+            reader.next::<Value>().await.unwrap(),
+            Some(json!({"result": "}]\"{[", "id": 1}))
+        );
+        assert_eq!(
+            reader.next::<Value>().await.unwrap(),
+            Some(json!({"result": ["\\"]}))
+        );
+        assert_eq!(reader.next::<Value>().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn large_message_is_read_in_chunks() {
+        let value = json!({"result": "a".repeat(READ_CHUNK_SIZE * 4)});
+        let bytes = serde_json::to_vec(&value).unwrap();
+        let (first, second) = bytes.split_at(READ_CHUNK_SIZE + 1);
+        let mut reader = JsonMessageReader::new(first.chain(second));
+
+        assert_eq!(reader.next::<Value>().await.unwrap(), Some(value));
+        assert_eq!(reader.next::<Value>().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn non_container_message_is_an_error() {
+        let mut reader = JsonMessageReader::new(&b"42"[..]);
+        assert!(matches!(reader.next::<Value>().await, Err(Error::Ipc(_))));
+    }
+
+    #[tokio::test]
+    async fn truncated_message_is_an_error() {
+        let mut reader = JsonMessageReader::new(&br#"{"id":1"#[..]);
+        assert!(matches!(reader.next::<Value>().await, Err(Error::Ipc(_))));
+    }
+
+    #[test]
+    fn ipc_urls() {
+        let url = SensitiveUrl::parse("ipc:///tmp/engine.ipc").unwrap();
+        assert_eq!(ipc_path(&url), Some(PathBuf::from("/tmp/engine.ipc")));
+        let url = SensitiveUrl::parse("http://localhost:8551").unwrap();
+        assert_eq!(ipc_path(&url), None);
+    }
+}
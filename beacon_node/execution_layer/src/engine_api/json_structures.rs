@@ -18,13 +18,13 @@ pub struct JsonRequestBody<'a> {
     pub id: serde_json::Value,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct JsonError {
     pub code: i64,
     pub message: String,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonResponseBody {
     pub jsonrpc: String,
@@ -463,6 +463,32 @@ impl<E: EthSpec> From<JsonBlobsBundleV1<E>> for BlobsBundle<E> {
     }
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec", rename_all = "camelCase")]
+pub struct JsonBlobAndProofV1<E: EthSpec> {
+    #[serde(with = "ssz_types::serde_utils::hex_fixed_vec")]
+    pub blob: Blob<E>,
+    pub proof: KzgProof,
+}
+
+impl<E: EthSpec> From<BlobAndProof<E>> for JsonBlobAndProofV1<E> {
+    fn from(blob_and_proof: BlobAndProof<E>) -> Self {
+        Self {
+            blob: blob_and_proof.blob,
+            proof: blob_and_proof.proof,
+        }
+    }
+}
+
+impl<E: EthSpec> From<JsonBlobAndProofV1<E>> for BlobAndProof<E> {
+    fn from(json_blob_and_proof: JsonBlobAndProofV1<E>) -> Self {
+        Self {
+            blob: json_blob_and_proof.blob,
+            proof: json_blob_and_proof.proof,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonForkchoiceStateV1 {
@@ -651,6 +677,11 @@ pub struct TransitionConfigurationV1 {
     pub terminal_block_number: u64,
 }
 
+/// The response to an `eth_getBalance` request.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonBalance(#[serde(with = "serde_utils::u256_hex_be")] pub Uint256);
+
 /// Serializes the `logs_bloom` field of an `ExecutionPayload`.
 pub mod serde_logs_bloom {
     use super::*;
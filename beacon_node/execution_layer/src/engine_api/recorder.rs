@@ -0,0 +1,282 @@
+//! Records engine API traffic to disk so that interop issues can be reproduced offline.
+//!
+//! Each request and its response is written as a single line of JSON (a `RecordedExchange`) to
+//! `engine_traffic.jsonl` in the recording directory. Once that file exceeds `max_file_size` it is
+//! rotated to `engine_traffic.jsonl.1`, with older files shifted up to `engine_traffic.jsonl.N`.
+//!
+//! Exchanges are written by a dedicated thread so that requests to the engine never wait on disk
+//! IO.
+//!
+//! A recording can be served by `test_utils::MockServer` to replay an execution engine session.
+
+use crate::json_structures::JsonResponseBody;
+use crate::metrics;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The name of the file currently being written to.
+pub const RECORDING_FILE_NAME: &str = "engine_traffic.jsonl";
+/// The default size at which the recording file is rotated.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+/// The default number of rotated files to keep, in addition to the current file.
+pub const DEFAULT_MAX_ROTATED_FILES: usize = 9;
+/// The number of exchanges which may be waiting to be written before further exchanges are
+/// dropped.
+const WRITE_QUEUE_CAPACITY: usize = 1_024;
+
+/// A single engine API request and the response (or error) it received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    /// Milliseconds since the UNIX epoch at which the request was sent.
+    pub timestamp_ms: u64,
+    /// The engine's URL, with any credentials redacted.
+    pub endpoint: String,
+    pub method: String,
+    pub params: serde_json::Value,
+    /// The time taken to receive a response or error.
+    pub duration_ms: u64,
+    /// The response body, if one was received.
+    pub response: Option<JsonResponseBody>,
+    /// The transport error, if no response was received.
+    pub error: Option<String>,
+}
+
+impl RecordedExchange {
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_ms)
+    }
+}
+
+enum Message {
+    Record(Box<RecordedExchange>),
+    /// Acknowledge once every previously recorded exchange has been written.
+    Flush(mpsc::Sender<()>),
+}
+
+/// Queues `RecordedExchange`s to be written to a rotating set of JSONL files.
+pub struct TrafficRecorder {
+    path: PathBuf,
+    tx: Mutex<mpsc::SyncSender<Message>>,
+}
+
+impl TrafficRecorder {
+    /// Start recording to `dir`, creating it if necessary and appending to any existing recording.
+    ///
+    /// The writer thread exits once the recorder is dropped.
+    pub fn new(dir: PathBuf, max_file_size: u64, max_rotated_files: usize) -> io::Result<Self> {
+        let mut writer = RecordingWriter::new(dir, max_file_size, max_rotated_files)?;
+        let path = writer.path();
+        let (tx, rx) = mpsc::sync_channel(WRITE_QUEUE_CAPACITY);
+        thread::Builder::new()
+            .name("engine_traffic_recorder".to_string())
+            .spawn(move || {
+                while let Ok(message) = rx.recv() {
+                    match message {
+                        Message::Record(exchange) => {
+                            if writer.write(&exchange).is_err() {
+                                metrics::inc_counter(
+                                    &metrics::EXECUTION_LAYER_TRAFFIC_RECORDING_ERRORS,
+                                );
+                            }
+                        }
+                        Message::Flush(ack) => {
+                            let _ = ack.send(());
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            path,
+            tx: Mutex::new(tx),
+        })
+    }
+
+    /// Returns the path of the file currently being written to.
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    /// Queue `exchange` to be appended to the recording.
+    ///
+    /// Recording is best-effort and never blocks: failures, including exchanges dropped because
+    /// the writer has fallen behind, are counted in a metric rather than affecting the request
+    /// being recorded.
+    pub fn record(&self, exchange: RecordedExchange) {
+        if self
+            .tx
+            .lock()
+            .try_send(Message::Record(Box::new(exchange)))
+            .is_err()
+        {
+            metrics::inc_counter(&metrics::EXECUTION_LAYER_TRAFFIC_RECORDING_ERRORS);
+        }
+    }
+
+    /// Blocks until every exchange queued by `Self::record` has been written.
+    pub fn flush(&self) {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        let sender = self.tx.lock().clone();
+        if sender.send(Message::Flush(ack_tx)).is_ok() {
+            let _ = ack_rx.recv();
+        }
+    }
+}
+
+/// Writes `RecordedExchange`s to a rotating set of JSONL files.
+struct RecordingWriter {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_rotated_files: usize,
+    /// The file currently being written to.
+    file: File,
+    /// The number of bytes in `file`.
+    size: u64,
+}
+
+impl RecordingWriter {
+    fn new(dir: PathBuf, max_file_size: u64, max_rotated_files: usize) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let file = Self::open(&dir)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            max_file_size,
+            max_rotated_files,
+            file,
+            size,
+        })
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(RECORDING_FILE_NAME)
+    }
+
+    fn open(dir: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(RECORDING_FILE_NAME))
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.{}", RECORDING_FILE_NAME, index))
+    }
+
+    fn write(&mut self, exchange: &RecordedExchange) -> io::Result<()> {
+        let mut line = serde_json::to_vec(exchange)?;
+        line.push(b'\n');
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_file_size {
+            self.rotate()?;
+            self.file = Self::open(&self.dir)?;
+            self.size = 0;
+        }
+        self.file.write_all(&line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift each rotated file up by one, discarding the oldest, and move the current file to
+    /// `.1`.
+    fn rotate(&self) -> io::Result<()> {
+        if self.max_rotated_files == 0 {
+            return std::fs::remove_file(self.path());
+        }
+        for index in (1..self.max_rotated_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                std::fs::rename(from, self.rotated_path(index + 1))?;
+            }
+        }
+        std::fs::rename(self.path(), self.rotated_path(1))
+    }
+}
+
+/// Returns the number of milliseconds since the UNIX epoch.
+pub fn timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// Read all exchanges from the recording at `path`.
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedExchange>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Unable to open recording {}: {}", path.display(), e))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(i, line)| {
+            let line = line.map_err(|e| format!("Unable to read recording: {}", e))?;
+            serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid exchange on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn exchange(i: u64) -> RecordedExchange {
+        RecordedExchange {
+            timestamp_ms: i,
+            endpoint: "http://localhost:8551/".to_string(),
+            method: "eth_syncing".to_string(),
+            params: json!([]),
+            duration_ms: 1,
+            response: Some(JsonResponseBody {
+                jsonrpc: "2.0".to_string(),
+                error: None,
+                result: json!(false),
+                id: json!(1),
+            }),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn records_and_reads() {
+        let dir = TempDir::new().unwrap();
+        let recorder = TrafficRecorder::new(dir.path().into(), u64::MAX, 1).unwrap();
+        for i in 0..3 {
+            recorder.record(exchange(i));
+        }
+        recorder.flush();
+        let recording = read_recording(&recorder.path()).unwrap();
+        assert_eq!(recording, (0..3).map(exchange).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rotates_files() {
+        let dir = TempDir::new().unwrap();
+        let line_len = serde_json::to_vec(&exchange(0)).unwrap().len() as u64 + 1;
+        // Allow two exchanges per file and keep two rotated files.
+        let mut writer = RecordingWriter::new(dir.path().into(), line_len * 2, 2).unwrap();
+        for i in 0..7 {
+            writer.write(&exchange(i)).unwrap();
+        }
+
+        let read = |path: PathBuf| {
+            read_recording(&path)
+                .unwrap()
+                .into_iter()
+                .map(|exchange| exchange.timestamp_ms)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(read(writer.path()), vec![6]);
+        assert_eq!(read(writer.rotated_path(1)), vec![4, 5]);
+        assert_eq!(read(writer.rotated_path(2)), vec![2, 3]);
+        assert!(!writer.rotated_path(3).exists());
+    }
+}
@@ -7,20 +7,25 @@
 use crate::payload_cache::PayloadCache;
 use arc_swap::ArcSwapOption;
 use auth::{strip_prefix, Auth, JwtKey};
-use builder_client::BuilderHttpClient;
+use engine_api::recorder::{self, TrafficRecorder};
 pub use engine_api::EngineCapabilities;
 use engine_api::Error as ApiError;
 pub use engine_api::*;
 pub use engine_api::{http, http::deposit_methods, http::HttpJsonRpc};
 use engines::{Engine, EngineError};
 pub use engines::{EngineState, ForkchoiceState};
+use eth2::lighthouse::{EngineDisagreement, EngineStatusVote};
 use eth2::types::FullPayloadContents;
 use eth2::types::{builder_bid::SignedBuilderBid, BlobsBundle, ForkVersionedResponse};
 use ethers_core::types::Transaction as EthersTransaction;
 use fork_choice::ForkchoiceUpdateParameters;
+use futures::future::join_all;
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
 use lru::LruCache;
-use payload_status::process_payload_status;
-pub use payload_status::PayloadStatus;
+use payload_status::{decided_outcome, process_payload_status, select_payload_status, StatusVote};
+pub use payload_status::{PayloadStatus, PayloadStatusPolicy};
+use relays::{RelayBid, RelayFault, Relays};
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -30,13 +35,13 @@ use std::fmt;
 use std::future::Future;
 use std::io::Write;
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::AsRefStr;
 use task_executor::TaskExecutor;
 use tokio::{
-    sync::{Mutex, MutexGuard, RwLock},
+    sync::{broadcast, Mutex, MutexGuard, RwLock},
     time::sleep,
 };
 use tokio_stream::wrappers::WatchStream;
@@ -47,6 +52,7 @@ use types::non_zero_usize::new_non_zero_usize;
 use types::payload::BlockProductionVersion;
 use types::{
     AbstractExecPayload, BlobsList, ExecutionPayloadDeneb, KzgProofs, SignedBlindedBeaconBlock,
+    VersionedHash,
 };
 use types::{
     BeaconStateError, BlindedPayload, ChainSpec, Epoch, ExecPayload, ExecutionPayloadCapella,
@@ -60,6 +66,7 @@ mod keccak;
 mod metrics;
 pub mod payload_cache;
 mod payload_status;
+pub mod relays;
 pub mod test_utils;
 
 /// Indicates the default jwt authenticated execution endpoint.
@@ -72,6 +79,9 @@ pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 
+/// Number of engine disagreement notifications buffered for slow subscribers.
+const ENGINE_DISAGREEMENT_CHANNEL_CAPACITY: usize = 16;
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -135,12 +145,24 @@ pub enum Error {
         transactions_root: Hash256,
     },
     InvalidJWTSecret(String),
+    TrafficRecorder(String),
     InvalidForkForPayload,
     InvalidPayloadBody(String),
     InvalidPayloadConversion,
     InvalidBlobConversion(String),
+    GetBlobsNotSupported,
     BeaconStateError(BeaconStateError),
     PayloadTypeMismatch,
+    BuilderPayloadMismatch {
+        expected: Hash256,
+        received: Hash256,
+    },
+    BuilderBlobsMismatch {
+        expected: usize,
+    },
+    /// The relay which supplied the bid for a blinded block is not known, so the block is not
+    /// revealed to any relay.
+    UnknownWinningRelay(ExecutionBlockHash),
 }
 
 impl From<BeaconStateError> for Error {
@@ -301,6 +323,14 @@ pub struct Proposer {
     payload_attributes: PayloadAttributes,
 }
 
+/// The change in the balance of a fee recipient across an execution block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeRecipientBalanceChange {
+    Increased(Uint256),
+    /// The fee recipient spent more than it was paid, e.g. by sending transactions in the block.
+    Decreased(Uint256),
+}
+
 /// Information from the beacon chain that is necessary for querying the builder API.
 pub struct BuilderParams {
     pub pubkey: PublicKeyBytes,
@@ -326,8 +356,16 @@ pub enum FailedCondition {
 type PayloadContentsRefTuple<'a, T> = (ExecutionPayloadRef<'a, T>, Option<&'a BlobsBundle<T>>);
 
 struct Inner<E: EthSpec> {
+    /// The first configured execution engine.
     engine: Arc<Engine>,
-    builder: ArcSwapOption<BuilderHttpClient>,
+    /// Any additional execution engines, which receive every `newPayload` and
+    /// `forkchoiceUpdated` call alongside `engine`.
+    redundant_engines: Vec<Arc<Engine>>,
+    /// How the payload statuses of all engines are combined.
+    payload_status_policy: PayloadStatusPolicy,
+    /// Notifies subscribers when the engines return conflicting payload statuses.
+    engine_disagreement_tx: broadcast::Sender<EngineDisagreement>,
+    builder: ArcSwapOption<Relays>,
     execution_engine_forkchoice_lock: Mutex<()>,
     suggested_fee_recipient: Option<Address>,
     proposer_preparation_data: Mutex<HashMap<u64, ProposerPreparationDataEntry>>,
@@ -348,11 +386,19 @@ pub struct Config {
     /// Endpoint urls for EL nodes that are running the engine api.
     pub execution_endpoints: Vec<SensitiveUrl>,
     /// Endpoint urls for services providing the builder api.
-    pub builder_url: Option<SensitiveUrl>,
+    pub builder_urls: Vec<SensitiveUrl>,
     /// User agent to send with requests to the builder API.
     pub builder_user_agent: Option<String>,
+    /// Timeout applied to requests for a bid from each builder.
+    pub builder_header_timeout: Option<Duration>,
     /// JWT secrets for the above endpoints running the engine api.
+    ///
+    /// Either one secret per endpoint, or a single secret shared by all endpoints.
     pub secret_files: Vec<PathBuf>,
+    /// How payload statuses are combined when several execution endpoints are configured.
+    pub payload_status_policy: PayloadStatusPolicy,
+    /// If set, all engine API requests and responses are recorded to this directory.
+    pub engine_traffic_dir: Option<PathBuf>,
     /// The default fee recipient to use on the beacon node if none if provided from
     /// the validator client during block preparation.
     pub suggested_fee_recipient: Option<Address>,
@@ -377,9 +423,12 @@ impl<T: EthSpec> ExecutionLayer<T> {
     pub fn from_config(config: Config, executor: TaskExecutor, log: Logger) -> Result<Self, Error> {
         let Config {
             execution_endpoints: urls,
-            builder_url,
+            builder_urls,
             builder_user_agent,
+            builder_header_timeout,
             secret_files,
+            payload_status_policy,
+            engine_traffic_dir,
             suggested_fee_recipient,
             jwt_id,
             jwt_version,
@@ -387,20 +436,107 @@ impl<T: EthSpec> ExecutionLayer<T> {
             execution_timeout_multiplier,
         } = config;
 
-        if urls.len() > 1 {
-            warn!(log, "Only the first execution engine url will be used");
+        if urls.is_empty() {
+            return Err(Error::NoEngine);
         }
-        let execution_url = urls.into_iter().next().ok_or(Error::NoEngine)?;
 
         // Use the default jwt secret path if not provided via cli.
-        let secret_file = secret_files
+        let secret_files = if secret_files.is_empty() {
+            vec![default_datadir.join(DEFAULT_JWT_FILE)]
+        } else {
+            secret_files
+        };
+        if secret_files.len() != 1 && secret_files.len() != urls.len() {
+            return Err(Error::InvalidJWTSecret(format!(
+                "{} JWT secrets provided for {} execution endpoints",
+                secret_files.len(),
+                urls.len()
+            )));
+        }
+
+        let recorder = engine_traffic_dir
+            .map(|dir| {
+                let recorder = TrafficRecorder::new(
+                    dir,
+                    recorder::DEFAULT_MAX_FILE_SIZE,
+                    recorder::DEFAULT_MAX_ROTATED_FILES,
+                )
+                .map_err(|e| Error::TrafficRecorder(format!("{:?}", e)))?;
+                info!(log, "Recording engine API traffic"; "path" => %recorder.path().display());
+                Ok::<_, Error>(Arc::new(recorder))
+            })
+            .transpose()?;
+
+        let mut engines = urls
             .into_iter()
-            .next()
-            .unwrap_or_else(|| default_datadir.join(DEFAULT_JWT_FILE));
+            .enumerate()
+            .map(|(i, execution_url)| {
+                let api = if let Some(ipc_path) = ipc::ipc_path(&execution_url) {
+                    // IPC sockets are protected by file permissions, so no JWT is required.
+                    debug!(log, "Loaded IPC execution endpoint"; "path" => %ipc_path.display());
+                    HttpJsonRpc::new(execution_url, execution_timeout_multiplier)
+                } else {
+                    let secret_file = secret_files.get(i).unwrap_or(&secret_files[0]);
+                    let jwt_key = Self::load_jwt_key(secret_file, &log)?;
+                    let auth = Auth::new(jwt_key, jwt_id.clone(), jwt_version.clone());
+                    debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
+                    HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
+                }
+                .map_err(Error::ApiError)?;
+                let api = match &recorder {
+                    Some(recorder) => api.with_recorder(recorder.clone()),
+                    None => api,
+                };
+                Ok(Arc::new(Engine::new(api, executor.clone(), &log)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let engine = engines.remove(0);
+
+        if !engines.is_empty() {
+            info!(
+                log,
+                "Using redundant execution engines";
+                "count" => engines.len() + 1,
+                "payload_status_policy" => %payload_status_policy,
+            );
+        }
+
+        let (engine_disagreement_tx, _) = broadcast::channel(ENGINE_DISAGREEMENT_CHANNEL_CAPACITY);
+
+        let inner = Inner {
+            engine,
+            redundant_engines: engines,
+            payload_status_policy,
+            engine_disagreement_tx,
+            builder: ArcSwapOption::empty(),
+            execution_engine_forkchoice_lock: <_>::default(),
+            suggested_fee_recipient,
+            proposer_preparation_data: Mutex::new(HashMap::new()),
+            proposers: RwLock::new(HashMap::new()),
+            execution_blocks: Mutex::new(LruCache::new(EXECUTION_BLOCKS_LRU_CACHE_SIZE)),
+            executor,
+            payload_cache: PayloadCache::default(),
+            log,
+            last_new_payload_errored: RwLock::new(false),
+        };
+
+        let el = Self {
+            inner: Arc::new(inner),
+        };
+
+        if !builder_urls.is_empty() {
+            el.set_builder_urls(builder_urls, builder_user_agent, builder_header_timeout)?;
+        }
+
+        Ok(el)
+    }
 
-        let jwt_key = if secret_file.exists() {
+    /// Read the JWT secret from `secret_file`, generating and writing a new secret if the file
+    /// does not exist.
+    fn load_jwt_key(secret_file: &Path, log: &Logger) -> Result<JwtKey, Error> {
+        if secret_file.exists() {
             // Read secret from file if it already exists
-            std::fs::read_to_string(&secret_file)
+            std::fs::read_to_string(secret_file)
                 .map_err(|e| format!("Failed to read JWT secret file. Error: {:?}", e))
                 .and_then(|ref s| {
                     let secret = JwtKey::from_slice(
@@ -416,7 +552,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             std::fs::File::options()
                 .write(true)
                 .create_new(true)
-                .open(&secret_file)
+                .open(secret_file)
                 .map_err(|e| format!("Failed to open JWT secret file. Error: {:?}", e))
                 .and_then(|mut f| {
                     let secret = auth::JwtKey::random();
@@ -425,46 +561,37 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     Ok(secret)
                 })
                 .map_err(Error::InvalidJWTSecret)
-        }?;
-
-        let engine: Engine = {
-            let auth = Auth::new(jwt_key, jwt_id, jwt_version);
-            debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
-            let api = HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
-                .map_err(Error::ApiError)?;
-            Engine::new(api, executor.clone(), &log)
-        };
-
-        let inner = Inner {
-            engine: Arc::new(engine),
-            builder: ArcSwapOption::empty(),
-            execution_engine_forkchoice_lock: <_>::default(),
-            suggested_fee_recipient,
-            proposer_preparation_data: Mutex::new(HashMap::new()),
-            proposers: RwLock::new(HashMap::new()),
-            execution_blocks: Mutex::new(LruCache::new(EXECUTION_BLOCKS_LRU_CACHE_SIZE)),
-            executor,
-            payload_cache: PayloadCache::default(),
-            log,
-            last_new_payload_errored: RwLock::new(false),
-        };
-
-        let el = Self {
-            inner: Arc::new(inner),
-        };
+        }
+    }
 
-        if let Some(builder_url) = builder_url {
-            el.set_builder_url(builder_url, builder_user_agent)?;
+    /// Returns the engine which should serve requests that are only sent to a single engine.
+    ///
+    /// This is the first synced engine, falling back to the first online engine and then to the
+    /// primary engine.
+    async fn engine(&self) -> &Arc<Engine> {
+        let mut online = None;
+        for engine in self.engines() {
+            if engine.is_synced().await {
+                return engine;
+            }
+            if online.is_none() && !engine.is_offline().await {
+                online = Some(engine);
+            }
         }
+        online.unwrap_or(&self.inner.engine)
+    }
 
-        Ok(el)
+    /// Iterate over all configured engines, starting with the primary engine.
+    fn engines(&self) -> impl Iterator<Item = &Arc<Engine>> {
+        std::iter::once(&self.inner.engine).chain(self.inner.redundant_engines.iter())
     }
 
-    fn engine(&self) -> &Arc<Engine> {
-        &self.inner.engine
+    /// Subscribe to notifications of conflicting payload statuses from the execution engines.
+    pub fn subscribe_engine_disagreements(&self) -> broadcast::Receiver<EngineDisagreement> {
+        self.inner.engine_disagreement_tx.subscribe()
     }
 
-    pub fn builder(&self) -> Option<Arc<BuilderHttpClient>> {
+    pub fn builder(&self) -> Option<Arc<Relays>> {
         self.inner.builder.load_full()
     }
 
@@ -477,15 +604,42 @@ impl<T: EthSpec> ExecutionLayer<T> {
         builder_url: SensitiveUrl,
         builder_user_agent: Option<String>,
     ) -> Result<(), Error> {
-        let builder_client = BuilderHttpClient::new(builder_url.clone(), builder_user_agent)
-            .map_err(Error::Builder)?;
+        self.set_builder_urls(vec![builder_url], builder_user_agent, None)
+    }
+
+    /// Set the builder URLs after initialization, replacing any existing builders.
+    ///
+    /// Bids are requested from every builder and the most valuable valid bid is used.
+    pub fn set_builder_urls(
+        &self,
+        builder_urls: Vec<SensitiveUrl>,
+        builder_user_agent: Option<String>,
+        builder_header_timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        if builder_urls.is_empty() {
+            return Err(Error::NoPayloadBuilder);
+        }
+        let relays = Relays::new(
+            builder_urls.clone(),
+            builder_user_agent,
+            builder_header_timeout,
+            self.log().clone(),
+        )
+        .map_err(Error::Builder)?;
+        if let Some(previous) = self.builder() {
+            relays.inherit_winning_bids(&previous);
+        }
         info!(
             self.log(),
             "Using external block builder";
-            "builder_url" => ?builder_url,
-            "local_user_agent" => builder_client.get_user_agent(),
+            "builder_urls" => ?builder_urls,
+            "local_user_agent" => ?relays
+                .iter()
+                .next()
+                .map(|relay| relay.client().get_user_agent()),
+            "header_timeout_ms" => ?builder_header_timeout.map(|timeout| timeout.as_millis()),
         );
-        self.inner.builder.swap(Some(Arc::new(builder_client)));
+        self.inner.builder.swap(Some(Arc::new(relays)));
         Ok(())
     }
 
@@ -523,6 +677,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
     pub async fn get_current_difficulty(&self) -> Result<Uint256, ApiError> {
         let block = self
             .engine()
+            .await
             .api
             .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
             .await?
@@ -536,11 +691,12 @@ impl<T: EthSpec> ExecutionLayer<T> {
         self.inner.execution_blocks.lock().await
     }
 
-    /// Gives access to a channel containing if the last engine state is online or not.
+    /// Gives access to a channel containing if the last state of the primary engine is online or
+    /// not.
     ///
     /// This can be called several times.
     pub async fn get_responsiveness_watch(&self) -> WatchStream<EngineState> {
-        self.engine().watch_state().await
+        self.inner.engine.watch_state().await
     }
 
     /// Note: this function returns a mutex guard, be careful to avoid deadlocks.
@@ -592,7 +748,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
     /// Performs a single execution of the watchdog routine.
     pub async fn watchdog_task(&self) {
-        self.engine().upcheck().await;
+        self.upcheck().await;
     }
 
     /// Spawns a routine which cleans the cached proposer data periodically.
@@ -634,9 +790,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
         self.spawn(preparation_cleaner, "exec_preparation_cleanup");
     }
 
-    /// Returns `true` if the execution engine is synced and reachable.
+    /// Returns `true` if any execution engine is synced and reachable.
     pub async fn is_synced(&self) -> bool {
-        self.engine().is_synced().await
+        for engine in self.engines() {
+            if engine.is_synced().await {
+                return true;
+            }
+        }
+        false
     }
 
     /// Execution nodes return a "SYNCED" response when they do not have any peers.
@@ -650,6 +811,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         if synced {
             if let Ok(Some(block)) = self
                 .engine()
+                .await
                 .api
                 .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
                 .await
@@ -668,7 +830,12 @@ impl<T: EthSpec> ExecutionLayer<T> {
     /// be used to give an indication on the HTTP API that the node's execution layer is struggling,
     /// which can in turn be used by the VC.
     pub async fn is_offline_or_erroring(&self) -> bool {
-        self.engine().is_offline().await || *self.inner.last_new_payload_errored.read().await
+        for engine in self.engines() {
+            if !engine.is_offline().await {
+                return *self.inner.last_new_payload_errored.read().await;
+            }
+        }
+        true
     }
 
     /// Updates the proposer preparation data provided by validators
@@ -882,32 +1049,30 @@ impl<T: EthSpec> ExecutionLayer<T> {
     /// Fetches local and builder paylaods concurrently, Logs and returns results.
     async fn fetch_builder_and_local_payloads(
         &self,
-        builder: &BuilderHttpClient,
+        builder: &Relays,
         parent_hash: ExecutionBlockHash,
         builder_params: &BuilderParams,
         payload_attributes: &PayloadAttributes,
         forkchoice_update_params: ForkchoiceUpdateParameters,
         current_fork: ForkName,
-    ) -> (
-        Result<Option<ForkVersionedResponse<SignedBuilderBid<T>>>, builder_client::Error>,
-        Result<GetPayloadResponse<T>, Error>,
-    ) {
+    ) -> (Vec<RelayBid<T>>, Result<GetPayloadResponse<T>, Error>) {
         let slot = builder_params.slot;
         let pubkey = &builder_params.pubkey;
 
         info!(
             self.log(),
             "Requesting blinded header from connected builder";
+            "relays" => builder.len(),
             "slot" => ?slot,
             "pubkey" => ?pubkey,
             "parent_hash" => ?parent_hash,
         );
 
-        // Wait for the builder *and* local EL to produce a payload (or return an error).
-        let ((relay_result, relay_duration), (local_result, local_duration)) = tokio::join!(
+        // Wait for the builders *and* local EL to produce a payload (or return an error).
+        let ((relay_bids, _), (local_result, local_duration)) = tokio::join!(
             timed_future(metrics::GET_BLINDED_PAYLOAD_BUILDER, async {
                 builder
-                    .get_builder_header::<T>(slot, parent_hash, pubkey)
+                    .get_builder_headers::<T>(slot, parent_hash, pubkey)
                     .await
             }),
             timed_future(metrics::GET_BLINDED_PAYLOAD_LOCAL, async {
@@ -925,24 +1090,127 @@ impl<T: EthSpec> ExecutionLayer<T> {
             })
         );
 
-        info!(
-            self.log(),
-            "Requested blinded execution payload";
-            "relay_fee_recipient" => match &relay_result {
-                Ok(Some(r)) => format!("{:?}", r.data.message.header().fee_recipient()),
-                Ok(None) => "empty response".to_string(),
-                Err(_) => "request failed".to_string(),
-            },
-            "relay_response_ms" => relay_duration.as_millis(),
-            "local_fee_recipient" => match &local_result {
-                Ok(get_payload_response) => format!("{:?}", get_payload_response.fee_recipient()),
-                Err(_) => "request failed".to_string()
-            },
-            "local_response_ms" => local_duration.as_millis(),
-            "parent_hash" => ?parent_hash,
-        );
+        for relay_bid in &relay_bids {
+            info!(
+                self.log(),
+                "Requested blinded execution payload";
+                "relay" => relay_bid.relay.name(),
+                "relay_fee_recipient" => match &relay_bid.result {
+                    Ok(Some(r)) => format!("{:?}", r.data.message.header().fee_recipient()),
+                    Ok(None) => "empty response".to_string(),
+                    Err(_) => "request failed".to_string(),
+                },
+                "relay_value" => match &relay_bid.result {
+                    Ok(Some(r)) => r.data.message.value().to_string(),
+                    _ => "none".to_string(),
+                },
+                "relay_response_ms" => relay_bid.duration.as_millis(),
+                "local_fee_recipient" => match &local_result {
+                    Ok(get_payload_response) => format!("{:?}", get_payload_response.fee_recipient()),
+                    Err(_) => "request failed".to_string()
+                },
+                "local_response_ms" => local_duration.as_millis(),
+                "parent_hash" => ?parent_hash,
+            );
+        }
+
+        (relay_bids, local_result)
+    }
+
+    /// Selects the most valuable valid bid from the responses of all relays.
+    ///
+    /// Returns `Ok(None)` if no relay returned a valid bid and an error only if every relay
+    /// failed to respond.
+    #[allow(clippy::too_many_arguments)]
+    fn select_builder_bid(
+        &self,
+        builder: &Relays,
+        relay_bids: Vec<RelayBid<T>>,
+        parent_hash: ExecutionBlockHash,
+        payload_attributes: &PayloadAttributes,
+        block_number: Option<u64>,
+        current_fork: ForkName,
+        spec: &ChainSpec,
+    ) -> Result<Option<ForkVersionedResponse<SignedBuilderBid<T>>>, builder_client::Error> {
+        let mut first_error = None;
+        let mut any_response = false;
+        let mut best_bid: Option<(ForkVersionedResponse<SignedBuilderBid<T>>, _)> = None;
+
+        for RelayBid { relay, result, .. } in relay_bids {
+            let bid = match result {
+                Ok(Some(bid)) => bid,
+                Ok(None) => {
+                    any_response = true;
+                    continue;
+                }
+                Err(e) => {
+                    warn!(
+                        self.log(),
+                        "Builder error when requesting payload";
+                        "relay" => relay.name(),
+                        "relay_error" => ?e,
+                        "parent_hash" => ?parent_hash,
+                    );
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            any_response = true;
 
-        (relay_result, local_result)
+            if let Err(reason) = verify_builder_bid(
+                &bid,
+                parent_hash,
+                payload_attributes,
+                block_number,
+                current_fork,
+                spec,
+            ) {
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
+                    &[reason.as_ref().as_ref()],
+                );
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_RELAY_BID_OUTCOME,
+                    &[relay.name(), metrics::RELAY_INVALID],
+                );
+                relay.record_fault(RelayFault::InvalidBid, self.log());
+                warn!(
+                    self.log(),
+                    "Builder returned invalid payload";
+                    "info" => "ignoring bid",
+                    "relay" => relay.name(),
+                    "reason" => %reason,
+                    "relay_block_hash" => ?bid.data.message.header().block_hash(),
+                    "parent_hash" => ?parent_hash,
+                );
+                continue;
+            }
+            relay.record_valid_bid();
+
+            let is_best = best_bid.as_ref().map_or(true, |(best, _)| {
+                bid.data.message.value() > best.data.message.value()
+            });
+            if is_best {
+                best_bid = Some((bid, relay));
+            }
+        }
+
+        match (best_bid, first_error) {
+            (Some((bid, relay)), _) => {
+                info!(
+                    self.log(),
+                    "Selected builder bid";
+                    "relay" => relay.name(),
+                    "relay_value" => %bid.data.message.value(),
+                    "relay_block_hash" => ?bid.data.message.header().block_hash(),
+                    "parent_hash" => ?parent_hash,
+                );
+                builder.record_winning_bid(bid.data.message.header().block_hash(), relay);
+                Ok(Some(bid))
+            }
+            (None, Some(e)) if !any_response => Err(e),
+            (None, _) => Ok(None),
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1006,7 +1274,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 .map(ProvenancedPayload::Local);
         }
 
-        let (relay_result, local_result) = self
+        let (relay_bids, local_result) = self
             .fetch_builder_and_local_payloads(
                 builder.as_ref(),
                 parent_hash,
@@ -1017,6 +1285,16 @@ impl<T: EthSpec> ExecutionLayer<T> {
             )
             .await;
 
+        let relay_result = self.select_builder_bid(
+            builder.as_ref(),
+            relay_bids,
+            parent_hash,
+            payload_attributes,
+            local_result.as_ref().ok().map(|local| local.block_number()),
+            current_fork,
+            spec,
+        );
+
         match (relay_result, local_result) {
             (Err(e), Ok(local)) => {
                 warn!(
@@ -1078,33 +1356,6 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "parent_hash" => ?parent_hash,
                 );
 
-                // check relay payload validity
-                if let Err(reason) = verify_builder_bid(
-                    &relay,
-                    parent_hash,
-                    payload_attributes,
-                    Some(local.block_number()),
-                    current_fork,
-                    spec,
-                ) {
-                    // relay payload invalid -> return local
-                    metrics::inc_counter_vec(
-                        &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
-                        &[reason.as_ref().as_ref()],
-                    );
-                    warn!(
-                        self.log(),
-                        "Builder returned invalid payload";
-                        "info" => "using local payload",
-                        "reason" => %reason,
-                        "relay_block_hash" => ?header.block_hash(),
-                        "parent_hash" => ?parent_hash,
-                    );
-                    return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
-                        local.try_into()?,
-                    )));
-                }
-
                 let relay_value = *relay.data.message.value();
 
                 let boosted_relay_value = match builder_boost_factor {
@@ -1164,31 +1415,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     "parent_hash" => ?parent_hash,
                 );
 
-                match verify_builder_bid(
-                    &relay,
-                    parent_hash,
-                    payload_attributes,
-                    None,
-                    current_fork,
-                    spec,
-                ) {
-                    Ok(()) => Ok(ProvenancedPayload::try_from(relay.data.message)?),
-                    Err(reason) => {
-                        metrics::inc_counter_vec(
-                            &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
-                            &[reason.as_ref().as_ref()],
-                        );
-                        crit!(
-                            self.log(),
-                            "Builder returned invalid payload";
-                            "info" => "no local payload either - unable to propose block",
-                            "reason" => %reason,
-                            "relay_block_hash" => ?header.block_hash(),
-                            "parent_hash" => ?parent_hash,
-                        );
-                        Err(Error::CannotProduceHeader)
-                    }
-                }
+                Ok(ProvenancedPayload::try_from(relay.data.message)?)
             }
         }
     }
@@ -1222,7 +1449,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             PayloadContentsRefTuple<T>,
         ) -> Option<FullPayloadContents<T>>,
     ) -> Result<GetPayloadResponseType<T>, Error> {
-        self.engine()
+        self.engine().await
             .request(move |engine| async move {
                 let payload_id = if let Some(id) = engine
                     .get_payload_id(&parent_hash, payload_attributes)
@@ -1338,26 +1565,150 @@ impl<T: EthSpec> ExecutionLayer<T> {
         );
 
         let result = self
-            .engine()
-            .request(|engine| engine.api.new_payload(new_payload_request))
+            .request_payload_status(metrics::NEW_PAYLOAD, block_hash, |engine| {
+                let new_payload_request = new_payload_request.clone();
+                let log = self.log().clone();
+                async move {
+                    let result = engine
+                        .request(|engine| engine.api.new_payload(new_payload_request))
+                        .await;
+
+                    if let Ok(status) = &result {
+                        metrics::inc_counter_vec(
+                            &metrics::EXECUTION_LAYER_PAYLOAD_STATUS,
+                            &["new_payload", status.status.into()],
+                        );
+                    }
+
+                    process_payload_status(block_hash, result, &log)
+                }
+            })
             .await;
+        *self.inner.last_new_payload_errored.write().await = result.is_err();
+
+        result.map_err(Box::new).map_err(Error::EngineError)
+    }
+
+    /// Sends a request to every engine and combines the statuses they return using the
+    /// configured `PayloadStatusPolicy`.
+    ///
+    /// Returns as soon as the outcome cannot be changed by the engines which have yet to respond.
+    /// Their requests are completed in the background, only to report any disagreement between
+    /// the engines.
+    async fn request_payload_status<F, R>(
+        &self,
+        method: &'static str,
+        block_hash: ExecutionBlockHash,
+        request: F,
+    ) -> Result<PayloadStatus, EngineError>
+    where
+        F: Fn(Arc<Engine>) -> R,
+        R: Future<Output = Result<PayloadStatus, EngineError>> + Send + 'static,
+    {
+        let policy = self.inner.payload_status_policy;
+        let mut pending = self
+            .engines()
+            .enumerate()
+            .map(|(i, engine)| request(engine.clone()).map(move |status| (i, status)))
+            .collect::<FuturesUnordered<_>>();
+        let mut votes = vec![None; pending.len()];
+        let mut statuses = Vec::with_capacity(pending.len());
+
+        let mut outcome = None;
+        while let Some((i, status)) = pending.next().await {
+            votes[i] = Some(StatusVote::new(&status));
+            statuses.push((i, status));
+
+            let responded = votes.iter().flatten().copied().collect::<Vec<_>>();
+            outcome = decided_outcome(policy, &responded, pending.len());
+            if outcome.is_some() {
+                break;
+            }
+        }
+        // The outcome is always decided once every engine has responded.
+        let outcome = outcome.unwrap_or(StatusVote::Error);
+
+        // Prefer the status of the first engine in the order of `Self::engines`.
+        statuses.sort_by_key(|(i, _)| *i);
+        let result = select_payload_status(
+            outcome,
+            statuses.into_iter().map(|(_, status)| status).collect(),
+        );
 
-        if let Ok(status) = &result {
-            metrics::inc_counter_vec(
-                &metrics::EXECUTION_LAYER_PAYLOAD_STATUS,
-                &["new_payload", status.status.into()],
+        if pending.is_empty() {
+            self.report_disagreement(method, block_hash, votes, outcome);
+        } else {
+            let el = self.clone();
+            self.inner.executor.spawn(
+                async move {
+                    while let Some((i, status)) = pending.next().await {
+                        votes[i] = Some(StatusVote::new(&status));
+                    }
+                    el.report_disagreement(method, block_hash, votes, outcome);
+                },
+                "engine_disagreement",
             );
         }
-        *self.inner.last_new_payload_errored.write().await = result.is_err();
 
-        process_payload_status(block_hash, result, self.log())
-            .map_err(Box::new)
-            .map_err(Error::EngineError)
+        result
     }
 
-    /// Update engine sync status.
+    /// Reports any disagreement between the votes of each engine (in the order of
+    /// `Self::engines`).
+    fn report_disagreement(
+        &self,
+        method: &'static str,
+        block_hash: ExecutionBlockHash,
+        votes: Vec<Option<StatusVote>>,
+        outcome: StatusVote,
+    ) {
+        let votes = votes
+            .into_iter()
+            .map(|vote| vote.unwrap_or(StatusVote::Error))
+            .collect::<Vec<_>>();
+        let mut responses = votes.iter().filter(|vote| **vote != StatusVote::Error);
+        let disagreement = responses
+            .next()
+            .map_or(false, |first| responses.any(|vote| vote != first));
+        if !disagreement {
+            return;
+        }
+
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_ENGINE_DISAGREEMENTS,
+            &[method, outcome.as_ref()],
+        );
+
+        let votes = self
+            .engines()
+            .zip(votes)
+            .map(|(engine, vote)| EngineStatusVote {
+                engine: engine.api.url.to_string(),
+                status: vote.as_ref().to_string(),
+            })
+            .collect::<Vec<_>>();
+        warn!(
+            self.log(),
+            "Execution engines disagree on payload status";
+            "method" => method,
+            "block_hash" => ?block_hash,
+            "votes" => ?votes,
+            "outcome" => outcome.as_ref(),
+            "policy" => %self.inner.payload_status_policy,
+        );
+
+        // An error here just means there are no subscribers.
+        let _ = self.inner.engine_disagreement_tx.send(EngineDisagreement {
+            method: method.to_string(),
+            block_hash,
+            votes,
+            outcome: outcome.as_ref().to_string(),
+        });
+    }
+
+    /// Update the sync status of all engines.
     pub async fn upcheck(&self) {
-        self.engine().upcheck().await;
+        join_all(self.engines().map(|engine| engine.upcheck())).await;
     }
 
     /// Register that the given `validator_index` is going to produce a block at `slot`.
@@ -1471,31 +1822,44 @@ impl<T: EthSpec> ExecutionLayer<T> {
             finalized_block_hash,
         };
 
-        self.engine()
-            .set_latest_forkchoice_state(forkchoice_state)
-            .await;
+        for engine in self.engines() {
+            engine.set_latest_forkchoice_state(forkchoice_state).await;
+        }
 
-        let result = self
-            .engine()
-            .request(|engine| async move {
-                engine
-                    .notify_forkchoice_updated(forkchoice_state, payload_attributes, self.log())
-                    .await
-            })
-            .await;
+        self.request_payload_status(metrics::FORKCHOICE_UPDATED, head_block_hash, |engine| {
+            let payload_attributes = payload_attributes.clone();
+            let log = self.log().clone();
+            async move {
+                let result = engine
+                    .request(|engine| {
+                        let log = &log;
+                        async move {
+                            engine
+                                .notify_forkchoice_updated(
+                                    forkchoice_state,
+                                    payload_attributes,
+                                    log,
+                                )
+                                .await
+                        }
+                    })
+                    .await;
 
-        if let Ok(status) = &result {
-            metrics::inc_counter_vec(
-                &metrics::EXECUTION_LAYER_PAYLOAD_STATUS,
-                &["forkchoice_updated", status.payload_status.status.into()],
-            );
-        }
+                if let Ok(status) = &result {
+                    metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_PAYLOAD_STATUS,
+                        &["forkchoice_updated", status.payload_status.status.into()],
+                    );
+                }
 
-        process_payload_status(
-            head_block_hash,
-            result.map(|response| response.payload_status),
-            self.log(),
-        )
+                process_payload_status(
+                    head_block_hash,
+                    result.map(|response| response.payload_status),
+                    &log,
+                )
+            }
+        })
+        .await
         .map_err(Box::new)
         .map_err(Error::EngineError)
     }
@@ -1514,6 +1878,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         age_limit: Option<Duration>,
     ) -> Result<EngineCapabilities, Error> {
         self.engine()
+            .await
             .request(|engine| engine.get_engine_capabilities(age_limit))
             .await
             .map_err(Box::new)
@@ -1539,6 +1904,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
         let hash_opt = self
             .engine()
+            .await
             .request(|engine| async move {
                 let terminal_block_hash = spec.terminal_block_hash;
                 if terminal_block_hash != ExecutionBlockHash::zero() {
@@ -1666,6 +2032,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         );
 
         self.engine()
+            .await
             .request(|engine| async move {
                 if let Some(pow_block) = self.get_pow_block(engine, block_hash).await? {
                     if let Some(pow_parent) =
@@ -1725,6 +2092,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         hashes: Vec<ExecutionBlockHash>,
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
         self.engine()
+            .await
             .request(|engine: &Engine| async move {
                 engine.api.get_payload_bodies_by_hash_v1(hashes).await
             })
@@ -1740,6 +2108,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
         let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_RANGE);
         self.engine()
+            .await
             .request(|engine: &Engine| async move {
                 engine
                     .api
@@ -1751,6 +2120,30 @@ impl<T: EthSpec> ExecutionLayer<T> {
             .map_err(Error::EngineError)
     }
 
+    /// Fetch blobs from the execution engine's blob pool by versioned hash.
+    ///
+    /// The response contains an entry for each hash, which is `None` if the engine does not have
+    /// the blob. Returns an error if the engine does not support `engine_getBlobsV1`.
+    pub async fn get_blobs(
+        &self,
+        versioned_hashes: Vec<VersionedHash>,
+    ) -> Result<Vec<Option<BlobAndProof<T>>>, Error> {
+        let capabilities = self.get_engine_capabilities(None).await?;
+        if !capabilities.get_blobs_v1 {
+            return Err(Error::GetBlobsNotSupported);
+        }
+
+        let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_BLOBS);
+        self.engine()
+            .await
+            .request(
+                |engine: &Engine| async move { engine.api.get_blobs_v1(versioned_hashes).await },
+            )
+            .await
+            .map_err(Box::new)
+            .map_err(Error::EngineError)
+    }
+
     /// Fetch a full payload from the execution node.
     ///
     /// This will fail if the payload is not from the finalized portion of the chain.
@@ -1802,18 +2195,62 @@ impl<T: EthSpec> ExecutionLayer<T> {
         query: BlockByNumberQuery<'_>,
     ) -> Result<Option<ExecutionBlock>, Error> {
         self.engine()
+            .await
             .request(|engine| async move { engine.api.get_block_by_number(query).await })
             .await
             .map_err(Box::new)
             .map_err(Error::EngineError)
     }
 
+    /// Returns the change in the balance of `fee_recipient` across the execution block
+    /// `block_hash`, whose parent is `parent_hash`.
+    ///
+    /// The change is measured from the execution node's state rather than the payload itself,
+    /// so it includes priority fees as well as any direct transfers made by a builder. It also
+    /// includes any transactions sent by the fee recipient in the block, which may result in a
+    /// decrease.
+    pub async fn get_fee_recipient_balance_change(
+        &self,
+        fee_recipient: Address,
+        block_hash: ExecutionBlockHash,
+        parent_hash: ExecutionBlockHash,
+    ) -> Result<FeeRecipientBalanceChange, Error> {
+        self.engine()
+            .await
+            .request(|engine| async move {
+                let before = engine.api.get_balance(fee_recipient, parent_hash).await?;
+                let after = engine.api.get_balance(fee_recipient, block_hash).await?;
+                if after >= before {
+                    Ok(FeeRecipientBalanceChange::Increased(after - before))
+                } else {
+                    Ok(FeeRecipientBalanceChange::Decreased(before - after))
+                }
+            })
+            .await
+            .map_err(Box::new)
+            .map_err(Error::EngineError)
+    }
+
+    /// Record a fault against the relay which supplied the payload with `block_hash`, after the
+    /// payload paid the fee recipient less than the value of the relay's bid.
+    ///
+    /// Payloads which were not supplied by a relay are ignored.
+    pub fn record_builder_value_shortfall(&self, block_hash: &ExecutionBlockHash) {
+        if let Some(relay) = self
+            .builder()
+            .and_then(|builder| builder.winning_relay(block_hash))
+        {
+            relay.record_fault(RelayFault::ValueShortfall, self.log());
+        }
+    }
+
     pub async fn get_payload_by_hash_legacy(
         &self,
         hash: ExecutionBlockHash,
         fork: ForkName,
     ) -> Result<Option<ExecutionPayload<T>>, Error> {
         self.engine()
+            .await
             .request(|engine| async move {
                 self.get_payload_by_hash_from_engine(engine, hash, fork)
                     .await
@@ -1957,11 +2394,49 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     builder
                         .post_builder_blinded_blocks(block)
                         .await
-                        .map_err(Error::Builder)
                         .map(|d| d.data)
                 })
                 .await;
 
+            // Check that the revealed payload is the one committed to by the builder's bid.
+            let payload_result = payload_result.and_then(|unblinded_response| {
+                let Ok(header) = block.message().execution_payload() else {
+                    return Ok(unblinded_response);
+                };
+                let expected = header.tree_hash_root();
+                let received = unblinded_response.payload_ref().tree_hash_root();
+                let result = if expected != received {
+                    Err(Error::BuilderPayloadMismatch { expected, received })
+                } else if let Ok(commitments) = block.message().body().blob_kzg_commitments() {
+                    // The blobs bundle must contain exactly the blobs committed to by the block.
+                    match &unblinded_response {
+                        FullPayloadContents::PayloadAndBlobs(payload_and_blobs)
+                            if payload_and_blobs.blobs_bundle.commitments == *commitments
+                                && payload_and_blobs.blobs_bundle.proofs.len()
+                                    == commitments.len()
+                                && payload_and_blobs.blobs_bundle.blobs.len()
+                                    == commitments.len() =>
+                        {
+                            Ok(())
+                        }
+                        _ => Err(Error::BuilderBlobsMismatch {
+                            expected: commitments.len(),
+                        }),
+                    }
+                } else {
+                    Ok(())
+                };
+                match result {
+                    Ok(()) => Ok(unblinded_response),
+                    Err(e) => {
+                        if let Some(relay) = builder.winning_relay(&header.block_hash()) {
+                            relay.record_fault(RelayFault::PayloadMismatch, self.log());
+                        }
+                        Err(e)
+                    }
+                }
+            });
+
             match &payload_result {
                 Ok(unblinded_response) => {
                     metrics::inc_counter_vec(
@@ -2178,7 +2653,9 @@ fn noop<T: EthSpec>(
 mod test {
     use super::*;
     use crate::test_utils::MockExecutionLayer as GenericMockExecutionLayer;
+    use crate::test_utils::{Action, MockServer, Rule, Scenario, DEFAULT_JWT_SECRET};
     use task_executor::test_utils::TestRuntime;
+    use tempfile::NamedTempFile;
     use types::MainnetEthSpec;
 
     type MockExecutionLayer = GenericMockExecutionLayer<MainnetEthSpec>;
@@ -2216,7 +2693,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_block_prior_to_terminal_block()
             .with_terminal_block(|spec, el, _| async move {
-                el.engine().upcheck().await;
+                el.engine().await.upcheck().await;
                 assert_eq!(
                     el.get_terminal_pow_block_hash(&spec, timestamp_now())
                         .await
@@ -2243,7 +2720,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_block_prior_to_terminal_block()
             .with_terminal_block(|spec, el, _| async move {
-                el.engine().upcheck().await;
+                el.engine().await.upcheck().await;
                 assert_eq!(
                     el.get_terminal_pow_block_hash(&spec, timestamp_now())
                         .await
@@ -2271,7 +2748,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_terminal_block()
             .with_terminal_block(|spec, el, terminal_block| async move {
-                el.engine().upcheck().await;
+                el.engine().await.upcheck().await;
                 assert_eq!(
                     el.is_valid_terminal_pow_block_hash(terminal_block.unwrap().block_hash, &spec)
                         .await
@@ -2288,7 +2765,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_terminal_block()
             .with_terminal_block(|spec, el, terminal_block| async move {
-                el.engine().upcheck().await;
+                el.engine().await.upcheck().await;
                 let invalid_terminal_block = terminal_block.unwrap().parent_hash;
 
                 assert_eq!(
@@ -2307,7 +2784,7 @@ mod test {
         MockExecutionLayer::default_params(runtime.task_executor.clone())
             .move_to_terminal_block()
             .with_terminal_block(|spec, el, _| async move {
-                el.engine().upcheck().await;
+                el.engine().await.upcheck().await;
                 let missing_terminal_block = ExecutionBlockHash::repeat_byte(42);
 
                 assert_eq!(
@@ -2319,4 +2796,146 @@ mod test {
             })
             .await;
     }
+
+    /// How long the slow engine in the redundant engine tests takes to respond.
+    const SLOW_ENGINE_DELAY: Duration = Duration::from_secs(3);
+
+    /// Builds an execution layer with an execution engine for each of `servers`.
+    fn redundant_execution_layer(
+        servers: &[MockServer<MainnetEthSpec>],
+        payload_status_policy: PayloadStatusPolicy,
+        executor: TaskExecutor,
+    ) -> (ExecutionLayer<MainnetEthSpec>, NamedTempFile) {
+        let jwt_file = NamedTempFile::new().unwrap();
+        std::fs::write(jwt_file.path(), hex::encode(DEFAULT_JWT_SECRET)).unwrap();
+
+        let config = Config {
+            execution_endpoints: servers
+                .iter()
+                .map(|server| SensitiveUrl::parse(&server.url()).unwrap())
+                .collect(),
+            secret_files: vec![jwt_file.path().into()],
+            payload_status_policy,
+            ..Default::default()
+        };
+        let el =
+            ExecutionLayer::from_config(config, executor.clone(), executor.log().clone()).unwrap();
+        (el, jwt_file)
+    }
+
+    async fn forkchoice_updated(
+        el: &ExecutionLayer<MainnetEthSpec>,
+        head_block_hash: ExecutionBlockHash,
+    ) -> PayloadStatus {
+        el.notify_forkchoice_updated(
+            head_block_hash,
+            ExecutionBlockHash::zero(),
+            ExecutionBlockHash::zero(),
+            Slot::new(1),
+            Hash256::repeat_byte(42),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn disagreement_count(method: &str, outcome: StatusVote) -> u64 {
+        metrics::get_int_counter(
+            &metrics::EXECUTION_LAYER_ENGINE_DISAGREEMENTS,
+            &[method, outcome.as_ref()],
+        )
+        .map_or(0, |counter| counter.get())
+    }
+
+    fn vote_statuses(disagreement: &EngineDisagreement) -> Vec<&str> {
+        disagreement
+            .votes
+            .iter()
+            .map(|vote| vote.status.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn redundant_engines_do_not_wait_for_decided_outcome() {
+        let runtime = TestRuntime::default();
+        let servers = (0..3)
+            .map(|_| MockServer::unit_testing())
+            .collect::<Vec<_>>();
+        servers[0].all_payloads_valid_on_forkchoice_updated();
+        servers[1].all_payloads_valid_on_forkchoice_updated();
+        // The third engine disagrees, but too slowly to change the majority.
+        servers[2].all_payloads_invalid_on_forkchoice_updated(ExecutionBlockHash::zero());
+        servers[2].set_scenario(Scenario {
+            rules: vec![Rule {
+                method: Some("engine_forkchoiceUpdated*".into()),
+                block_hash: None,
+                from_secs: None,
+                until_secs: None,
+                skip: 0,
+                times: None,
+                action: Action::Delay {
+                    millis: SLOW_ENGINE_DELAY.as_millis() as u64,
+                },
+            }],
+        });
+        let (el, _jwt_file) = redundant_execution_layer(
+            &servers,
+            PayloadStatusPolicy::Majority,
+            runtime.task_executor.clone(),
+        );
+        let mut disagreements = el.subscribe_engine_disagreements();
+        let count_before = disagreement_count(metrics::FORKCHOICE_UPDATED, StatusVote::Valid);
+
+        let head_block_hash = ExecutionBlockHash::repeat_byte(1);
+        let started = Instant::now();
+        let status = forkchoice_updated(&el, head_block_hash).await;
+        assert_eq!(status, PayloadStatus::Valid);
+        assert!(
+            started.elapsed() < SLOW_ENGINE_DELAY,
+            "the outcome should not wait for the slow engine"
+        );
+
+        // The slow engine is still awaited in the background to report the disagreement.
+        let disagreement = tokio::time::timeout(SLOW_ENGINE_DELAY * 2, disagreements.recv())
+            .await
+            .expect("disagreement should be reported")
+            .unwrap();
+        assert_eq!(disagreement.method, metrics::FORKCHOICE_UPDATED);
+        assert_eq!(disagreement.block_hash, head_block_hash);
+        assert_eq!(disagreement.outcome, "valid");
+        assert_eq!(vote_statuses(&disagreement), ["valid", "valid", "invalid"]);
+        assert_eq!(
+            disagreement_count(metrics::FORKCHOICE_UPDATED, StatusVote::Valid),
+            count_before + 1
+        );
+    }
+
+    #[tokio::test]
+    async fn redundant_engines_syncing_vetoes_all_policy() {
+        let runtime = TestRuntime::default();
+        let servers = (0..2)
+            .map(|_| MockServer::unit_testing())
+            .collect::<Vec<_>>();
+        servers[0].all_payloads_valid_on_forkchoice_updated();
+        servers[1].all_payloads_syncing_on_forkchoice_updated();
+        let (el, _jwt_file) = redundant_execution_layer(
+            &servers,
+            PayloadStatusPolicy::All,
+            runtime.task_executor.clone(),
+        );
+        let mut disagreements = el.subscribe_engine_disagreements();
+        let count_before = disagreement_count(metrics::FORKCHOICE_UPDATED, StatusVote::Syncing);
+
+        let head_block_hash = ExecutionBlockHash::repeat_byte(2);
+        let status = forkchoice_updated(&el, head_block_hash).await;
+        assert_eq!(status, PayloadStatus::Syncing);
+
+        let disagreement = disagreements.try_recv().unwrap();
+        assert_eq!(disagreement.block_hash, head_block_hash);
+        assert_eq!(disagreement.outcome, "syncing");
+        assert_eq!(vote_statuses(&disagreement), ["valid", "syncing"]);
+        assert_eq!(
+            disagreement_count(metrics::FORKCHOICE_UPDATED, StatusVote::Syncing),
+            count_before + 1
+        );
+    }
 }
@@ -15,6 +15,11 @@ pub const LOCAL: &str = "local";
 pub const BUILDER: &str = "builder";
 pub const SUCCESS: &str = "success";
 pub const FAILURE: &str = "failure";
+pub const RELAY_BID: &str = "bid";
+pub const RELAY_NO_BID: &str = "no_bid";
+pub const RELAY_ERROR: &str = "error";
+pub const RELAY_TIMEOUT: &str = "timeout";
+pub const RELAY_INVALID: &str = "invalid";
 
 lazy_static::lazy_static! {
     pub static ref EXECUTION_LAYER_PROPOSER_INSERTED: Result<IntCounter> = try_create_int_counter(
@@ -49,6 +54,10 @@ lazy_static::lazy_static! {
         "execution_layer_get_payload_bodies_by_range_time",
         "Time to fetch a range of payload bodies from the EE"
     );
+    pub static ref EXECUTION_LAYER_GET_BLOBS: Result<Histogram> = try_create_histogram(
+        "execution_layer_get_blobs_time",
+        "Time to fetch blobs from the EE blob pool"
+    );
     pub static ref EXECUTION_LAYER_VERIFY_BLOCK_HASH: Result<Histogram> = try_create_histogram_with_buckets(
         "execution_layer_verify_block_hash_time",
         "Time to verify the execution block hash in Lighthouse, without the EL",
@@ -59,6 +68,15 @@ lazy_static::lazy_static! {
         "Indicates the payload status returned for a particular method",
         &["method", "status"]
     );
+    pub static ref EXECUTION_LAYER_TRAFFIC_RECORDING_ERRORS: Result<IntCounter> = try_create_int_counter(
+        "execution_layer_traffic_recording_errors_total",
+        "Count of engine API exchanges which could not be written to the traffic recording",
+    );
+    pub static ref EXECUTION_LAYER_ENGINE_DISAGREEMENTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_engine_disagreements",
+        "Count of payload statuses on which redundant execution engines disagreed",
+        &["method", "outcome"]
+    );
     pub static ref EXECUTION_LAYER_GET_PAYLOAD_OUTCOME: Result<IntCounterVec> = try_create_int_counter_vec(
         "execution_layer_get_payload_outcome",
         "The success/failure outcomes from calling get_payload",
@@ -84,4 +102,31 @@ lazy_static::lazy_static! {
         "The gwei bid value of payloads received by local EEs or builders. Only shows values up to i64::max_value.",
         &["source"]
     );
+    pub static ref EXECUTION_LAYER_RELAY_BID_OUTCOME: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_relay_bid_outcome",
+        "The outcome of each request for a bid from a builder relay",
+        &["relay", "outcome"]
+    );
+    pub static ref EXECUTION_LAYER_RELAY_RESPONSE_TIMES: Result<HistogramVec> =
+        try_create_histogram_vec_with_buckets(
+        "execution_layer_relay_response_times",
+        "Duration of requests for a bid from each builder relay",
+        decimal_buckets(-2, 1),
+        &["relay"]
+    );
+    pub static ref EXECUTION_LAYER_RELAY_WINS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_relay_wins",
+        "Count of times each builder relay supplied the best valid bid",
+        &["relay"]
+    );
+    pub static ref EXECUTION_LAYER_RELAY_FAULTS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_relay_faults",
+        "Count of faults recorded against each builder relay",
+        &["relay", "fault"]
+    );
+    pub static ref EXECUTION_LAYER_RELAY_ENABLED: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "execution_layer_relay_enabled",
+        "Set to 1 if a builder relay is enabled or 0 if it has been disabled by the circuit breaker",
+        &["relay"]
+    );
 }
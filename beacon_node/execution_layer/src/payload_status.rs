@@ -1,6 +1,8 @@
 use crate::engine_api::{Error as ApiError, PayloadStatusV1, PayloadStatusV1Status};
 use crate::engines::EngineError;
+use serde::{Deserialize, Serialize};
 use slog::{warn, Logger};
+use strum::{AsRefStr, Display, EnumString};
 use types::ExecutionBlockHash;
 
 /// Provides a simpler, easier to parse version of `PayloadStatusV1` for upstream users.
@@ -22,6 +24,123 @@ pub enum PayloadStatus {
     },
 }
 
+/// Determines how the payload statuses returned by several execution engines are combined into a
+/// single status.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString, Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum PayloadStatusPolicy {
+    /// A payload is valid if any engine considers it valid.
+    AnyValid,
+    /// A payload is valid or invalid if more than half of the responding engines agree.
+    #[default]
+    Majority,
+    /// A payload is only valid if every engine considers it valid, and invalid if any engine
+    /// considers it invalid.
+    All,
+}
+
+/// The broad class of a single engine's response, used for voting between engines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum StatusVote {
+    Valid,
+    /// Includes `INVALID_BLOCK_HASH`.
+    Invalid,
+    /// Includes `ACCEPTED`.
+    Syncing,
+    Error,
+}
+
+impl StatusVote {
+    pub fn new(status: &Result<PayloadStatus, EngineError>) -> Self {
+        match status {
+            Ok(PayloadStatus::Valid) => StatusVote::Valid,
+            Ok(PayloadStatus::Invalid { .. }) | Ok(PayloadStatus::InvalidBlockHash { .. }) => {
+                StatusVote::Invalid
+            }
+            Ok(PayloadStatus::Syncing) | Ok(PayloadStatus::Accepted) => StatusVote::Syncing,
+            Err(_) => StatusVote::Error,
+        }
+    }
+}
+
+/// Returns the outcome of the vote once `votes` decide it, regardless of how the `pending`
+/// engines which have yet to respond vote.
+///
+/// An outcome of `StatusVote::Error` means that every engine errored.
+pub fn decided_outcome(
+    policy: PayloadStatusPolicy,
+    votes: &[StatusVote],
+    pending: usize,
+) -> Option<StatusVote> {
+    // The outcome only depends on the number of votes in each class, so try every way the
+    // pending engines could split between the classes.
+    let mut outcome = None;
+    for valid in 0..=pending {
+        for invalid in 0..=pending - valid {
+            for syncing in 0..=pending - valid - invalid {
+                let error = pending - valid - invalid - syncing;
+                let mut all_votes = votes.to_vec();
+                all_votes.extend(std::iter::repeat(StatusVote::Valid).take(valid));
+                all_votes.extend(std::iter::repeat(StatusVote::Invalid).take(invalid));
+                all_votes.extend(std::iter::repeat(StatusVote::Syncing).take(syncing));
+                all_votes.extend(std::iter::repeat(StatusVote::Error).take(error));
+
+                let possible = vote_outcome(policy, &all_votes);
+                match outcome {
+                    None => outcome = Some(possible),
+                    Some(outcome) if outcome != possible => return None,
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    outcome
+}
+
+/// Returns the first status in `statuses` which matches `outcome`, or `SYNCING` if there is none.
+pub fn select_payload_status(
+    outcome: StatusVote,
+    statuses: Vec<Result<PayloadStatus, EngineError>>,
+) -> Result<PayloadStatus, EngineError> {
+    statuses
+        .into_iter()
+        .find(|status| StatusVote::new(status) == outcome)
+        .unwrap_or(Ok(PayloadStatus::Syncing))
+}
+
+/// The outcome of the vote between the engines which returned `votes`, according to `policy`.
+///
+/// Engines which errored do not take part in the vote, and the outcome is `StatusVote::Error`
+/// only if every engine errored. When there is no decisive vote the payload is treated as
+/// `SYNCING`, so that the beacon node imports it optimistically rather than following a single
+/// engine's verdict.
+///
+/// With a single engine the outcome is always that engine's vote.
+fn vote_outcome(policy: PayloadStatusPolicy, votes: &[StatusVote]) -> StatusVote {
+    let count = |class| votes.iter().filter(|vote| **vote == class).count();
+    let valid = count(StatusVote::Valid);
+    let invalid = count(StatusVote::Invalid);
+    let responded = votes.len() - count(StatusVote::Error);
+
+    if responded == 0 {
+        return StatusVote::Error;
+    }
+
+    match policy {
+        PayloadStatusPolicy::AnyValid if valid > 0 => StatusVote::Valid,
+        PayloadStatusPolicy::AnyValid if invalid > 0 => StatusVote::Invalid,
+        PayloadStatusPolicy::Majority if valid * 2 > responded => StatusVote::Valid,
+        PayloadStatusPolicy::Majority if invalid * 2 > responded => StatusVote::Invalid,
+        PayloadStatusPolicy::All if invalid > 0 => StatusVote::Invalid,
+        PayloadStatusPolicy::All if valid == votes.len() => StatusVote::Valid,
+        _ => StatusVote::Syncing,
+    }
+}
+
 /// Processes the response from the execution engine.
 pub fn process_payload_status(
     head_block_hash: ExecutionBlockHash,
@@ -108,3 +227,136 @@ pub fn process_payload_status(
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid() -> PayloadStatus {
+        PayloadStatus::Invalid {
+            latest_valid_hash: None,
+            validation_error: None,
+        }
+    }
+
+    /// Combine `statuses`, where `None` represents an engine error.
+    fn combine(
+        policy: PayloadStatusPolicy,
+        statuses: Vec<Option<PayloadStatus>>,
+    ) -> Option<PayloadStatus> {
+        let statuses = statuses
+            .into_iter()
+            .map(|status| status.ok_or(EngineError::Offline))
+            .collect::<Vec<_>>();
+        let votes = statuses.iter().map(StatusVote::new).collect::<Vec<_>>();
+        select_payload_status(vote_outcome(policy, &votes), statuses).ok()
+    }
+
+    #[test]
+    fn single_engine_is_unchanged() {
+        for policy in [
+            PayloadStatusPolicy::AnyValid,
+            PayloadStatusPolicy::Majority,
+            PayloadStatusPolicy::All,
+        ] {
+            for status in [
+                Some(PayloadStatus::Valid),
+                Some(invalid()),
+                Some(PayloadStatus::Syncing),
+                Some(PayloadStatus::Accepted),
+                None,
+            ] {
+                assert_eq!(combine(policy, vec![status.clone()]), status);
+            }
+        }
+    }
+
+    #[test]
+    fn majority() {
+        let policy = PayloadStatusPolicy::Majority;
+        let valid = Some(PayloadStatus::Valid);
+        assert_eq!(
+            combine(policy, vec![valid.clone(), valid.clone(), Some(invalid())]),
+            valid
+        );
+        assert_eq!(
+            combine(
+                policy,
+                vec![valid.clone(), Some(invalid()), Some(invalid())]
+            ),
+            Some(invalid())
+        );
+        assert_eq!(
+            combine(policy, vec![valid.clone(), Some(invalid())]),
+            Some(PayloadStatus::Syncing)
+        );
+        // Errors do not take part in the vote.
+        assert_eq!(combine(policy, vec![valid.clone(), None]), valid);
+    }
+
+    #[test]
+    fn any_valid() {
+        let policy = PayloadStatusPolicy::AnyValid;
+        assert_eq!(
+            combine(
+                policy,
+                vec![
+                    Some(invalid()),
+                    Some(PayloadStatus::Syncing),
+                    Some(PayloadStatus::Valid)
+                ]
+            ),
+            Some(PayloadStatus::Valid)
+        );
+        assert_eq!(
+            combine(policy, vec![Some(PayloadStatus::Syncing), Some(invalid())]),
+            Some(invalid())
+        );
+    }
+
+    #[test]
+    fn all() {
+        let policy = PayloadStatusPolicy::All;
+        let valid = Some(PayloadStatus::Valid);
+        assert_eq!(combine(policy, vec![valid.clone(), valid.clone()]), valid);
+        assert_eq!(
+            combine(policy, vec![valid.clone(), None]),
+            Some(PayloadStatus::Syncing)
+        );
+        assert_eq!(
+            combine(
+                policy,
+                vec![valid, Some(PayloadStatus::Accepted), Some(invalid())]
+            ),
+            Some(invalid())
+        );
+    }
+
+    #[test]
+    fn decided_before_all_engines_respond() {
+        use StatusVote::*;
+
+        // Two of three engines agreeing is decisive for the majority.
+        let policy = PayloadStatusPolicy::Majority;
+        assert_eq!(decided_outcome(policy, &[Valid, Valid], 1), Some(Valid));
+        assert_eq!(
+            decided_outcome(policy, &[Invalid, Invalid], 1),
+            Some(Invalid)
+        );
+        assert_eq!(decided_outcome(policy, &[Valid, Invalid], 1), None);
+        // The remaining engines could error, leaving the responding engine as the majority.
+        assert_eq!(decided_outcome(policy, &[Valid], 2), None);
+
+        let policy = PayloadStatusPolicy::AnyValid;
+        assert_eq!(decided_outcome(policy, &[Valid], 2), Some(Valid));
+        assert_eq!(decided_outcome(policy, &[Invalid], 2), None);
+
+        let policy = PayloadStatusPolicy::All;
+        assert_eq!(decided_outcome(policy, &[Invalid], 2), Some(Invalid));
+        assert_eq!(decided_outcome(policy, &[Syncing], 2), None);
+        assert_eq!(decided_outcome(policy, &[Valid, Valid], 1), None);
+
+        // Once every engine has responded the outcome is always decided.
+        assert_eq!(decided_outcome(policy, &[Error, Error], 0), Some(Error));
+    }
+}
@@ -0,0 +1,465 @@
+//! Provides a set of builder relays which are queried concurrently for bids.
+//!
+//! Validator registrations are sent to every relay. Bids are requested from every relay at the
+//! same time and each relay which supplies a winning bid is remembered, so that the signed blinded
+//! block is only ever revealed to the relay which built it. A signed blinded block whose relay is
+//! not known is never revealed.
+//!
+//! Each relay has a reputation which is reduced whenever it misbehaves. A relay which crosses
+//! `RELAY_FAULT_THRESHOLD` faults within `RELAY_FAULT_WINDOW`, or which commits a fault that may
+//! cause a missed proposal, is disabled for `RELAY_COOL_DOWN`.
+use crate::metrics;
+use builder_client::{BuilderHttpClient, Error};
+use eth2::lighthouse::BuilderRelayStatus;
+use eth2::types::builder_bid::SignedBuilderBid;
+use eth2::types::{
+    EthSpec, ExecutionBlockHash, ForkVersionedResponse, FullPayloadContents, PublicKeyBytes,
+    SignedBlindedBeaconBlock, SignedValidatorRegistrationData, Slot,
+};
+use eth2::StatusCode;
+use futures::future::join_all;
+use lru::LruCache;
+use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
+use slog::{debug, warn, Logger};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use strum::AsRefStr;
+use types::non_zero_usize::new_non_zero_usize;
+use types::ExecPayload;
+
+/// The number of winning bids for which the supplying relay is remembered.
+const WINNING_BIDS_LRU_CACHE_SIZE: std::num::NonZeroUsize = new_non_zero_usize(64);
+
+/// The number of faults within `RELAY_FAULT_WINDOW` which cause a relay to be disabled.
+pub const RELAY_FAULT_THRESHOLD: usize = 3;
+/// The period over which faults are counted towards `RELAY_FAULT_THRESHOLD`.
+pub const RELAY_FAULT_WINDOW: Duration = Duration::from_secs(60 * 60);
+/// The period for which a relay is disabled once it trips the circuit breaker.
+pub const RELAY_COOL_DOWN: Duration = Duration::from_secs(60 * 60);
+
+/// Misbehaviour by a relay which counts against its reputation.
+#[derive(Debug, Clone, Copy, PartialEq, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum RelayFault {
+    /// The relay failed to reveal the payload for a signed blinded block.
+    WithheldPayload,
+    /// The relay returned a bid which failed verification.
+    InvalidBid,
+    /// The relay did not respond to a request for a bid before the timeout.
+    LateResponse,
+    /// The payload or blobs revealed by the relay did not match the block it bid.
+    PayloadMismatch,
+    /// The payment to the fee recipient of an imported block was less than the value of its bid.
+    ValueShortfall,
+}
+
+impl RelayFault {
+    /// Faults after a blinded block has been signed cause a missed proposal, so they disable the
+    /// relay immediately.
+    fn is_severe(self) -> bool {
+        matches!(
+            self,
+            RelayFault::WithheldPayload | RelayFault::PayloadMismatch
+        )
+    }
+
+    /// A shortfall is only detected once the block has been imported and does not cost the
+    /// proposal, so it is reported without counting towards `RELAY_FAULT_THRESHOLD`.
+    fn counts_towards_threshold(self) -> bool {
+        !matches!(self, RelayFault::ValueShortfall)
+    }
+}
+
+#[derive(Default)]
+struct RelayHealth {
+    recent_faults: VecDeque<Instant>,
+    disabled_until: Option<Instant>,
+    times_disabled: u64,
+    valid_bids: u64,
+    withheld_payloads: u64,
+    invalid_bids: u64,
+    late_responses: u64,
+    payload_mismatches: u64,
+    value_shortfalls: u64,
+}
+
+impl RelayHealth {
+    fn is_enabled(&self, now: Instant) -> bool {
+        self.disabled_until.map_or(true, |until| now >= until)
+    }
+
+    fn recent_faults(&self, now: Instant) -> usize {
+        self.recent_faults
+            .iter()
+            .filter(|time| now.saturating_duration_since(**time) <= RELAY_FAULT_WINDOW)
+            .count()
+    }
+
+    /// Record `fault`, returning `true` if it caused the relay to be disabled.
+    fn record_fault(&mut self, fault: RelayFault, now: Instant) -> bool {
+        match fault {
+            RelayFault::WithheldPayload => self.withheld_payloads += 1,
+            RelayFault::InvalidBid => self.invalid_bids += 1,
+            RelayFault::LateResponse => self.late_responses += 1,
+            RelayFault::PayloadMismatch => self.payload_mismatches += 1,
+            RelayFault::ValueShortfall => self.value_shortfalls += 1,
+        }
+        if !fault.counts_towards_threshold() {
+            return false;
+        }
+
+        while self.recent_faults.front().map_or(false, |time| {
+            now.saturating_duration_since(*time) > RELAY_FAULT_WINDOW
+        }) {
+            self.recent_faults.pop_front();
+        }
+        self.recent_faults.push_back(now);
+
+        if self.is_enabled(now)
+            && (fault.is_severe() || self.recent_faults.len() >= RELAY_FAULT_THRESHOLD)
+        {
+            self.disabled_until = Some(now + RELAY_COOL_DOWN);
+            self.times_disabled += 1;
+            self.recent_faults.clear();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A single builder relay.
+pub struct Relay {
+    client: BuilderHttpClient,
+    /// The redacted URL of the relay, safe for use in logs and metric labels.
+    name: String,
+    health: Mutex<RelayHealth>,
+}
+
+impl Relay {
+    pub fn client(&self) -> &BuilderHttpClient {
+        &self.client
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `false` if the relay is disabled by the circuit breaker.
+    pub fn is_enabled(&self) -> bool {
+        self.health.lock().is_enabled(Instant::now())
+    }
+
+    /// Record a valid bid from this relay.
+    pub fn record_valid_bid(&self) {
+        self.health.lock().valid_bids += 1;
+    }
+
+    /// Record `fault` against this relay, disabling it if it has crossed the threshold.
+    pub fn record_fault(&self, fault: RelayFault, log: &Logger) {
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_RELAY_FAULTS,
+            &[self.name(), fault.as_ref()],
+        );
+        if self.health.lock().record_fault(fault, Instant::now()) {
+            metrics::set_gauge_vec(&metrics::EXECUTION_LAYER_RELAY_ENABLED, &[self.name()], 0);
+            warn!(
+                log,
+                "Disabling builder relay";
+                "info" => "the relay will not be queried for bids until the cool-down expires",
+                "relay" => self.name(),
+                "fault" => ?fault,
+                "cool_down_secs" => RELAY_COOL_DOWN.as_secs(),
+            );
+        }
+    }
+
+    pub fn status(&self) -> BuilderRelayStatus {
+        let now = Instant::now();
+        let health = self.health.lock();
+        BuilderRelayStatus {
+            relay: self.name.clone(),
+            enabled: health.is_enabled(now),
+            disabled_for_secs: health
+                .disabled_until
+                .filter(|until| *until > now)
+                .map(|until| until.saturating_duration_since(now).as_secs()),
+            recent_faults: health.recent_faults(now) as u64,
+            times_disabled: health.times_disabled,
+            valid_bids: health.valid_bids,
+            withheld_payloads: health.withheld_payloads,
+            invalid_bids: health.invalid_bids,
+            late_responses: health.late_responses,
+            payload_mismatches: health.payload_mismatches,
+            value_shortfalls: health.value_shortfalls,
+        }
+    }
+}
+
+/// The response from a single relay to a `get_builder_header` request.
+pub struct RelayBid<E: EthSpec> {
+    pub relay: Arc<Relay>,
+    pub result: Result<Option<ForkVersionedResponse<SignedBuilderBid<E>>>, Error>,
+    pub duration: Duration,
+}
+
+pub struct Relays {
+    relays: Vec<Arc<Relay>>,
+    /// Maps the block hash of each winning bid to the relay which supplied it.
+    winning_bids: Mutex<LruCache<ExecutionBlockHash, Arc<Relay>>>,
+    log: Logger,
+}
+
+impl Relays {
+    pub fn new(
+        urls: Vec<SensitiveUrl>,
+        user_agent: Option<String>,
+        get_header_timeout: Option<Duration>,
+        log: Logger,
+    ) -> Result<Self, Error> {
+        let relays = urls
+            .into_iter()
+            .map(|url| {
+                let mut client = BuilderHttpClient::new(url.clone(), user_agent.clone())?;
+                if let Some(timeout) = get_header_timeout {
+                    client = client.with_get_header_timeout(timeout);
+                }
+                let name = url.to_string();
+                metrics::set_gauge_vec(&metrics::EXECUTION_LAYER_RELAY_ENABLED, &[&name], 1);
+                Ok(Arc::new(Relay {
+                    client,
+                    name,
+                    health: Mutex::new(RelayHealth::default()),
+                }))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            relays,
+            winning_bids: Mutex::new(LruCache::new(WINNING_BIDS_LRU_CACHE_SIZE)),
+            log,
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Relay>> {
+        self.relays.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.relays.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.relays.is_empty()
+    }
+
+    /// Returns the reputation of every relay.
+    pub fn status(&self) -> Vec<BuilderRelayStatus> {
+        self.relays.iter().map(|relay| relay.status()).collect()
+    }
+
+    /// Register validators with every relay.
+    ///
+    /// Succeeds if at least one relay accepted the registrations, otherwise returns the error from
+    /// the first relay.
+    pub async fn post_builder_validators(
+        &self,
+        validators: &[SignedValidatorRegistrationData],
+    ) -> Result<(), Error> {
+        let results = join_all(
+            self.relays
+                .iter()
+                .map(|relay| relay.client.post_builder_validators(validators)),
+        )
+        .await;
+
+        first_success(results)
+    }
+
+    /// Request a bid from every enabled relay concurrently.
+    ///
+    /// Each relay is bounded by its own `get_header` timeout, so this function returns once every
+    /// relay has responded or timed out.
+    pub async fn get_builder_headers<E: EthSpec>(
+        &self,
+        slot: Slot,
+        parent_hash: ExecutionBlockHash,
+        pubkey: &PublicKeyBytes,
+    ) -> Vec<RelayBid<E>> {
+        let enabled_relays = self.relays.iter().filter(|relay| {
+            let enabled = relay.is_enabled();
+            metrics::set_gauge_vec(
+                &metrics::EXECUTION_LAYER_RELAY_ENABLED,
+                &[relay.name()],
+                enabled as i64,
+            );
+            if !enabled {
+                debug!(
+                    self.log,
+                    "Skipping disabled builder relay";
+                    "relay" => relay.name(),
+                    "slot" => slot,
+                );
+            }
+            enabled
+        });
+
+        join_all(enabled_relays.map(|relay| async move {
+            let start = Instant::now();
+            let result = relay
+                .client
+                .get_builder_header::<E>(slot, parent_hash, pubkey)
+                .await;
+            let duration = start.elapsed();
+
+            let outcome = match &result {
+                Ok(Some(_)) => metrics::RELAY_BID,
+                Ok(None) => metrics::RELAY_NO_BID,
+                Err(Error::HttpClient(e)) if e.inner().is_timeout() => {
+                    relay.record_fault(RelayFault::LateResponse, &self.log);
+                    metrics::RELAY_TIMEOUT
+                }
+                Err(_) => metrics::RELAY_ERROR,
+            };
+            metrics::inc_counter_vec(
+                &metrics::EXECUTION_LAYER_RELAY_BID_OUTCOME,
+                &[relay.name(), outcome],
+            );
+            metrics::observe_timer_vec(
+                &metrics::EXECUTION_LAYER_RELAY_RESPONSE_TIMES,
+                &[relay.name()],
+                duration,
+            );
+
+            RelayBid {
+                relay: relay.clone(),
+                result,
+                duration,
+            }
+        }))
+        .await
+    }
+
+    /// Remember that `relay` supplied the winning bid for the payload with `block_hash`.
+    pub fn record_winning_bid(&self, block_hash: ExecutionBlockHash, relay: Arc<Relay>) {
+        metrics::inc_counter_vec(&metrics::EXECUTION_LAYER_RELAY_WINS, &[relay.name()]);
+        self.winning_bids.lock().put(block_hash, relay);
+    }
+
+    /// Returns the relay which supplied the winning bid for the payload with `block_hash`.
+    pub fn winning_relay(&self, block_hash: &ExecutionBlockHash) -> Option<Arc<Relay>> {
+        self.winning_bids.lock().get(block_hash).cloned()
+    }
+
+    /// Remember the winning bids of `previous` whose relays are also in `self`, so that blocks
+    /// produced before the relays were replaced can still be revealed.
+    pub fn inherit_winning_bids(&self, previous: &Relays) {
+        let previous_bids = previous.winning_bids.lock();
+        let mut winning_bids = self.winning_bids.lock();
+        // Iterate from least to most recently used so that the recency order is preserved.
+        for (block_hash, previous_relay) in previous_bids.iter().rev() {
+            if let Some(relay) = self
+                .relays
+                .iter()
+                .find(|relay| relay.name() == previous_relay.name())
+            {
+                winning_bids.put(*block_hash, relay.clone());
+            }
+        }
+    }
+
+    /// Reveal a signed blinded block to the relay which supplied its bid.
+    ///
+    /// A failure to reveal the payload is recorded against that relay. If the relay is unknown
+    /// (e.g. the node restarted after producing the block) then an error is returned, since
+    /// revealing the block to any other relay would leak the signed header to relays which did not
+    /// build it.
+    pub async fn post_builder_blinded_blocks<E: EthSpec>(
+        &self,
+        block: &SignedBlindedBeaconBlock<E>,
+    ) -> Result<ForkVersionedResponse<FullPayloadContents<E>>, crate::Error> {
+        let block_hash = block
+            .message()
+            .execution_payload()
+            .map_err(|_| crate::Error::InvalidForkForPayload)?
+            .block_hash();
+        let relay = self
+            .winning_relay(&block_hash)
+            .ok_or(crate::Error::UnknownWinningRelay(block_hash))?;
+
+        let result = relay.client.post_builder_blinded_blocks(block).await;
+        if result.is_err() {
+            relay.record_fault(RelayFault::WithheldPayload, &self.log);
+        }
+        result.map_err(crate::Error::Builder)
+    }
+}
+
+/// Returns the first successful result, or the first error if every relay failed.
+fn first_success<T>(results: Vec<Result<T, Error>>) -> Result<T, Error> {
+    results
+        .into_iter()
+        .reduce(|acc, result| acc.or_else(|e| result.map_err(|_| e)))
+        .unwrap_or(Err(Error::StatusCode(StatusCode::SERVICE_UNAVAILABLE)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn severe_fault_disables_immediately() {
+        let now = Instant::now();
+        let mut health = RelayHealth::default();
+
+        assert!(health.record_fault(RelayFault::WithheldPayload, now));
+        assert!(!health.is_enabled(now));
+        assert!(health.is_enabled(now + RELAY_COOL_DOWN));
+        assert_eq!(health.times_disabled, 1);
+        assert_eq!(health.withheld_payloads, 1);
+    }
+
+    #[test]
+    fn minor_faults_disable_at_threshold() {
+        let now = Instant::now();
+        let mut health = RelayHealth::default();
+
+        for i in 0..RELAY_FAULT_THRESHOLD - 1 {
+            let time = now + Duration::from_secs(i as u64);
+            assert!(!health.record_fault(RelayFault::InvalidBid, time));
+            assert!(health.is_enabled(time));
+        }
+        assert!(health.record_fault(RelayFault::LateResponse, now));
+        assert!(!health.is_enabled(now));
+    }
+
+    #[test]
+    fn value_shortfalls_do_not_disable() {
+        let now = Instant::now();
+        let mut health = RelayHealth::default();
+
+        for i in 0..RELAY_FAULT_THRESHOLD {
+            let time = now + Duration::from_secs(i as u64);
+            assert!(!health.record_fault(RelayFault::ValueShortfall, time));
+            assert!(health.is_enabled(time));
+        }
+        assert_eq!(health.value_shortfalls, RELAY_FAULT_THRESHOLD as u64);
+        assert_eq!(health.recent_faults(now), 0);
+    }
+
+    #[test]
+    fn faults_outside_window_are_forgotten() {
+        let now = Instant::now();
+        let mut health = RelayHealth::default();
+
+        for i in 0..RELAY_FAULT_THRESHOLD * 2 {
+            let time = now + (RELAY_FAULT_WINDOW + Duration::from_secs(1)) * i as u32;
+            assert!(!health.record_fault(RelayFault::LateResponse, time));
+            assert_eq!(health.recent_faults(time), 1);
+        }
+        assert_eq!(health.late_responses, RELAY_FAULT_THRESHOLD as u64 * 2);
+        assert_eq!(health.times_disabled, 0);
+    }
+}
@@ -5,7 +5,8 @@ use crate::{
         json_structures::{
             JsonForkchoiceUpdatedV1Response, JsonPayloadStatusV1, JsonPayloadStatusV1Status,
         },
-        ExecutionBlock, PayloadAttributes, PayloadId, PayloadStatusV1, PayloadStatusV1Status,
+        BlobAndProof, ExecutionBlock, PayloadAttributes, PayloadId, PayloadStatusV1,
+        PayloadStatusV1Status,
     },
     ExecutionBlockWithTransactions,
 };
@@ -21,12 +22,12 @@ use std::sync::Arc;
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 use types::{
-    Blob, ChainSpec, EthSpec, ExecutionBlockHash, ExecutionPayload, ExecutionPayloadCapella,
-    ExecutionPayloadDeneb, ExecutionPayloadHeader, ExecutionPayloadMerge, ForkName, Hash256,
-    Transaction, Transactions, Uint256,
+    Address, Blob, ChainSpec, EthSpec, ExecutionBlockHash, ExecutionPayload,
+    ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadHeader, ExecutionPayloadMerge,
+    ForkName, Hash256, Transaction, Transactions, Uint256, VersionedHash,
 };
 
-use super::DEFAULT_TERMINAL_BLOCK;
+use super::{DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI, DEFAULT_TERMINAL_BLOCK};
 
 const TEST_BLOB_BUNDLE: &[u8] = include_bytes!("fixtures/mainnet/test_blobs_bundle.ssz");
 
@@ -136,6 +137,8 @@ pub struct ExecutionBlockGenerator<T: EthSpec> {
      * deneb stuff
      */
     pub blobs_bundles: HashMap<PayloadId, BlobsBundle<T>>,
+    /// Blobs which the engine has seen in blob transactions, served by `engine_getBlobsV1`.
+    pub blob_pool: HashMap<VersionedHash, BlobAndProof<T>>,
     pub kzg: Option<Arc<Kzg>>,
     rng: Arc<Mutex<StdRng>>,
 }
@@ -169,6 +172,7 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
             shanghai_time,
             cancun_time,
             blobs_bundles: <_>::default(),
+            blob_pool: <_>::default(),
             kzg: kzg.map(Arc::new),
             rng: make_rng(),
         };
@@ -226,6 +230,32 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
             .map(|block| block.as_execution_block(self.terminal_total_difficulty))
     }
 
+    /// Returns the balance of `address` after the block `block_hash`.
+    ///
+    /// Each post-merge block pays `DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI` to its fee recipient and no
+    /// other balance changes are modelled.
+    pub fn balance_at(&self, address: Address, block_hash: ExecutionBlockHash) -> Option<Uint256> {
+        let mut balance = Uint256::zero();
+        let mut block = self.block_by_hash(block_hash).or_else(|| {
+            self.pending_payloads
+                .get(&block_hash)
+                .cloned()
+                .map(Block::PoS)
+        })?;
+
+        while let Block::PoS(payload) = &block {
+            if payload.fee_recipient() == address {
+                balance += Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI);
+            }
+            match self.block_by_hash(payload.parent_hash()) {
+                Some(parent) => block = parent,
+                None => break,
+            }
+        }
+
+        Some(balance)
+    }
+
     pub fn execution_block_with_txs_by_hash(
         &self,
         hash: ExecutionBlockHash,
@@ -261,6 +291,26 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
         self.insert_pow_blocks(next_block..=target_block)
     }
 
+    /// Remove the last `depth` blocks of the canonical chain, moving the head to their ancestor.
+    ///
+    /// This simulates the execution engine re-orging to a shorter chain. Removed payloads must be
+    /// sent to the engine again before they can become the head.
+    pub fn rewind(&mut self, depth: u64) -> Result<(), String> {
+        for _ in 0..depth {
+            let head = self.latest_block().ok_or("no head block")?;
+            let parent = self
+                .block_by_hash(head.parent_hash())
+                .ok_or_else(|| format!("unable to rewind past block {}", head.block_number()))?;
+            self.blocks.remove(&head.block_hash());
+            if let Some(block_hashes) = self.block_hashes.get_mut(&head.block_number()) {
+                block_hashes.retain(|block_hash| *block_hash != head.block_hash());
+            }
+            self.head_block = Some(parent);
+        }
+
+        Ok(())
+    }
+
     pub fn drop_all_blocks(&mut self) {
         self.blocks = <_>::default();
         self.block_hashes = <_>::default();
@@ -425,6 +475,22 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
         self.blobs_bundles.get(id).cloned()
     }
 
+    /// Returns the pooled blob for each of `versioned_hashes`, if any.
+    pub fn get_pooled_blobs(
+        &self,
+        versioned_hashes: &[VersionedHash],
+    ) -> Vec<Option<BlobAndProof<T>>> {
+        versioned_hashes
+            .iter()
+            .map(|versioned_hash| self.blob_pool.get(versioned_hash).cloned())
+            .collect()
+    }
+
+    /// Forget all pooled blobs, as if they had been evicted from the engine's mempool.
+    pub fn clear_blob_pool(&mut self) {
+        self.blob_pool.clear();
+    }
+
     pub fn new_payload(&mut self, payload: ExecutionPayload<T>) -> PayloadStatusV1 {
         let Some(parent) = self.blocks.get(&payload.parent_hash()) else {
             return PayloadStatusV1 {
@@ -635,6 +701,21 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
                         .push(tx)
                         .map_err(|_| "transactions are full".to_string())?;
                 }
+                // The blob transactions would have been in the engine's mempool.
+                for ((commitment, proof), blob) in bundle
+                    .commitments
+                    .iter()
+                    .zip(bundle.proofs.iter())
+                    .zip(bundle.blobs.iter())
+                {
+                    self.blob_pool.insert(
+                        commitment.calculate_versioned_hash(),
+                        BlobAndProof {
+                            blob: blob.clone(),
+                            proof: *proof,
+                        },
+                    );
+                }
                 self.blobs_bundles.insert(id, bundle);
             }
         }
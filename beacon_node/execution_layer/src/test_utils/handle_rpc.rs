@@ -5,7 +5,7 @@ use crate::test_utils::DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
 use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
-use types::{EthSpec, ForkName};
+use types::{EthSpec, ForkName, VersionedHash};
 
 pub const GENERIC_ERROR_CODE: i64 = -1234;
 pub const BAD_PARAMS_ERROR_CODE: i64 = -32602;
@@ -93,6 +93,33 @@ pub async fn handle_rpc<T: EthSpec>(
                 .unwrap())
             }
         }
+        ETH_GET_BALANCE => {
+            let address =
+                get_param::<Address>(params, 0).map_err(|s| (s, BAD_PARAMS_ERROR_CODE))?;
+            let block_hash = params
+                .get(1)
+                .and_then(|block| block.get("blockHash"))
+                .and_then(JsonValue::as_str)
+                .ok_or_else(|| "missing/invalid params[1].blockHash value".to_string())
+                .and_then(|s| {
+                    s.parse()
+                        .map_err(|e| format!("unable to parse hash: {:?}", e))
+                })
+                .map_err(|s| (s, BAD_PARAMS_ERROR_CODE))?;
+
+            let balance = ctx
+                .execution_block_generator
+                .read()
+                .balance_at(address, block_hash)
+                .ok_or_else(|| {
+                    (
+                        format!("unknown block hash {:?}", block_hash),
+                        GENERIC_ERROR_CODE,
+                    )
+                })?;
+
+            Ok(serde_json::to_value(JsonBalance(balance)).unwrap())
+        }
         ENGINE_NEW_PAYLOAD_V1 | ENGINE_NEW_PAYLOAD_V2 | ENGINE_NEW_PAYLOAD_V3 => {
             let request = match method {
                 ENGINE_NEW_PAYLOAD_V1 => JsonExecutionPayload::V1(
@@ -512,6 +539,20 @@ pub async fn handle_rpc<T: EthSpec>(
 
             Ok(serde_json::to_value(response).unwrap())
         }
+        ENGINE_GET_BLOBS_V1 => {
+            let versioned_hashes: Vec<VersionedHash> =
+                get_param(params, 0).map_err(|s| (s, BAD_PARAMS_ERROR_CODE))?;
+
+            let response: Vec<_> = ctx
+                .execution_block_generator
+                .read()
+                .get_pooled_blobs(&versioned_hashes)
+                .into_iter()
+                .map(|blob_and_proof| blob_and_proof.map(JsonBlobAndProofV1::<T>::from))
+                .collect();
+
+            Ok(serde_json::to_value(response).unwrap())
+        }
         other => Err((
             format!("The method {} does not exist/is not available", other),
             METHOD_NOT_FOUND_CODE,
@@ -1,6 +1,7 @@
+use crate::test_utils::execution_block_generator::load_test_blobs_bundle;
 use crate::test_utils::{DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_JWT_SECRET};
 use crate::{Config, ExecutionLayer, PayloadAttributes};
-use eth2::types::{BlobsBundle, BlockId, StateId, ValidatorId};
+use eth2::types::{BlobsBundle, BlockId, FullPayloadContents, StateId, ValidatorId};
 use eth2::{BeaconNodeHttpClient, Timeouts};
 use fork_choice::ForkchoiceUpdateParameters;
 use parking_lot::RwLock;
@@ -199,7 +200,11 @@ pub struct MockBuilder<E: EthSpec> {
     val_registration_cache: Arc<RwLock<HashMap<PublicKeyBytes, SignedValidatorRegistrationData>>>,
     builder_sk: SecretKey,
     operations: Arc<RwLock<Vec<Operation>>>,
+    persistent_operations: Arc<RwLock<Vec<Operation>>>,
     invalidate_signatures: Arc<RwLock<bool>>,
+    withhold_payloads: Arc<RwLock<bool>>,
+    mismatch_blobs_bundles: Arc<RwLock<bool>>,
+    blinded_blocks_received: Arc<RwLock<usize>>,
 }
 
 impl<E: EthSpec> MockBuilder<E> {
@@ -249,7 +254,11 @@ impl<E: EthSpec> MockBuilder<E> {
             val_registration_cache: Arc::new(RwLock::new(HashMap::new())),
             builder_sk: sk,
             operations: Arc::new(RwLock::new(vec![])),
+            persistent_operations: Arc::new(RwLock::new(vec![])),
             invalidate_signatures: Arc::new(RwLock::new(false)),
+            withhold_payloads: Arc::new(RwLock::new(false)),
+            mismatch_blobs_bundles: Arc::new(RwLock::new(false)),
+            blinded_blocks_received: Arc::new(RwLock::new(0)),
         }
     }
 
@@ -259,6 +268,11 @@ impl<E: EthSpec> MockBuilder<E> {
         self.operations.write().insert(0, op);
     }
 
+    /// Apply `op` to every bid, after any operations added with `add_operation`.
+    pub fn add_persistent_operation(&self, op: Operation) {
+        self.persistent_operations.write().push(op);
+    }
+
     pub fn invalid_signatures(&self) {
         *self.invalidate_signatures.write() = true;
     }
@@ -267,11 +281,29 @@ impl<E: EthSpec> MockBuilder<E> {
         *self.invalidate_signatures.write() = false;
     }
 
+    /// Refuse to reveal the payload for any signed blinded block.
+    pub fn withhold_payloads(&self) {
+        *self.withhold_payloads.write() = true;
+    }
+
+    /// Reveal a blobs bundle which does not match the commitments in the signed blinded block.
+    pub fn mismatch_blobs_bundles(&self) {
+        *self.mismatch_blobs_bundles.write() = true;
+    }
+
+    /// The number of signed blinded blocks which have been sent to this builder.
+    pub fn blinded_blocks_received(&self) -> usize {
+        *self.blinded_blocks_received.read()
+    }
+
     fn apply_operations<B: BidStuff<E>>(&self, bid: &mut B) {
         let mut guard = self.operations.write();
         while let Some(op) = guard.pop() {
             op.apply(bid);
         }
+        for op in self.persistent_operations.read().iter() {
+            op.clone().apply(bid);
+        }
     }
 }
 
@@ -314,6 +346,7 @@ pub fn serve<E: EthSpec>(
         .and(ctx_filter.clone())
         .and_then(
             |block: SignedBlindedBeaconBlock<E>, builder: MockBuilder<E>| async move {
+                *builder.blinded_blocks_received.write() += 1;
                 let slot = block.slot();
                 let root = match block {
                     SignedBlindedBeaconBlock::Base(_) | types::SignedBeaconBlock::Altair(_) => {
@@ -330,11 +363,21 @@ pub fn serve<E: EthSpec>(
                     }
                 };
 
+                if *builder.withhold_payloads.read() {
+                    return Err(reject("payload withheld"));
+                }
+
                 let fork_name = builder.spec.fork_name_at_slot::<E>(slot);
-                let payload = builder
+                let mut payload = builder
                     .el
                     .get_payload_by_root(&root)
                     .ok_or_else(|| reject("missing payload for tx root"))?;
+
+                if *builder.mismatch_blobs_bundles.read() {
+                    if let FullPayloadContents::PayloadAndBlobs(payload_and_blobs) = &mut payload {
+                        mismatch_blobs_bundle(&mut payload_and_blobs.blobs_bundle)?;
+                    }
+                }
                 let resp: ForkVersionedResponse<_> = ForkVersionedResponse {
                     version: Some(fork_name),
                     metadata: Default::default(),
@@ -644,6 +687,32 @@ pub fn serve<E: EthSpec>(
     Ok((listening_socket, server))
 }
 
+/// Remove the last blob from `bundle`, or add a blob if it is empty, so that it no longer matches
+/// the commitments in the block.
+fn mismatch_blobs_bundle<E: EthSpec>(bundle: &mut BlobsBundle<E>) -> Result<(), Rejection> {
+    if bundle.commitments.is_empty() {
+        let (commitment, proof, blob) =
+            load_test_blobs_bundle::<E>().map_err(|_| reject("couldn't load test blob"))?;
+        bundle
+            .commitments
+            .push(commitment)
+            .and_then(|()| bundle.proofs.push(proof))
+            .and_then(|()| bundle.blobs.push(blob))
+            .map_err(|_| reject("blobs bundle is full"))?;
+    } else {
+        let mut commitments = Vec::from(bundle.commitments.clone());
+        let mut proofs = Vec::from(bundle.proofs.clone());
+        let mut blobs = Vec::from(bundle.blobs.clone());
+        commitments.pop();
+        proofs.pop();
+        blobs.pop();
+        bundle.commitments = commitments.into();
+        bundle.proofs = proofs.into();
+        bundle.blobs = blobs.into();
+    }
+    Ok(())
+}
+
 fn reject(msg: &'static str) -> Rejection {
     warp::reject::custom(Custom(msg.to_string()))
 }
@@ -1,4 +1,4 @@
-//! Provides a mock execution engine HTTP JSON-RPC API for use in testing.
+//! Provides a mock execution engine HTTP and IPC JSON-RPC API for use in testing.
 
 use crate::engine_api::auth::JwtKey;
 use crate::engine_api::{
@@ -12,12 +12,13 @@ use kzg::Kzg;
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use slog::{info, Logger};
+use slog::{info, warn, Logger};
 use std::collections::HashMap;
 use std::convert::Infallible;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::{runtime, sync::oneshot};
 use types::{EthSpec, ExecutionBlockHash, Uint256};
@@ -31,6 +32,8 @@ pub use execution_block_generator::{
 pub use hook::Hook;
 pub use mock_builder::{MockBuilder, Operation};
 pub use mock_execution_layer::MockExecutionLayer;
+pub use replay::Replay;
+pub use scenario::{Action, ActiveScenario, Rule, Scenario};
 
 pub const DEFAULT_TERMINAL_DIFFICULTY: u64 = 6400;
 pub const DEFAULT_TERMINAL_BLOCK: u64 = 64;
@@ -49,6 +52,7 @@ pub const DEFAULT_ENGINE_CAPABILITIES: EngineCapabilities = EngineCapabilities {
     get_payload_v1: true,
     get_payload_v2: true,
     get_payload_v3: true,
+    get_blobs_v1: true,
 };
 
 mod execution_block_generator;
@@ -56,6 +60,8 @@ mod handle_rpc;
 mod hook;
 mod mock_builder;
 mod mock_execution_layer;
+mod replay;
+mod scenario;
 
 /// Configuration for the MockExecutionLayer.
 pub struct MockExecutionConfig {
@@ -84,6 +90,7 @@ impl Default for MockExecutionConfig {
 
 pub struct MockServer<T: EthSpec> {
     _shutdown_tx: oneshot::Sender<()>,
+    _ipc_shutdown_txs: Vec<oneshot::Sender<()>>,
     listen_socket_addr: SocketAddr,
     last_echo_request: Arc<RwLock<Option<Bytes>>>,
     pub ctx: Arc<Context<T>>,
@@ -143,6 +150,8 @@ impl<T: EthSpec> MockServer<T> {
             new_payload_statuses: <_>::default(),
             fcu_payload_statuses: <_>::default(),
             syncing_response: Arc::new(Mutex::new(Ok(false))),
+            replay: <_>::default(),
+            scenario: <_>::default(),
             engine_capabilities: Arc::new(RwLock::new(DEFAULT_ENGINE_CAPABILITIES)),
             _phantom: PhantomData,
         });
@@ -166,8 +175,28 @@ impl<T: EthSpec> MockServer<T> {
 
         handle.spawn(server_future);
 
+        // Serve JSON-RPC requests on `ipc_path` and echo requests on a sibling socket.
+        let mut ipc_shutdown_txs = vec![];
+        if let Some(path) = ctx.config.ipc_path.clone() {
+            for (path, echo) in [(ipc_echo_path(&path), true), (path, false)] {
+                let (ipc_shutdown_tx, ipc_shutdown_rx) = oneshot::channel();
+                let shutdown_future = async {
+                    let _ = ipc_shutdown_rx.await;
+                };
+                let serve = || serve_ipc(ctx.clone(), path, echo, shutdown_future).unwrap();
+                let server_future = if runtime::Handle::try_current().is_err() {
+                    handle.block_on(async { serve() })
+                } else {
+                    serve()
+                };
+                handle.spawn(server_future);
+                ipc_shutdown_txs.push(ipc_shutdown_tx);
+            }
+        }
+
         Self {
             _shutdown_tx: shutdown_tx,
+            _ipc_shutdown_txs: ipc_shutdown_txs,
             listen_socket_addr,
             last_echo_request,
             ctx,
@@ -216,6 +245,25 @@ impl<T: EthSpec> MockServer<T> {
         )
     }
 
+    /// Returns the `ipc://` URL of the server, if it is serving requests via IPC.
+    pub fn ipc_url(&self) -> Option<String> {
+        self.ctx
+            .config
+            .ipc_path
+            .as_ref()
+            .map(|path| format!("ipc://{}", path.display()))
+    }
+
+    /// Returns the `ipc://` URL of the socket which echoes requests, the IPC equivalent of the
+    /// `/echo` path.
+    pub fn ipc_echo_url(&self) -> Option<String> {
+        self.ctx
+            .config
+            .ipc_path
+            .as_ref()
+            .map(|path| format!("ipc://{}", ipc_echo_path(path).display()))
+    }
+
     pub fn last_echo_request(&self) -> Bytes {
         self.last_echo_request
             .write()
@@ -227,6 +275,17 @@ impl<T: EthSpec> MockServer<T> {
         self.ctx.preloaded_responses.lock().push(response)
     }
 
+    /// Serve responses from `replay` rather than generating them.
+    pub fn set_replay(&self, replay: Replay) {
+        *self.ctx.replay.lock() = Some(replay);
+    }
+
+    /// Inject the faults described by `scenario`, replacing any previous scenario. Time windows
+    /// in the scenario are measured from now.
+    pub fn set_scenario(&self, scenario: Scenario) {
+        *self.ctx.scenario.lock() = Some(ActiveScenario::new(scenario));
+    }
+
     pub fn take_previous_request(&self) -> Option<serde_json::Value> {
         self.ctx.previous_request.lock().take()
     }
@@ -517,6 +576,11 @@ pub struct Context<T: EthSpec> {
         Arc<Mutex<HashMap<ExecutionBlockHash, Result<PayloadStatusV1, String>>>>,
     pub syncing_response: Arc<Mutex<Result<bool, String>>>,
 
+    /// A recorded session to serve responses from, instead of `execution_block_generator`.
+    pub replay: Arc<Mutex<Option<Replay>>>,
+    /// Faults to inject, which take priority over replayed and generated responses.
+    pub scenario: Arc<Mutex<Option<ActiveScenario>>>,
+
     pub engine_capabilities: Arc<RwLock<EngineCapabilities>>,
     pub _phantom: PhantomData<T>,
 }
@@ -542,6 +606,8 @@ impl<T: EthSpec> Context<T> {
 pub struct Config {
     pub listen_addr: Ipv4Addr,
     pub listen_port: u16,
+    /// If set, requests are also served via IPC on a Unix domain socket at this path.
+    pub ipc_path: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -549,6 +615,7 @@ impl Default for Config {
         Self {
             listen_addr: Ipv4Addr::new(127, 0, 0, 1),
             listen_port: 0,
+            ipc_path: None,
         }
     }
 }
@@ -615,6 +682,88 @@ async fn handle_rejection(err: Rejection) -> Result<impl warp::Reply, Infallible
     Ok(warp::reply::with_status(json, code))
 }
 
+/// Returns the path of the socket which echoes requests when serving via IPC on `ipc_path`.
+fn ipc_echo_path(ipc_path: &Path) -> PathBuf {
+    let mut path = ipc_path.as_os_str().to_owned();
+    path.push(".echo");
+    path.into()
+}
+
+/// Returns the JSON-RPC response to `body`, which has the given `id`, serialized for sending.
+///
+/// Preloaded responses take priority over responses from the active scenario, which take priority
+/// over replayed responses and then responses generated by `handle_rpc`. Delays and re-orgs from
+/// the scenario are applied whichever response is used.
+async fn rpc_response<T: EthSpec>(
+    id: u64,
+    body: serde_json::Value,
+    ctx: Arc<Context<T>>,
+) -> String {
+    let preloaded_response = {
+        let mut preloaded_responses = ctx.preloaded_responses.lock();
+        if !preloaded_responses.is_empty() {
+            Some(preloaded_responses.remove(0))
+        } else {
+            None
+        }
+    };
+
+    if let Some(preloaded_response) = preloaded_response {
+        return preloaded_response.to_string();
+    }
+
+    let effects = ctx
+        .scenario
+        .lock()
+        .as_mut()
+        .map(|scenario| scenario.effects(id, &body))
+        .unwrap_or_default();
+    if effects.reorg_depth > 0 {
+        if let Err(e) = ctx
+            .execution_block_generator
+            .write()
+            .rewind(effects.reorg_depth)
+        {
+            warn!(ctx.log, "Unable to re-org execution chain"; "error" => e);
+        }
+    }
+    if !effects.delay.is_zero() {
+        tokio::time::sleep(effects.delay).await;
+    }
+    if let Some(response) = effects.response {
+        return response;
+    }
+
+    let replayed_response = ctx
+        .replay
+        .lock()
+        .as_mut()
+        .map(|replay| replay.response(id, &body));
+    if let Some((response, delay)) = replayed_response {
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        return response.to_string();
+    }
+
+    let response = match handle_rpc(body, ctx).await {
+        Ok(result) => json!({
+            "id": id,
+            "jsonrpc": JSONRPC_VERSION,
+            "result": result
+        }),
+        Err((message, code)) => json!({
+            "id": id,
+            "jsonrpc": JSONRPC_VERSION,
+            "error": {
+                "code": code,
+                "message": message
+            }
+        }),
+    };
+    response.to_string()
+}
+
 /// Creates a server that will serve requests using information from `ctx`.
 ///
 /// The server will shut down gracefully when the `shutdown` future resolves.
@@ -651,39 +800,10 @@ pub fn serve<T: EthSpec>(
                 .get("id")
                 .and_then(serde_json::Value::as_u64)
                 .ok_or_else(|| warp::reject::custom(MissingIdField))?;
-            let preloaded_response = {
-                let mut preloaded_responses = ctx.preloaded_responses.lock();
-                if !preloaded_responses.is_empty() {
-                    Some(preloaded_responses.remove(0))
-                } else {
-                    None
-                }
-            };
-
-            let response = if let Some(preloaded_response) = preloaded_response {
-                preloaded_response
-            } else {
-                match handle_rpc(body, ctx).await {
-                    Ok(result) => json!({
-                        "id": id,
-                        "jsonrpc": JSONRPC_VERSION,
-                        "result": result
-                    }),
-                    Err((message, code)) => json!({
-                        "id": id,
-                        "jsonrpc": JSONRPC_VERSION,
-                        "error": {
-                            "code": code,
-                            "message": message
-                        }
-                    }),
-                }
-            };
+            let response = rpc_response(id, body, ctx).await;
 
             Ok::<_, warp::reject::Rejection>(
-                warp::http::Response::builder()
-                    .status(200)
-                    .body(serde_json::to_string(&response).expect("response must be valid JSON")),
+                warp::http::Response::builder().status(200).body(response),
             )
         });
 
@@ -722,3 +842,77 @@ pub fn serve<T: EthSpec>(
 
     Ok((listening_socket, server))
 }
+
+/// Creates a server that will serve requests via IPC on the socket at `path`, using information
+/// from `ctx`.
+///
+/// If `echo` is `true` the server behaves like the HTTP `/echo` path, storing each request in
+/// `ctx.last_echo_request` and returning it unchanged. Auth is not checked, matching execution
+/// clients which rely on the permissions of the socket file.
+///
+/// The server will shut down when the `shutdown` future resolves.
+#[cfg(unix)]
+pub fn serve_ipc<T: EthSpec>(
+    ctx: Arc<Context<T>>,
+    path: PathBuf,
+    echo: bool,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<impl Future<Output = ()>, Error> {
+    use crate::engine_api::ipc::JsonMessageReader;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::UnixListener;
+
+    let listener = UnixListener::bind(&path)
+        .map_err(|e| format!("Unable to bind IPC socket {}: {}", path.display(), e))?;
+
+    info!(
+        ctx.log,
+        "IPC server started";
+        "path" => %path.display(),
+    );
+
+    let server = async move {
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(_) => continue,
+            };
+            let ctx = ctx.clone();
+            tokio::spawn(async move {
+                let (read, mut write) = stream.into_split();
+                let mut reader = JsonMessageReader::new(read);
+                while let Ok(Some(body)) = reader.next::<serde_json::Value>().await {
+                    let response = if echo {
+                        let response = body.to_string();
+                        *ctx.last_echo_request.write() = Some(response.clone().into());
+                        response
+                    } else {
+                        let id = body
+                            .get("id")
+                            .and_then(serde_json::Value::as_u64)
+                            .unwrap_or_default();
+                        rpc_response(id, body, ctx.clone()).await
+                    };
+                    if write.write_all(response.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    };
+
+    Ok(async move {
+        futures::future::select(Box::pin(server), Box::pin(shutdown)).await;
+        let _ = std::fs::remove_file(path);
+    })
+}
+
+#[cfg(not(unix))]
+pub fn serve_ipc<T: EthSpec>(
+    _ctx: Arc<Context<T>>,
+    _path: PathBuf,
+    _echo: bool,
+    _shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<impl Future<Output = ()>, Error> {
+    Err::<std::future::Ready<()>, _>("IPC is only supported on Unix platforms".to_string().into())
+}
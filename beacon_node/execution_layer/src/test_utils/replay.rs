@@ -0,0 +1,151 @@
+//! Serves engine API responses from a recording made by `TrafficRecorder`, so that a beacon node
+//! can be re-run deterministically against a captured execution engine session.
+
+use super::handle_rpc::GENERIC_ERROR_CODE;
+use crate::engine_api::http::JSONRPC_VERSION;
+use crate::engine_api::recorder::{read_recording, RecordedExchange};
+use serde_json::{json, Value as JsonValue};
+use std::path::Path;
+use std::time::Duration;
+
+pub struct Replay {
+    exchanges: Vec<RecordedExchange>,
+    used: Vec<bool>,
+    /// Delay each response by the time it originally took.
+    honour_timing: bool,
+}
+
+impl Replay {
+    pub fn new(exchanges: Vec<RecordedExchange>, honour_timing: bool) -> Self {
+        Self {
+            used: vec![false; exchanges.len()],
+            exchanges,
+            honour_timing,
+        }
+    }
+
+    pub fn from_file(path: &Path, honour_timing: bool) -> Result<Self, String> {
+        read_recording(path).map(|exchanges| Self::new(exchanges, honour_timing))
+    }
+
+    /// Returns the number of recorded exchanges which have not been replayed.
+    pub fn remaining(&self) -> usize {
+        self.used.iter().filter(|used| !**used).count()
+    }
+
+    /// Returns the response to `request` and the delay to apply before sending it.
+    ///
+    /// The first unused exchange with the same method and params is replayed. If there is none,
+    /// the first unused exchange with the same method is replayed instead, so that a replay can
+    /// continue past small differences in the requests. A JSON-RPC error is returned if no
+    /// exchange for the method remains.
+    pub fn response(&mut self, id: u64, request: &JsonValue) -> (JsonValue, Option<Duration>) {
+        let method = request.get("method").and_then(JsonValue::as_str);
+        let params = request.get("params").unwrap_or(&JsonValue::Null);
+
+        let unused = |i: &usize| !self.used[*i];
+        let same_method = |i: &usize| Some(self.exchanges[*i].method.as_str()) == method;
+        let index = (0..self.exchanges.len())
+            .filter(unused)
+            .filter(same_method)
+            .find(|i| self.exchanges[*i].params == *params)
+            .or_else(|| (0..self.exchanges.len()).filter(unused).find(same_method));
+
+        let Some(index) = index else {
+            return (
+                error_response(
+                    id,
+                    format!("no recorded response remaining for {:?}", method),
+                ),
+                None,
+            );
+        };
+        self.used[index] = true;
+
+        let exchange = &self.exchanges[index];
+        let response = match &exchange.response {
+            Some(body) => {
+                let mut response = serde_json::to_value(body).expect("response is valid JSON");
+                response["id"] = json!(id);
+                response
+            }
+            None => error_response(
+                id,
+                format!(
+                    "replayed transport error: {}",
+                    exchange.error.as_deref().unwrap_or("unknown")
+                ),
+            ),
+        };
+        let delay = self.honour_timing.then(|| exchange.duration());
+
+        (response, delay)
+    }
+}
+
+fn error_response(id: u64, message: String) -> JsonValue {
+    json!({
+        "id": id,
+        "jsonrpc": JSONRPC_VERSION,
+        "error": {
+            "code": GENERIC_ERROR_CODE,
+            "message": message
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::{Auth, JwtKey};
+    use crate::engine_api::recorder::TrafficRecorder;
+    use crate::engine_api::BlockByNumberQuery;
+    use crate::test_utils::{MockServer, DEFAULT_JWT_SECRET};
+    use crate::{HttpJsonRpc, LATEST_TAG};
+    use sensitive_url::SensitiveUrl;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use types::MainnetEthSpec;
+
+    fn client(server: &MockServer<MainnetEthSpec>) -> HttpJsonRpc {
+        let url = SensitiveUrl::parse(&server.url()).unwrap();
+        let auth = Auth::new(JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap(), None, None);
+        HttpJsonRpc::new_with_auth(url, auth, None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_session() {
+        let dir = TempDir::new().unwrap();
+        let recorder = Arc::new(TrafficRecorder::new(dir.path().into(), u64::MAX, 0).unwrap());
+
+        // Record a session against a live mock server.
+        let server = MockServer::<MainnetEthSpec>::unit_testing();
+        let recording_client = client(&server).with_recorder(recorder.clone());
+        recording_client.upcheck().await.unwrap();
+        let block = recording_client
+            .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
+            .await
+            .unwrap()
+            .unwrap();
+        drop(server);
+        recorder.flush();
+
+        // Replay the session against a fresh server which would otherwise report an error.
+        let server = MockServer::<MainnetEthSpec>::unit_testing();
+        *server.ctx.syncing_response.lock() = Err("not replayed".to_string());
+        server.set_replay(Replay::from_file(&recorder.path(), false).unwrap());
+        let replay_client = client(&server);
+
+        replay_client.upcheck().await.unwrap();
+        let replayed_block = replay_client
+            .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayed_block, block);
+        assert_eq!(server.ctx.replay.lock().as_ref().unwrap().remaining(), 0);
+
+        // The recording is exhausted.
+        assert!(replay_client.upcheck().await.is_err());
+    }
+}
@@ -0,0 +1,404 @@
+//! Declarative fault-injection scenarios for the mock execution engine.
+//!
+//! A `Scenario` is a list of rules, loaded from YAML or JSON, which are checked in order against
+//! each JSON-RPC request. For example:
+//!
+//! ```yaml
+//! rules:
+//!   # Report the first two payloads as syncing.
+//!   - method: engine_newPayload*
+//!     times: 2
+//!     action: { type: payload_status, status: SYNCING }
+//!   # Add latency to every request during the second minute.
+//!   - from_secs: 60
+//!     until_secs: 120
+//!     action: { type: delay, millis: 2000 }
+//!   # Re-org the execution chain, then return malformed JSON on the next `eth_syncing`.
+//!   - method: engine_forkchoiceUpdated*
+//!     skip: 10
+//!     times: 1
+//!     action: { type: reorg, depth: 2 }
+//!   - method: eth_syncing
+//!     action: { type: malformed }
+//! ```
+//!
+//! `delay` and `reorg` actions are applied and the remaining rules are checked. The first matching
+//! rule with any other action supplies the response. Requests without such a rule are handled as
+//! usual.
+
+use super::handle_rpc::GENERIC_ERROR_CODE;
+use crate::engine_api::http::*;
+use crate::json_structures::{
+    JsonForkchoiceUpdatedV1Response, JsonPayloadStatusV1, JsonPayloadStatusV1Status,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use types::ExecutionBlockHash;
+
+/// The response body used by `Action::Malformed` if none is given.
+pub const DEFAULT_MALFORMED_BODY: &str = "{\"jsonrpc\":\"2.0\",\"id\":";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub rules: Vec<Rule>,
+}
+
+impl Scenario {
+    /// Load a scenario from a YAML file, or a JSON file if `path` has a `.json` extension.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Unable to open scenario {}: {}", path.display(), e))?;
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "json")
+        {
+            serde_json::from_reader(file).map_err(|e| format!("Invalid scenario: {}", e))
+        } else {
+            serde_yaml::from_reader(file).map_err(|e| format!("Invalid scenario: {}", e))
+        }
+    }
+}
+
+/// Applies `action` to the requests matched by all of the other fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// The method to match, e.g. `engine_newPayloadV3`. A trailing `*` matches any suffix, e.g.
+    /// `engine_newPayload*` matches every version of `newPayload`.
+    pub method: Option<String>,
+    /// The block hash to match. This is the payload's hash for `newPayload`, the head block hash
+    /// for `forkchoiceUpdated` and the requested hash for `eth_getBlockByHash`.
+    pub block_hash: Option<ExecutionBlockHash>,
+    /// Match requests made at least this many seconds after the scenario started.
+    pub from_secs: Option<u64>,
+    /// Match requests made less than this many seconds after the scenario started.
+    pub until_secs: Option<u64>,
+    /// The number of matching requests to let through before the rule applies.
+    #[serde(default)]
+    pub skip: u64,
+    /// The maximum number of requests the rule applies to.
+    pub times: Option<u64>,
+    pub action: Action,
+}
+
+impl Rule {
+    fn matches(
+        &self,
+        method: &str,
+        block_hash: Option<ExecutionBlockHash>,
+        elapsed: Duration,
+    ) -> bool {
+        let method_matches =
+            self.method
+                .as_deref()
+                .map_or(true, |pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => method.starts_with(prefix),
+                    None => method == pattern,
+                });
+        let block_hash_matches = self
+            .block_hash
+            .map_or(true, |hash| block_hash == Some(hash));
+        let elapsed = elapsed.as_secs();
+        let time_matches = self.from_secs.map_or(true, |from| elapsed >= from)
+            && self.until_secs.map_or(true, |until| elapsed < until);
+
+        method_matches && block_hash_matches && time_matches
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    /// Respond with a payload status. For `forkchoiceUpdated` the status is wrapped in a response
+    /// without a payload ID. A `VALID` status without a `latest_valid_hash` is given the block hash
+    /// of the request.
+    PayloadStatus {
+        status: JsonPayloadStatusV1Status,
+        latest_valid_hash: Option<ExecutionBlockHash>,
+        validation_error: Option<String>,
+    },
+    /// Respond with a JSON-RPC error.
+    Error {
+        #[serde(default = "generic_error_code")]
+        code: i64,
+        message: String,
+    },
+    /// Respond with `result`, verbatim.
+    Result { result: JsonValue },
+    /// Respond with `body`, which need not be valid JSON.
+    Malformed {
+        #[serde(default = "default_malformed_body")]
+        body: String,
+    },
+    /// Delay the response.
+    Delay { millis: u64 },
+    /// Remove the last `depth` blocks of the execution chain before responding, as if the engine
+    /// had re-orged to a shorter chain.
+    Reorg { depth: u64 },
+}
+
+fn generic_error_code() -> i64 {
+    GENERIC_ERROR_CODE
+}
+
+fn default_malformed_body() -> String {
+    DEFAULT_MALFORMED_BODY.to_string()
+}
+
+/// A `Scenario` being served, along with the state required to apply its rules.
+pub struct ActiveScenario {
+    rules: Vec<Rule>,
+    /// The number of requests matched by each rule.
+    matches: Vec<u64>,
+    started: Instant,
+}
+
+/// The effects of a scenario on a single request.
+#[derive(Debug, Default, PartialEq)]
+pub struct Effects {
+    pub delay: Duration,
+    pub reorg_depth: u64,
+    /// The response body, if it should not be generated as usual.
+    pub response: Option<String>,
+}
+
+impl ActiveScenario {
+    pub fn new(scenario: Scenario) -> Self {
+        Self {
+            matches: vec![0; scenario.rules.len()],
+            rules: scenario.rules,
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns the effects of the rules which apply to `request`.
+    pub fn effects(&mut self, id: u64, request: &JsonValue) -> Effects {
+        let method = request
+            .get("method")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default();
+        let block_hash = request_block_hash(method, request);
+        let elapsed = self.started.elapsed();
+
+        let mut effects = Effects::default();
+        for (rule, matches) in self.rules.iter().zip(self.matches.iter_mut()) {
+            if !rule.matches(method, block_hash, elapsed) {
+                continue;
+            }
+            *matches += 1;
+            if *matches <= rule.skip
+                || rule
+                    .times
+                    .map_or(false, |times| *matches > rule.skip + times)
+            {
+                continue;
+            }
+
+            let response = match &rule.action {
+                Action::Delay { millis } => {
+                    effects.delay += Duration::from_millis(*millis);
+                    continue;
+                }
+                Action::Reorg { depth } => {
+                    effects.reorg_depth += depth;
+                    continue;
+                }
+                Action::PayloadStatus {
+                    status,
+                    latest_valid_hash,
+                    validation_error,
+                } => {
+                    let payload_status = JsonPayloadStatusV1 {
+                        status: *status,
+                        latest_valid_hash: latest_valid_hash.or_else(|| {
+                            block_hash.filter(|_| *status == JsonPayloadStatusV1Status::Valid)
+                        }),
+                        validation_error: validation_error.clone(),
+                    };
+                    let result = if is_forkchoice_updated(method) {
+                        serde_json::to_value(JsonForkchoiceUpdatedV1Response {
+                            payload_status,
+                            payload_id: None,
+                        })
+                    } else {
+                        serde_json::to_value(payload_status)
+                    };
+                    result_body(id, result.expect("payload status is valid JSON"))
+                }
+                Action::Error { code, message } => json!({
+                    "id": id,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "error": {
+                        "code": code,
+                        "message": message
+                    }
+                })
+                .to_string(),
+                Action::Result { result } => result_body(id, result.clone()),
+                Action::Malformed { body } => body.clone(),
+            };
+            effects.response = Some(response);
+            break;
+        }
+        effects
+    }
+}
+
+fn result_body(id: u64, result: JsonValue) -> String {
+    json!({
+        "id": id,
+        "jsonrpc": JSONRPC_VERSION,
+        "result": result
+    })
+    .to_string()
+}
+
+fn is_forkchoice_updated(method: &str) -> bool {
+    matches!(
+        method,
+        ENGINE_FORKCHOICE_UPDATED_V1 | ENGINE_FORKCHOICE_UPDATED_V2 | ENGINE_FORKCHOICE_UPDATED_V3
+    )
+}
+
+/// Returns the block hash which `request` concerns, if any.
+fn request_block_hash(method: &str, request: &JsonValue) -> Option<ExecutionBlockHash> {
+    let param = request.get("params")?.get(0)?;
+    let hash = match method {
+        ENGINE_NEW_PAYLOAD_V1 | ENGINE_NEW_PAYLOAD_V2 | ENGINE_NEW_PAYLOAD_V3 => {
+            param.get("blockHash")?
+        }
+        _ if is_forkchoice_updated(method) => param.get("headBlockHash")?,
+        ETH_GET_BLOCK_BY_HASH => param,
+        _ => return None,
+    };
+    serde_json::from_value(hash.clone()).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::{Auth, JwtKey};
+    use crate::engine_api::BlockByNumberQuery;
+    use crate::test_utils::{MockServer, DEFAULT_JWT_SECRET};
+    use crate::{HttpJsonRpc, LATEST_TAG};
+    use sensitive_url::SensitiveUrl;
+    use types::MainnetEthSpec;
+
+    const SCENARIO: &str = r#"
+rules:
+  - method: engine_newPayload*
+    block_hash: "0x0000000000000000000000000000000000000000000000000000000000000001"
+    action: { type: payload_status, status: INVALID, validation_error: bad }
+  - method: engine_newPayload*
+    skip: 1
+    times: 2
+    action: { type: payload_status, status: SYNCING }
+  - method: engine_forkchoiceUpdatedV3
+    action: { type: delay, millis: 5 }
+  - method: engine_forkchoiceUpdatedV3
+    action: { type: reorg, depth: 1 }
+  - method: engine_forkchoiceUpdatedV3
+    action: { type: payload_status, status: VALID }
+  - from_secs: 3600
+    action: { type: error, message: late }
+"#;
+
+    fn request(method: &str, block_hash: u64) -> JsonValue {
+        let hash = ExecutionBlockHash::from_root(types::Hash256::from_low_u64_be(block_hash));
+        let param = if is_forkchoice_updated(method) {
+            json!({ "headBlockHash": hash })
+        } else {
+            json!({ "blockHash": hash })
+        };
+        json!({ "method": method, "params": [param] })
+    }
+
+    async fn latest_block_number(client: &HttpJsonRpc) -> u64 {
+        client
+            .get_block_by_number(BlockByNumberQuery::Tag(LATEST_TAG))
+            .await
+            .unwrap()
+            .unwrap()
+            .block_number
+    }
+
+    fn status(effects: &Effects) -> Option<JsonValue> {
+        let response: JsonValue = serde_json::from_str(effects.response.as_ref()?).unwrap();
+        Some(response["result"]["status"].clone())
+    }
+
+    #[test]
+    fn applies_rules_in_order() {
+        let scenario: Scenario = serde_yaml::from_str(SCENARIO).unwrap();
+        let mut active = ActiveScenario::new(scenario);
+
+        let mut new_payload =
+            |block_hash| status(&active.effects(1, &request(ENGINE_NEW_PAYLOAD_V3, block_hash)));
+        // The block hash rule always applies.
+        assert_eq!(new_payload(1), Some(json!("INVALID")));
+        // The first request is skipped, the next two are syncing and the rest are unaffected.
+        assert_eq!(new_payload(2), None);
+        assert_eq!(new_payload(2), Some(json!("SYNCING")));
+        assert_eq!(new_payload(1), Some(json!("INVALID")));
+        assert_eq!(new_payload(2), Some(json!("SYNCING")));
+        assert_eq!(new_payload(2), None);
+
+        let effects = active.effects(1, &request(ENGINE_FORKCHOICE_UPDATED_V3, 3));
+        assert_eq!(effects.delay, Duration::from_millis(5));
+        assert_eq!(effects.reorg_depth, 1);
+        let response: JsonValue = serde_json::from_str(&effects.response.unwrap()).unwrap();
+        assert_eq!(
+            response["result"]["payloadStatus"]["latestValidHash"],
+            json!(ExecutionBlockHash::from_root(
+                types::Hash256::from_low_u64_be(3)
+            ))
+        );
+
+        // Rules for other methods and time windows do not apply.
+        let effects = active.effects(1, &request(ENGINE_FORKCHOICE_UPDATED_V2, 3));
+        assert_eq!(effects, Effects::default());
+    }
+
+    #[test]
+    fn json_and_yaml_are_equivalent() {
+        let yaml: Scenario = serde_yaml::from_str(SCENARIO).unwrap();
+        let json: Scenario = serde_json::from_str(&serde_json::to_string(&yaml).unwrap()).unwrap();
+        assert_eq!(json, yaml);
+    }
+
+    #[tokio::test]
+    async fn reorg_and_malformed_response() {
+        let server = MockServer::<MainnetEthSpec>::unit_testing();
+        server
+            .execution_block_generator()
+            .move_to_terminal_block()
+            .unwrap();
+        server.set_scenario(
+            serde_yaml::from_str(
+                r#"
+rules:
+  - method: eth_getBlockByNumber
+    times: 1
+    action: { type: reorg, depth: 2 }
+  - method: eth_syncing
+    action: { type: malformed }
+"#,
+            )
+            .unwrap(),
+        );
+
+        let url = SensitiveUrl::parse(&server.url()).unwrap();
+        let auth = Auth::new(JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap(), None, None);
+        let client = HttpJsonRpc::new_with_auth(url, auth, None).unwrap();
+
+        // The chain is re-orged by the first request only.
+        let terminal_block = server.execution_block_generator().terminal_block_number;
+        assert_eq!(latest_block_number(&client).await, terminal_block - 2);
+        assert_eq!(latest_block_number(&client).await, terminal_block - 2);
+
+        assert!(client.upcheck().await.is_err());
+    }
+}
@@ -17,6 +17,7 @@ mod metrics;
 mod produce_block;
 mod proposer_duties;
 mod publish_blocks;
+mod rate_limits;
 mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
@@ -79,11 +80,11 @@ use tokio_stream::{
 };
 use types::{
     fork_versioned_response::EmptyMetadata, Attestation, AttestationData, AttestationShufflingId,
-    AttesterSlashing, BeaconStateError, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName,
-    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch,
-    SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
-    SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
-    SyncCommitteeMessage, SyncContributionData,
+    AttesterSlashing, BeaconStateError, CommitteeCache, ConfigAndPreset, DepositTreeSnapshot,
+    Epoch, EthSpec, ForkName, ForkVersionedResponse, Hash256, ProposerPreparationData,
+    ProposerSlashing, RelativeEpoch, SignedAggregateAndProof, SignedBlindedBeaconBlock,
+    SignedBlsToExecutionChange, SignedContributionAndProof, SignedValidatorRegistrationData,
+    SignedVoluntaryExit, Slot, SyncCommitteeMessage, SyncContributionData,
 };
 use validator::pubkey_to_validator_index;
 use version::{
@@ -3520,7 +3521,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp::body::json())
-        .and(network_tx_filter)
+        .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
@@ -4066,6 +4067,63 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/sync/status
+    let get_lighthouse_sync_status = warp::path("lighthouse")
+        .and(warp::path("sync"))
+        .and(warp::path("status"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.sync_status_report(),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/network/rate_limits
+    let get_lighthouse_network_rate_limits = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("rate_limits"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.rate_limiter.read().clone(),
+                    ))
+                })
+            },
+        );
+
+    // POST lighthouse/network/rate_limits
+    let post_lighthouse_network_rate_limits = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("rate_limits"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .and(network_tx_filter)
+        .then(
+            |update: eth2::lighthouse::RateLimitsUpdate,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    rate_limits::update_rate_limits(update, &network_globals, &network_tx).await?;
+                    Ok(warp::reply::json(&()).into_response())
+                })
+            },
+        );
+
     // GET lighthouse/nat
     let get_lighthouse_nat = warp::path("lighthouse")
         .and(warp::path("nat"))
@@ -4083,6 +4141,24 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/nat/reachability
+    let get_lighthouse_nat_reachability = warp::path("lighthouse")
+        .and(warp::path("nat"))
+        .and(warp::path("reachability"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.reachability_report(),
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/peers
     let get_lighthouse_peers = warp::path("lighthouse")
         .and(warp::path("peers"))
@@ -4189,6 +4265,33 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/validator/proposals
+    let get_lighthouse_validator_proposals = warp::path("lighthouse")
+        .and(warp::path("validator"))
+        .and(warp::path("proposals"))
+        .and(warp::query::<eth2::lighthouse::ProposalRewardsQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::ProposalRewardsQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if query.from > query.to {
+                        return Err(warp_utils::reject::custom_bad_request(format!(
+                            "invalid slot range: {}-{}",
+                            query.from, query.to
+                        )));
+                    }
+                    chain
+                        .get_proposal_rewards(query.from, query.to)
+                        .map(api_types::GenericResponse::from)
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
     // GET lighthouse/eth1/syncing
     let get_lighthouse_eth1_syncing = warp::path("lighthouse")
         .and(warp::path("eth1"))
@@ -4222,6 +4325,31 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/builder/relays
+    let get_lighthouse_builder_relays = warp::path("lighthouse")
+        .and(warp::path("builder"))
+        .and(warp::path("relays"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .execution_layer
+                        .as_ref()
+                        .and_then(|el| el.builder())
+                        .map(|relays| api_types::GenericResponse::from(relays.status()))
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(
+                                "No builder relays are configured. See the --builder CLI flag."
+                                    .to_string(),
+                            )
+                        })
+                })
+            },
+        );
+
     // GET lighthouse/eth1/block_cache
     let get_lighthouse_eth1_block_cache = warp::path("lighthouse")
         .and(warp::path("eth1"))
@@ -4244,6 +4372,73 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/eth1/deposit_snapshot
+    let post_lighthouse_eth1_deposit_snapshot = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("deposit_snapshot"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(eth1_service_filter.clone())
+        .then(
+            |snapshot: DepositTreeSnapshot,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             eth1_service: eth1::Service| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    eth1_service
+                        .import_deposit_snapshot(&snapshot)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_bad_request(format!(
+                                "unable to import deposit snapshot: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/eth1/deposit_snapshot/consistency
+    let get_lighthouse_eth1_deposit_snapshot_consistency = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("deposit_snapshot"))
+        .and(warp::path("consistency"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(eth1_service_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             eth1_service: eth1::Service| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let finalized_eth1_data = StateId(api_types::StateId::Finalized)
+                        .map_state_and_execution_optimistic_and_finalized(
+                            &chain,
+                            |state, _, _| Ok(state.eth1_data().clone()),
+                        )?;
+                    let snapshot = eth1_service.get_deposit_snapshot();
+                    let local_deposit_root =
+                        eth1_service.deposit_root_at_count(finalized_eth1_data.deposit_count);
+
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::DepositSnapshotConsistency {
+                            finalized_deposit_count: finalized_eth1_data.deposit_count,
+                            finalized_deposit_root: finalized_eth1_data.deposit_root,
+                            snapshot_deposit_count: snapshot
+                                .as_ref()
+                                .map(|snapshot| snapshot.deposit_count),
+                            snapshot_deposit_root: snapshot
+                                .as_ref()
+                                .map(|snapshot| snapshot.deposit_root),
+                            local_deposit_root,
+                            consistent: local_deposit_root
+                                .map(|root| root == finalized_eth1_data.deposit_root),
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/eth1/deposit_cache
     let get_lighthouse_eth1_deposit_cache = warp::path("lighthouse")
         .and(warp::path("eth1"))
@@ -4486,6 +4681,9 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
+                                api_types::EventTopic::EngineDisagreement => {
+                                    event_handler.subscribe_engine_disagreement()
+                                }
                             };
 
                             receivers.push(
@@ -4622,15 +4820,21 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_ui_health)
                 .uor(get_lighthouse_ui_validator_count)
                 .uor(get_lighthouse_syncing)
+                .uor(get_lighthouse_sync_status)
+                .uor(get_lighthouse_network_rate_limits)
                 .uor(get_lighthouse_nat)
+                .uor(get_lighthouse_nat_reachability)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
                 .uor(get_lighthouse_proto_array)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
+                .uor(get_lighthouse_validator_proposals)
                 .uor(get_lighthouse_eth1_syncing)
+                .uor(get_lighthouse_builder_relays)
                 .uor(get_lighthouse_eth1_block_cache)
                 .uor(get_lighthouse_eth1_deposit_cache)
+                .uor(get_lighthouse_eth1_deposit_snapshot_consistency)
                 .uor(get_lighthouse_beacon_states_ssz)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
@@ -4692,6 +4896,8 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_lighthouse_liveness)
                     .uor(post_lighthouse_database_reconstruct)
                     .uor(post_lighthouse_block_rewards)
+                    .uor(post_lighthouse_network_rate_limits)
+                    .uor(post_lighthouse_eth1_deposit_snapshot)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
                     .recover(warp_utils::reject::handle_rejection),
@@ -6,6 +6,7 @@ use beacon_chain::{
     AvailabilityProcessingStatus, BeaconChain, BeaconChainError, BeaconChainTypes, BlockError,
     IntoGossipVerifiedBlockContents, NotifyExecutionLayer,
 };
+use eth2::lighthouse::PayloadSource;
 use eth2::types::{into_full_block_and_blobs, BroadcastValidation, ErrorMessage};
 use eth2::types::{FullPayloadContents, PublishBlockRequest};
 use execution_layer::ProvenancedPayload;
@@ -246,6 +247,34 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
             if is_locally_built_block {
                 late_block_logging(&chain, seen_timestamp, block.message(), root, "local", &log)
             }
+
+            // Record the rewards of the proposal once the payload's effects can be queried from
+            // the execution layer.
+            let payload_source = if is_locally_built_block {
+                PayloadSource::Local
+            } else {
+                PayloadSource::Builder
+            };
+            let reward_chain = chain.clone();
+            let reward_block = Arc::new(block.clone());
+            let reward_log = log.clone();
+            chain.task_executor.spawn(
+                async move {
+                    if let Err(e) = reward_chain
+                        .record_proposal_reward(root, reward_block, payload_source)
+                        .await
+                    {
+                        debug!(
+                            reward_log,
+                            "Unable to record proposal reward";
+                            "error" => ?e,
+                            "root" => ?root,
+                        );
+                    }
+                },
+                "record_proposal_reward",
+            );
+
             Ok(warp::reply().into_response())
         }
         Ok(AvailabilityProcessingStatus::MissingComponents(_, block_root)) => {
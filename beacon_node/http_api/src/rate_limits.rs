@@ -0,0 +1,97 @@
+use eth2::lighthouse::RateLimitsUpdate;
+use lighthouse_network::rpc::config::{InboundRateLimiterUpdate, RateLimiterConfig};
+use lighthouse_network::{NetworkGlobals, PeerId};
+use network::NetworkMessage;
+use std::str::FromStr;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use types::EthSpec;
+
+/// Validates `update`, sends it to the network service to be applied to the rate limiters and
+/// waits for the result.
+pub async fn update_rate_limits<T: EthSpec>(
+    update: RateLimitsUpdate,
+    network_globals: &NetworkGlobals<T>,
+    network_tx: &UnboundedSender<NetworkMessage<T>>,
+) -> Result<(), warp::Rejection> {
+    let RateLimitsUpdate {
+        default,
+        trusted_peers,
+        peers,
+        outbound,
+    } = update;
+
+    let updates_inbound = default.is_some() || trusted_peers.is_some() || !peers.is_empty();
+    {
+        let report = network_globals.rate_limiter.read();
+        if updates_inbound && !report.enabled {
+            return Err(warp_utils::reject::custom_bad_request(
+                "inbound rate limiting is disabled".to_string(),
+            ));
+        }
+        if outbound.is_some() && report.outbound_quotas.is_none() {
+            return Err(warp_utils::reject::custom_bad_request(
+                "outbound rate limiting is disabled".to_string(),
+            ));
+        }
+    }
+
+    let inbound = InboundRateLimiterUpdate {
+        default: default.as_deref().map(parse_quotas).transpose()?,
+        trusted_peers: trusted_peers
+            .as_deref()
+            .map(parse_optional_quotas)
+            .transpose()?,
+        peers: peers
+            .iter()
+            .map(|(peer_id, quotas)| {
+                let peer_id = PeerId::from_str(peer_id).map_err(|e| {
+                    warp_utils::reject::custom_bad_request(format!(
+                        "invalid peer id {}: {}",
+                        peer_id, e
+                    ))
+                })?;
+                Ok((peer_id, parse_optional_quotas(quotas)?))
+            })
+            .collect::<Result<_, warp::Rejection>>()?,
+    };
+    let outbound = outbound.as_deref().map(parse_quotas).transpose()?;
+
+    let (result_tx, result_rx) = oneshot::channel();
+    crate::publish_network_message(
+        network_tx,
+        NetworkMessage::UpdateRateLimits {
+            inbound: updates_inbound.then_some(inbound),
+            outbound,
+            result_tx,
+        },
+    )?;
+    result_rx
+        .await
+        .map_err(|_| {
+            warp_utils::reject::custom_server_error(
+                "network service dropped the rate limits update".to_string(),
+            )
+        })?
+        .map_err(|e| {
+            warp_utils::reject::custom_bad_request(format!("unable to update rate limits: {}", e))
+        })
+}
+
+/// Parses quotas in the format of the `--inbound-rate-limiter` flag.
+fn parse_quotas(quotas: &str) -> Result<RateLimiterConfig, warp::Rejection> {
+    let config = RateLimiterConfig::from_str(quotas)
+        .and_then(|config| config.validate().map(|()| config))
+        .map_err(|e| {
+            warp_utils::reject::custom_bad_request(format!("invalid quotas {}: {}", quotas, e))
+        })?;
+    Ok(config)
+}
+
+/// Parses quotas, treating an empty string as the removal of an override.
+fn parse_optional_quotas(quotas: &str) -> Result<Option<RateLimiterConfig>, warp::Rejection> {
+    if quotas.is_empty() {
+        Ok(None)
+    } else {
+        parse_quotas(quotas).map(Some)
+    }
+}
@@ -47,6 +47,7 @@ pub struct ApiServer<E: EthSpec, SFut: Future<Output = ()>> {
     pub server: SFut,
     pub listening_socket: SocketAddr,
     pub network_rx: NetworkReceivers<E>,
+    pub network_globals: Arc<NetworkGlobals<E>>,
     pub local_enr: Enr,
     pub external_peer_id: PeerId,
 }
@@ -214,7 +215,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         },
         chain: Some(chain),
         network_senders: Some(network_senders),
-        network_globals: Some(network_globals),
+        network_globals: Some(network_globals.clone()),
         beacon_processor_send: Some(beacon_processor_send),
         eth1_service: Some(eth1_service),
         sse_logging_components: None,
@@ -227,6 +228,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         server,
         listening_socket,
         network_rx: network_receivers,
+        network_globals,
         local_enr: enr,
         external_peer_id: peer_id,
     }
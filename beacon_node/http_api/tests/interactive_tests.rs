@@ -4,8 +4,10 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
     ChainConfig,
 };
+use eth2::lighthouse::PayloadSource;
 use eth2::types::ProduceBlockV3Response;
 use eth2::types::{DepositContractData, StateId};
+use execution_layer::test_utils::DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
 use execution_layer::{ForkchoiceState, PayloadAttributes};
 use http_api::test_utils::InteractiveTester;
 use parking_lot::Mutex;
@@ -19,7 +21,7 @@ use std::time::Duration;
 use tree_hash::TreeHash;
 use types::{
     Address, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, MainnetEthSpec,
-    MinimalEthSpec, ProposerPreparationData, Slot,
+    MinimalEthSpec, ProposerPreparationData, Slot, Uint256,
 };
 
 type E = MainnetEthSpec;
@@ -840,3 +842,101 @@ pub async fn fork_choice_before_proposal() {
     // D's parent is B.
     assert_eq!(block_d.parent_root(), block_root_b.into());
 }
+
+// Test that a block published via the HTTP API has its rewards recorded, including the value paid
+// to the proposer's fee recipient according to the execution layer.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn proposal_rewards_recorded_on_publish() {
+    let mut spec = ForkName::latest().make_genesis_spec(E::default_spec());
+    spec.terminal_total_difficulty = 1.into();
+
+    let validator_count = 32;
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    mock_el.server.all_payloads_valid();
+    mock_el
+        .server
+        .ctx
+        .execution_block_generator
+        .write()
+        .move_to_terminal_block()
+        .unwrap();
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            3,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+    harness.chain.per_slot_task().await;
+
+    let slot = harness.get_current_slot();
+    let mut state = harness.get_current_state();
+    complete_state_advance(&mut state, None, slot, &harness.chain.spec).unwrap();
+    let proposer_index = state
+        .get_beacon_proposer_index(slot, &harness.chain.spec)
+        .unwrap();
+    let randao_reveal = harness
+        .sign_randao_reveal(&state, proposer_index, slot)
+        .into();
+
+    let block_contents = tester
+        .client
+        .get_validator_blocks::<E>(slot, &randao_reveal, None)
+        .await
+        .unwrap()
+        .data;
+    let signed_block_contents = block_contents.sign(
+        &harness.validator_keypairs[proposer_index].sk,
+        &state.fork(),
+        state.genesis_validators_root(),
+        &harness.chain.spec,
+    );
+    let block_root = signed_block_contents.signed_block().canonical_root();
+    tester
+        .client
+        .post_beacon_blocks(&signed_block_contents)
+        .await
+        .unwrap();
+
+    // The reward is recorded in the background once the block has been imported.
+    let mut reward = None;
+    for _ in 0..50 {
+        reward = tester
+            .client
+            .get_lighthouse_validator_proposals(slot, slot)
+            .await
+            .unwrap()
+            .data
+            .pop();
+        if reward.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let reward = reward.expect("proposal reward should be recorded");
+
+    let expected_value = Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI);
+    assert_eq!(reward.slot, slot);
+    assert_eq!(reward.block_root, block_root);
+    assert_eq!(reward.proposer_index, proposer_index as u64);
+    assert_eq!(reward.payload_source, PayloadSource::Local);
+    assert_eq!(reward.bid_value, Some(expected_value));
+    assert_eq!(reward.paid_value, Some(expected_value));
+    assert!(reward.consensus_reward.is_some());
+    assert_eq!(reward.balance_decrease, None);
+
+    // Proposals outside the range are not returned.
+    assert!(tester
+        .client
+        .get_lighthouse_validator_proposals(slot + 1, slot + 10)
+        .await
+        .unwrap()
+        .data
+        .is_empty());
+}
@@ -4,6 +4,7 @@ use beacon_chain::{
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
 use environment::null_logger;
+use eth2::lighthouse::PayloadSource;
 use eth2::{
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
@@ -12,6 +13,7 @@ use eth2::{
     },
     BeaconNodeHttpClient, Error, StatusCode, Timeouts,
 };
+use execution_layer::relays::RELAY_FAULT_THRESHOLD;
 use execution_layer::test_utils::{
     MockBuilder, Operation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
 };
@@ -21,8 +23,8 @@ use http_api::{
     test_utils::{create_api_server, ApiServer},
     BlockId, StateId,
 };
-use lighthouse_network::{Enr, EnrExt, PeerId};
-use network::NetworkReceivers;
+use lighthouse_network::{types::SyncState, Enr, EnrExt, NetworkGlobals, PeerId};
+use network::{NetworkMessage, NetworkReceivers};
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
@@ -71,9 +73,11 @@ struct ApiTester {
     proposer_slashing: ProposerSlashing,
     voluntary_exit: SignedVoluntaryExit,
     network_rx: NetworkReceivers<E>,
+    network_globals: Arc<NetworkGlobals<E>>,
     local_enr: Enr,
     external_peer_id: PeerId,
     mock_builder: Option<Arc<MockBuilder<E>>>,
+    mock_builders: Vec<Arc<MockBuilder<E>>>,
 }
 
 struct ApiTesterConfig {
@@ -118,6 +122,11 @@ impl ApiTester {
     }
 
     pub async fn new_from_config(config: ApiTesterConfig) -> Self {
+        Self::new_from_config_with_relays(config, 1).await
+    }
+
+    /// Create a tester whose beacon node requests bids from `relays` mock builders.
+    pub async fn new_from_config_with_relays(config: ApiTesterConfig, relays: usize) -> Self {
         let spec = config.spec;
 
         let mut harness = BeaconChainHarness::builder(MainnetEthSpec)
@@ -251,6 +260,7 @@ impl ApiTester {
             server,
             listening_socket,
             network_rx,
+            network_globals,
             local_enr,
             external_peer_id,
         } = create_api_server(chain.clone(), &harness.runtime, log).await;
@@ -262,7 +272,7 @@ impl ApiTester {
         let beacon_api_port = listening_socket.port();
         let beacon_url =
             SensitiveUrl::parse(format!("http://127.0.0.1:{beacon_api_port}").as_str()).unwrap();
-        let mock_builder_server = harness.set_mock_builder(beacon_url.clone());
+        let mock_builder_server = harness.set_mock_builders(beacon_url.clone(), relays);
 
         // Start the mock builder service prior to building the chain out.
         harness.runtime.task_executor.spawn(
@@ -271,6 +281,7 @@ impl ApiTester {
         );
 
         let mock_builder = harness.mock_builder.clone();
+        let mock_builders = harness.mock_builders.clone();
 
         let client = BeaconNodeHttpClient::new(
             beacon_url,
@@ -289,9 +300,11 @@ impl ApiTester {
             proposer_slashing,
             voluntary_exit,
             network_rx,
+            network_globals,
             local_enr,
             external_peer_id,
             mock_builder,
+            mock_builders,
         }
     }
 
@@ -344,6 +357,7 @@ impl ApiTester {
             server,
             listening_socket,
             network_rx,
+            network_globals,
             local_enr,
             external_peer_id,
         } = create_api_server(chain.clone(), &harness.runtime, log).await;
@@ -372,9 +386,11 @@ impl ApiTester {
             proposer_slashing,
             voluntary_exit,
             network_rx,
+            network_globals,
             local_enr,
             external_peer_id,
             mock_builder: None,
+            mock_builders: vec![],
         }
     }
 
@@ -413,6 +429,19 @@ impl ApiTester {
         tester
     }
 
+    pub async fn new_multi_relay_mev_tester(relays: usize) -> Self {
+        let mut config = ApiTesterConfig {
+            retain_historic_states: false,
+            spec: E::default_spec(),
+        };
+        config.spec.altair_fork_epoch = Some(Epoch::new(0));
+        config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+        Self::new_from_config_with_relays(config, relays)
+            .await
+            .test_post_validator_register_validator()
+            .await
+    }
+
     fn skip_slots(self, count: u64) -> Self {
         for _ in 0..count {
             self.chain
@@ -3824,6 +3853,92 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_builder_proposal_reward_recorded(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+        let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+
+        let (payload_type, _) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, None)
+            .await
+            .unwrap();
+        let signed_blinded_block = match payload_type.data {
+            ProduceBlockV3Response::Blinded(block) => {
+                block.sign(sk, &fork, genesis_validators_root, &self.chain.spec)
+            }
+            ProduceBlockV3Response::Full(_) => panic!("Expecting a blinded payload"),
+        };
+        let block_root = signed_blinded_block.canonical_root();
+
+        // Changing the fee recipient after production must not affect the recorded reward.
+        let registered_fee_recipient = Address::from_low_u64_be(proposer_index);
+        self.chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .update_proposer_preparation(
+                epoch,
+                &[ProposerPreparationData {
+                    validator_index: proposer_index,
+                    fee_recipient: Address::repeat_byte(0x42),
+                }],
+            )
+            .await;
+
+        self.client
+            .post_beacon_blinded_blocks(&signed_blinded_block)
+            .await
+            .unwrap();
+
+        // The reward is recorded in the background once the block has been imported.
+        let mut reward = None;
+        for _ in 0..50 {
+            reward = self
+                .client
+                .get_lighthouse_validator_proposals(slot, slot)
+                .await
+                .unwrap()
+                .data
+                .pop();
+            if reward.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let reward = reward.expect("proposal reward should be recorded");
+
+        assert_eq!(reward.block_root, block_root);
+        assert_eq!(reward.payload_source, PayloadSource::Builder);
+        assert_eq!(reward.fee_recipient, registered_fee_recipient);
+        assert_eq!(
+            reward.bid_value,
+            Some(Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI))
+        );
+        // The mock execution layer only credits its own payload value to the fee recipient, so
+        // the builder appears to have paid less than its bid.
+        assert_eq!(
+            reward.paid_value,
+            Some(Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI))
+        );
+        assert_eq!(reward.balance_decrease, None);
+
+        // The shortfall is recorded against the relay, without disabling it.
+        let status = self
+            .client
+            .get_lighthouse_builder_relays()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(status[0].value_shortfalls, 1);
+        assert!(status[0].enabled);
+
+        self
+    }
+
     pub async fn test_payload_rejects_invalid_parent_hash(self) -> Self {
         let invalid_parent_hash =
             "0x4242424242424242424242424242424242424242424242424242424242424242"
@@ -4723,6 +4838,204 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_builder_payload_v3_chosen_from_most_valuable_relay(self) -> Self {
+        let values = [1, 3, 2];
+        for (mock_builder, value) in self.mock_builders.iter().zip(values) {
+            mock_builder.add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + value,
+            )));
+        }
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let (payload_type, metadata) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, None)
+            .await
+            .unwrap();
+
+        let block_hash = match payload_type.data {
+            ProduceBlockV3Response::Blinded(block) => {
+                block.body().execution_payload().unwrap().block_hash()
+            }
+            ProduceBlockV3Response::Full(_) => panic!("Expecting a blinded payload"),
+        };
+        assert_eq!(
+            metadata.execution_payload_value,
+            Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 3)
+        );
+
+        // The block must only be revealed to the relay which supplied the winning bid.
+        let relays = self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .builder()
+            .unwrap();
+        let winning_relay = relays.winning_relay(&block_hash).unwrap();
+        assert!(Arc::ptr_eq(&winning_relay, relays.iter().nth(1).unwrap()));
+
+        self
+    }
+
+    /// Produce and sign a blinded block whose winning bid came from the second relay.
+    async fn sign_blinded_block_from_second_relay(&self) -> SignedBlindedBeaconBlock<E> {
+        let values = [1, 3, 2];
+        for (mock_builder, value) in self.mock_builders.iter().zip(values) {
+            mock_builder.add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + value,
+            )));
+        }
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+        let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+
+        let (payload_type, _) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, None)
+            .await
+            .unwrap();
+
+        match payload_type.data {
+            ProduceBlockV3Response::Blinded(block) => {
+                block.sign(sk, &fork, genesis_validators_root, &self.chain.spec)
+            }
+            ProduceBlockV3Response::Full(_) => panic!("Expecting a blinded payload"),
+        }
+    }
+
+    pub async fn test_blinded_block_only_revealed_to_winning_relay(self) -> Self {
+        let signed_blinded_block = self.sign_blinded_block_from_second_relay().await;
+
+        self.client
+            .post_beacon_blinded_blocks(&signed_blinded_block)
+            .await
+            .unwrap();
+
+        let received = self
+            .mock_builders
+            .iter()
+            .map(|mock_builder| mock_builder.blinded_blocks_received())
+            .collect::<Vec<_>>();
+        assert_eq!(received, vec![0, 1, 0]);
+
+        self
+    }
+
+    pub async fn test_blinded_block_not_revealed_to_unknown_relay(self) -> Self {
+        let signed_blinded_block = self.sign_blinded_block_from_second_relay().await;
+
+        // Replace the relays with a set which does not include the winning relay.
+        let execution_layer = self.chain.execution_layer.as_ref().unwrap();
+        let relay_urls = execution_layer
+            .builder()
+            .unwrap()
+            .iter()
+            .map(|relay| SensitiveUrl::parse(relay.name()).unwrap())
+            .collect::<Vec<_>>();
+        execution_layer
+            .set_builder_urls(
+                vec![relay_urls[0].clone(), relay_urls[2].clone()],
+                None,
+                None,
+            )
+            .unwrap();
+
+        assert!(self
+            .client
+            .post_beacon_blinded_blocks(&signed_blinded_block)
+            .await
+            .is_err());
+
+        // The signed block must not have been revealed to any relay.
+        for mock_builder in &self.mock_builders {
+            assert_eq!(mock_builder.blinded_blocks_received(), 0);
+        }
+
+        self
+    }
+
+    pub async fn test_payload_v3_ignores_invalid_relay_bid(self) -> Self {
+        // The most valuable bid has an invalid signature and must be ignored.
+        self.mock_builders[0].invalid_signatures();
+        let values = [10, 1, 2];
+        for (mock_builder, value) in self.mock_builders.iter().zip(values) {
+            mock_builder.add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + value,
+            )));
+        }
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let (payload_type, metadata) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, None)
+            .await
+            .unwrap();
+
+        match payload_type.data {
+            ProduceBlockV3Response::Blinded(_) => (),
+            ProduceBlockV3Response::Full(_) => panic!("Expecting a blinded payload"),
+        };
+        assert_eq!(
+            metadata.execution_payload_value,
+            Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 2)
+        );
+
+        self
+    }
+
+    pub async fn test_relay_disabled_after_invalid_bids(self) -> Self {
+        self.mock_builders[0].invalid_signatures();
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        for _ in 0..RELAY_FAULT_THRESHOLD + 1 {
+            let (payload_type, _) = self
+                .client
+                .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, None)
+                .await
+                .unwrap();
+            match payload_type.data {
+                ProduceBlockV3Response::Blinded(_) => (),
+                ProduceBlockV3Response::Full(_) => panic!("Expecting a blinded payload"),
+            };
+        }
+
+        let status = self
+            .client
+            .get_lighthouse_builder_relays()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(status.len(), 2);
+
+        // The misbehaving relay is no longer queried once it has been disabled.
+        assert!(!status[0].enabled);
+        assert!(status[0].disabled_for_secs.is_some());
+        assert_eq!(status[0].invalid_bids, RELAY_FAULT_THRESHOLD as u64);
+        assert_eq!(status[0].times_disabled, 1);
+
+        assert!(status[1].enabled);
+        assert_eq!(status[1].valid_bids, RELAY_FAULT_THRESHOLD as u64 + 1);
+        assert_eq!(status[1].invalid_bids, 0);
+
+        self
+    }
+
     pub async fn test_local_payload_chosen_when_equally_profitable(self) -> Self {
         // Mutate value.
         self.mock_builder
@@ -5005,6 +5318,113 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_sync_status(self) -> Self {
+        let result = self.client.get_lighthouse_sync_status().await.unwrap().data;
+
+        // The test network globals are always synced and no sync processes are running.
+        assert_eq!(result.state, SyncState::Synced);
+        assert!(result.range_chains.is_empty());
+        assert!(result.lookups.single_block_lookups.is_empty());
+
+        self
+    }
+
+    pub async fn test_lighthouse_network_rate_limits(mut self) -> Self {
+        let report = self
+            .client
+            .get_lighthouse_network_rate_limits()
+            .await
+            .unwrap()
+            .data;
+
+        // The test network globals are not attached to an RPC behaviour, so rate limiting is
+        // disabled and updates must be rejected.
+        assert!(!report.enabled);
+        let update = eth2::lighthouse::RateLimitsUpdate {
+            default: Some("beacon_blocks_by_range:2048/10".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            self.client
+                .post_lighthouse_network_rate_limits(&update)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+
+        // Pretend the network service has enabled both rate limiters.
+        {
+            let mut report = self.network_globals.rate_limiter.write();
+            report.enabled = true;
+            report.outbound_quotas = Some("ping:2/10".to_string());
+        }
+
+        // Invalid quotas are rejected without reaching the network service.
+        let update = eth2::lighthouse::RateLimitsUpdate {
+            outbound: Some("beacon_blocks_by_range:0/10".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            self.client
+                .post_lighthouse_network_rate_limits(&update)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+        assert!(self.network_rx.network_recv.recv().now_or_never().is_none());
+
+        // Valid updates are forwarded and the result of the network service is returned.
+        let update = eth2::lighthouse::RateLimitsUpdate {
+            default: Some("beacon_blocks_by_range:2048/10".to_string()),
+            peers: [(self.external_peer_id.to_string(), String::new())].into(),
+            outbound: Some("ping:3/10".to_string()),
+            ..Default::default()
+        };
+        for network_result in [Ok(()), Err("Inbound rate limiting is disabled".to_string())] {
+            let client = &self.client;
+            let network_recv = &mut self.network_rx.network_recv;
+            let external_peer_id = self.external_peer_id;
+            let expect_success = network_result.is_ok();
+            let (result, ()) = futures::future::join(
+                client.post_lighthouse_network_rate_limits(&update),
+                async move {
+                    match network_recv.recv().await {
+                        Some(NetworkMessage::UpdateRateLimits {
+                            inbound,
+                            outbound,
+                            result_tx,
+                        }) => {
+                            let inbound = inbound.unwrap();
+                            assert_eq!(
+                                inbound.default,
+                                Some("beacon_blocks_by_range:2048/10".parse().unwrap())
+                            );
+                            assert_eq!(inbound.trusted_peers, None);
+                            assert_eq!(inbound.peers, [(external_peer_id, None)].into());
+                            assert_eq!(outbound, Some("ping:3/10".parse().unwrap()));
+                            result_tx.send(network_result).unwrap();
+                        }
+                        other => panic!("unexpected network message {:?}", other),
+                    }
+                },
+            )
+            .await;
+            if expect_success {
+                result.unwrap();
+            } else {
+                assert_eq!(result.unwrap_err().status().map(Into::into), Some(400));
+            }
+        }
+
+        *self.network_globals.rate_limiter.write() = report;
+
+        self
+    }
+
     pub async fn test_get_lighthouse_proto_array(self) -> Self {
         self.client.get_lighthouse_proto_array().await.unwrap();
 
@@ -5057,6 +5477,79 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_eth1_deposit_snapshot_consistency(self) -> Self {
+        let (state, _, _) = StateId(CoreStateId::Finalized).state(&self.chain).unwrap();
+        let finalized_eth1_data = state.eth1_data().clone();
+
+        let consistency = self
+            .client
+            .get_lighthouse_eth1_deposit_snapshot_consistency()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(
+            consistency.finalized_deposit_count,
+            finalized_eth1_data.deposit_count
+        );
+        assert_eq!(
+            consistency.finalized_deposit_root,
+            finalized_eth1_data.deposit_root
+        );
+        assert_eq!(
+            consistency.consistent,
+            consistency
+                .local_deposit_root
+                .map(|root| root == finalized_eth1_data.deposit_root)
+        );
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_eth1_deposit_snapshot(self) -> Self {
+        let mut snapshot = DepositTreeSnapshot {
+            finalized: vec![Hash256::repeat_byte(1)],
+            deposit_root: Hash256::zero(),
+            deposit_count: 1,
+            execution_block_hash: Hash256::repeat_byte(2),
+            execution_block_height: 100,
+        };
+
+        // A snapshot whose root does not match its finalized hashes is rejected.
+        let result = self
+            .client
+            .post_lighthouse_eth1_deposit_snapshot(&snapshot)
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+
+        snapshot.deposit_root = snapshot.calculate_root().unwrap();
+        self.client
+            .post_lighthouse_eth1_deposit_snapshot(&snapshot)
+            .await
+            .unwrap();
+
+        let consistency = self
+            .client
+            .get_lighthouse_eth1_deposit_snapshot_consistency()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(consistency.snapshot_deposit_count, Some(1));
+        assert_eq!(
+            consistency.snapshot_deposit_root,
+            Some(snapshot.deposit_root)
+        );
+
+        // A snapshot which is not newer than the imported snapshot is rejected.
+        let result = self
+            .client
+            .post_lighthouse_eth1_deposit_snapshot(&snapshot)
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_beacon_states_ssz(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let result = self
@@ -6115,6 +6608,14 @@ async fn post_validator_register_fee_recipient_mutation_v3() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_proposal_reward_recorded() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_builder_proposal_reward_recorded()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_blinded_block_invalid_parent_hash() {
     ApiTester::new_mev_tester()
@@ -6271,6 +6772,40 @@ async fn builder_payload_chosen_by_profit() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_payload_chosen_from_multiple_relays() {
+    ApiTester::new_multi_relay_mev_tester(3)
+        .await
+        .test_builder_payload_v3_chosen_from_most_valuable_relay()
+        .await
+        .test_payload_v3_ignores_invalid_relay_bid()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_blinded_block_only_revealed_to_winning_relay() {
+    ApiTester::new_multi_relay_mev_tester(3)
+        .await
+        .test_blinded_block_only_revealed_to_winning_relay()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_blinded_block_not_revealed_to_unknown_relay() {
+    ApiTester::new_multi_relay_mev_tester(3)
+        .await
+        .test_blinded_block_not_revealed_to_unknown_relay()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_relay_circuit_breaker() {
+    ApiTester::new_multi_relay_mev_tester(2)
+        .await
+        .test_relay_disabled_after_invalid_bids()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_payload_chosen_by_profit_v3() {
     ApiTester::new_mev_tester_default_payload_value()
@@ -6361,6 +6896,10 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_sync_status()
+        .await
+        .test_lighthouse_network_rate_limits()
+        .await
         .test_get_lighthouse_proto_array()
         .await
         .test_get_lighthouse_validator_inclusion()
@@ -6373,6 +6912,10 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_eth1_deposit_cache()
         .await
+        .test_get_lighthouse_eth1_deposit_snapshot_consistency()
+        .await
+        .test_post_lighthouse_eth1_deposit_snapshot()
+        .await
         .test_get_lighthouse_beacon_states_ssz()
         .await
         .test_get_lighthouse_staking()
@@ -1,5 +1,5 @@
 use crate::listen_addr::{ListenAddr, ListenAddress};
-use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig, RateLimiterConfig};
 use crate::types::GossipKind;
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -149,6 +149,9 @@ pub struct Config {
     /// Whether light client protocols should be enabled.
     pub enable_light_client_server: bool,
 
+    /// Whether the finalized state should be served to trusted peers for checkpoint sync.
+    pub enable_finalized_state_server: bool,
+
     /// Configuration for the outbound rate limiter (requests made by this node).
     pub outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
 
@@ -158,6 +161,9 @@ pub struct Config {
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
 
+    /// Quotas of the inbound rate limiter for trusted peers, instead of the default quotas.
+    pub trusted_peers_inbound_rate_limiter_config: Option<RateLimiterConfig>,
+
     /// Whether to disable logging duplicate gossip messages as WARN. If set to true, duplicate  
     /// errors will be logged at DEBUG level.
     pub disable_duplicate_warn_logs: bool,
@@ -375,9 +381,11 @@ impl Default for Config {
             proposer_only: false,
             metrics_enabled: false,
             enable_light_client_server: false,
+            enable_finalized_state_server: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
+            trusted_peers_inbound_rate_limiter_config: None,
             disable_duplicate_warn_logs: false,
         }
     }
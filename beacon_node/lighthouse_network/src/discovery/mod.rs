@@ -8,6 +8,7 @@ pub mod enr_ext;
 
 // Allow external use of the lighthouse ENR builder
 use crate::service::TARGET_SUBNET_PEERS;
+use crate::types::Transport;
 use crate::{error, Enr, NetworkConfig, NetworkGlobals, Subnet, SubnetDiscovery};
 use crate::{metrics, ClearDialError};
 use discv5::{enr::NodeId, Discv5};
@@ -30,6 +31,7 @@ pub use libp2p::{
     },
 };
 use lru::LruCache;
+use rand::seq::SliceRandom;
 use slog::{crit, debug, error, info, trace, warn};
 use ssz::Encode;
 use std::num::NonZeroUsize;
@@ -71,6 +73,12 @@ pub const FIND_NODE_QUERY_CLOSEST_PEERS: usize = 16;
 const DURATION_DIFFERENCE: Duration = Duration::from_millis(1);
 /// The capacity of the Discovery ENR cache.
 const ENR_CACHE_CAPACITY: NonZeroUsize = new_non_zero_usize(50);
+/// The interval at which routing table entries are pinged to learn the address they observe us on.
+///
+/// Discv5 does not surface the PONGs of its own liveness pings, so we send our own.
+const OBSERVED_ADDRESS_PING_INTERVAL: Duration = Duration::from_secs(300);
+/// The number of routing table entries pinged each `OBSERVED_ADDRESS_PING_INTERVAL`.
+const OBSERVED_ADDRESS_PING_PEERS: usize = 16;
 
 /// A query has completed. This result contains a mapping of discovered peer IDs to the `min_ttl`
 /// of the peer if it is specified.
@@ -183,6 +191,13 @@ pub struct Discovery<TSpec: EthSpec> {
     /// The discv5 event stream.
     event_stream: EventStream,
 
+    /// Triggers pinging a sample of the routing table.
+    observed_address_ping_interval: tokio::time::Interval,
+
+    /// Active pings, resolving to the address the remote peer observes us on.
+    observed_address_pings:
+        FuturesUnordered<std::pin::Pin<Box<dyn Future<Output = Option<SocketAddr>> + Send>>>,
+
     /// Indicates if the discovery service has been started. When the service is disabled, this is
     /// always false.
     pub started: bool,
@@ -321,6 +336,8 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
             active_queries: FuturesUnordered::new(),
             discv5,
             event_stream,
+            observed_address_ping_interval: tokio::time::interval(OBSERVED_ADDRESS_PING_INTERVAL),
+            observed_address_pings: FuturesUnordered::new(),
             started: !config.disable_discovery,
             update_ports,
             log,
@@ -902,6 +919,39 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         None
     }
 
+    /// Pings a random sample of the routing table. The PONGs contain the address each peer
+    /// observes us on, which is fed to the reachability self-test.
+    fn ping_for_observed_addresses(&mut self) {
+        let mut enrs = self.discv5.table_entries_enr();
+        enrs.shuffle(&mut rand::thread_rng());
+        for enr in enrs.into_iter().take(OBSERVED_ADDRESS_PING_PEERS) {
+            let ping = self.discv5.send_ping(enr);
+            let log = self.log.clone();
+            self.observed_address_pings.push(Box::pin(async move {
+                match ping.await {
+                    Ok(pong) => Some(SocketAddr::new(pong.ip, u16::from(pong.port))),
+                    Err(e) => {
+                        trace!(log, "Discovery ping failed"; "error" => ?e);
+                        None
+                    }
+                }
+            }));
+        }
+    }
+
+    /// Drives the pings, recording the addresses reported in their PONGs.
+    fn poll_observed_address_pings(&mut self, cx: &mut Context) {
+        while self.observed_address_ping_interval.poll_tick(cx).is_ready() {
+            self.ping_for_observed_addresses();
+        }
+        while let Poll::Ready(Some(observed)) = self.observed_address_pings.poll_next_unpin(cx) {
+            if let Some(address) = observed {
+                self.network_globals
+                    .record_observed_address(Transport::Discv5, address);
+            }
+        }
+    }
+
     /// Drives the queries returning any results from completed queries.
     fn poll_queries(&mut self, cx: &mut Context) -> Option<HashMap<Enr, Option<Instant>>> {
         while let Poll::Ready(Some(query_result)) = self.active_queries.poll_next_unpin(cx) {
@@ -959,6 +1009,9 @@ impl<TSpec: EthSpec> NetworkBehaviour for Discovery<TSpec> {
         // Process the query queue
         self.process_queue();
 
+        // Learn the address peers observe us on from the PONGs of our pings
+        self.poll_observed_address_pings(cx);
+
         // Drive the queries and return any results from completed queries
         if let Some(peers) = self.poll_queries(cx) {
             // return the result to the peer manager
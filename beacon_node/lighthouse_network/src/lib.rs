@@ -33,6 +33,12 @@ impl From<PeerIdSerialized> for PeerId {
     }
 }
 
+impl From<PeerId> for PeerIdSerialized {
+    fn from(peer_id: PeerId) -> Self {
+        Self(peer_id)
+    }
+}
+
 impl FromStr for PeerIdSerialized {
     type Err = String;
 
@@ -9,6 +9,22 @@ lazy_static! {
         "libp2p_address_update_total",
         "Count of libp2p socked updated events (when our view of our IP address has changed)"
     );
+    pub static ref REACHABILITY_OBSERVED_ADDRESS_REPORTS: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_reachability_observed_address_reports_total",
+            "Count of external addresses of the local node reported by peers via identify and discv5",
+            &["transport", "ip_family"]
+        );
+    pub static ref REACHABILITY_CONNECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_reachability_connections_total",
+        "Count of established connections per transport, IP family and direction",
+        &["transport", "ip_family", "direction"]
+    );
+    pub static ref REACHABILITY_ENR_MISMATCH: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "libp2p_reachability_enr_mismatch",
+        "Set to 1 if the ENR disagrees with the external address observed by peers",
+        &["transport", "ip_family"]
+    );
     pub static ref PEERS_CONNECTED: Result<IntGauge> = try_create_int_gauge(
         "libp2p_peers",
         "Count of libp2p peers currently connected"
@@ -79,6 +95,18 @@ lazy_static! {
         "RPC requests total",
         &["type"]
     );
+    pub static ref TOTAL_RPC_REQUESTS_RATE_LIMITED: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_rpc_requests_rate_limited_total",
+            "Inbound RPC requests rejected by the rate limiter, per protocol",
+            &["protocol"]
+        );
+    pub static ref TOTAL_RPC_REQUESTS_TOO_LARGE: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_rpc_requests_too_large_total",
+            "Inbound RPC requests exceeding the maximum quota of the rate limiter, per protocol",
+            &["protocol"]
+        );
     pub static ref PEER_ACTION_EVENTS_PER_CLIENT: Result<IntCounterVec> =
         try_create_int_counter_vec(
             "libp2p_peer_actions_per_client",
@@ -156,6 +184,11 @@ lazy_static! {
     );
 }
 
+/// Returns `true` if we consider the NAT open, as determined by `check_nat`.
+pub fn nat_open() -> bool {
+    NAT_OPEN.as_ref().map(|v| v.get()).unwrap_or(0) != 0
+}
+
 /// Checks if we consider the NAT open.
 ///
 /// Conditions for an open NAT:
@@ -166,7 +199,7 @@ lazy_static! {
 ///    manage to get at least one inbound peer, we are exposed correctly.
 pub fn check_nat() {
     // NAT is already deemed open.
-    if NAT_OPEN.as_ref().map(|v| v.get()).unwrap_or(0) != 0 {
+    if nat_open() {
         return;
     }
     if ADDRESS_UPDATE_COUNT.as_ref().map(|v| v.get()).unwrap_or(0) != 0
@@ -16,7 +16,7 @@ use types::EthSpec;
 use crate::discovery::enr_ext::EnrExt;
 use crate::peer_manager::peerdb::BanResult;
 use crate::rpc::GoodbyeReason;
-use crate::types::SyncState;
+use crate::types::{transport_socket_addr, IpFamily, SyncState};
 use crate::{metrics, ClearDialError};
 
 use super::{ConnectingType, PeerManager, PeerManagerEvent};
@@ -242,6 +242,14 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             metrics::check_nat();
         }
 
+        if let Some((transport, address)) = transport_socket_addr(endpoint.get_remote_address()) {
+            self.network_globals.reachability.write().record_connection(
+                transport,
+                IpFamily::from(&address.ip()),
+                endpoint.is_listener(),
+            );
+        }
+
         // increment prometheus metrics
         if self.metrics_enabled {
             let remote_addr = endpoint.get_remote_address();
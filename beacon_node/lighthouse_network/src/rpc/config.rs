@@ -1,12 +1,19 @@
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display},
     str::FromStr,
     time::Duration,
 };
 
-use super::{rate_limiter::Quota, Protocol};
+use super::{
+    methods::MAX_FINALIZED_STATE_CHUNKS,
+    rate_limiter::{Limiter, Quota},
+    Protocol,
+};
 
+use libp2p::PeerId;
 use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
 
 /// Auxiliary struct to aid on configuration parsing.
 ///
@@ -80,6 +87,17 @@ impl FromStr for InboundRateLimiterConfig {
     }
 }
 
+/// Changes to the quotas of the inbound rate limiter, applied at runtime.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InboundRateLimiterUpdate {
+    /// New quotas for peers without an override.
+    pub default: Option<RateLimiterConfig>,
+    /// New quotas for trusted peers. `Some(None)` removes the trusted peer quotas.
+    pub trusted_peers: Option<Option<RateLimiterConfig>>,
+    /// New peer specific quotas. A `None` value removes the override for that peer.
+    pub peers: HashMap<PeerId, Option<RateLimiterConfig>>,
+}
+
 /// Configurations for the rate limiter.
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RateLimiterConfig {
@@ -92,6 +110,7 @@ pub struct RateLimiterConfig {
     pub(super) blobs_by_range_quota: Quota,
     pub(super) blobs_by_root_quota: Quota,
     pub(super) light_client_bootstrap_quota: Quota,
+    pub(super) finalized_state_quota: Quota,
 }
 
 impl RateLimiterConfig {
@@ -104,6 +123,50 @@ impl RateLimiterConfig {
     pub const DEFAULT_BLOBS_BY_RANGE_QUOTA: Quota = Quota::n_every(768, 10);
     pub const DEFAULT_BLOBS_BY_ROOT_QUOTA: Quota = Quota::n_every(128, 10);
     pub const DEFAULT_LIGHT_CLIENT_BOOTSTRAP_QUOTA: Quota = Quota::one_every(10);
+    // A finalized state request costs as many tokens as it may have chunks.
+    pub const DEFAULT_FINALIZED_STATE_QUOTA: Quota =
+        Quota::n_every(MAX_FINALIZED_STATE_CHUNKS, 300);
+
+    /// Returns the quota for `protocol`.
+    pub(super) fn quota(&self, protocol: Protocol) -> &Quota {
+        match protocol {
+            Protocol::Ping => &self.ping_quota,
+            Protocol::Status => &self.status_quota,
+            Protocol::MetaData => &self.meta_data_quota,
+            Protocol::Goodbye => &self.goodbye_quota,
+            Protocol::BlocksByRange => &self.blocks_by_range_quota,
+            Protocol::BlocksByRoot => &self.blocks_by_root_quota,
+            Protocol::BlobsByRange => &self.blobs_by_range_quota,
+            Protocol::BlobsByRoot => &self.blobs_by_root_quota,
+            Protocol::LightClientBootstrap => &self.light_client_bootstrap_quota,
+            Protocol::FinalizedState => &self.finalized_state_quota,
+        }
+    }
+
+    /// Checks that every quota can be used by a rate limiter.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        for protocol in Protocol::iter() {
+            Limiter::<()>::from_quota(self.quota(protocol).clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays the configuration in the same format parsed by `FromStr`, with every protocol
+/// present.
+impl Display for RateLimiterConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let quotas = Protocol::iter()
+            .map(|protocol| {
+                ProtocolQuota {
+                    protocol,
+                    quota: self.quota(protocol).clone(),
+                }
+                .to_string()
+            })
+            .collect::<Vec<_>>();
+        f.write_str(&quotas.join(";"))
+    }
 }
 
 impl Default for RateLimiterConfig {
@@ -118,6 +181,7 @@ impl Default for RateLimiterConfig {
             blobs_by_range_quota: Self::DEFAULT_BLOBS_BY_RANGE_QUOTA,
             blobs_by_root_quota: Self::DEFAULT_BLOBS_BY_ROOT_QUOTA,
             light_client_bootstrap_quota: Self::DEFAULT_LIGHT_CLIENT_BOOTSTRAP_QUOTA,
+            finalized_state_quota: Self::DEFAULT_FINALIZED_STATE_QUOTA,
         }
     }
 }
@@ -164,6 +228,7 @@ impl FromStr for RateLimiterConfig {
         let mut blobs_by_range_quota = None;
         let mut blobs_by_root_quota = None;
         let mut light_client_bootstrap_quota = None;
+        let mut finalized_state_quota = None;
 
         for proto_def in s.split(';') {
             let ProtocolQuota { protocol, quota } = proto_def.parse()?;
@@ -180,6 +245,7 @@ impl FromStr for RateLimiterConfig {
                 Protocol::LightClientBootstrap => {
                     light_client_bootstrap_quota = light_client_bootstrap_quota.or(quota)
                 }
+                Protocol::FinalizedState => finalized_state_quota = finalized_state_quota.or(quota),
             }
         }
         Ok(RateLimiterConfig {
@@ -196,6 +262,8 @@ impl FromStr for RateLimiterConfig {
             blobs_by_root_quota: blobs_by_root_quota.unwrap_or(Self::DEFAULT_BLOBS_BY_ROOT_QUOTA),
             light_client_bootstrap_quota: light_client_bootstrap_quota
                 .unwrap_or(Self::DEFAULT_LIGHT_CLIENT_BOOTSTRAP_QUOTA),
+            finalized_state_quota: finalized_state_quota
+                .unwrap_or(Self::DEFAULT_FINALIZED_STATE_QUOTA),
         })
     }
 }
@@ -215,4 +283,16 @@ mod tests {
         };
         assert_eq!(quota.to_string().parse(), Ok(quota))
     }
+
+    #[test]
+    fn test_config_inverse() {
+        let config: RateLimiterConfig = "beacon_blocks_by_range:2048/10;ping:1/5".parse().unwrap();
+        assert_eq!(config.to_string().parse(), Ok(config));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config: RateLimiterConfig = "beacon_blocks_by_range:0/10".parse().unwrap();
+        assert!(config.validate().is_err());
+    }
 }
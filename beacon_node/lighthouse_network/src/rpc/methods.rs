@@ -5,7 +5,10 @@ use regex::bytes::Regex;
 use serde::Serialize;
 use ssz::Encode;
 use ssz_derive::{Decode, Encode};
-use ssz_types::{typenum::U256, VariableList};
+use ssz_types::{
+    typenum::{U16777216, U256},
+    VariableList,
+};
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -21,6 +24,13 @@ use types::{
 pub type MaxErrorLen = U256;
 pub const MAX_ERROR_LEN: u64 = 256;
 
+/// Upper bound on the data carried by a single `FinalizedStateChunk`. In practice chunks are
+/// further bounded by `max_rpc_size`.
+pub type MaxFinalizedStateChunkLen = U16777216;
+
+/// Maximum number of chunks a finalized state may be split into.
+pub const MAX_FINALIZED_STATE_CHUNKS: u64 = 512;
+
 /// Wrapper over SSZ List to represent error message in rpc responses.
 #[derive(Debug, Clone)]
 pub struct ErrorType(pub VariableList<u8, MaxErrorLen>);
@@ -390,6 +400,9 @@ pub enum RPCResponse<T: EthSpec> {
     /// A response to a get BLOBS_BY_ROOT request.
     BlobsByRoot(Arc<BlobSidecar<T>>),
 
+    /// A chunk of the response to a get FINALIZED_STATE_BY_ROOT request.
+    FinalizedState(Arc<FinalizedStateChunk>),
+
     /// A PONG response to a PING request.
     Pong(Ping),
 
@@ -411,6 +424,9 @@ pub enum ResponseTermination {
 
     /// Blobs by root stream termination.
     BlobsByRoot,
+
+    /// Finalized state stream termination.
+    FinalizedState,
 }
 
 /// The structured response containing a result/code indicating success or failure
@@ -432,6 +448,70 @@ pub struct LightClientBootstrapRequest {
     pub root: Hash256,
 }
 
+/// Request the finalized state for the given checkpoint block root from a trusted peer.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct FinalizedStateRequest {
+    pub block_root: Hash256,
+}
+
+/// A slice of the SSZ bytes of a finalized `BeaconState`.
+///
+/// States are much larger than the maximum RPC message size, so they are streamed as a sequence
+/// of chunks which the requester reassembles.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct FinalizedStateChunk {
+    /// The length of the full SSZ encoded state.
+    pub total_length: u64,
+    /// The position of `data` within the SSZ encoded state.
+    pub offset: u64,
+    pub data: VariableList<u8, MaxFinalizedStateChunkLen>,
+}
+
+impl FinalizedStateChunk {
+    /// The SSZ overhead of a chunk: two `u64`s and the offset of `data`.
+    pub const OVERHEAD: usize = 8 + 8 + ssz::BYTES_PER_LENGTH_OFFSET;
+
+    pub fn min_size() -> usize {
+        Self::OVERHEAD
+    }
+
+    pub fn max_size() -> usize {
+        Self::OVERHEAD + <MaxFinalizedStateChunkLen as ssz_types::typenum::Unsigned>::to_usize()
+    }
+
+    /// Splits `state_bytes` into chunks which each fit in a message of `max_rpc_size` bytes.
+    pub fn split(state_bytes: &[u8], max_rpc_size: usize) -> Result<Vec<Self>, String> {
+        let chunk_len = std::cmp::min(
+            max_rpc_size.saturating_sub(Self::OVERHEAD),
+            Self::max_size() - Self::OVERHEAD,
+        );
+        if chunk_len == 0 {
+            return Err(format!("max_rpc_size of {max_rpc_size} is too small"));
+        }
+        let chunks = state_bytes
+            .chunks(chunk_len)
+            .enumerate()
+            .map(|(i, data)| {
+                Ok(Self {
+                    total_length: state_bytes.len() as u64,
+                    offset: (i * chunk_len) as u64,
+                    data: VariableList::new(data.to_vec())
+                        .map_err(|e| format!("Invalid chunk length: {e:?}"))?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if chunks.len() as u64 > MAX_FINALIZED_STATE_CHUNKS {
+            return Err(format!(
+                "State of {} bytes requires {} chunks, more than the maximum of {}",
+                state_bytes.len(),
+                chunks.len(),
+                MAX_FINALIZED_STATE_CHUNKS
+            ));
+        }
+        Ok(chunks)
+    }
+}
+
 /// The code assigned to an erroneous `RPCResponse`.
 #[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
@@ -485,6 +565,7 @@ impl<T: EthSpec> RPCCodedResponse<T> {
                 RPCResponse::Pong(_) => false,
                 RPCResponse::MetaData(_) => false,
                 RPCResponse::LightClientBootstrap(_) => false,
+                RPCResponse::FinalizedState(_) => true,
             },
             RPCCodedResponse::Error(_, _) => true,
             // Stream terminations are part of responses that have chunks
@@ -523,6 +604,7 @@ impl<T: EthSpec> RPCResponse<T> {
             RPCResponse::Pong(_) => Protocol::Ping,
             RPCResponse::MetaData(_) => Protocol::MetaData,
             RPCResponse::LightClientBootstrap(_) => Protocol::LightClientBootstrap,
+            RPCResponse::FinalizedState(_) => Protocol::FinalizedState,
         }
     }
 }
@@ -572,6 +654,13 @@ impl<T: EthSpec> std::fmt::Display for RPCResponse<T> {
                     bootstrap.header.beacon.slot
                 )
             }
+            RPCResponse::FinalizedState(chunk) => write!(
+                f,
+                "FinalizedState: offset: {}, length: {}, total length: {}",
+                chunk.offset,
+                chunk.data.len(),
+                chunk.total_length
+            ),
         }
     }
 }
@@ -6,6 +6,7 @@
 
 use futures::future::FutureExt;
 use handler::RPCHandler;
+use libp2p::swarm::behaviour::ConnectionClosed;
 use libp2p::swarm::{
     handler::ConnectionHandler, CloseConnection, ConnectionId, NetworkBehaviour, NotifyHandler,
     ToSwarm,
@@ -13,13 +14,17 @@ use libp2p::swarm::{
 use libp2p::swarm::{FromSwarm, SubstreamProtocol, THandlerInEvent};
 use libp2p::PeerId;
 use rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr};
-use slog::{crit, debug, o};
+use slog::{crit, debug, info, o};
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 use types::{EthSpec, ForkContext};
 
+use crate::metrics;
+use crate::types::NetworkGlobals;
+
 pub(crate) use handler::{HandlerErr, HandlerEvent};
 pub(crate) use methods::{MetaData, MetaDataV1, MetaDataV2, Ping, RPCCodedResponse, RPCResponse};
 pub(crate) use protocol::InboundRequest;
@@ -32,7 +37,10 @@ pub use methods::{
 pub(crate) use outbound::OutboundRequest;
 pub use protocol::{max_rpc_size, Protocol, RPCError};
 
-use self::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use self::config::{
+    InboundRateLimiterConfig, InboundRateLimiterUpdate, OutboundRateLimiterConfig,
+    RateLimiterConfig,
+};
 use self::protocol::RPCProtocol;
 use self::self_limiter::SelfRateLimiter;
 
@@ -119,48 +127,236 @@ pub struct NetworkParams {
 pub struct RPC<Id: ReqId, TSpec: EthSpec> {
     /// Rate limiter
     limiter: Option<RateLimiter>,
+    /// The quotas of the inbound rate limiter, used for reporting and overrides.
+    inbound_quotas: InboundQuotas,
     /// Rate limiter for our own requests.
     self_limiter: Option<SelfRateLimiter<Id, TSpec>>,
+    /// The quotas of the rate limiter for our own requests, used for reporting.
+    outbound_quotas: Option<RateLimiterConfig>,
     /// Queue of events to be processed.
     events: Vec<BehaviourAction<Id, TSpec>>,
     fork_context: Arc<ForkContext>,
     enable_light_client_server: bool,
+    enable_finalized_state_server: bool,
     /// Slog logger for RPC behaviour.
     log: slog::Logger,
     /// Networking constant values
     network_params: NetworkParams,
+    /// Used to expose the state of the rate limiters.
+    network_globals: Arc<NetworkGlobals<TSpec>>,
+}
+
+/// The quotas used by the inbound rate limiter.
+///
+/// Peer specific quotas take precedence over the quotas for trusted peers, which take precedence
+/// over the default quotas.
+struct InboundQuotas {
+    default: Option<RateLimiterConfig>,
+    trusted_peers: HashSet<PeerId>,
+    trusted_peer_quotas: Option<RateLimiterConfig>,
+    peer_quotas: HashMap<PeerId, RateLimiterConfig>,
+}
+
+impl InboundQuotas {
+    /// Returns the quotas overriding the default quotas for `peer_id`, if any.
+    fn override_for(&self, peer_id: &PeerId) -> Option<&RateLimiterConfig> {
+        self.peer_quotas.get(peer_id).or_else(|| {
+            self.trusted_peer_quotas
+                .as_ref()
+                .filter(|_| self.trusted_peers.contains(peer_id))
+        })
+    }
 }
 
 impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         fork_context: Arc<ForkContext>,
         enable_light_client_server: bool,
+        enable_finalized_state_server: bool,
         inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
+        trusted_peers_rate_limiter_config: Option<RateLimiterConfig>,
         outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
+        network_globals: Arc<NetworkGlobals<TSpec>>,
         log: slog::Logger,
         network_params: NetworkParams,
     ) -> Self {
         let log = log.new(o!("service" => "libp2p_rpc"));
 
-        let inbound_limiter = inbound_rate_limiter_config.map(|config| {
+        let inbound_limiter = inbound_rate_limiter_config.as_ref().map(|config| {
             debug!(log, "Using inbound rate limiting params"; "config" => ?config);
-            RateLimiter::new_with_config(config.0)
+            RateLimiter::new_with_config(config.0.clone())
                 .expect("Inbound limiter configuration parameters are valid")
         });
 
-        let self_limiter = outbound_rate_limiter_config.map(|config| {
-            SelfRateLimiter::new(config, log.clone()).expect("Configuration parameters are valid")
+        let self_limiter = outbound_rate_limiter_config.as_ref().map(|config| {
+            SelfRateLimiter::new(config.clone(), log.clone())
+                .expect("Configuration parameters are valid")
         });
 
-        RPC {
+        let inbound_quotas = InboundQuotas {
+            default: inbound_rate_limiter_config.map(|config| config.0),
+            trusted_peers: network_globals
+                .peers
+                .read()
+                .peers()
+                .filter(|(_, info)| info.is_trusted())
+                .map(|(peer_id, _)| *peer_id)
+                .collect(),
+            trusted_peer_quotas: trusted_peers_rate_limiter_config,
+            peer_quotas: HashMap::new(),
+        };
+
+        let mut rpc = RPC {
             limiter: inbound_limiter,
+            inbound_quotas,
             self_limiter,
+            outbound_quotas: outbound_rate_limiter_config.map(|config| config.0),
             events: Vec::new(),
             fork_context,
             enable_light_client_server,
+            enable_finalized_state_server,
             log,
             network_params,
+            network_globals,
+        };
+
+        if let Some(limiter) = rpc.limiter.as_mut() {
+            if let Some(quotas) = rpc.inbound_quotas.trusted_peer_quotas.as_ref() {
+                debug!(rpc.log, "Using inbound rate limiting params for trusted peers"; "config" => ?quotas);
+                for peer_id in rpc.inbound_quotas.trusted_peers.iter() {
+                    limiter
+                        .set_peer_quotas(*peer_id, quotas)
+                        .expect("Trusted peer limiter configuration parameters are valid");
+                }
+            }
+        }
+        rpc.update_rate_limiter_report();
+
+        rpc
+    }
+
+    /// Updates the quotas of the inbound rate limiter.
+    ///
+    /// Either the whole update is applied or, if any of the quotas is invalid, none of it.
+    pub fn update_inbound_rate_limits(
+        &mut self,
+        update: InboundRateLimiterUpdate,
+    ) -> Result<(), &'static str> {
+        let Some(limiter) = self.limiter.as_mut() else {
+            return Err("Inbound rate limiting is disabled");
+        };
+
+        let InboundRateLimiterUpdate {
+            default,
+            trusted_peers,
+            peers,
+        } = update;
+        for config in default
+            .iter()
+            .chain(trusted_peers.iter().flatten())
+            .chain(peers.values().flatten())
+        {
+            config.validate()?;
+        }
+
+        if let Some(default) = default {
+            limiter.set_quotas(&default)?;
+            self.inbound_quotas.default = Some(default);
+        }
+
+        let mut affected_peers = HashSet::new();
+        if let Some(trusted_peer_quotas) = trusted_peers {
+            self.inbound_quotas.trusted_peer_quotas = trusted_peer_quotas;
+            affected_peers.extend(self.inbound_quotas.trusted_peers.iter().copied());
+        }
+        for (peer_id, quotas) in peers {
+            match quotas {
+                Some(quotas) => self.inbound_quotas.peer_quotas.insert(peer_id, quotas),
+                None => self.inbound_quotas.peer_quotas.remove(&peer_id),
+            };
+            affected_peers.insert(peer_id);
+        }
+
+        for peer_id in affected_peers {
+            match self.inbound_quotas.override_for(&peer_id) {
+                Some(quotas) => limiter.set_peer_quotas(peer_id, quotas)?,
+                None => limiter.remove_peer_quotas(&peer_id),
+            }
         }
+
+        info!(
+            self.log,
+            "Updated inbound rate limits";
+            "default" => ?self.inbound_quotas.default,
+            "trusted_peers" => ?self.inbound_quotas.trusted_peer_quotas,
+            "peer_overrides" => self.inbound_quotas.peer_quotas.len(),
+        );
+        self.update_rate_limiter_report();
+        Ok(())
+    }
+
+    /// Replaces the quotas of the rate limiter for our own requests.
+    pub fn update_outbound_rate_limits(
+        &mut self,
+        quotas: RateLimiterConfig,
+    ) -> Result<(), &'static str> {
+        let Some(self_limiter) = self.self_limiter.as_mut() else {
+            return Err("Outbound rate limiting is disabled");
+        };
+
+        self_limiter.set_quotas(&quotas)?;
+        info!(self.log, "Updated outbound rate limits"; "quotas" => ?quotas);
+        self.outbound_quotas = Some(quotas);
+        self.update_rate_limiter_report();
+        Ok(())
+    }
+
+    /// Publishes the current quotas to the network globals.
+    fn update_rate_limiter_report(&self) {
+        let mut report = self.network_globals.rate_limiter.write();
+        report.enabled = self.limiter.is_some();
+        report.default_quotas = self
+            .inbound_quotas
+            .default
+            .as_ref()
+            .map(|quotas| quotas.to_string());
+        report.trusted_peer_quotas = self
+            .inbound_quotas
+            .trusted_peer_quotas
+            .as_ref()
+            .map(|quotas| quotas.to_string());
+        report.peer_quotas = self
+            .inbound_quotas
+            .peer_quotas
+            .iter()
+            .map(|(peer_id, quotas)| (peer_id.to_string(), quotas.to_string()))
+            .collect();
+        report.outbound_quotas = self
+            .outbound_quotas
+            .as_ref()
+            .map(|quotas| quotas.to_string());
+    }
+
+    /// Records a request rejected by the inbound rate limiter.
+    fn record_rate_limited_request(&self, peer_id: &PeerId, protocol: Protocol) {
+        metrics::inc_counter_vec(
+            &metrics::TOTAL_RPC_REQUESTS_RATE_LIMITED,
+            &[protocol.as_ref()],
+        );
+        self.network_globals
+            .rate_limiter
+            .write()
+            .record_rejection(peer_id.to_string(), protocol.as_ref());
+    }
+
+    /// Records a request which exceeds the maximum quota of the inbound rate limiter.
+    fn record_too_large_request(&self, protocol: Protocol) {
+        metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS_TOO_LARGE, &[protocol.as_ref()]);
+        self.network_globals
+            .rate_limiter
+            .write()
+            .record_too_large(protocol.as_ref());
     }
 
     /// Sends an RPC response.
@@ -233,6 +429,7 @@ where
                 fork_context: self.fork_context.clone(),
                 max_rpc_size: max_rpc_size(&self.fork_context, self.network_params.max_chunk_size),
                 enable_light_client_server: self.enable_light_client_server,
+                enable_finalized_state_server: self.enable_finalized_state_server,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
             },
@@ -263,6 +460,7 @@ where
                 fork_context: self.fork_context.clone(),
                 max_rpc_size: max_rpc_size(&self.fork_context, self.network_params.max_chunk_size),
                 enable_light_client_server: self.enable_light_client_server,
+                enable_finalized_state_server: self.enable_finalized_state_server,
                 phantom: PhantomData,
                 ttfb_timeout: self.network_params.ttfb_timeout,
             },
@@ -282,9 +480,22 @@ where
         Ok(handler)
     }
 
-    fn on_swarm_event(&mut self, _event: FromSwarm) {
+    fn on_swarm_event(&mut self, event: FromSwarm) {
         // NOTE: FromSwarm is a non exhaustive enum so updates should be based on release notes more
         // than compiler feedback
+        if let FromSwarm::ConnectionClosed(ConnectionClosed {
+            peer_id,
+            remaining_established: 0,
+            ..
+        }) = event
+        {
+            // Only keep rejection counts for connected peers.
+            self.network_globals
+                .rate_limiter
+                .write()
+                .rejections_per_peer
+                .remove(&peer_id.to_string());
+        }
     }
 
     fn on_connection_handler_event(
@@ -309,6 +520,7 @@ where
                         Err(RateLimitedErr::TooLarge) => {
                             // we set the batch sizes, so this is a coding/config err for most protocols
                             let protocol = req.versioned_protocol().protocol();
+                            self.record_too_large_request(protocol);
                             if matches!(protocol, Protocol::BlocksByRange)
                                 || matches!(protocol, Protocol::BlobsByRange)
                             {
@@ -330,6 +542,10 @@ where
                         Err(RateLimitedErr::TooSoon(wait_time)) => {
                             debug!(self.log, "Request exceeds the rate limit";
                         "request" => %req, "peer_id" => %peer_id, "wait_time_ms" => wait_time.as_millis());
+                            self.record_rate_limited_request(
+                                &peer_id,
+                                req.versioned_protocol().protocol(),
+                            );
                             // send an error code to the peer.
                             // the handler upon receiving the error code will send it back to the behaviour
                             self.send_response(
@@ -413,6 +629,7 @@ where
                             ResponseTermination::BlocksByRoot => Protocol::BlocksByRoot,
                             ResponseTermination::BlobsByRange => Protocol::BlobsByRange,
                             ResponseTermination::BlobsByRoot => Protocol::BlobsByRoot,
+                            ResponseTermination::FinalizedState => Protocol::FinalizedState,
                         },
                     ),
                 };
@@ -435,3 +652,133 @@ where
         slog::Result::Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ChainSpec, Hash256, MainnetEthSpec, Slot};
+
+    type E = MainnetEthSpec;
+
+    fn build_rpc(
+        inbound: Option<&str>,
+        outbound: Option<&str>,
+        trusted_peers: Vec<PeerId>,
+    ) -> RPC<u64, E> {
+        let log = slog::Logger::root(slog::Discard, o!());
+        let spec = ChainSpec::mainnet();
+        let fork_context = Arc::new(ForkContext::new::<E>(Slot::new(0), Hash256::zero(), &spec));
+        let network_globals = Arc::new(NetworkGlobals::new_test_globals(trusted_peers, &log));
+        RPC::new(
+            fork_context,
+            false,
+            false,
+            inbound.map(|quotas| quotas.parse().unwrap()),
+            Some("ping:5/1".parse().unwrap()),
+            outbound.map(|quotas| quotas.parse().unwrap()),
+            network_globals,
+            log,
+            NetworkParams {
+                max_chunk_size: 1024,
+                ttfb_timeout: Duration::from_secs(5),
+                resp_timeout: Duration::from_secs(10),
+            },
+        )
+    }
+
+    fn quotas(quotas: &str) -> RateLimiterConfig {
+        quotas.parse().unwrap()
+    }
+
+    #[test]
+    fn update_inbound_rate_limits() {
+        let trusted_peer = PeerId::random();
+        let peer = PeerId::random();
+        let mut rpc = build_rpc(Some("ping:2/10"), None, vec![trusted_peer]);
+
+        let update = InboundRateLimiterUpdate {
+            default: Some(quotas("ping:3/10")),
+            trusted_peers: Some(Some(quotas("ping:6/1"))),
+            peers: HashMap::from([(peer, Some(quotas("status:1/1")))]),
+        };
+        rpc.update_inbound_rate_limits(update).unwrap();
+
+        let report = rpc.network_globals.rate_limiter.read().clone();
+        assert!(report.enabled);
+        assert_eq!(report.default_quotas, Some(quotas("ping:3/10").to_string()));
+        assert_eq!(
+            report.trusted_peer_quotas,
+            Some(quotas("ping:6/1").to_string())
+        );
+        assert_eq!(
+            report.peer_quotas,
+            HashMap::from([(peer.to_string(), quotas("status:1/1").to_string())])
+        );
+
+        // Removing overrides falls back to the default quotas.
+        let update = InboundRateLimiterUpdate {
+            default: None,
+            trusted_peers: Some(None),
+            peers: HashMap::from([(peer, None)]),
+        };
+        rpc.update_inbound_rate_limits(update).unwrap();
+
+        let report = rpc.network_globals.rate_limiter.read().clone();
+        assert_eq!(report.default_quotas, Some(quotas("ping:3/10").to_string()));
+        assert_eq!(report.trusted_peer_quotas, None);
+        assert!(report.peer_quotas.is_empty());
+    }
+
+    #[test]
+    fn invalid_inbound_update_is_not_applied() {
+        let peer = PeerId::random();
+        let mut rpc = build_rpc(Some("ping:2/10"), None, vec![]);
+        let report_before = rpc.network_globals.rate_limiter.read().clone();
+
+        let update = InboundRateLimiterUpdate {
+            default: Some(quotas("ping:3/10")),
+            trusted_peers: None,
+            peers: HashMap::from([(peer, Some(quotas("beacon_blocks_by_range:0/10")))]),
+        };
+        assert!(rpc.update_inbound_rate_limits(update).is_err());
+        assert_eq!(*rpc.network_globals.rate_limiter.read(), report_before);
+    }
+
+    #[test]
+    fn update_disabled_rate_limits() {
+        let mut rpc = build_rpc(None, None, vec![]);
+
+        let update = InboundRateLimiterUpdate {
+            default: Some(quotas("ping:3/10")),
+            ..Default::default()
+        };
+        assert!(rpc.update_inbound_rate_limits(update).is_err());
+        assert!(rpc
+            .update_outbound_rate_limits(quotas("ping:3/10"))
+            .is_err());
+
+        let report = rpc.network_globals.rate_limiter.read().clone();
+        assert!(!report.enabled);
+        assert_eq!(report.default_quotas, None);
+        assert_eq!(report.outbound_quotas, None);
+    }
+
+    #[test]
+    fn update_outbound_rate_limits() {
+        let mut rpc = build_rpc(None, Some("ping:2/10"), vec![]);
+        assert_eq!(
+            rpc.network_globals.rate_limiter.read().outbound_quotas,
+            Some(quotas("ping:2/10").to_string())
+        );
+
+        assert!(rpc
+            .update_outbound_rate_limits(quotas("beacon_blocks_by_range:0/10"))
+            .is_err());
+        rpc.update_outbound_rate_limits(quotas("ping:3/10"))
+            .unwrap();
+        assert_eq!(
+            rpc.network_globals.rate_limiter.read().outbound_quotas,
+            Some(quotas("ping:3/10").to_string())
+        );
+    }
+}
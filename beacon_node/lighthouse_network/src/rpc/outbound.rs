@@ -37,6 +37,7 @@ pub enum OutboundRequest<TSpec: EthSpec> {
     BlocksByRoot(BlocksByRootRequest),
     BlobsByRange(BlobsByRangeRequest),
     BlobsByRoot(BlobsByRootRequest),
+    FinalizedState(FinalizedStateRequest),
     Ping(Ping),
     MetaData(MetadataRequest<TSpec>),
 }
@@ -80,6 +81,10 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
                 SupportedProtocol::BlobsByRootV1,
                 Encoding::SSZSnappy,
             )],
+            OutboundRequest::FinalizedState(_) => vec![ProtocolId::new(
+                SupportedProtocol::FinalizedStateV1,
+                Encoding::SSZSnappy,
+            )],
             OutboundRequest::Ping(_) => vec![ProtocolId::new(
                 SupportedProtocol::PingV1,
                 Encoding::SSZSnappy,
@@ -101,6 +106,7 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
             OutboundRequest::BlocksByRoot(req) => req.block_roots().len() as u64,
             OutboundRequest::BlobsByRange(req) => req.max_blobs_requested::<TSpec>(),
             OutboundRequest::BlobsByRoot(req) => req.blob_ids.len() as u64,
+            OutboundRequest::FinalizedState(_) => MAX_FINALIZED_STATE_CHUNKS,
             OutboundRequest::Ping(_) => 1,
             OutboundRequest::MetaData(_) => 1,
         }
@@ -121,6 +127,7 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
             },
             OutboundRequest::BlobsByRange(_) => SupportedProtocol::BlobsByRangeV1,
             OutboundRequest::BlobsByRoot(_) => SupportedProtocol::BlobsByRootV1,
+            OutboundRequest::FinalizedState(_) => SupportedProtocol::FinalizedStateV1,
             OutboundRequest::Ping(_) => SupportedProtocol::PingV1,
             OutboundRequest::MetaData(req) => match req {
                 MetadataRequest::V1(_) => SupportedProtocol::MetaDataV1,
@@ -139,6 +146,7 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
             OutboundRequest::BlocksByRoot(_) => ResponseTermination::BlocksByRoot,
             OutboundRequest::BlobsByRange(_) => ResponseTermination::BlobsByRange,
             OutboundRequest::BlobsByRoot(_) => ResponseTermination::BlobsByRoot,
+            OutboundRequest::FinalizedState(_) => ResponseTermination::FinalizedState,
             OutboundRequest::Status(_) => unreachable!(),
             OutboundRequest::Goodbye(_) => unreachable!(),
             OutboundRequest::Ping(_) => unreachable!(),
@@ -196,6 +204,9 @@ impl<TSpec: EthSpec> std::fmt::Display for OutboundRequest<TSpec> {
             OutboundRequest::BlocksByRoot(req) => write!(f, "Blocks by root: {:?}", req),
             OutboundRequest::BlobsByRange(req) => write!(f, "Blobs by range: {:?}", req),
             OutboundRequest::BlobsByRoot(req) => write!(f, "Blobs by root: {:?}", req),
+            OutboundRequest::FinalizedState(req) => {
+                write!(f, "Finalized state by root: {}", req.block_root)
+            }
             OutboundRequest::Ping(ping) => write!(f, "Ping: {}", ping.data),
             OutboundRequest::MetaData(_) => write!(f, "MetaData request"),
         }
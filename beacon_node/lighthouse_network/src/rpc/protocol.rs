@@ -13,7 +13,7 @@ use std::io;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
-use strum::{AsRefStr, Display, EnumString, IntoStaticStr};
+use strum::{AsRefStr, Display, EnumIter, EnumString, IntoStaticStr};
 use tokio_io_timeout::TimeoutStream;
 use tokio_util::{
     codec::Framed,
@@ -147,7 +147,7 @@ pub fn rpc_block_limits_by_fork(current_fork: ForkName) -> RpcLimits {
 }
 
 /// Protocol names to be used.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, AsRefStr, Display, EnumIter)]
 #[strum(serialize_all = "snake_case")]
 pub enum Protocol {
     /// The Status protocol name.
@@ -174,6 +174,9 @@ pub enum Protocol {
     /// The `LightClientBootstrap` protocol name.
     #[strum(serialize = "light_client_bootstrap")]
     LightClientBootstrap,
+    /// The `FinalizedState` protocol name.
+    #[strum(serialize = "finalized_state_by_root")]
+    FinalizedState,
 }
 
 impl Protocol {
@@ -188,6 +191,7 @@ impl Protocol {
             Protocol::Ping => None,
             Protocol::MetaData => None,
             Protocol::LightClientBootstrap => None,
+            Protocol::FinalizedState => Some(ResponseTermination::FinalizedState),
         }
     }
 }
@@ -213,6 +217,7 @@ pub enum SupportedProtocol {
     MetaDataV1,
     MetaDataV2,
     LightClientBootstrapV1,
+    FinalizedStateV1,
 }
 
 impl SupportedProtocol {
@@ -230,6 +235,7 @@ impl SupportedProtocol {
             SupportedProtocol::MetaDataV1 => "1",
             SupportedProtocol::MetaDataV2 => "2",
             SupportedProtocol::LightClientBootstrapV1 => "1",
+            SupportedProtocol::FinalizedStateV1 => "1",
         }
     }
 
@@ -247,6 +253,7 @@ impl SupportedProtocol {
             SupportedProtocol::MetaDataV1 => Protocol::MetaData,
             SupportedProtocol::MetaDataV2 => Protocol::MetaData,
             SupportedProtocol::LightClientBootstrapV1 => Protocol::LightClientBootstrap,
+            SupportedProtocol::FinalizedStateV1 => Protocol::FinalizedState,
         }
     }
 
@@ -287,6 +294,7 @@ pub struct RPCProtocol<TSpec: EthSpec> {
     pub fork_context: Arc<ForkContext>,
     pub max_rpc_size: usize,
     pub enable_light_client_server: bool,
+    pub enable_finalized_state_server: bool,
     pub phantom: PhantomData<TSpec>,
     pub ttfb_timeout: Duration,
 }
@@ -304,6 +312,12 @@ impl<TSpec: EthSpec> UpgradeInfo for RPCProtocol<TSpec> {
                 Encoding::SSZSnappy,
             ));
         }
+        if self.enable_finalized_state_server {
+            supported_protocols.push(ProtocolId::new(
+                SupportedProtocol::FinalizedStateV1,
+                Encoding::SSZSnappy,
+            ));
+        }
         supported_protocols
     }
 }
@@ -377,6 +391,10 @@ impl ProtocolId {
                 <LightClientBootstrapRequest as Encode>::ssz_fixed_len(),
                 <LightClientBootstrapRequest as Encode>::ssz_fixed_len(),
             ),
+            Protocol::FinalizedState => RpcLimits::new(
+                <FinalizedStateRequest as Encode>::ssz_fixed_len(),
+                <FinalizedStateRequest as Encode>::ssz_fixed_len(),
+            ),
             Protocol::MetaData => RpcLimits::new(0, 0), // Metadata requests are empty
         }
     }
@@ -405,6 +423,10 @@ impl ProtocolId {
                 <LightClientBootstrapRequest as Encode>::ssz_fixed_len(),
                 <LightClientBootstrapRequest as Encode>::ssz_fixed_len(),
             ),
+            Protocol::FinalizedState => RpcLimits::new(
+                FinalizedStateChunk::min_size(),
+                FinalizedStateChunk::max_size(),
+            ),
         }
     }
 
@@ -423,7 +445,8 @@ impl ProtocolId {
             | SupportedProtocol::PingV1
             | SupportedProtocol::MetaDataV1
             | SupportedProtocol::MetaDataV2
-            | SupportedProtocol::GoodbyeV1 => false,
+            | SupportedProtocol::GoodbyeV1
+            | SupportedProtocol::FinalizedStateV1 => false,
         }
     }
 }
@@ -528,6 +551,7 @@ pub enum InboundRequest<TSpec: EthSpec> {
     BlobsByRange(BlobsByRangeRequest),
     BlobsByRoot(BlobsByRootRequest),
     LightClientBootstrap(LightClientBootstrapRequest),
+    FinalizedState(FinalizedStateRequest),
     Ping(Ping),
     MetaData(MetadataRequest<TSpec>),
 }
@@ -548,6 +572,7 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
             InboundRequest::Ping(_) => 1,
             InboundRequest::MetaData(_) => 1,
             InboundRequest::LightClientBootstrap(_) => 1,
+            InboundRequest::FinalizedState(_) => MAX_FINALIZED_STATE_CHUNKS,
         }
     }
 
@@ -572,6 +597,7 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
                 MetadataRequest::V2(_) => SupportedProtocol::MetaDataV2,
             },
             InboundRequest::LightClientBootstrap(_) => SupportedProtocol::LightClientBootstrapV1,
+            InboundRequest::FinalizedState(_) => SupportedProtocol::FinalizedStateV1,
         }
     }
 
@@ -585,6 +611,7 @@ impl<TSpec: EthSpec> InboundRequest<TSpec> {
             InboundRequest::BlocksByRoot(_) => ResponseTermination::BlocksByRoot,
             InboundRequest::BlobsByRange(_) => ResponseTermination::BlobsByRange,
             InboundRequest::BlobsByRoot(_) => ResponseTermination::BlobsByRoot,
+            InboundRequest::FinalizedState(_) => ResponseTermination::FinalizedState,
             InboundRequest::Status(_) => unreachable!(),
             InboundRequest::Goodbye(_) => unreachable!(),
             InboundRequest::Ping(_) => unreachable!(),
@@ -698,6 +725,9 @@ impl<TSpec: EthSpec> std::fmt::Display for InboundRequest<TSpec> {
             InboundRequest::LightClientBootstrap(bootstrap) => {
                 write!(f, "LightClientBootstrap: {}", bootstrap.root)
             }
+            InboundRequest::FinalizedState(req) => {
+                write!(f, "FinalizedState: {}", req.block_root)
+            }
         }
     }
 }
@@ -9,6 +9,7 @@ use std::hash::Hash;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
+use strum::IntoEnumIterator;
 use tokio::time::Interval;
 use types::EthSpec;
 
@@ -100,6 +101,8 @@ pub struct RPCRateLimiter {
     blbroot_rl: Limiter<PeerId>,
     /// LightClientBootstrap rate limiter.
     lcbootstrap_rl: Limiter<PeerId>,
+    /// FinalizedState rate limiter.
+    fstate_rl: Limiter<PeerId>,
 }
 
 /// Error type for non conformant requests
@@ -132,6 +135,8 @@ pub struct RPCRateLimiterBuilder {
     blbroot_quota: Option<Quota>,
     /// Quota for the LightClientBootstrap protocol.
     lcbootstrap_quota: Option<Quota>,
+    /// Quota for the FinalizedState protocol.
+    fstate_quota: Option<Quota>,
 }
 
 impl RPCRateLimiterBuilder {
@@ -148,6 +153,7 @@ impl RPCRateLimiterBuilder {
             Protocol::BlobsByRange => self.blbrange_quota = q,
             Protocol::BlobsByRoot => self.blbroot_quota = q,
             Protocol::LightClientBootstrap => self.lcbootstrap_quota = q,
+            Protocol::FinalizedState => self.fstate_quota = q,
         }
         self
     }
@@ -167,6 +173,9 @@ impl RPCRateLimiterBuilder {
         let lcbootstrap_quote = self
             .lcbootstrap_quota
             .ok_or("LightClientBootstrap quota not specified")?;
+        let fstate_quota = self
+            .fstate_quota
+            .ok_or("FinalizedState quota not specified")?;
 
         let blbrange_quota = self
             .blbrange_quota
@@ -186,6 +195,7 @@ impl RPCRateLimiterBuilder {
         let blbrange_rl = Limiter::from_quota(blbrange_quota)?;
         let blbroot_rl = Limiter::from_quota(blbroots_quota)?;
         let lcbootstrap_rl = Limiter::from_quota(lcbootstrap_quote)?;
+        let fstate_rl = Limiter::from_quota(fstate_quota)?;
 
         // check for peers to prune every 30 seconds, starting in 30 seconds
         let prune_every = tokio::time::Duration::from_secs(30);
@@ -202,6 +212,7 @@ impl RPCRateLimiterBuilder {
             blbrange_rl,
             blbroot_rl,
             lcbootstrap_rl,
+            fstate_rl,
             init_time: Instant::now(),
         })
     }
@@ -244,6 +255,7 @@ impl RPCRateLimiter {
             blobs_by_range_quota,
             blobs_by_root_quota,
             light_client_bootstrap_quota,
+            finalized_state_quota,
         } = config;
 
         Self::builder()
@@ -256,6 +268,7 @@ impl RPCRateLimiter {
             .set_quota(Protocol::BlobsByRange, blobs_by_range_quota)
             .set_quota(Protocol::BlobsByRoot, blobs_by_root_quota)
             .set_quota(Protocol::LightClientBootstrap, light_client_bootstrap_quota)
+            .set_quota(Protocol::FinalizedState, finalized_state_quota)
             .build()
     }
 
@@ -272,9 +285,46 @@ impl RPCRateLimiter {
         let time_since_start = self.init_time.elapsed();
         let tokens = request.expected_responses().max(1);
 
-        let check =
-            |limiter: &mut Limiter<PeerId>| limiter.allows(time_since_start, peer_id, tokens);
-        let limiter = match request.protocol() {
+        let limiter = self.limiter_mut(request.protocol());
+        limiter.allows(time_since_start, peer_id, tokens)
+    }
+
+    /// Replaces the quotas used for peers without an override.
+    ///
+    /// The state of each peer's buckets is kept, so that a reload does not grant peers a fresh
+    /// burst of tokens.
+    pub fn set_quotas(&mut self, config: &RateLimiterConfig) -> Result<(), &'static str> {
+        config.validate()?;
+        for protocol in Protocol::iter() {
+            self.limiter_mut(protocol)
+                .set_quota(config.quota(protocol))?;
+        }
+        Ok(())
+    }
+
+    /// Sets the quotas used for `peer_id`, instead of the default quotas.
+    pub fn set_peer_quotas(
+        &mut self,
+        peer_id: PeerId,
+        config: &RateLimiterConfig,
+    ) -> Result<(), &'static str> {
+        config.validate()?;
+        for protocol in Protocol::iter() {
+            self.limiter_mut(protocol)
+                .set_key_quota(peer_id, config.quota(protocol))?;
+        }
+        Ok(())
+    }
+
+    /// Removes any quota override for `peer_id`.
+    pub fn remove_peer_quotas(&mut self, peer_id: &PeerId) {
+        for protocol in Protocol::iter() {
+            self.limiter_mut(protocol).remove_key_quota(peer_id);
+        }
+    }
+
+    fn limiter_mut(&mut self, protocol: Protocol) -> &mut Limiter<PeerId> {
+        match protocol {
             Protocol::Ping => &mut self.ping_rl,
             Protocol::Status => &mut self.status_rl,
             Protocol::MetaData => &mut self.metadata_rl,
@@ -284,8 +334,8 @@ impl RPCRateLimiter {
             Protocol::BlobsByRange => &mut self.blbrange_rl,
             Protocol::BlobsByRoot => &mut self.blbroot_rl,
             Protocol::LightClientBootstrap => &mut self.lcbootstrap_rl,
-        };
-        check(limiter)
+            Protocol::FinalizedState => &mut self.fstate_rl,
+        }
     }
 
     pub fn prune(&mut self) {
@@ -298,6 +348,7 @@ impl RPCRateLimiter {
         self.bbroots_rl.prune(time_since_start);
         self.blbrange_rl.prune(time_since_start);
         self.blbroot_rl.prune(time_since_start);
+        self.fstate_rl.prune(time_since_start);
     }
 }
 
@@ -316,16 +367,25 @@ impl Future for RPCRateLimiter {
 /// Per key rate limiter using the token bucket / leaky bucket as a meter rate limiting algorithm,
 /// with the GCRA implementation.
 pub struct Limiter<Key: Hash + Eq + Clone> {
+    /// The GCRA parameters used for keys without an override.
+    params: GcraParams,
+    /// GCRA parameters for keys which have been given a different quota.
+    overrides: FnvHashMap<Key, GcraParams>,
+    /// Time when the bucket will be full for each peer. TAT (theoretical arrival time) from GCRA.
+    tat_per_key: FnvHashMap<Key, Nanosecs>,
+}
+
+/// The GCRA parameters derived from a `Quota`.
+#[derive(Clone, Copy)]
+struct GcraParams {
     /// After how long is the bucket considered full via replenishing 1T every `t`.
     tau: Nanosecs,
     /// How often is 1T replenished.
     t: Nanosecs,
-    /// Time when the bucket will be full for each peer. TAT (theoretical arrival time) from GCRA.
-    tat_per_key: FnvHashMap<Key, Nanosecs>,
 }
 
-impl<Key: Hash + Eq + Clone> Limiter<Key> {
-    pub fn from_quota(quota: Quota) -> Result<Self, &'static str> {
+impl GcraParams {
+    fn from_quota(quota: &Quota) -> Result<Self, &'static str> {
         if quota.max_tokens == 0 {
             return Err("Max number of tokens should be positive");
         }
@@ -339,13 +399,38 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
         let tau = tau
             .try_into()
             .map_err(|_| "total replenish time is too long")?;
+        Ok(GcraParams { tau, t })
+    }
+}
+
+impl<Key: Hash + Eq + Clone> Limiter<Key> {
+    pub fn from_quota(quota: Quota) -> Result<Self, &'static str> {
         Ok(Limiter {
-            tau,
-            t,
+            params: GcraParams::from_quota(&quota)?,
+            overrides: FnvHashMap::default(),
             tat_per_key: FnvHashMap::default(),
         })
     }
 
+    /// Replaces the quota used for keys without an override.
+    ///
+    /// The current state of each key's bucket is kept.
+    pub fn set_quota(&mut self, quota: &Quota) -> Result<(), &'static str> {
+        self.params = GcraParams::from_quota(quota)?;
+        Ok(())
+    }
+
+    /// Sets a quota for `key` which is used instead of the default quota.
+    pub fn set_key_quota(&mut self, key: Key, quota: &Quota) -> Result<(), &'static str> {
+        self.overrides.insert(key, GcraParams::from_quota(quota)?);
+        Ok(())
+    }
+
+    /// Removes the quota override for `key`, if any.
+    pub fn remove_key_quota(&mut self, key: &Key) {
+        self.overrides.remove(key);
+    }
+
     pub fn allows(
         &mut self,
         time_since_start: Duration,
@@ -353,8 +438,8 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
         tokens: u64,
     ) -> Result<(), RateLimitedErr> {
         let time_since_start = time_since_start.as_nanos() as u64;
-        let tau = self.tau;
-        let t = self.t;
+        let GcraParams { tau, t } = self.overrides.get(key).unwrap_or(&self.params);
+        let (tau, t) = (*tau, *t);
         // how long does it take to replenish these tokens
         let additional_time = t * tokens;
         if additional_time > tau {
@@ -393,7 +478,7 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::rate_limiter::{Limiter, Quota};
+    use crate::rpc::rate_limiter::{Limiter, Quota, RateLimitedErr};
     use std::time::Duration;
 
     #[test]
@@ -433,6 +518,30 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn key_quota_override() {
+        let mut limiter = Limiter::from_quota(Quota::one_every(10)).unwrap();
+        let (trusted, other) = (1, 2);
+        limiter
+            .set_key_quota(trusted, &Quota::n_every(4, 10))
+            .unwrap();
+
+        for _ in 0..4 {
+            assert!(limiter.allows(Duration::ZERO, &trusted, 1).is_ok());
+        }
+        assert!(limiter.allows(Duration::ZERO, &trusted, 1).is_err());
+
+        assert!(limiter.allows(Duration::ZERO, &other, 1).is_ok());
+        assert!(limiter.allows(Duration::ZERO, &other, 1).is_err());
+
+        // Once the override is removed, the key falls back to the default quota.
+        limiter.remove_key_quota(&trusted);
+        assert!(matches!(
+            limiter.allows(Duration::ZERO, &trusted, 2),
+            Err(RateLimitedErr::TooLarge)
+        ));
+    }
+
     #[test]
     fn it_works_b() {
         let mut limiter = Limiter::from_quota(Quota {
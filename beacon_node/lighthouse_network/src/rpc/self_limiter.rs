@@ -12,7 +12,7 @@ use tokio_util::time::DelayQueue;
 use types::EthSpec;
 
 use super::{
-    config::OutboundRateLimiterConfig,
+    config::{OutboundRateLimiterConfig, RateLimiterConfig},
     rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr},
     BehaviourAction, OutboundRequest, Protocol, RPCSend, ReqId,
 };
@@ -63,6 +63,13 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
         })
     }
 
+    /// Replaces the quotas applied to our own requests.
+    ///
+    /// Requests which are already queued are sent once the new quotas allow them.
+    pub fn set_quotas(&mut self, config: &RateLimiterConfig) -> Result<(), &'static str> {
+        self.limiter.set_quotas(config)
+    }
+
     /// Checks if the rate limiter allows the request. If it's allowed, returns the
     /// [`ToSwarm`] that should be emitted. When not allowed, the request is delayed
     /// until it can be sent.
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use libp2p::swarm::ConnectionId;
 use types::{BlobSidecar, EthSpec, LightClientBootstrap, SignedBeaconBlock};
 
-use crate::rpc::methods::{BlobsByRangeRequest, BlobsByRootRequest};
+use crate::rpc::methods::{
+    BlobsByRangeRequest, BlobsByRootRequest, FinalizedStateChunk, FinalizedStateRequest,
+};
 use crate::rpc::{
     methods::{
         BlocksByRangeRequest, BlocksByRootRequest, LightClientBootstrapRequest,
@@ -42,6 +44,8 @@ pub enum Request {
     LightClientBootstrap(LightClientBootstrapRequest),
     /// A request blobs root request.
     BlobsByRoot(BlobsByRootRequest),
+    /// A finalized state request, used to checkpoint sync from trusted peers.
+    FinalizedState(FinalizedStateRequest),
 }
 
 impl<TSpec: EthSpec> std::convert::From<Request> for OutboundRequest<TSpec> {
@@ -69,6 +73,7 @@ impl<TSpec: EthSpec> std::convert::From<Request> for OutboundRequest<TSpec> {
             }
             Request::BlobsByRange(r) => OutboundRequest::BlobsByRange(r),
             Request::BlobsByRoot(r) => OutboundRequest::BlobsByRoot(r),
+            Request::FinalizedState(r) => OutboundRequest::FinalizedState(r),
             Request::Status(s) => OutboundRequest::Status(s),
         }
     }
@@ -94,6 +99,8 @@ pub enum Response<TSpec: EthSpec> {
     BlobsByRoot(Option<Arc<BlobSidecar<TSpec>>>),
     /// A response to a LightClientUpdate request.
     LightClientBootstrap(LightClientBootstrap<TSpec>),
+    /// A chunk of a finalized state. A None response signals the end of the state.
+    FinalizedState(Option<Arc<FinalizedStateChunk>>),
 }
 
 impl<TSpec: EthSpec> std::convert::From<Response<TSpec>> for RPCCodedResponse<TSpec> {
@@ -115,6 +122,10 @@ impl<TSpec: EthSpec> std::convert::From<Response<TSpec>> for RPCCodedResponse<TS
                 Some(b) => RPCCodedResponse::Success(RPCResponse::BlobsByRange(b)),
                 None => RPCCodedResponse::StreamTermination(ResponseTermination::BlobsByRange),
             },
+            Response::FinalizedState(r) => match r {
+                Some(c) => RPCCodedResponse::Success(RPCResponse::FinalizedState(c)),
+                None => RPCCodedResponse::StreamTermination(ResponseTermination::FinalizedState),
+            },
             Response::Status(s) => RPCCodedResponse::Success(RPCResponse::Status(s)),
             Response::LightClientBootstrap(b) => {
                 RPCCodedResponse::Success(RPCResponse::LightClientBootstrap(b))
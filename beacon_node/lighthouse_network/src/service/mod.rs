@@ -14,8 +14,8 @@ use crate::rpc::*;
 use crate::service::behaviour::BehaviourEvent;
 pub use crate::service::behaviour::Gossipsub;
 use crate::types::{
-    fork_core_topics, subnet_from_topic_hash, GossipEncoding, GossipKind, GossipTopic,
-    SnappyTransform, Subnet, SubnetDiscovery, ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS,
+    fork_core_topics, subnet_from_topic_hash, transport_socket_addr, GossipEncoding, GossipKind,
+    GossipTopic, SnappyTransform, Subnet, SubnetDiscovery, ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS,
     CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS, LIGHT_CLIENT_GOSSIP_TOPICS,
 };
 use crate::EnrExt;
@@ -169,6 +169,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 config.disable_peer_scoring,
                 &log,
             );
+            globals.reachability.write().set_listening(&config);
             Arc::new(globals)
         };
 
@@ -289,8 +290,11 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         let eth2_rpc = RPC::new(
             ctx.fork_context.clone(),
             config.enable_light_client_server,
+            config.enable_finalized_state_server,
             config.inbound_rate_limiter_config.clone(),
+            config.trusted_peers_inbound_rate_limiter_config.clone(),
             config.outbound_rate_limiter_config.clone(),
+            network_globals.clone(),
             log.clone(),
             network_params,
         );
@@ -1135,6 +1139,9 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             Request::BlobsByRoot { .. } => {
                 metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["blobs_by_root"])
             }
+            Request::FinalizedState(_) => {
+                metrics::inc_counter_vec(&metrics::TOTAL_RPC_REQUESTS, &["finalized_state"])
+            }
         }
         NetworkEvent::RequestReceived {
             peer_id,
@@ -1447,6 +1454,14 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                         );
                         Some(event)
                     }
+                    InboundRequest::FinalizedState(req) => {
+                        let event = self.build_request(
+                            peer_request_id,
+                            peer_id,
+                            Request::FinalizedState(req),
+                        );
+                        Some(event)
+                    }
                 }
             }
             HandlerEvent::Ok(RPCReceived::Response(id, resp)) => {
@@ -1484,6 +1499,9 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                     RPCResponse::LightClientBootstrap(bootstrap) => {
                         self.build_response(id, peer_id, Response::LightClientBootstrap(bootstrap))
                     }
+                    RPCResponse::FinalizedState(chunk) => {
+                        self.build_response(id, peer_id, Response::FinalizedState(Some(chunk)))
+                    }
                 }
             }
             HandlerEvent::Ok(RPCReceived::EndOfStream(id, termination)) => {
@@ -1492,6 +1510,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                     ResponseTermination::BlocksByRoot => Response::BlocksByRoot(None),
                     ResponseTermination::BlobsByRange => Response::BlobsByRange(None),
                     ResponseTermination::BlobsByRoot => Response::BlobsByRoot(None),
+                    ResponseTermination::FinalizedState => Response::FinalizedState(None),
                 };
                 self.build_response(id, peer_id, response)
             }
@@ -1516,6 +1535,10 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                     );
                     info.listen_addrs.truncate(MAX_IDENTIFY_ADDRESSES);
                 }
+                if let Some((transport, address)) = transport_socket_addr(&info.observed_addr) {
+                    self.network_globals
+                        .record_observed_address(transport, address);
+                }
                 // send peer info to the peer manager.
                 self.peer_manager_mut().identify(&peer_id, &info);
             }
@@ -1,12 +1,16 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::peerdb::PeerDB;
 use crate::rpc::{MetaData, MetaDataV2};
-use crate::types::{BackFillState, SyncState};
+use crate::types::{
+    BackFillState, IpFamily, RateLimiterReport, ReachabilityReport, ReachabilityTracker, SyncState,
+    SyncStatusReport, Transport,
+};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, GossipTopic, Multiaddr, PeerId};
 use parking_lot::RwLock;
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use types::EthSpec;
 
 pub struct NetworkGlobals<TSpec: EthSpec> {
@@ -26,6 +30,12 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// A detailed report of the progress of all sync processes.
+    pub sync_status_report: RwLock<SyncStatusReport>,
+    /// The quotas of the RPC rate limiters and the inbound requests they rejected.
+    pub rate_limiter: RwLock<RateLimiterReport>,
+    /// The addresses and connections used to test the reachability of the node.
+    pub reachability: RwLock<ReachabilityTracker>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -45,6 +55,9 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::NotRequired),
+            sync_status_report: RwLock::new(SyncStatusReport::default()),
+            rate_limiter: RwLock::new(RateLimiterReport::default()),
+            reachability: RwLock::new(ReachabilityTracker::default()),
         }
     }
 
@@ -94,6 +107,37 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.backfill_state.read().clone()
     }
 
+    /// Returns the most recent detailed sync report, with an up-to-date sync state.
+    pub fn sync_status_report(&self) -> SyncStatusReport {
+        let mut report = self.sync_status_report.read().clone();
+        report.state = self.sync_state();
+        report
+    }
+
+    /// Records an external address of the local node as reported by a remote peer.
+    pub fn record_observed_address(&self, transport: Transport, address: SocketAddr) {
+        let local_enr = self.local_enr();
+        let mut reachability = self.reachability.write();
+        reachability.record_observed_address(transport, address);
+        reachability.update_enr_mismatch_metric(
+            &local_enr,
+            transport,
+            IpFamily::from(&address.ip()),
+        );
+    }
+
+    /// Returns the result of the reachability self-test against the current local ENR.
+    pub fn reachability_report(&self) -> ReachabilityReport {
+        let discovery_metrics = discv5::metrics::Metrics::from(discv5::Discv5::<
+            discv5::DefaultProtocolId,
+        >::raw_metrics());
+        self.reachability.read().report(
+            &self.local_enr(),
+            crate::metrics::nat_open(),
+            discovery_metrics.unsolicited_requests_per_second,
+        )
+    }
+
     /// Returns a `Client` type if one is known for the `PeerId`.
     pub fn client(&self, peer_id: &PeerId) -> Client {
         self.peers
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use strum::AsRefStr;
+use types::{Hash256, Slot};
+
+/// The file extension used for the SSZ bytes of an archived gossip object.
+pub const INVALID_OBJECT_SSZ_EXTENSION: &str = "ssz";
+/// The file extension used for the metadata of an archived gossip object.
+pub const INVALID_OBJECT_METADATA_EXTENSION: &str = "json";
+
+/// The types of gossip objects which may be archived after failing validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum InvalidObjectKind {
+    Block,
+    BlobSidecar,
+    Attestation,
+    AggregateAndProof,
+    BlsToExecutionChange,
+}
+
+/// Describes a gossip object that was rejected by this node.
+///
+/// This is stored as JSON alongside the SSZ bytes of the object so that it can be replayed later
+/// (e.g., with `lcli replay-invalid`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvalidObjectMetadata {
+    pub kind: InvalidObjectKind,
+    /// The slot of the object, if it has one.
+    pub slot: Option<Slot>,
+    /// The tree hash root of the object (the block root for blocks).
+    pub root: Hash256,
+    /// The peer which sent us the object.
+    pub peer_id: String,
+    /// The gossip topic on which the object was received, excluding fork digest and encoding.
+    pub topic: String,
+    /// The reason the object was rejected.
+    pub error: String,
+    /// The time at which the object was received, in milliseconds since the UNIX epoch.
+    pub seen_timestamp_ms: u64,
+}
+
+impl InvalidObjectMetadata {
+    /// Returns the directory within `base_dir` in which objects of this kind are stored.
+    pub fn dir(&self, base_dir: &Path) -> PathBuf {
+        base_dir.join(self.kind.as_ref())
+    }
+
+    /// Returns the path at which the SSZ bytes of the object are stored.
+    pub fn ssz_path(&self, base_dir: &Path) -> PathBuf {
+        self.dir(base_dir).join(format!(
+            "{}.{}",
+            self.file_stem(),
+            INVALID_OBJECT_SSZ_EXTENSION
+        ))
+    }
+
+    /// Returns the path at which this metadata is stored.
+    pub fn metadata_path(&self, base_dir: &Path) -> PathBuf {
+        self.dir(base_dir).join(format!(
+            "{}.{}",
+            self.file_stem(),
+            INVALID_OBJECT_METADATA_EXTENSION
+        ))
+    }
+
+    fn file_stem(&self) -> String {
+        match self.slot {
+            Some(slot) => format!("{}_{:?}", slot, self.root),
+            None => format!("{:?}", self.root),
+        }
+    }
+}
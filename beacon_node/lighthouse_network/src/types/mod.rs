@@ -1,8 +1,12 @@
 pub mod error;
 mod globals;
+mod invalid_object;
 mod pubsub;
+mod rate_limiter;
+mod reachability;
 mod subnet;
 mod sync_state;
+mod sync_status;
 mod topics;
 
 use types::{BitVector, EthSpec};
@@ -13,9 +17,22 @@ pub type EnrSyncCommitteeBitfield<T> = BitVector<<T as EthSpec>::SyncCommitteeSu
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
 pub use globals::NetworkGlobals;
+pub use invalid_object::{
+    InvalidObjectKind, InvalidObjectMetadata, INVALID_OBJECT_METADATA_EXTENSION,
+    INVALID_OBJECT_SSZ_EXTENSION,
+};
 pub use pubsub::{PubsubMessage, SnappyTransform};
+pub use rate_limiter::RateLimiterReport;
+pub use reachability::{
+    transport_socket_addr, IpFamily, ObservedAddress, ReachabilityReport, ReachabilityTracker,
+    Transport, TransportReachability,
+};
 pub use subnet::{Subnet, SubnetDiscovery};
 pub use sync_state::{BackFillState, SyncState};
+pub use sync_status::{
+    BackFillReport, BatchReport, LookupsReport, ParentLookupReport, RangeChainReport,
+    SingleLookupReport, SyncProgress, SyncStatusReport,
+};
 pub use topics::{
     core_topics_to_subscribe, fork_core_topics, subnet_from_topic_hash, GossipEncoding, GossipKind,
     GossipTopic, ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS, CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS,
@@ -0,0 +1,52 @@
+//! The state of the RPC rate limiters, shared with the HTTP API.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The quotas in use by the RPC rate limiters and the inbound requests they have rejected.
+///
+/// Quotas are represented in the format accepted by the `--inbound-rate-limiter` and
+/// `--self-limiter` flags.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimiterReport {
+    /// Whether inbound rate limiting is enabled. If not, all inbound fields are empty.
+    pub enabled: bool,
+    /// The quotas applied to peers without an override.
+    pub default_quotas: Option<String>,
+    /// The quotas applied to trusted peers, unless they have a peer specific override.
+    pub trusted_peer_quotas: Option<String>,
+    /// Peer specific quotas, keyed by peer id.
+    pub peer_quotas: HashMap<String, String>,
+    /// The quotas applied to our own requests, if outbound rate limiting is enabled.
+    pub outbound_quotas: Option<String>,
+    /// The number of rejected requests per protocol since startup.
+    pub rejections_per_protocol: HashMap<String, u64>,
+    /// The number of rejected requests per protocol for each connected peer, keyed by peer id.
+    pub rejections_per_peer: HashMap<String, HashMap<String, u64>>,
+    /// The number of requests per protocol since startup which exceeded the maximum quota and
+    /// could never be served. These are not counted as rejections.
+    pub too_large_per_protocol: HashMap<String, u64>,
+}
+
+impl RateLimiterReport {
+    /// Records a request from `peer_id` which was rejected by the rate limiter.
+    pub fn record_rejection(&mut self, peer_id: String, protocol: &str) {
+        *self
+            .rejections_per_protocol
+            .entry(protocol.to_string())
+            .or_default() += 1;
+        *self
+            .rejections_per_peer
+            .entry(peer_id)
+            .or_default()
+            .entry(protocol.to_string())
+            .or_default() += 1;
+    }
+
+    /// Records a request which was larger than the quota for its protocol.
+    pub fn record_too_large(&mut self, protocol: &str) {
+        *self
+            .too_large_per_protocol
+            .entry(protocol.to_string())
+            .or_default() += 1;
+    }
+}
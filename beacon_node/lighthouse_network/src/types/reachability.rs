@@ -0,0 +1,492 @@
+//! A self-test of how remote peers reach the local node, shared with the HTTP API.
+//!
+//! Remote peers tell us the address they observe us on via identify (TCP and QUIC) and discv5
+//! PONGs (UDP). Every PONG to a ping sent by the discovery behaviour is recorded.
+use crate::{metrics, Enr, EnrExt, NetworkConfig};
+use libp2p::multiaddr::{Multiaddr, Protocol};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+
+/// The maximum number of distinct observed addresses tracked per transport and IP family.
+///
+/// Peers can report arbitrary addresses, so the least reported address is evicted when full.
+const MAX_OBSERVED_ADDRESSES: usize = 16;
+/// The number of reports an observed address needs before it is used for recommendations.
+const MIN_REPORTS_FOR_RECOMMENDATION: u64 = 3;
+/// The number of outbound connections after which a lack of inbound connections is reported.
+const MIN_OUTBOUND_FOR_INBOUND_CHECK: u64 = 20;
+
+/// A transport over which the local node can be reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    Tcp,
+    Quic,
+    Discv5,
+}
+
+impl Transport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Transport::Tcp => "tcp",
+            Transport::Quic => "quic",
+            Transport::Discv5 => "discv5",
+        }
+    }
+}
+
+/// The IP family of an address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl IpFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IpFamily::Ipv4 => "ipv4",
+            IpFamily::Ipv6 => "ipv6",
+        }
+    }
+}
+
+impl From<&IpAddr> for IpFamily {
+    fn from(ip: &IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(_) => IpFamily::Ipv4,
+            IpAddr::V6(_) => IpFamily::Ipv6,
+        }
+    }
+}
+
+/// An external address reported by remote peers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObservedAddress {
+    pub address: SocketAddr,
+    /// The number of times this address has been reported.
+    pub reports: u64,
+}
+
+/// The reachability of the local node over a single transport and IP family.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransportReachability {
+    pub transport: Transport,
+    pub ip_family: IpFamily,
+    /// Whether the local node listens on this transport and IP family.
+    pub listening: bool,
+    /// The address advertised in the local ENR, if any.
+    pub enr_address: Option<SocketAddr>,
+    /// The external addresses reported by remote peers, most reported first.
+    pub observed_addresses: Vec<ObservedAddress>,
+    /// The number of connections remote peers have opened to us since startup.
+    ///
+    /// Always zero for discv5, which is connectionless.
+    pub inbound_connections: u64,
+    /// The number of connections we have opened to remote peers since startup.
+    pub outbound_connections: u64,
+}
+
+/// The result of the reachability self-test.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReachabilityReport {
+    /// The estimate of whether the node is exposed to the internet, as per `/lighthouse/nat`.
+    pub nat_open: bool,
+    /// The number of unsolicited discv5 requests per second, which are only received if the
+    /// discv5 UDP port is reachable.
+    pub discv5_unsolicited_requests_per_second: f64,
+    pub transports: Vec<TransportReachability>,
+    /// Suggested changes to the local ENR or network setup.
+    pub recommendations: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Observations {
+    observed: HashMap<SocketAddr, u64>,
+    inbound_connections: u64,
+    outbound_connections: u64,
+}
+
+/// Records the observations used to build a `ReachabilityReport`.
+#[derive(Debug, Default)]
+pub struct ReachabilityTracker {
+    listening: HashSet<(Transport, IpFamily)>,
+    observations: HashMap<(Transport, IpFamily), Observations>,
+}
+
+impl ReachabilityTracker {
+    /// Records the transports and IP families the node listens on.
+    pub fn set_listening(&mut self, config: &NetworkConfig) {
+        let listen_addrs = config.listen_addrs();
+        let families = listen_addrs
+            .v4()
+            .map(|_| IpFamily::Ipv4)
+            .into_iter()
+            .chain(listen_addrs.v6().map(|_| IpFamily::Ipv6));
+        for ip_family in families {
+            self.listening.insert((Transport::Tcp, ip_family));
+            if !config.disable_quic_support {
+                self.listening.insert((Transport::Quic, ip_family));
+            }
+            if !config.disable_discovery {
+                self.listening.insert((Transport::Discv5, ip_family));
+            }
+        }
+    }
+
+    /// Records an external address of the local node as reported by a remote peer.
+    pub fn record_observed_address(&mut self, transport: Transport, address: SocketAddr) {
+        let ip_family = IpFamily::from(&address.ip());
+        metrics::inc_counter_vec(
+            &metrics::REACHABILITY_OBSERVED_ADDRESS_REPORTS,
+            &[transport.as_str(), ip_family.as_str()],
+        );
+
+        let observed = &mut self
+            .observations
+            .entry((transport, ip_family))
+            .or_default()
+            .observed;
+        if !observed.contains_key(&address) && observed.len() >= MAX_OBSERVED_ADDRESSES {
+            if let Some(least_reported) = observed
+                .iter()
+                .min_by_key(|(_, reports)| **reports)
+                .map(|(address, _)| *address)
+            {
+                observed.remove(&least_reported);
+            }
+        }
+        *observed.entry(address).or_default() += 1;
+    }
+
+    /// Records an established libp2p connection.
+    pub fn record_connection(&mut self, transport: Transport, ip_family: IpFamily, inbound: bool) {
+        let observations = self.observations.entry((transport, ip_family)).or_default();
+        let direction = if inbound {
+            observations.inbound_connections += 1;
+            "inbound"
+        } else {
+            observations.outbound_connections += 1;
+            "outbound"
+        };
+        metrics::inc_counter_vec(
+            &metrics::REACHABILITY_CONNECTIONS,
+            &[transport.as_str(), ip_family.as_str(), direction],
+        );
+    }
+
+    /// Builds a report of the current observations against the local ENR.
+    pub fn report(
+        &self,
+        enr: &Enr,
+        nat_open: bool,
+        discv5_unsolicited_requests_per_second: f64,
+    ) -> ReachabilityReport {
+        let mut transports = vec![];
+        let mut recommendations = vec![];
+
+        for transport in [Transport::Tcp, Transport::Quic, Transport::Discv5] {
+            for ip_family in [IpFamily::Ipv4, IpFamily::Ipv6] {
+                let reachability = self.transport_reachability(enr, transport, ip_family);
+                recommend(&reachability, &mut recommendations);
+                set_enr_mismatch_metric(&reachability);
+                transports.push(reachability);
+            }
+        }
+
+        ReachabilityReport {
+            nat_open,
+            discv5_unsolicited_requests_per_second,
+            transports,
+            recommendations,
+        }
+    }
+
+    /// Updates the ENR mismatch metric of a single transport and IP family.
+    pub fn update_enr_mismatch_metric(&self, enr: &Enr, transport: Transport, ip_family: IpFamily) {
+        set_enr_mismatch_metric(&self.transport_reachability(enr, transport, ip_family));
+    }
+
+    fn transport_reachability(
+        &self,
+        enr: &Enr,
+        transport: Transport,
+        ip_family: IpFamily,
+    ) -> TransportReachability {
+        let (mut observed_addresses, inbound_connections, outbound_connections) = self
+            .observations
+            .get(&(transport, ip_family))
+            .map(|observations| {
+                let observed = observations
+                    .observed
+                    .iter()
+                    .map(|(address, reports)| ObservedAddress {
+                        address: *address,
+                        reports: *reports,
+                    })
+                    .collect::<Vec<_>>();
+                (
+                    observed,
+                    observations.inbound_connections,
+                    observations.outbound_connections,
+                )
+            })
+            .unwrap_or_default();
+        observed_addresses.sort_by(|a, b| b.reports.cmp(&a.reports));
+
+        TransportReachability {
+            transport,
+            ip_family,
+            listening: self.listening.contains(&(transport, ip_family)),
+            enr_address: enr_address(enr, transport, ip_family),
+            observed_addresses,
+            inbound_connections,
+            outbound_connections,
+        }
+    }
+}
+
+/// Returns the transport and socket address of a multiaddr, if it is an IP-based TCP or QUIC
+/// address.
+pub fn transport_socket_addr(addr: &Multiaddr) -> Option<(Transport, SocketAddr)> {
+    let mut iter = addr.iter();
+    let ip = match iter.next()? {
+        Protocol::Ip4(ip) => IpAddr::V4(ip),
+        Protocol::Ip6(ip) => IpAddr::V6(ip),
+        _ => return None,
+    };
+    match iter.next()? {
+        Protocol::Tcp(port) => Some((Transport::Tcp, SocketAddr::new(ip, port))),
+        Protocol::Udp(port) if matches!(iter.next(), Some(Protocol::QuicV1)) => {
+            Some((Transport::Quic, SocketAddr::new(ip, port)))
+        }
+        _ => None,
+    }
+}
+
+/// The address advertised in the ENR for the given transport and IP family.
+fn enr_address(enr: &Enr, transport: Transport, ip_family: IpFamily) -> Option<SocketAddr> {
+    let (ip, port) = match ip_family {
+        IpFamily::Ipv4 => {
+            let port = match transport {
+                Transport::Tcp => enr.tcp4(),
+                Transport::Quic => enr.quic4(),
+                Transport::Discv5 => enr.udp4(),
+            };
+            (enr.ip4().map(IpAddr::V4), port)
+        }
+        IpFamily::Ipv6 => {
+            let port = match transport {
+                Transport::Tcp => enr.tcp6(),
+                Transport::Quic => enr.quic6(),
+                Transport::Discv5 => enr.udp6(),
+            };
+            (enr.ip6().map(IpAddr::V6), port)
+        }
+    };
+    Some(SocketAddr::new(ip?, port?))
+}
+
+/// The CLI flag used to set the ENR port of a transport and IP family.
+fn enr_port_flag(transport: Transport, ip_family: IpFamily) -> &'static str {
+    match (transport, ip_family) {
+        (Transport::Tcp, IpFamily::Ipv4) => "--enr-tcp-port",
+        (Transport::Tcp, IpFamily::Ipv6) => "--enr-tcp6-port",
+        (Transport::Quic, IpFamily::Ipv4) => "--enr-quic-port",
+        (Transport::Quic, IpFamily::Ipv6) => "--enr-quic6-port",
+        (Transport::Discv5, IpFamily::Ipv4) => "--enr-udp-port",
+        (Transport::Discv5, IpFamily::Ipv6) => "--enr-udp6-port",
+    }
+}
+
+/// The address most reported by peers, if it has enough reports to act upon.
+fn observed_address(reachability: &TransportReachability) -> Option<SocketAddr> {
+    reachability
+        .observed_addresses
+        .first()
+        .filter(|observed| observed.reports >= MIN_REPORTS_FOR_RECOMMENDATION)
+        .map(|observed| observed.address)
+}
+
+/// Outbound TCP connections use ephemeral ports, so only the IP of a TCP observation is
+/// meaningful. QUIC and discv5 send from their listening socket.
+fn ports_comparable(transport: Transport) -> bool {
+    transport != Transport::Tcp
+}
+
+/// Returns `true` if the ENR disagrees with the address remote peers observe.
+fn enr_mismatch(reachability: &TransportReachability) -> bool {
+    match (reachability.enr_address, observed_address(reachability)) {
+        (None, Some(_)) => reachability.listening,
+        (Some(enr_address), Some(observed)) => {
+            enr_address.ip() != observed.ip()
+                || (ports_comparable(reachability.transport)
+                    && enr_address.port() != observed.port())
+        }
+        (_, None) => false,
+    }
+}
+
+fn set_enr_mismatch_metric(reachability: &TransportReachability) {
+    metrics::set_gauge_vec(
+        &metrics::REACHABILITY_ENR_MISMATCH,
+        &[
+            reachability.transport.as_str(),
+            reachability.ip_family.as_str(),
+        ],
+        enr_mismatch(reachability) as i64,
+    );
+}
+
+/// Appends recommendations for a single transport and IP family.
+fn recommend(reachability: &TransportReachability, recommendations: &mut Vec<String>) {
+    let transport = reachability.transport.as_str();
+    let ip_family = reachability.ip_family.as_str();
+    let port_flag = enr_port_flag(reachability.transport, reachability.ip_family);
+    let observed = observed_address(reachability);
+    let ports_comparable = ports_comparable(reachability.transport);
+
+    match (reachability.enr_address, observed) {
+        (None, Some(observed)) if reachability.listening => {
+            let port = if ports_comparable {
+                format!("{} {}", port_flag, observed.port())
+            } else {
+                format!("{} <port>", port_flag)
+            };
+            recommendations.push(format!(
+                "The ENR does not advertise a {} {} address but peers observe {}. Consider \
+                 setting --enr-address {} and {}.",
+                transport,
+                ip_family,
+                observed,
+                observed.ip(),
+                port
+            ));
+        }
+        (Some(enr_address), Some(observed)) => {
+            if enr_address.ip() != observed.ip() {
+                recommendations.push(format!(
+                    "The ENR advertises {} for {} {} but peers observe {}. Consider setting \
+                     --enr-address {}.",
+                    enr_address,
+                    transport,
+                    ip_family,
+                    observed.ip(),
+                    observed.ip()
+                ));
+            } else if ports_comparable && enr_address.port() != observed.port() {
+                recommendations.push(format!(
+                    "The ENR advertises {} port {} but peers observe port {}. Consider setting \
+                     {} {}.",
+                    transport,
+                    enr_address.port(),
+                    observed.port(),
+                    port_flag,
+                    observed.port()
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(enr_address) = reachability.enr_address {
+        if reachability.transport != Transport::Discv5
+            && reachability.inbound_connections == 0
+            && reachability.outbound_connections >= MIN_OUTBOUND_FOR_INBOUND_CHECK
+        {
+            recommendations.push(format!(
+                "No inbound {} {} connections after {} outbound connections. Check that port {} \
+                 is open and forwarded to this node.",
+                transport,
+                ip_family,
+                reachability.outbound_connections,
+                enr_address.port()
+            ));
+        }
+    } else if reachability.listening && reachability.inbound_connections > 0 && observed.is_none() {
+        recommendations.push(format!(
+            "Peers connect over {} {} but the ENR does not advertise it. Consider setting \
+             --enr-address and {}.",
+            transport, ip_family, port_flag
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use discv5::enr::CombinedKey;
+    use std::net::Ipv4Addr;
+
+    fn enr_with_tcp(ip: Ipv4Addr, port: u16) -> Enr {
+        let key = CombinedKey::generate_secp256k1();
+        Enr::builder().ip4(ip).tcp4(port).build(&key).unwrap()
+    }
+
+    #[test]
+    fn multiaddr_transports() {
+        let tcp: Multiaddr = "/ip4/1.2.3.4/tcp/9000".parse().unwrap();
+        let quic: Multiaddr = "/ip6/::1/udp/9001/quic-v1".parse().unwrap();
+        let udp: Multiaddr = "/ip4/1.2.3.4/udp/9000".parse().unwrap();
+        assert_eq!(
+            transport_socket_addr(&tcp),
+            Some((Transport::Tcp, "1.2.3.4:9000".parse().unwrap()))
+        );
+        assert_eq!(
+            transport_socket_addr(&quic),
+            Some((Transport::Quic, "[::1]:9001".parse().unwrap()))
+        );
+        assert_eq!(transport_socket_addr(&udp), None);
+    }
+
+    #[test]
+    fn recommends_enr_address_on_mismatch() {
+        let mut tracker = ReachabilityTracker::default();
+        for _ in 0..MIN_REPORTS_FOR_RECOMMENDATION {
+            tracker.record_observed_address(Transport::Tcp, "5.6.7.8:41000".parse().unwrap());
+        }
+        let report = tracker.report(&enr_with_tcp(Ipv4Addr::new(1, 2, 3, 4), 9000), false, 0.0);
+        assert_eq!(report.recommendations.len(), 1);
+        assert!(report.recommendations[0].contains("--enr-address 5.6.7.8"));
+
+        let report = tracker.report(&enr_with_tcp(Ipv4Addr::new(5, 6, 7, 8), 9000), false, 0.0);
+        assert!(report.recommendations.is_empty());
+    }
+
+    #[test]
+    fn enr_mismatch_per_transport() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = Enr::builder()
+            .ip4(Ipv4Addr::new(1, 2, 3, 4))
+            .udp4(9000)
+            .build(&key)
+            .unwrap();
+        let mut tracker = ReachabilityTracker::default();
+        for _ in 0..MIN_REPORTS_FOR_RECOMMENDATION {
+            tracker.record_observed_address(Transport::Discv5, "1.2.3.4:9100".parse().unwrap());
+        }
+
+        let discv5 = tracker.transport_reachability(&enr, Transport::Discv5, IpFamily::Ipv4);
+        assert!(enr_mismatch(&discv5));
+        let quic = tracker.transport_reachability(&enr, Transport::Quic, IpFamily::Ipv4);
+        assert!(!enr_mismatch(&quic));
+    }
+
+    #[test]
+    fn observed_addresses_are_bounded() {
+        let mut tracker = ReachabilityTracker::default();
+        tracker.record_observed_address(Transport::Quic, "1.1.1.1:9001".parse().unwrap());
+        tracker.record_observed_address(Transport::Quic, "1.1.1.1:9001".parse().unwrap());
+        for port in 0..MAX_OBSERVED_ADDRESSES as u16 * 2 {
+            tracker.record_observed_address(
+                Transport::Quic,
+                SocketAddr::new(IpAddr::V4(Ipv4Addr::new(2, 2, 2, 2)), port),
+            );
+        }
+        let observed = &tracker.observations[&(Transport::Quic, IpFamily::Ipv4)].observed;
+        assert_eq!(observed.len(), MAX_OBSERVED_ADDRESSES);
+        assert_eq!(observed.get(&"1.1.1.1:9001".parse().unwrap()), Some(&2));
+    }
+}
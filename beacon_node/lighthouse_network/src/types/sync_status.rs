@@ -0,0 +1,144 @@
+//! A detailed view of the sync processes, refreshed periodically by the `SyncManager` and served
+//! on the `/lighthouse/sync/status` HTTP API endpoint.
+use crate::types::{BackFillState, SyncState};
+use serde::{Deserialize, Serialize};
+use types::{Epoch, Hash256, Slot};
+
+/// A snapshot of the progress of range sync, backfill sync and block lookups.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SyncStatusReport {
+    /// The global sync state of the node.
+    pub state: SyncState,
+    /// All finalized and head chains known to range sync.
+    pub range_chains: Vec<RangeChainReport>,
+    /// The progress of backfill sync.
+    pub backfill: BackFillReport,
+    /// Block and parent lookups in flight.
+    pub lookups: LookupsReport,
+}
+
+impl Default for SyncStatusReport {
+    fn default() -> Self {
+        Self {
+            state: SyncState::Stalled,
+            range_chains: vec![],
+            backfill: BackFillReport::default(),
+            lookups: LookupsReport::default(),
+        }
+    }
+}
+
+/// The progress of a single range sync chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RangeChainReport {
+    pub id: u64,
+    /// Either `finalized` or `head`.
+    pub sync_type: String,
+    /// Whether the chain is currently being synced (as opposed to stopped).
+    pub is_syncing: bool,
+    pub start_epoch: Epoch,
+    pub target_head_slot: Slot,
+    pub target_head_root: Hash256,
+    pub processing_target: Epoch,
+    pub to_be_downloaded: Epoch,
+    pub validated_batches: u64,
+    /// The peers which agree on the target head of this chain.
+    pub peers: Vec<String>,
+    pub batches: Vec<BatchReport>,
+    pub progress: SyncProgress,
+}
+
+/// The progress of backfill sync.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BackFillReport {
+    pub state: BackFillState,
+    pub current_start: Epoch,
+    pub processing_target: Epoch,
+    pub to_be_downloaded: Epoch,
+    pub validated_batches: u64,
+    /// The peers which have served batches for this backfill sync.
+    pub participating_peers: Vec<String>,
+    pub batches: Vec<BatchReport>,
+    pub progress: SyncProgress,
+}
+
+impl Default for BackFillReport {
+    fn default() -> Self {
+        Self {
+            state: BackFillState::NotRequired,
+            current_start: Epoch::new(0),
+            processing_target: Epoch::new(0),
+            to_be_downloaded: Epoch::new(0),
+            validated_batches: 0,
+            participating_peers: vec![],
+            batches: vec![],
+            progress: SyncProgress::default(),
+        }
+    }
+}
+
+/// The state of a single batch of a range or backfill sync.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub start_slot: Slot,
+    pub end_slot: Slot,
+    /// One of `awaiting_download`, `downloading`, `awaiting_processing`, `processing`,
+    /// `awaiting_validation`, `poisoned` or `failed`.
+    pub state: String,
+    /// The peer currently downloading (or which downloaded) this batch, if any.
+    pub peer: Option<String>,
+    /// The number of blocks downloaded for this batch, if known.
+    pub blocks: usize,
+    pub failed_download_attempts: usize,
+    pub failed_processing_attempts: usize,
+}
+
+/// Download and processing progress for a range or backfill sync.
+///
+/// Rates are measured in slots per second and are only available once the progress has been
+/// sampled at least twice.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncProgress {
+    /// Slots which have been downloaded, including those already processed.
+    pub downloaded_slots: u64,
+    /// Slots which have been processed.
+    pub processed_slots: u64,
+    /// Slots which remain to be processed.
+    pub remaining_slots: u64,
+    pub download_rate: Option<f64>,
+    pub processing_rate: Option<f64>,
+    /// Estimated number of seconds until `remaining_slots` have been processed.
+    pub eta_seconds: Option<u64>,
+}
+
+/// The block lookups currently in flight.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LookupsReport {
+    pub single_block_lookups: Vec<SingleLookupReport>,
+    pub parent_lookups: Vec<ParentLookupReport>,
+    /// The number of parent chains which have been downloaded and are being processed.
+    pub processing_parent_lookups: usize,
+}
+
+/// A lookup of a single block (and its blobs) by root.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SingleLookupReport {
+    pub block_root: Hash256,
+    /// The state of the block request, e.g. `awaiting_download`, `downloading` or `processing`.
+    pub block_state: String,
+    /// The state of the blobs request.
+    pub blob_state: String,
+    pub peers: usize,
+    pub failed_attempts: u8,
+}
+
+/// A lookup of the unknown ancestors of a block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ParentLookupReport {
+    /// The root of the block which triggered the lookup.
+    pub chain_hash: Hash256,
+    /// The root of the parent currently being requested.
+    pub current_block_root: Hash256,
+    pub downloaded_blocks: usize,
+    pub failed_attempts: u8,
+}
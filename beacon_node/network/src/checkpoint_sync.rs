@@ -0,0 +1,614 @@
+//! Obtains a finalized state and block from a set of trusted libp2p peers, as an alternative to
+//! checkpoint syncing from a beacon node HTTP API.
+//!
+//! A temporary libp2p service is started which only dials the trusted peers. The checkpoint block
+//! is requested with `BlocksByRoot` and its post-state with the `FinalizedState` protocol, which
+//! streams the SSZ bytes of the state in chunks. Both are verified against the weak subjectivity
+//! checkpoint supplied by the user.
+use lighthouse_network::rpc::methods::{
+    FinalizedStateChunk, FinalizedStateRequest, MAX_FINALIZED_STATE_CHUNKS,
+};
+use lighthouse_network::rpc::{BlocksByRootRequest, GoodbyeReason, RPCResponseErrorCode};
+use lighthouse_network::service::Network;
+use lighthouse_network::{
+    multiaddr::Protocol as MProtocol, rpc::StatusMessage, Context, Multiaddr, NetworkConfig,
+    NetworkEvent, NetworkGlobals, PeerAction, PeerId, ReportSource, Request, Response,
+};
+use slog::{debug, info, warn, Logger};
+use ssz::Decode;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{
+    BeaconState, ChainSpec, Checkpoint, EthSpec, ForkContext, Hash256, SignedBeaconBlock, Slot,
+};
+
+/// The maximum time to wait for the checkpoint to be obtained from the trusted peers.
+const CHECKPOINT_SYNC_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Identifies the requests sent to the trusted peers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CheckpointRequestId {
+    Status,
+    Block,
+    State,
+}
+
+/// The progress of the download from a single peer.
+#[derive(Default)]
+struct PeerDownload<E: EthSpec> {
+    block: Option<Arc<SignedBeaconBlock<E>>>,
+    /// Whether the `BlocksByRoot` stream has ended.
+    block_complete: bool,
+    state_bytes: Vec<u8>,
+    /// The state length claimed by the first chunk, which every other chunk must agree with.
+    state_length: Option<u64>,
+    state_chunks: u64,
+    state_complete: bool,
+}
+
+impl<E: EthSpec> PeerDownload<E> {
+    /// Appends `chunk` to the state, checking that it follows the previous chunks.
+    fn add_chunk(&mut self, chunk: &FinalizedStateChunk) -> Result<(), String> {
+        let state_length = *self.state_length.get_or_insert(chunk.total_length);
+        if chunk.total_length != state_length {
+            return Err(format!(
+                "chunk claims a state length of {}, but previous chunks claimed {}",
+                chunk.total_length, state_length
+            ));
+        }
+        self.state_chunks += 1;
+        if self.state_chunks > MAX_FINALIZED_STATE_CHUNKS {
+            return Err(format!(
+                "state was sent in more than the maximum of {} chunks",
+                MAX_FINALIZED_STATE_CHUNKS
+            ));
+        }
+        if chunk.data.is_empty() {
+            return Err("chunk is empty".to_string());
+        }
+        if chunk.offset != self.state_bytes.len() as u64 {
+            return Err(format!(
+                "unexpected chunk offset {}, expected {}",
+                chunk.offset,
+                self.state_bytes.len()
+            ));
+        }
+        let end = chunk.offset + chunk.data.len() as u64;
+        if end > chunk.total_length {
+            return Err(format!(
+                "chunk ends at {} which is beyond the state length {}",
+                end, chunk.total_length
+            ));
+        }
+        self.state_bytes.extend_from_slice(&chunk.data);
+        Ok(())
+    }
+
+    /// Marks the state as complete, checking that the chunks covered the whole state.
+    fn complete_state(&mut self) -> Result<(), String> {
+        self.state_complete = true;
+        let state_length = self.state_length.unwrap_or(0);
+        if self.state_bytes.len() as u64 != state_length {
+            return Err(format!(
+                "received {} bytes of a state of length {}",
+                self.state_bytes.len(),
+                state_length
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the checkpoint block once both the block and state streams have ended.
+    ///
+    /// The two requests are made concurrently, so either stream may end first.
+    fn completed_block(&self) -> Result<Option<&Arc<SignedBeaconBlock<E>>>, String> {
+        if !self.block_complete || !self.state_complete {
+            return Ok(None);
+        }
+        self.block
+            .as_ref()
+            .map(Some)
+            .ok_or_else(|| "peer did not send the checkpoint block".to_string())
+    }
+}
+
+/// Downloads the checkpoint block and state for `checkpoint` from `peers`.
+///
+/// Each address in `peers` must include the `/p2p/<peer_id>` component. Peers are tried one at a
+/// time until one of them provides a block and state which match `checkpoint`.
+pub async fn fetch_checkpoint_from_peers<E: EthSpec>(
+    executor: task_executor::TaskExecutor,
+    config: &NetworkConfig,
+    peers: &[Multiaddr],
+    checkpoint: Checkpoint,
+    genesis_state: &BeaconState<E>,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<(BeaconState<E>, SignedBeaconBlock<E>), String> {
+    let trusted_peers = peers
+        .iter()
+        .map(|addr| {
+            addr.iter()
+                .find_map(|protocol| match protocol {
+                    MProtocol::P2p(peer_id) => Some(peer_id),
+                    _ => None,
+                })
+                .ok_or_else(|| format!("Checkpoint sync peer {addr} has no /p2p/ peer id"))
+        })
+        .collect::<Result<Vec<PeerId>, String>>()?;
+
+    let genesis_validators_root = genesis_state.genesis_validators_root();
+    let current_slot = current_slot::<E>(genesis_state.genesis_time(), spec);
+    let fork_context = Arc::new(ForkContext::new::<E>(
+        current_slot,
+        genesis_validators_root,
+        spec,
+    ));
+    let enr_fork_id = spec.enr_fork_id::<E>(current_slot, genesis_validators_root);
+
+    // Only connect to the trusted peers.
+    let mut config = config.clone();
+    config.libp2p_nodes = peers.to_vec();
+    config.trusted_peers = trusted_peers.iter().map(|peer| (*peer).into()).collect();
+    config.boot_nodes_enr = vec![];
+    config.boot_nodes_multiaddr = vec![];
+    config.disable_discovery = true;
+    config.upnp_enabled = false;
+    config.topics = vec![];
+
+    let status = StatusMessage {
+        fork_digest: enr_fork_id.fork_digest,
+        // We have no chain yet, so report genesis with the root aliased to `0x00`.
+        finalized_root: Hash256::zero(),
+        finalized_epoch: spec.genesis_slot.epoch(E::slots_per_epoch()),
+        head_root: Hash256::zero(),
+        head_slot: spec.genesis_slot,
+    };
+
+    let ctx = Context {
+        config: &config,
+        enr_fork_id,
+        fork_context,
+        chain_spec: spec,
+        libp2p_registry: None,
+    };
+    let (mut network, network_globals) = Network::<CheckpointRequestId, E>::new(executor, ctx, log)
+        .await
+        .map_err(|e| format!("Unable to start libp2p for checkpoint sync: {e:?}"))?;
+
+    info!(
+        log,
+        "Requesting checkpoint from trusted peers";
+        "peers" => trusted_peers.len(),
+        "checkpoint_epoch" => checkpoint.epoch,
+        "checkpoint_root" => ?checkpoint.root,
+    );
+
+    let download = async {
+        let mut failed_peers = HashSet::new();
+        let mut current: Option<(PeerId, PeerDownload<E>)> = None;
+        loop {
+            match network.next_event().await {
+                NetworkEvent::PeerConnectedOutgoing(peer_id)
+                | NetworkEvent::PeerConnectedIncoming(peer_id) => {
+                    if !trusted_peers.contains(&peer_id) {
+                        network.goodbye_peer(
+                            &peer_id,
+                            GoodbyeReason::Unknown,
+                            ReportSource::SyncService,
+                        );
+                        continue;
+                    }
+                    network.send_request(
+                        peer_id,
+                        CheckpointRequestId::Status,
+                        Request::Status(status.clone()),
+                    );
+                    if current.is_none() && !failed_peers.contains(&peer_id) {
+                        request_checkpoint(&mut network, peer_id, checkpoint.root, spec);
+                        current = Some((peer_id, PeerDownload::default()));
+                    }
+                }
+                NetworkEvent::StatusPeer(peer_id) => network.send_request(
+                    peer_id,
+                    CheckpointRequestId::Status,
+                    Request::Status(status.clone()),
+                ),
+                NetworkEvent::RequestReceived {
+                    peer_id,
+                    id,
+                    request,
+                } => match request {
+                    Request::Status(_) => {
+                        network.send_response(peer_id, id, Response::Status(status.clone()))
+                    }
+                    _ => network.send_error_response(
+                        peer_id,
+                        id,
+                        RPCResponseErrorCode::ResourceUnavailable,
+                        "Node is checkpoint syncing".into(),
+                    ),
+                },
+                NetworkEvent::ResponseReceived {
+                    peer_id, response, ..
+                } => {
+                    let Some((current_peer, peer_download)) = current.as_mut() else {
+                        continue;
+                    };
+                    if *current_peer != peer_id {
+                        continue;
+                    }
+                    let result = match response {
+                        Response::BlocksByRoot(Some(block)) => {
+                            peer_download.block = Some(block);
+                            Ok(())
+                        }
+                        Response::BlocksByRoot(None) => {
+                            peer_download.block_complete = true;
+                            Ok(())
+                        }
+                        Response::FinalizedState(Some(chunk)) => {
+                            peer_download.add_chunk(&chunk).map_err(|e| {
+                                // The chunks are malformed, rather than the state being wrong.
+                                network.report_peer(
+                                    &peer_id,
+                                    PeerAction::LowToleranceError,
+                                    ReportSource::SyncService,
+                                    "invalid_finalized_state_chunk",
+                                );
+                                e
+                            })
+                        }
+                        Response::FinalizedState(None) => peer_download.complete_state(),
+                        _ => Ok(()),
+                    };
+
+                    let result = match result.and_then(|()| peer_download.completed_block()) {
+                        Ok(Some(block)) => {
+                            match verify_checkpoint(
+                                block,
+                                &peer_download.state_bytes,
+                                checkpoint,
+                                spec,
+                            ) {
+                                Ok(state) => {
+                                    return Ok((state, block.as_ref().clone()));
+                                }
+                                Err(e) => Err(e),
+                            }
+                        }
+                        Ok(None) => Ok(()),
+                        Err(e) => Err(e),
+                    };
+
+                    if let Err(error) = result {
+                        warn!(
+                            log,
+                            "Invalid checkpoint from trusted peer";
+                            "peer_id" => %peer_id,
+                            "error" => error,
+                        );
+                        failed_peers.insert(peer_id);
+                        current = next_peer(
+                            &mut network,
+                            &network_globals,
+                            &trusted_peers,
+                            &failed_peers,
+                            checkpoint.root,
+                            spec,
+                        )?;
+                    }
+                }
+                NetworkEvent::RPCFailed { peer_id, error, id } => {
+                    if id == CheckpointRequestId::Status
+                        || current.as_ref().map(|(peer, _)| *peer) != Some(peer_id)
+                    {
+                        continue;
+                    }
+                    warn!(
+                        log,
+                        "Checkpoint request to trusted peer failed";
+                        "peer_id" => %peer_id,
+                        "request" => ?id,
+                        "error" => %error,
+                    );
+                    failed_peers.insert(peer_id);
+                    current = next_peer(
+                        &mut network,
+                        &network_globals,
+                        &trusted_peers,
+                        &failed_peers,
+                        checkpoint.root,
+                        spec,
+                    )?;
+                }
+                NetworkEvent::PeerDisconnected(peer_id) => {
+                    if current.as_ref().map(|(peer, _)| *peer) == Some(peer_id) {
+                        debug!(log, "Trusted peer disconnected during checkpoint sync"; "peer_id" => %peer_id);
+                        failed_peers.insert(peer_id);
+                        current = next_peer(
+                            &mut network,
+                            &network_globals,
+                            &trusted_peers,
+                            &failed_peers,
+                            checkpoint.root,
+                            spec,
+                        )?;
+                    }
+                }
+                NetworkEvent::ZeroListeners => {
+                    return Err("All libp2p listeners closed during checkpoint sync".to_string())
+                }
+                NetworkEvent::PubsubMessage { .. } | NetworkEvent::NewListenAddr(_) => {}
+            }
+        }
+    };
+
+    let result = tokio::time::timeout(CHECKPOINT_SYNC_TIMEOUT, download)
+        .await
+        .map_err(|_| {
+            format!(
+                "Timed out after {}s waiting for the checkpoint from trusted peers",
+                CHECKPOINT_SYNC_TIMEOUT.as_secs()
+            )
+        })?;
+
+    for peer_id in &trusted_peers {
+        network.goodbye_peer(
+            peer_id,
+            GoodbyeReason::ClientShutdown,
+            ReportSource::SyncService,
+        );
+    }
+
+    result
+}
+
+/// Requests the checkpoint block and its post-state from `peer_id`.
+fn request_checkpoint<E: EthSpec>(
+    network: &mut Network<CheckpointRequestId, E>,
+    peer_id: PeerId,
+    block_root: Hash256,
+    spec: &ChainSpec,
+) {
+    network.send_request(
+        peer_id,
+        CheckpointRequestId::Block,
+        Request::BlocksByRoot(BlocksByRootRequest::new(vec![block_root], spec)),
+    );
+    network.send_request(
+        peer_id,
+        CheckpointRequestId::State,
+        Request::FinalizedState(FinalizedStateRequest { block_root }),
+    );
+}
+
+/// Requests the checkpoint from the next connected peer which has not failed yet.
+fn next_peer<E: EthSpec>(
+    network: &mut Network<CheckpointRequestId, E>,
+    network_globals: &NetworkGlobals<E>,
+    trusted_peers: &[PeerId],
+    failed_peers: &HashSet<PeerId>,
+    block_root: Hash256,
+    spec: &ChainSpec,
+) -> Result<Option<(PeerId, PeerDownload<E>)>, String> {
+    if trusted_peers
+        .iter()
+        .all(|peer_id| failed_peers.contains(peer_id))
+    {
+        return Err("None of the trusted peers provided a valid checkpoint".to_string());
+    }
+    let next = trusted_peers
+        .iter()
+        .find(|peer_id| {
+            !failed_peers.contains(peer_id) && network_globals.peers.read().is_connected(peer_id)
+        })
+        .copied();
+    Ok(next.map(|peer_id| {
+        request_checkpoint(network, peer_id, block_root, spec);
+        (peer_id, PeerDownload::default())
+    }))
+}
+
+/// Checks that `block` is the checkpoint block and that `state_bytes` decode to its post-state,
+/// i.e. a state whose root is the block's `state_root`.
+fn verify_checkpoint<E: EthSpec>(
+    block: &SignedBeaconBlock<E>,
+    state_bytes: &[u8],
+    checkpoint: Checkpoint,
+    spec: &ChainSpec,
+) -> Result<BeaconState<E>, String> {
+    let block_root = block.canonical_root();
+    if block_root != checkpoint.root {
+        return Err(format!(
+            "block root {block_root:?} does not match checkpoint root {:?}",
+            checkpoint.root
+        ));
+    }
+
+    // The checkpoint block must be in the slots leading up to the start of the checkpoint epoch.
+    // The state is the block's unadvanced post-state, which the beacon chain builder advances to
+    // the epoch boundary itself.
+    let slots_per_epoch = E::slots_per_epoch();
+    let aligned_epoch = (block.slot() + (slots_per_epoch - 1)).epoch(slots_per_epoch);
+    if aligned_epoch != checkpoint.epoch {
+        return Err(format!(
+            "block slot {} does not correspond to checkpoint epoch {}",
+            block.slot(),
+            checkpoint.epoch
+        ));
+    }
+
+    let mut state = BeaconState::from_ssz_bytes(state_bytes, spec)
+        .map_err(|e| format!("unable to decode state: {e:?}"))?;
+    let state_root = state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("unable to compute state root: {e:?}"))?;
+    if state_root != block.state_root() {
+        return Err(format!(
+            "state root {state_root:?} does not match block state root {:?}",
+            block.state_root()
+        ));
+    }
+    Ok(state)
+}
+
+/// Returns the current slot according to the system clock.
+fn current_slot<E: EthSpec>(genesis_time: u64, spec: &ChainSpec) -> Slot {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    spec.genesis_slot + now.saturating_sub(genesis_time) / spec.seconds_per_slot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::Encode;
+    use types::{BeaconBlock, Eth1Data, MainnetEthSpec, Signature};
+
+    type E = MainnetEthSpec;
+
+    const MAX_RPC_SIZE: usize = FinalizedStateChunk::OVERHEAD + 100;
+
+    fn state_bytes(len: usize) -> Vec<u8> {
+        (0..len).map(|i| i as u8).collect()
+    }
+
+    fn reassemble(chunks: &[FinalizedStateChunk]) -> Result<Vec<u8>, String> {
+        let mut download = PeerDownload::<E>::default();
+        for chunk in chunks {
+            download.add_chunk(chunk)?;
+        }
+        download.complete_state()?;
+        Ok(download.state_bytes)
+    }
+
+    #[test]
+    fn split_and_reassemble() {
+        for len in [1, 99, 100, 101, 1_000] {
+            let bytes = state_bytes(len);
+            let chunks = FinalizedStateChunk::split(&bytes, MAX_RPC_SIZE).unwrap();
+            assert_eq!(chunks.len(), (len + 99) / 100);
+            assert!(chunks
+                .iter()
+                .all(|chunk| chunk.as_ssz_bytes().len() <= MAX_RPC_SIZE));
+            assert_eq!(reassemble(&chunks).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn split_rejects_small_rpc_size() {
+        assert!(
+            FinalizedStateChunk::split(&state_bytes(10), FinalizedStateChunk::OVERHEAD).is_err()
+        );
+    }
+
+    #[test]
+    fn split_rejects_too_many_chunks() {
+        let bytes = state_bytes(MAX_FINALIZED_STATE_CHUNKS as usize * 100 + 1);
+        assert!(FinalizedStateChunk::split(&bytes, MAX_RPC_SIZE).is_err());
+    }
+
+    #[test]
+    fn reassemble_rejects_inconsistent_chunks() {
+        let chunks = FinalizedStateChunk::split(&state_bytes(1_000), MAX_RPC_SIZE).unwrap();
+
+        // A chunk which disagrees with the state length of the previous chunks.
+        let mut mismatched = chunks.clone();
+        mismatched[1].total_length += 1;
+        assert!(reassemble(&mismatched).is_err());
+
+        // Chunks which are out of order.
+        let mut reordered = chunks.clone();
+        reordered.swap(1, 2);
+        assert!(reassemble(&reordered).is_err());
+
+        // A chunk which extends beyond the state length.
+        let mut overlong = chunks.clone();
+        overlong
+            .iter_mut()
+            .for_each(|chunk| chunk.total_length = 950);
+        assert!(reassemble(&overlong).is_err());
+
+        // A stream which ends before the whole state has been sent.
+        assert!(reassemble(&chunks[..chunks.len() - 1]).is_err());
+
+        // A stream of empty chunks.
+        let empty = FinalizedStateChunk {
+            total_length: 1_000,
+            offset: 0,
+            data: vec![].into(),
+        };
+        assert!(reassemble(&[empty.clone(), empty]).is_err());
+    }
+
+    #[test]
+    fn waits_for_both_streams_to_end() {
+        let spec = E::default_spec();
+        let block = Arc::new(SignedBeaconBlock::from_block(
+            BeaconBlock::<E>::empty(&spec),
+            Signature::empty(),
+        ));
+
+        // The state stream ends before the block has arrived.
+        let mut download = PeerDownload::<E>::default();
+        download.complete_state().unwrap();
+        assert_eq!(download.completed_block(), Ok(None));
+
+        // The block arrives after the state, followed by the end of its stream.
+        download.block = Some(block.clone());
+        assert_eq!(download.completed_block(), Ok(None));
+        download.block_complete = true;
+        assert_eq!(download.completed_block(), Ok(Some(&block)));
+
+        // The block stream ends first without the block, then the state stream ends.
+        let mut download = PeerDownload::<E>::default();
+        download.block_complete = true;
+        assert_eq!(download.completed_block(), Ok(None));
+        download.complete_state().unwrap();
+        assert!(download.completed_block().is_err());
+    }
+
+    #[test]
+    fn verify_checkpoint_block_and_state() {
+        let spec = E::default_spec();
+        let mut state = BeaconState::<E>::new(0, Eth1Data::default(), &spec);
+        let state_root = state.update_tree_hash_cache().unwrap();
+        let state_bytes = state.as_ssz_bytes();
+
+        let mut block = BeaconBlock::<E>::empty(&spec);
+        *block.state_root_mut() = state_root;
+        let block = SignedBeaconBlock::from_block(block, Signature::empty());
+        let checkpoint = Checkpoint {
+            epoch: block.slot().epoch(E::slots_per_epoch()),
+            root: block.canonical_root(),
+        };
+
+        let verified = verify_checkpoint(&block, &state_bytes, checkpoint, &spec).unwrap();
+        assert_eq!(verified.slot(), state.slot());
+
+        // A block which is not the checkpoint block.
+        let wrong_root = Checkpoint {
+            root: Hash256::repeat_byte(1),
+            ..checkpoint
+        };
+        assert!(verify_checkpoint(&block, &state_bytes, wrong_root, &spec).is_err());
+
+        // A block which does not correspond to the checkpoint epoch.
+        let wrong_epoch = Checkpoint {
+            epoch: checkpoint.epoch + 1,
+            ..checkpoint
+        };
+        assert!(verify_checkpoint(&block, &state_bytes, wrong_epoch, &spec).is_err());
+
+        // A state which cannot be decoded.
+        assert!(verify_checkpoint(&block, &state_bytes[1..], checkpoint, &spec).is_err());
+
+        // A state which is not the block's post-state.
+        let other_state_bytes = BeaconState::<E>::new(1, Eth1Data::default(), &spec).as_ssz_bytes();
+        assert!(verify_checkpoint(&block, &other_state_bytes, checkpoint, &spec).is_err());
+    }
+}
@@ -2,6 +2,7 @@
 extern crate lazy_static;
 
 /// This crate provides the network server for Lighthouse.
+pub mod checkpoint_sync;
 pub mod error;
 #[allow(clippy::mutable_key_type)] // PeerId in hashmaps are no longer permitted by clippy
 pub mod service;
@@ -18,9 +18,10 @@ use beacon_chain::{
     AvailabilityProcessingStatus, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
     GossipVerifiedBlock, NotifyExecutionLayer,
 };
+use lighthouse_network::types::{GossipKind, InvalidObjectKind, InvalidObjectMetadata};
 use lighthouse_network::{Client, MessageAcceptance, MessageId, PeerAction, PeerId, ReportSource};
 use operation_pool::ReceivedPreCapella;
-use slog::{crit, debug, error, info, trace, warn, Logger};
+use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use ssz::Encode;
 use std::fs;
@@ -30,6 +31,7 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::HotColdDBError;
 use tokio::sync::mpsc;
+use tree_hash::TreeHash;
 use types::{
     Attestation, AttesterSlashing, BlobSidecar, EthSpec, Hash256, IndexedAttestation,
     LightClientFinalityUpdate, LightClientOptimisticUpdate, ProposerSlashing,
@@ -619,7 +621,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         metrics::set_gauge(&metrics::BEACON_BLOB_LAST_DELAY, delay.as_millis() as i64);
         match self
             .chain
-            .verify_blob_sidecar_for_gossip(blob_sidecar, blob_index)
+            .verify_blob_sidecar_for_gossip(blob_sidecar.clone(), blob_index)
         {
             Ok(gossip_verified_blob) => {
                 metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOB_VERIFIED_TOTAL);
@@ -713,6 +715,19 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             peer_id,
                             MessageAcceptance::Reject,
                         );
+                        self.maybe_store_invalid_object(
+                            &self.invalid_block_storage,
+                            InvalidObjectMetadata {
+                                kind: InvalidObjectKind::BlobSidecar,
+                                slot: Some(slot),
+                                root: blob_sidecar.tree_hash_root(),
+                                peer_id: peer_id.to_string(),
+                                topic: GossipKind::BlobSidecar(blob_index).to_string(),
+                                error: format!("{:?}", err),
+                                seen_timestamp_ms: seen_duration.as_millis() as u64,
+                            },
+                            blob_sidecar.as_ref(),
+                        );
                     }
                     GossipBlobError::FutureSlot { .. }
                     | GossipBlobError::RepeatBlob { .. }
@@ -1015,6 +1030,19 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     PeerAction::LowToleranceError,
                     "gossip_block_low",
                 );
+                self.maybe_store_invalid_object(
+                    &self.invalid_block_storage,
+                    InvalidObjectMetadata {
+                        kind: InvalidObjectKind::Block,
+                        slot: Some(block.slot()),
+                        root: block_root,
+                        peer_id: peer_id.to_string(),
+                        topic: GossipKind::BeaconBlock.to_string(),
+                        error: e.to_string(),
+                        seen_timestamp_ms: seen_duration.as_millis() as u64,
+                    },
+                    block.as_ref(),
+                );
                 return None;
             }
             // Note: This error variant cannot be reached when doing gossip validation
@@ -1121,6 +1149,56 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         }
     }
 
+    /// Spawn a task which fetches any blobs of `block` that we have not yet received from the
+    /// execution layer's blob pool and imports the block if this makes it available.
+    fn fetch_blobs_from_execution_layer(
+        self: &Arc<Self>,
+        block_root: Hash256,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    ) {
+        if block.num_expected_blobs() == 0 {
+            return;
+        }
+
+        let processor = self.clone();
+        self.executor.spawn(
+            async move {
+                match processor
+                    .chain
+                    .fetch_blobs_from_execution_layer(block_root, block)
+                    .await
+                {
+                    Ok(Some(AvailabilityProcessingStatus::Imported(block_root))) => {
+                        info!(
+                            processor.log,
+                            "Blobs from execution layer completed block";
+                            "block_root" => %block_root,
+                        );
+                        processor.chain.recompute_head_at_current_slot().await;
+                    }
+                    Ok(Some(AvailabilityProcessingStatus::MissingComponents(slot, block_root))) => {
+                        trace!(
+                            processor.log,
+                            "Processed blobs from execution layer, waiting for other components";
+                            "slot" => %slot,
+                            "block_root" => %block_root,
+                        );
+                    }
+                    Ok(None) | Err(BlockError::BlockIsAlreadyKnown) => {}
+                    Err(e) => {
+                        debug!(
+                            processor.log,
+                            "Failed to fetch blobs from execution layer";
+                            "block_root" => %block_root,
+                            "error" => ?e,
+                        );
+                    }
+                }
+            },
+            "fetch_blobs_gossip",
+        );
+    }
+
     /// Process the beacon block that has already passed gossip verification.
     ///
     /// Raises a log if there are errors.
@@ -1130,12 +1208,14 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         verified_block: GossipVerifiedBlock<T>,
         reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
         invalid_block_storage: InvalidBlockStorage,
-        // This value is not used presently, but it might come in handy for debugging.
-        _seen_duration: Duration,
+        seen_duration: Duration,
     ) {
         let block = verified_block.block.block_cloned();
         let block_root = verified_block.block_root;
 
+        // Fetch the block's blobs from the execution layer whilst the block is being processed.
+        self.fetch_blobs_from_execution_layer(block_root, block.clone());
+
         let result = self
             .chain
             .process_block_with_early_caching(block_root, verified_block, NotifyExecutionLayer::Yes)
@@ -1245,12 +1325,18 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         };
 
         if let Err(e) = &result {
-            self.maybe_store_invalid_block(
+            self.maybe_store_invalid_object(
                 &invalid_block_storage,
-                block_root,
-                &block,
-                e,
-                &self.log,
+                InvalidObjectMetadata {
+                    kind: InvalidObjectKind::Block,
+                    slot: Some(block.slot()),
+                    root: block_root,
+                    peer_id: peer_id.to_string(),
+                    topic: GossipKind::BeaconBlock.to_string(),
+                    error: e.to_string(),
+                    seen_timestamp_ms: seen_duration.as_millis() as u64,
+                },
+                block.as_ref(),
             );
         }
     }
@@ -1440,7 +1526,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         let change = match self
             .chain
-            .verify_bls_to_execution_change_for_gossip(bls_to_execution_change)
+            .verify_bls_to_execution_change_for_gossip(bls_to_execution_change.clone())
         {
             Ok(ObservationOutcome::New(change)) => change,
             Ok(ObservationOutcome::AlreadyKnown) => {
@@ -1480,6 +1566,21 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         PeerAction::HighToleranceError,
                         "invalid_bls_to_execution_change",
                     );
+                    self.maybe_store_invalid_object(
+                        &self.invalid_block_storage,
+                        InvalidObjectMetadata {
+                            kind: InvalidObjectKind::BlsToExecutionChange,
+                            slot: None,
+                            root: bls_to_execution_change.tree_hash_root(),
+                            peer_id: peer_id.to_string(),
+                            topic: GossipKind::BlsToExecutionChange.to_string(),
+                            error: format!("{:?}", e),
+                            seen_timestamp_ms: SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .map_or(0, |now| now.as_millis() as u64),
+                        },
+                        &bls_to_execution_change,
+                    );
                 }
                 return;
             }
@@ -1901,6 +2002,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -1915,6 +2017,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -1936,6 +2039,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -1957,6 +2061,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2037,6 +2142,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "type" => ?attestation_type,
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2152,6 +2258,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2166,6 +2273,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2179,6 +2287,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2192,6 +2301,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2205,6 +2315,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2222,6 +2333,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "received" => ?received,
                 );
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2235,6 +2347,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2248,6 +2361,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2261,6 +2375,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * The peer has published an invalid consensus message.
                  */
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2285,6 +2400,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 // In this case we wish to penalize gossipsub peers that do this to avoid future
                 // attestations that have too many skip slots.
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.maybe_store_invalid_attestation(peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::MidToleranceError,
@@ -2768,21 +2884,43 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         self.propagate_if_timely(is_timely, message_id, peer_id)
     }
 
-    /// Stores a block as a SSZ file, if and where `invalid_block_storage` dictates.
-    fn maybe_store_invalid_block(
+    /// Stores an invalid gossip object as a SSZ file alongside a JSON file containing its
+    /// metadata, if and where `invalid_block_storage` dictates.
+    fn maybe_store_invalid_object<O: Encode>(
         &self,
         invalid_block_storage: &InvalidBlockStorage,
-        block_root: Hash256,
-        block: &SignedBeaconBlock<T::EthSpec>,
-        error: &BlockError<T::EthSpec>,
-        log: &Logger,
+        metadata: InvalidObjectMetadata,
+        object: &O,
     ) {
         if let InvalidBlockStorage::Enabled(base_dir) = invalid_block_storage {
-            let block_path = base_dir.join(format!("{}_{:?}.ssz", block.slot(), block_root));
-            let error_path = base_dir.join(format!("{}_{:?}.error", block.slot(), block_root));
+            let dir = metadata.dir(base_dir);
+            if let Err(e) = fs::create_dir_all(&dir) {
+                error!(
+                    self.log,
+                    "Failed to create invalid object directory";
+                    "error" => ?e,
+                    "path" => ?dir,
+                );
+                return;
+            }
+
+            let ssz_path = metadata.ssz_path(base_dir);
+            let metadata_path = metadata.metadata_path(base_dir);
+            let metadata_bytes = match serde_json::to_vec_pretty(&metadata) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Failed to serialize invalid object metadata";
+                        "error" => ?e,
+                        "root" => ?metadata.root,
+                    );
+                    return;
+                }
+            };
 
             let write_file = |path: PathBuf, bytes: &[u8]| {
-                // No need to write the same file twice. For the error file,
+                // No need to write the same file twice. For the metadata file,
                 // this means that we'll remember the first error message but
                 // forget the rest.
                 if path.exists() {
@@ -2803,26 +2941,76 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     });
                 if let Err(e) = write_result {
                     error!(
-                        log,
-                        "Failed to store invalid block/error";
+                        self.log,
+                        "Failed to store invalid object/metadata";
                         "error" => e,
+                        "kind" => metadata.kind.as_ref(),
                         "path" => ?path,
-                        "root" => ?block_root,
-                        "slot" => block.slot(),
+                        "root" => ?metadata.root,
+                        "slot" => ?metadata.slot,
                     )
                 } else {
                     info!(
-                        log,
-                        "Stored invalid block/error ";
+                        self.log,
+                        "Stored invalid object/metadata";
+                        "kind" => metadata.kind.as_ref(),
                         "path" => ?path,
-                        "root" => ?block_root,
-                        "slot" => block.slot(),
+                        "root" => ?metadata.root,
+                        "slot" => ?metadata.slot,
                     )
                 }
             };
 
-            write_file(block_path, &block.as_ssz_bytes());
-            write_file(error_path, error.to_string().as_bytes());
+            write_file(ssz_path, &object.as_ssz_bytes());
+            write_file(metadata_path, &metadata_bytes);
+        }
+    }
+
+    /// Stores an `Attestation` or `SignedAggregateAndProof` which was rejected by gossip
+    /// verification, if and where `self.invalid_block_storage` dictates.
+    fn maybe_store_invalid_attestation(
+        &self,
+        peer_id: PeerId,
+        failed_att: &FailedAtt<T::EthSpec>,
+        error: &AttnError,
+    ) {
+        let slot = Some(failed_att.attestation().data.slot);
+        let error = format!("{:?}", error);
+        match failed_att {
+            FailedAtt::Unaggregate {
+                attestation,
+                subnet_id,
+                seen_timestamp,
+                ..
+            } => self.maybe_store_invalid_object(
+                &self.invalid_block_storage,
+                InvalidObjectMetadata {
+                    kind: InvalidObjectKind::Attestation,
+                    slot,
+                    root: attestation.tree_hash_root(),
+                    peer_id: peer_id.to_string(),
+                    topic: GossipKind::Attestation(*subnet_id).to_string(),
+                    error,
+                    seen_timestamp_ms: seen_timestamp.as_millis() as u64,
+                },
+                attestation.as_ref(),
+            ),
+            FailedAtt::Aggregate {
+                attestation,
+                seen_timestamp,
+            } => self.maybe_store_invalid_object(
+                &self.invalid_block_storage,
+                InvalidObjectMetadata {
+                    kind: InvalidObjectKind::AggregateAndProof,
+                    slot,
+                    root: attestation.tree_hash_root(),
+                    peer_id: peer_id.to_string(),
+                    topic: GossipKind::BeaconAggregateAndProof.to_string(),
+                    error,
+                    seen_timestamp_ms: seen_timestamp.as_millis() as u64,
+                },
+                attestation.as_ref(),
+            ),
         }
     }
 }
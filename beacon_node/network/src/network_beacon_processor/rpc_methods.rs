@@ -5,18 +5,21 @@ use crate::sync::SyncMessage;
 use beacon_chain::{BeaconChainError, BeaconChainTypes, HistoricalBlockError, WhenSlotSkipped};
 use beacon_processor::SendOnDrop;
 use itertools::process_results;
-use lighthouse_network::rpc::methods::{BlobsByRangeRequest, BlobsByRootRequest};
+use lighthouse_network::rpc::methods::{
+    BlobsByRangeRequest, BlobsByRootRequest, FinalizedStateChunk, FinalizedStateRequest,
+};
 use lighthouse_network::rpc::StatusMessage;
 use lighthouse_network::rpc::*;
 use lighthouse_network::{PeerId, PeerRequestId, ReportSource, Response, SyncInfo};
 use slog::{debug, error, warn};
 use slot_clock::SlotClock;
+use ssz::Encode;
 use std::collections::{hash_map::Entry, HashMap};
 use std::sync::Arc;
 use task_executor::TaskExecutor;
 use tokio_stream::StreamExt;
 use types::blob_sidecar::BlobIdentifier;
-use types::{Epoch, EthSpec, ForkName, Hash256, Slot};
+use types::{Epoch, EthSpec, ForkContext, ForkName, Hash256, Slot};
 
 impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     /* Auxiliary functions */
@@ -330,6 +333,124 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         };
     }
 
+    /// Handle a `FinalizedState` request from the peer.
+    ///
+    /// The state is only served if the requested block is finalized. It is sent as a stream of
+    /// chunks which each fit in a single RPC message.
+    pub fn handle_finalized_state_request(
+        self: &Arc<Self>,
+        peer_id: PeerId,
+        request_id: PeerRequestId,
+        request: FinalizedStateRequest,
+    ) {
+        let block_root = request.block_root;
+        let is_trusted = self
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&peer_id)
+            .map_or(false, |info| info.is_trusted());
+        if !is_trusted {
+            debug!(self.log, "Refusing finalized state request from untrusted peer";
+                "peer" => %peer_id,
+            );
+            return self.send_error_response(
+                peer_id,
+                RPCResponseErrorCode::ResourceUnavailable,
+                "Finalized state is only served to trusted peers".into(),
+                request_id,
+            );
+        }
+
+        match self.finalized_state_chunks(block_root) {
+            Ok(Some(chunks)) => {
+                debug!(self.log, "Sending finalized state";
+                    "block_root" => ?block_root,
+                    "peer" => %peer_id,
+                    "chunks" => chunks.len()
+                );
+                for chunk in chunks {
+                    self.send_response(
+                        peer_id,
+                        Response::FinalizedState(Some(Arc::new(chunk))),
+                        request_id,
+                    );
+                }
+                self.send_response(peer_id, Response::FinalizedState(None), request_id);
+            }
+            Ok(None) => self.send_error_response(
+                peer_id,
+                RPCResponseErrorCode::ResourceUnavailable,
+                "Finalized state not available".into(),
+                request_id,
+            ),
+            Err(e) => {
+                self.send_error_response(
+                    peer_id,
+                    RPCResponseErrorCode::ServerError,
+                    "Failed loading finalized state".into(),
+                    request_id,
+                );
+                error!(self.log, "Error serving finalized state";
+                    "block_root" => ?block_root,
+                    "peer" => %peer_id,
+                    "error" => e
+                )
+            }
+        }
+    }
+
+    /// Loads the post-state of the finalized block `block_root` and splits it into chunks.
+    ///
+    /// Returns `Ok(None)` if the block is unknown or not finalized.
+    fn finalized_state_chunks(
+        &self,
+        block_root: Hash256,
+    ) -> Result<Option<Vec<FinalizedStateChunk>>, String> {
+        let Some(block) = self
+            .chain
+            .get_blinded_block(&block_root)
+            .map_err(|e| format!("Error loading block: {e:?}"))?
+        else {
+            return Ok(None);
+        };
+
+        let finalized_slot = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+        let canonical_root = self
+            .chain
+            .block_root_at_slot(block.slot(), WhenSlotSkipped::None)
+            .map_err(|e| format!("Error loading block root: {e:?}"))?;
+        if block.slot() > finalized_slot || canonical_root != Some(block_root) {
+            return Ok(None);
+        }
+
+        let Some(state) = self
+            .chain
+            .get_state(&block.state_root(), Some(block.slot()))
+            .map_err(|e| format!("Error loading state: {e:?}"))?
+        else {
+            return Ok(None);
+        };
+
+        let current_slot = self.chain.slot().unwrap_or(block.slot());
+        let fork_context = ForkContext::new::<T::EthSpec>(
+            current_slot,
+            self.chain.genesis_validators_root,
+            &self.chain.spec,
+        );
+        let max_rpc_size = lighthouse_network::rpc::max_rpc_size(
+            &fork_context,
+            self.chain.spec.max_chunk_size as usize,
+        );
+        FinalizedStateChunk::split(&state.as_ssz_bytes(), max_rpc_size).map(Some)
+    }
+
     /// Handle a `BlocksByRange` request from the peer.
     pub fn handle_blocks_by_range_request(
         self: Arc<Self>,
@@ -126,6 +126,15 @@ impl TestRig {
             .make_block(head.beacon_state.clone(), harness.chain.slot().unwrap())
             .await;
 
+        // Empty the mock EL's blob pool so that blobs are only received through the network.
+        harness
+            .mock_execution_layer
+            .as_ref()
+            .unwrap()
+            .server
+            .execution_block_generator()
+            .clear_blob_pool();
+
         let head_state_root = head.beacon_state_root();
         let attestations = harness
             .get_unaggregated_attestations(
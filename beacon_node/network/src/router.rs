@@ -220,6 +220,10 @@ impl<T: BeaconChainTypes> Router<T> {
                 self.network_beacon_processor
                     .send_lightclient_bootstrap_request(peer_id, request_id, request),
             ),
+            Request::FinalizedState(request) => self.handle_beacon_processor_send_result(
+                self.network_beacon_processor
+                    .send_finalized_state_request(peer_id, request_id, request),
+            ),
         }
     }
 
@@ -250,7 +254,7 @@ impl<T: BeaconChainTypes> Router<T> {
             Response::BlobsByRoot(blob) => {
                 self.on_blobs_by_root_response(peer_id, request_id, blob);
             }
-            Response::LightClientBootstrap(_) => unreachable!(),
+            Response::LightClientBootstrap(_) | Response::FinalizedState(_) => unreachable!(),
         }
     }
 
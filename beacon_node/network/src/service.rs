@@ -19,7 +19,10 @@ use lighthouse_network::service::Network;
 use lighthouse_network::types::GossipKind;
 use lighthouse_network::{prometheus_client::registry::Registry, MessageAcceptance};
 use lighthouse_network::{
-    rpc::{GoodbyeReason, RPCResponseErrorCode},
+    rpc::{
+        config::{InboundRateLimiterUpdate, RateLimiterConfig},
+        GoodbyeReason, RPCResponseErrorCode,
+    },
     Context, PeerAction, PeerRequestId, PubsubMessage, ReportSource, Request, Response, Subnet,
 };
 use lighthouse_network::{
@@ -31,7 +34,7 @@ use std::{collections::HashSet, pin::Pin, sync::Arc, time::Duration};
 use store::HotColdDB;
 use strum::IntoStaticStr;
 use task_executor::ShutdownReason;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio::time::Sleep;
 use types::{
     ChainSpec, EthSpec, ForkContext, Slot, SubnetId, SyncCommitteeSubscription, SyncSubnetId,
@@ -112,6 +115,12 @@ pub enum NetworkMessage<T: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Updates the quotas of the RPC rate limiters, sending back whether the update was applied.
+    UpdateRateLimits {
+        inbound: Option<InboundRateLimiterUpdate>,
+        outbound: Option<RateLimiterConfig>,
+        result_tx: oneshot::Sender<Result<(), String>>,
+    },
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -693,6 +702,23 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::UpdateRateLimits {
+                inbound,
+                outbound,
+                result_tx,
+            } => {
+                let rpc = self.libp2p.eth2_rpc_mut();
+                let result = inbound
+                    .map_or(Ok(()), |update| rpc.update_inbound_rate_limits(update))
+                    .and_then(|()| {
+                        outbound.map_or(Ok(()), |quotas| rpc.update_outbound_rate_limits(quotas))
+                    });
+                if let Err(e) = result {
+                    warn!(self.log, "Failed to update rate limits"; "error" => e);
+                }
+                // The HTTP API may have timed out, in which case there is no one to notify.
+                let _ = result_tx.send(result.map_err(str::to_string));
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
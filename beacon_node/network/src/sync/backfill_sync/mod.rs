@@ -16,7 +16,7 @@ use crate::sync::range_sync::{
 };
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use lighthouse_network::types::{BackFillState, NetworkGlobals};
+use lighthouse_network::types::{BackFillReport, BackFillState, NetworkGlobals, SyncProgress};
 use lighthouse_network::{PeerAction, PeerId};
 use rand::seq::SliceRandom;
 use slog::{crit, debug, error, info, warn};
@@ -293,6 +293,54 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         })
     }
 
+    /// Returns a summary of the backfill sync for the sync status report.
+    ///
+    /// The download and processing rates of the returned `progress` are left empty, they are
+    /// computed by the `SyncManager` which samples the progress over time.
+    pub fn report(&self) -> BackFillReport {
+        let slots_in_state = |predicate: fn(&BatchState<T::EthSpec>) -> bool| {
+            self.batches
+                .values()
+                .filter(|batch| predicate(batch.state()))
+                .map(|batch| batch.slots())
+                .sum::<u64>()
+        };
+        let processed_slots =
+            self.validated_batches * BACKFILL_EPOCHS_PER_BATCH * T::EthSpec::slots_per_epoch()
+                + slots_in_state(|state| matches!(state, BatchState::AwaitingValidation(_)));
+        let downloaded_slots = processed_slots
+            + slots_in_state(|state| {
+                matches!(
+                    state,
+                    BatchState::AwaitingProcessing(..) | BatchState::Processing(_)
+                )
+            });
+
+        BackFillReport {
+            state: self.state(),
+            current_start: self.current_start,
+            processing_target: self.processing_target,
+            to_be_downloaded: self.to_be_downloaded,
+            validated_batches: self.validated_batches,
+            participating_peers: self
+                .participating_peers
+                .iter()
+                .map(|peer_id| peer_id.to_string())
+                .collect(),
+            batches: self.batches.values().map(|batch| batch.report()).collect(),
+            progress: SyncProgress {
+                downloaded_slots,
+                processed_slots,
+                remaining_slots: self
+                    .current_start
+                    .start_slot(T::EthSpec::slots_per_epoch())
+                    .saturating_sub(self.beacon_chain.genesis_backfill_slot)
+                    .as_u64(),
+                ..SyncProgress::default()
+            },
+        }
+    }
+
     /// A fully synced peer has joined us.
     /// If we are in a failed state, update a local variable to indicate we are able to restart
     /// the failed sync on the next attempt.
@@ -1183,3 +1231,75 @@ enum ResetEpochError {
     /// Backfill is not required.
     NotRequired,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+    use slog::{o, Discard};
+    use types::MinimalEthSpec as E;
+
+    #[test]
+    fn report_counts_batches_by_state() {
+        let log = slog::Logger::root(Discard, o!());
+        let harness = BeaconChainHarness::<EphemeralHarnessType<E>>::builder(E)
+            .default_spec()
+            .logger(log.clone())
+            .deterministic_keypairs(1)
+            .fresh_ephemeral_store()
+            .build();
+        let globals = Arc::new(NetworkGlobals::new_test_globals(Vec::new(), &log));
+        let mut backfill = BackFillSync::new(harness.chain.clone(), globals, log);
+        let peer = PeerId::random();
+        let slots_per_epoch = E::slots_per_epoch();
+
+        // Backfill has validated two batches and is processing epoch 8.
+        backfill.current_start = Epoch::new(10);
+        backfill.processing_target = Epoch::new(8);
+        backfill.to_be_downloaded = Epoch::new(5);
+        backfill.validated_batches = 2;
+        backfill.participating_peers.insert(peer);
+        for (epoch, state) in [
+            (9, "awaiting_validation"),
+            (8, "processing"),
+            (7, "awaiting_processing"),
+            (6, "downloading"),
+            (5, "failed_download"),
+        ] {
+            backfill.batches.insert(
+                Epoch::new(epoch),
+                BatchInfo::new_in_state(&Epoch::new(epoch), BACKFILL_EPOCHS_PER_BATCH, state, peer),
+            );
+        }
+
+        let report = backfill.report();
+        assert_eq!(report.state, BackFillState::NotRequired);
+        assert_eq!(report.current_start, Epoch::new(10));
+        assert_eq!(report.participating_peers, vec![peer.to_string()]);
+
+        // Batches are reported in ascending epoch order.
+        let states = report
+            .batches
+            .iter()
+            .map(|batch| batch.state.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                "awaiting_download",
+                "downloading",
+                "awaiting_processing",
+                "processing",
+                "awaiting_validation",
+            ]
+        );
+        assert_eq!(report.batches[0].failed_download_attempts, 1);
+
+        assert_eq!(report.progress.processed_slots, 3 * slots_per_epoch);
+        assert_eq!(report.progress.downloaded_slots, 5 * slots_per_epoch);
+        assert_eq!(
+            report.progress.remaining_slots,
+            10 * slots_per_epoch - harness.chain.genesis_backfill_slot.as_u64()
+        );
+    }
+}
@@ -22,6 +22,7 @@ pub use common::Parent;
 pub use common::RequestState;
 use fnv::FnvHashMap;
 use lighthouse_network::rpc::RPCError;
+use lighthouse_network::types::LookupsReport;
 use lighthouse_network::{PeerAction, PeerId};
 use lru_cache::LRUTimeCache;
 pub use single_block_lookup::{BlobRequestState, BlockRequestState};
@@ -76,6 +77,23 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         }
     }
 
+    /// Returns a summary of all lookups in flight for the sync status report.
+    pub fn report(&self) -> LookupsReport {
+        LookupsReport {
+            single_block_lookups: self
+                .single_block_lookups
+                .values()
+                .map(|lookup| lookup.report())
+                .collect(),
+            parent_lookups: self
+                .parent_lookups
+                .iter()
+                .map(|lookup| lookup.report())
+                .collect(),
+            processing_parent_lookups: self.processing_parent_lookups.len(),
+        }
+    }
+
     /* Lookup requests */
 
     /// Creates a lookup for the block with the given `block_root` and immediately triggers it.
@@ -8,6 +8,7 @@ use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::data_availability_checker::{ChildComponents, DataAvailabilityChecker};
 use beacon_chain::BeaconChainTypes;
 use itertools::Itertools;
+use lighthouse_network::types::ParentLookupReport;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use store::Hash256;
@@ -78,6 +79,20 @@ impl<T: BeaconChainTypes> ParentLookup<T> {
         }
     }
 
+    /// Returns a summary of this lookup for the sync status report.
+    pub fn report(&self) -> ParentLookupReport {
+        ParentLookupReport {
+            chain_hash: self.chain_hash,
+            current_block_root: self.current_parent_request.block_root(),
+            downloaded_blocks: self.downloaded_blocks.len(),
+            failed_attempts: self
+                .current_parent_request
+                .block_request_state
+                .state
+                .failed_attempts(),
+        }
+    }
+
     pub fn contains_block(&self, block_root: &Hash256) -> bool {
         self.downloaded_blocks
             .iter()
@@ -8,6 +8,7 @@ use beacon_chain::data_availability_checker::{
 };
 use beacon_chain::data_availability_checker::{AvailabilityView, ChildComponents};
 use beacon_chain::BeaconChainTypes;
+use lighthouse_network::types::SingleLookupReport;
 use lighthouse_network::PeerAction;
 use slog::{trace, Logger};
 use std::collections::HashSet;
@@ -26,6 +27,17 @@ pub enum State {
     Processing { peer_id: PeerId },
 }
 
+impl State {
+    /// A short, human readable name for the state, used in sync status reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            State::AwaitingDownload => "awaiting_download",
+            State::Downloading { .. } => "downloading",
+            State::Processing { .. } => "processing",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, IntoStaticStr)]
 pub enum LookupVerifyError {
     RootMismatch,
@@ -106,6 +118,17 @@ impl<L: Lookup, T: BeaconChainTypes> SingleBlockLookup<L, T> {
         all_peers
     }
 
+    /// Returns a summary of this lookup for the sync status report.
+    pub fn report(&self) -> SingleLookupReport {
+        SingleLookupReport {
+            block_root: self.block_root(),
+            block_state: self.block_request_state.state.state.name().to_string(),
+            blob_state: self.blob_request_state.state.state.name().to_string(),
+            peers: self.all_peers().len(),
+            failed_attempts: self.block_request_state.state.failed_attempts(),
+        }
+    }
+
     /// Send the necessary requests for blocks and/or blobs. This will check whether we have
     /// downloaded the block and/or blobs already and will not send requests if so. It will also
     /// inspect the request state or blocks and blobs to ensure we are not already processing or
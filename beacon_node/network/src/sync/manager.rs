@@ -37,6 +37,7 @@ use super::backfill_sync::{BackFillSync, ProcessResult, SyncStart};
 use super::block_lookups::BlockLookups;
 use super::network_context::{BlockOrBlob, SyncNetworkContext};
 use super::peer_sync_info::{remote_sync_type, PeerSyncType};
+use super::progress::{SyncProcessId, SyncProgressTracker};
 use super::range_sync::{RangeSync, RangeSyncType, EPOCHS_PER_BATCH};
 use crate::network_beacon_processor::{ChainSegmentProcessId, NetworkBeaconProcessor};
 use crate::service::NetworkMessage;
@@ -53,7 +54,7 @@ use beacon_chain::{
 };
 use futures::StreamExt;
 use lighthouse_network::rpc::RPCError;
-use lighthouse_network::types::{NetworkGlobals, SyncState};
+use lighthouse_network::types::{NetworkGlobals, SyncState, SyncStatusReport};
 use lighthouse_network::SyncInfo;
 use lighthouse_network::{PeerAction, PeerId};
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -61,7 +62,7 @@ use std::boxed::Box;
 use std::ops::IndexMut;
 use std::ops::Sub;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::{BlobSidecar, EthSpec, Hash256, SignedBeaconBlock, Slot};
@@ -75,6 +76,9 @@ use types::{BlobSidecar, EthSpec, Hash256, SignedBeaconBlock, Slot};
 /// blocks for.
 pub const SLOT_IMPORT_TOLERANCE: usize = 32;
 
+/// How often the detailed sync status report in the `NetworkGlobals` is refreshed.
+const SYNC_STATUS_REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
 pub type Id = u32;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
@@ -213,6 +217,9 @@ pub struct SyncManager<T: BeaconChainTypes> {
 
     block_lookups: BlockLookups<T>,
 
+    /// Measures the rate of progress of range and backfill syncs for the sync status report.
+    progress_tracker: SyncProgressTracker,
+
     /// The logger for the import manager.
     log: Logger,
 }
@@ -250,6 +257,7 @@ pub fn spawn<T: BeaconChainTypes>(
             beacon_chain.data_availability_checker.clone(),
             log.clone(),
         ),
+        progress_tracker: SyncProgressTracker::default(),
         log: log.clone(),
     };
 
@@ -585,6 +593,8 @@ impl<T: BeaconChainTypes> SyncManager<T> {
             futures::stream::iter(ee_responsiveness_watch.await).flatten()
         };
 
+        let mut status_report_interval = tokio::time::interval(SYNC_STATUS_REPORT_INTERVAL);
+
         // process any inbound messages
         loop {
             tokio::select! {
@@ -594,10 +604,38 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 Some(engine_state) = check_ee_stream.next(), if check_ee => {
                     self.handle_new_execution_engine_state(engine_state);
                 }
+                _ = status_report_interval.tick() => {
+                    self.update_sync_status_report();
+                }
             }
         }
     }
 
+    /// Refreshes the detailed sync status report served by the HTTP API.
+    fn update_sync_status_report(&mut self) {
+        let now = Instant::now();
+        let mut range_chains = self.range_sync.report();
+        let mut backfill = self.backfill_sync.report();
+
+        let mut active = Vec::with_capacity(range_chains.len() + 1);
+        for chain in range_chains.iter_mut() {
+            let id = SyncProcessId::RangeChain(chain.id);
+            self.progress_tracker.observe(id, &mut chain.progress, now);
+            active.push(id);
+        }
+        self.progress_tracker
+            .observe(SyncProcessId::BackFill, &mut backfill.progress, now);
+        active.push(SyncProcessId::BackFill);
+        self.progress_tracker.retain(&active);
+
+        *self.network_globals().sync_status_report.write() = SyncStatusReport {
+            state: self.network_globals().sync_state(),
+            range_chains,
+            backfill,
+            lookups: self.block_lookups.report(),
+        };
+    }
+
     fn handle_message(&mut self, sync_message: SyncMessage<T::EthSpec>) {
         match sync_message {
             SyncMessage::AddPeer(peer_id, info) => {
@@ -7,6 +7,7 @@ mod block_sidecar_coupling;
 pub mod manager;
 mod network_context;
 mod peer_sync_info;
+mod progress;
 mod range_sync;
 
 pub use manager::{BatchProcessResult, SyncMessage};
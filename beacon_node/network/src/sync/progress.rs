@@ -0,0 +1,169 @@
+//! Tracks the rate at which range and backfill syncs progress, in order to provide download and
+//! processing rates as well as an estimated time to completion in the sync status report.
+use super::range_sync::ChainId;
+use lighthouse_network::types::SyncProgress;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// The weight given to the most recent sample when updating a rate.
+const RATE_SMOOTHING_FACTOR: f64 = 0.3;
+
+/// Identifies a sync process whose progress is being tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncProcessId {
+    RangeChain(ChainId),
+    BackFill,
+}
+
+/// The last observed progress of a sync process, along with the smoothed rates.
+struct ProgressSample {
+    time: Instant,
+    downloaded_slots: u64,
+    processed_slots: u64,
+    download_rate: Option<f64>,
+    processing_rate: Option<f64>,
+}
+
+/// Computes exponentially weighted download and processing rates for sync processes.
+#[derive(Default)]
+pub struct SyncProgressTracker {
+    samples: HashMap<SyncProcessId, ProgressSample>,
+}
+
+impl SyncProgressTracker {
+    /// Records the progress of `id` observed at `now` and fills in the rates and ETA of
+    /// `progress`.
+    ///
+    /// If the progress of a process goes backwards (e.g. a chain was restarted) its rates are
+    /// reset.
+    pub fn observe(&mut self, id: SyncProcessId, progress: &mut SyncProgress, now: Instant) {
+        let sample = match self.samples.get(&id) {
+            Some(previous)
+                if progress.downloaded_slots >= previous.downloaded_slots
+                    && progress.processed_slots >= previous.processed_slots =>
+            {
+                let elapsed = now.saturating_duration_since(previous.time).as_secs_f64();
+                if elapsed > 0.0 {
+                    let rate = |current: u64, previous_value: u64, previous_rate: Option<f64>| {
+                        let instant = (current - previous_value) as f64 / elapsed;
+                        Some(match previous_rate {
+                            Some(rate) => {
+                                RATE_SMOOTHING_FACTOR * instant
+                                    + (1.0 - RATE_SMOOTHING_FACTOR) * rate
+                            }
+                            None => instant,
+                        })
+                    };
+                    ProgressSample {
+                        time: now,
+                        downloaded_slots: progress.downloaded_slots,
+                        processed_slots: progress.processed_slots,
+                        download_rate: rate(
+                            progress.downloaded_slots,
+                            previous.downloaded_slots,
+                            previous.download_rate,
+                        ),
+                        processing_rate: rate(
+                            progress.processed_slots,
+                            previous.processed_slots,
+                            previous.processing_rate,
+                        ),
+                    }
+                } else {
+                    return Self::fill(progress, previous);
+                }
+            }
+            _ => ProgressSample {
+                time: now,
+                downloaded_slots: progress.downloaded_slots,
+                processed_slots: progress.processed_slots,
+                download_rate: None,
+                processing_rate: None,
+            },
+        };
+
+        Self::fill(progress, &sample);
+        self.samples.insert(id, sample);
+    }
+
+    /// Forgets all processes which are not in `active`.
+    pub fn retain(&mut self, active: &[SyncProcessId]) {
+        self.samples.retain(|id, _| active.contains(id));
+    }
+
+    fn fill(progress: &mut SyncProgress, sample: &ProgressSample) {
+        progress.download_rate = sample.download_rate;
+        progress.processing_rate = sample.processing_rate;
+        progress.eta_seconds = sample
+            .processing_rate
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| (progress.remaining_slots as f64 / rate).ceil() as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn progress(downloaded_slots: u64, processed_slots: u64, remaining_slots: u64) -> SyncProgress {
+        SyncProgress {
+            downloaded_slots,
+            processed_slots,
+            remaining_slots,
+            ..SyncProgress::default()
+        }
+    }
+
+    #[test]
+    fn rates_and_eta() {
+        let mut tracker = SyncProgressTracker::default();
+        let start = Instant::now();
+
+        let mut first = progress(0, 0, 1000);
+        tracker.observe(SyncProcessId::BackFill, &mut first, start);
+        assert_eq!(first.processing_rate, None);
+        assert_eq!(first.eta_seconds, None);
+
+        let mut second = progress(64, 32, 968);
+        tracker.observe(
+            SyncProcessId::BackFill,
+            &mut second,
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(second.download_rate, Some(32.0));
+        assert_eq!(second.processing_rate, Some(16.0));
+        assert_eq!(second.eta_seconds, Some(61));
+
+        // A second sample is smoothed with the first.
+        let mut third = progress(64, 64, 936);
+        tracker.observe(
+            SyncProcessId::BackFill,
+            &mut third,
+            start + Duration::from_secs(4),
+        );
+        assert_eq!(third.download_rate, Some(0.7 * 32.0));
+        assert_eq!(third.processing_rate, Some(16.0));
+    }
+
+    #[test]
+    fn reset_on_regression() {
+        let mut tracker = SyncProgressTracker::default();
+        let start = Instant::now();
+        let id = SyncProcessId::RangeChain(1);
+
+        tracker.observe(id, &mut progress(0, 0, 100), start);
+        tracker.observe(
+            id,
+            &mut progress(32, 32, 68),
+            start + Duration::from_secs(1),
+        );
+
+        let mut restarted = progress(0, 0, 100);
+        tracker.observe(id, &mut restarted, start + Duration::from_secs(2));
+        assert_eq!(restarted.processing_rate, None);
+
+        tracker.retain(&[]);
+        assert!(tracker.samples.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::sync::manager::Id;
 use beacon_chain::block_verification_types::{AsBlock, RpcBlock};
 use lighthouse_network::rpc::methods::BlocksByRangeRequest;
+use lighthouse_network::types::BatchReport;
 use lighthouse_network::PeerId;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
@@ -139,6 +140,19 @@ impl<T: EthSpec> BatchState<T> {
     pub fn poison(&mut self) -> BatchState<T> {
         std::mem::replace(self, BatchState::Poisoned)
     }
+
+    /// A short, human readable name for the state, used in sync status reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BatchState::AwaitingDownload => "awaiting_download",
+            BatchState::Downloading(..) => "downloading",
+            BatchState::AwaitingProcessing(..) => "awaiting_processing",
+            BatchState::Processing(_) => "processing",
+            BatchState::AwaitingValidation(_) => "awaiting_validation",
+            BatchState::Poisoned => "poisoned",
+            BatchState::Failed => "failed",
+        }
+    }
 }
 
 impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
@@ -250,6 +264,34 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         &self.failed_processing_attempts
     }
 
+    /// The number of slots covered by this batch.
+    pub fn slots(&self) -> u64 {
+        self.end_slot.saturating_sub(self.start_slot).as_u64()
+    }
+
+    /// Returns a summary of this batch for the sync status report.
+    pub fn report(&self) -> BatchReport {
+        let blocks = match &self.state {
+            BatchState::Downloading(_, blocks, _) | BatchState::AwaitingProcessing(_, blocks) => {
+                blocks.len()
+            }
+            _ => 0,
+        };
+        let (failed_download_attempts, failed_processing_attempts) = self.failed_attempts();
+        BatchReport {
+            start_slot: self.start_slot,
+            end_slot: self.end_slot,
+            state: self.state.name().to_string(),
+            peer: match &self.state {
+                BatchState::Poisoned => None,
+                _ => self.current_peer().map(|peer_id| peer_id.to_string()),
+            },
+            blocks,
+            failed_download_attempts,
+            failed_processing_attempts,
+        }
+    }
+
     /// Adds a block to a downloading batch.
     pub fn add_block(&mut self, block: RpcBlock<T>) -> Result<(), WrongState> {
         match self.state.poison() {
@@ -547,3 +589,42 @@ impl<T: EthSpec> std::fmt::Debug for BatchState<T> {
         }
     }
 }
+
+#[cfg(test)]
+impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
+    /// Creates a batch without blocks and drives it into the state named `state`, as returned by
+    /// `BatchState::name`. The special state `failed_download` is an `awaiting_download` batch
+    /// with one failed download attempt by `peer`.
+    pub fn new_in_state(
+        start_epoch: &Epoch,
+        num_of_epochs: u64,
+        state: &str,
+        peer: PeerId,
+    ) -> Self {
+        let mut batch = Self::new(start_epoch, num_of_epochs, ByRangeRequestType::Blocks);
+        if state == "awaiting_download" {
+            return batch;
+        }
+        assert!(batch.start_downloading_from_peer(peer, 1).is_ok());
+        if state == "downloading" {
+            return batch;
+        }
+        if state == "failed_download" {
+            assert!(batch.download_failed(true).is_ok());
+            return batch;
+        }
+        assert!(batch.download_completed().is_ok());
+        if state == "awaiting_processing" {
+            return batch;
+        }
+        assert!(batch.start_processing().is_ok());
+        if state == "processing" {
+            return batch;
+        }
+        assert!(batch
+            .processing_completed(BatchProcessingResult::Success)
+            .is_ok());
+        assert_eq!(state, batch.state.name(), "unsupported batch state");
+        batch
+    }
+}
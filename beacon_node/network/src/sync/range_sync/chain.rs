@@ -1,4 +1,5 @@
 use super::batch::{BatchInfo, BatchProcessingResult, BatchState};
+use super::RangeSyncType;
 use crate::network_beacon_processor::ChainSegmentProcessId;
 use crate::sync::{
     manager::Id, network_context::SyncNetworkContext, BatchOperationOutcome, BatchProcessResult,
@@ -6,6 +7,7 @@ use crate::sync::{
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::BeaconChainTypes;
 use fnv::FnvHashMap;
+use lighthouse_network::types::{RangeChainReport, SyncProgress};
 use lighthouse_network::{PeerAction, PeerId};
 use rand::seq::SliceRandom;
 use slog::{crit, debug, o, warn};
@@ -171,6 +173,60 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         self.validated_batches * EPOCHS_PER_BATCH
     }
 
+    /// Returns a summary of the state of this chain for the sync status report.
+    ///
+    /// The download and processing rates of the returned `progress` are left empty, they are
+    /// computed by the `SyncManager` which samples the progress over time.
+    pub fn report(&self, sync_type: RangeSyncType) -> RangeChainReport {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let processing_start = self.processing_target.start_slot(slots_per_epoch);
+        let processed_slots = processing_start
+            .saturating_sub(self.start_epoch.start_slot(slots_per_epoch))
+            .as_u64();
+        let downloaded_slots = processed_slots
+            + self
+                .batches
+                .range(self.processing_target..)
+                .filter(|(_, batch)| {
+                    matches!(
+                        batch.state(),
+                        BatchState::AwaitingProcessing(..) | BatchState::Processing(_)
+                    )
+                })
+                .map(|(_, batch)| batch.slots())
+                .sum::<u64>();
+
+        RangeChainReport {
+            id: self.id,
+            sync_type: match sync_type {
+                RangeSyncType::Finalized => "finalized".to_string(),
+                RangeSyncType::Head => "head".to_string(),
+            },
+            is_syncing: self.is_syncing(),
+            start_epoch: self.start_epoch,
+            target_head_slot: self.target_head_slot,
+            target_head_root: self.target_head_root,
+            processing_target: self.processing_target,
+            to_be_downloaded: self.to_be_downloaded,
+            validated_batches: self.validated_batches,
+            peers: self
+                .peers
+                .keys()
+                .map(|peer_id| peer_id.to_string())
+                .collect(),
+            batches: self.batches.values().map(|batch| batch.report()).collect(),
+            progress: SyncProgress {
+                downloaded_slots,
+                processed_slots,
+                remaining_slots: self
+                    .target_head_slot
+                    .saturating_sub(processing_start)
+                    .as_u64(),
+                ..SyncProgress::default()
+            },
+        }
+    }
+
     /// Removes a peer from the chain.
     /// If the peer has active batches, those are considered failed and re-requested.
     pub fn remove_peer(
@@ -1134,3 +1190,99 @@ impl RemoveChain {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::builder::Witness;
+    use beacon_chain::eth1_chain::CachingEth1Backend;
+    use slog::Discard;
+    use slot_clock::TestingSlotClock;
+    use store::MemoryStore;
+    use types::MinimalEthSpec as E;
+
+    type TestBeaconChainType =
+        Witness<TestingSlotClock, CachingEth1Backend<E>, E, MemoryStore<E>, MemoryStore<E>>;
+
+    #[test]
+    fn report_counts_batches_by_state() {
+        let log = slog::Logger::root(Discard, o!());
+        let peer = PeerId::random();
+        let slots_per_epoch = E::slots_per_epoch();
+        let target_head_slot = Slot::new(10 * slots_per_epoch);
+        let mut chain = SyncingChain::<TestBeaconChainType>::new(
+            Epoch::new(0),
+            target_head_slot,
+            Hash256::repeat_byte(1),
+            peer,
+            &log,
+        );
+
+        // Epoch 0 has been processed and awaits validation, the chain is processing epoch 1.
+        for (epoch, state) in [
+            (0, "awaiting_validation"),
+            (1, "processing"),
+            (2, "awaiting_processing"),
+            (3, "downloading"),
+            (4, "failed_download"),
+        ] {
+            chain.batches.insert(
+                Epoch::new(epoch),
+                BatchInfo::new_in_state(&Epoch::new(epoch), EPOCHS_PER_BATCH, state, peer),
+            );
+        }
+        chain.processing_target = Epoch::new(1);
+        chain.to_be_downloaded = Epoch::new(5);
+
+        let report = chain.report(RangeSyncType::Finalized);
+        assert_eq!(report.sync_type, "finalized");
+        assert!(!report.is_syncing);
+        assert_eq!(report.peers, vec![peer.to_string()]);
+        assert_eq!(report.processing_target, Epoch::new(1));
+        assert_eq!(report.to_be_downloaded, Epoch::new(5));
+
+        let states = report
+            .batches
+            .iter()
+            .map(|batch| batch.state.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                "awaiting_validation",
+                "processing",
+                "awaiting_processing",
+                "downloading",
+                "awaiting_download",
+            ]
+        );
+        assert_eq!(report.batches[4].failed_download_attempts, 1);
+        assert_eq!(report.batches[3].peer, Some(peer.to_string()));
+
+        // Only batches from the processing target onwards count as downloaded.
+        assert_eq!(report.progress.processed_slots, slots_per_epoch);
+        assert_eq!(report.progress.downloaded_slots, 3 * slots_per_epoch);
+        assert_eq!(report.progress.remaining_slots, 9 * slots_per_epoch);
+        assert_eq!(report.progress.download_rate, None);
+    }
+
+    #[test]
+    fn report_of_an_empty_chain() {
+        let log = slog::Logger::root(Discard, o!());
+        let slots_per_epoch = E::slots_per_epoch();
+        let chain = SyncingChain::<TestBeaconChainType>::new(
+            Epoch::new(2),
+            Slot::new(4 * slots_per_epoch),
+            Hash256::repeat_byte(1),
+            PeerId::random(),
+            &log,
+        );
+
+        let report = chain.report(RangeSyncType::Head);
+        assert_eq!(report.sync_type, "head");
+        assert!(report.batches.is_empty());
+        assert_eq!(report.progress.processed_slots, 0);
+        assert_eq!(report.progress.downloaded_slots, 0);
+        assert_eq!(report.progress.remaining_slots, 2 * slots_per_epoch);
+    }
+}
@@ -10,6 +10,7 @@ use crate::metrics;
 use crate::sync::network_context::SyncNetworkContext;
 use beacon_chain::BeaconChainTypes;
 use fnv::FnvHashMap;
+use lighthouse_network::types::RangeChainReport;
 use lighthouse_network::PeerId;
 use lighthouse_network::SyncInfo;
 use slog::{crit, debug, error};
@@ -388,6 +389,19 @@ impl<T: BeaconChainTypes, C: BlockStorage> ChainCollection<T, C> {
         };
     }
 
+    /// Returns a report of every finalized and head chain, finalized chains first.
+    pub fn report(&self) -> Vec<RangeChainReport> {
+        self.finalized_chains
+            .values()
+            .map(|chain| chain.report(RangeSyncType::Finalized))
+            .chain(
+                self.head_chains
+                    .values()
+                    .map(|chain| chain.report(RangeSyncType::Head)),
+            )
+            .collect()
+    }
+
     /// Returns if `true` if any finalized chains exist, `false` otherwise.
     pub fn is_finalizing_sync(&self) -> bool {
         !self.finalized_chains.is_empty()
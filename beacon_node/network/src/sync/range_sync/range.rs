@@ -50,6 +50,7 @@ use crate::sync::BatchProcessResult;
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use lighthouse_network::rpc::GoodbyeReason;
+use lighthouse_network::types::RangeChainReport;
 use lighthouse_network::PeerId;
 use lighthouse_network::SyncInfo;
 use lru_cache::LRUTimeCache;
@@ -102,6 +103,11 @@ where
         self.chains.state()
     }
 
+    /// Returns a report of all chains currently known to range sync.
+    pub fn report(&self) -> Vec<RangeChainReport> {
+        self.chains.report()
+    }
+
     /// A useful peer has been added. The SyncManager has identified this peer as needing either
     /// a finalized or head chain sync. This processes the peer and starts/resumes any chain that
     /// may need to be synced as a result. A new peer, may increase the peer pool of a finalized
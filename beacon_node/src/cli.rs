@@ -344,6 +344,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             .takes_value(true)
             .hidden(true)
         )
+        .arg(
+            Arg::with_name("inbound-rate-limiter-trusted-peers")
+            .long("inbound-rate-limiter-trusted-peers")
+            .help(
+                "Configures the quotas of the inbound rate limiter for peers passed to \
+                --trusted-peers, using the same format as --inbound-rate-limiter. Quotas can also \
+                be changed at runtime through the HTTP API."
+            )
+            .takes_value(true)
+            .hidden(true)
+        )
         .arg(
             Arg::with_name("disable-backfill-rate-limiting")
                 .long("disable-backfill-rate-limiting")
@@ -570,6 +581,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Purges the eth1 block and deposit caches")
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("deposit-snapshot")
+                .long("deposit-snapshot")
+                .value_name("SNAPSHOT_SSZ")
+                .help("Import a finalized EIP-4881 deposit tree snapshot on startup, so that \
+                       deposit logs are only downloaded from the snapshot's execution block \
+                       onwards. The snapshot must agree with any deposits already in the \
+                       database, and is ignored if it is not newer than the finalized deposits.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("deposit-snapshot-url")
+                .long("deposit-snapshot-url")
+                .value_name("BEACON_NODE")
+                .help("Download a finalized EIP-4881 deposit tree snapshot from a trusted beacon \
+                       node on startup, and import it as per --deposit-snapshot.")
+                .takes_value(true)
+                .conflicts_with("deposit-snapshot")
+        )
         .arg(
             Arg::with_name("eth1-blocks-per-log-query")
                 .long("eth1-blocks-per-log-query")
@@ -632,7 +662,27 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .alias("execution-endpoints")
                 .help("Server endpoint for an execution layer JWT-authenticated HTTP \
                        JSON-RPC connection. Uses the same endpoint to populate the \
-                       deposit cache.")
+                       deposit cache. IPC endpoints may be supplied as ipc:///path/to/engine.ipc, \
+                       in which case no JWT secret is required. Multiple comma-separated \
+                       endpoints may be supplied, in \
+                       which case payloads are verified by every endpoint and the results are \
+                       combined according to --execution-status-policy. The first endpoint is \
+                       used to populate the deposit cache.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("execution-status-policy")
+                .long("execution-status-policy")
+                .value_name("POLICY")
+                .help("How payload statuses are combined when multiple execution endpoints are \
+                       supplied. \"any-valid\" treats a payload as valid if any endpoint \
+                       considers it valid. \"majority\" requires more than half of the \
+                       responding endpoints to agree. \"all\" requires every endpoint to \
+                       consider a payload valid and treats it as invalid if any endpoint does. \
+                       Payloads without a decisive result are imported optimistically.")
+                .possible_values(&["any-valid", "majority", "all"])
+                .default_value("majority")
+                .requires("execution-endpoint")
                 .takes_value(true)
         )
         .arg(
@@ -641,7 +691,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("EXECUTION-JWT")
                 .alias("jwt-secrets")
                 .help("File path which contains the hex-encoded JWT secret for the \
-                       execution endpoint provided in the --execution-endpoint flag.")
+                       execution endpoint provided in the --execution-endpoint flag. When \
+                       multiple execution endpoints are supplied, either one path shared by all \
+                       endpoints or one comma-separated path per endpoint may be given.")
                 .requires("execution-endpoint")
                 .takes_value(true)
         )
@@ -693,7 +745,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("builder")
                 .alias("payload-builder")
                 .alias("payload-builders")
-                .help("The URL of a service compatible with the MEV-boost API.")
+                .help("The URL of a service compatible with the MEV-boost API. Multiple \
+                       comma-separated URLs may be supplied, in which case bids are requested \
+                       from every builder and the most valuable valid bid is used.")
                 .requires("execution-endpoint")
                 .takes_value(true)
         )
@@ -705,6 +759,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("1")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("execution-traffic-record-dir")
+                .long("execution-traffic-record-dir")
+                .value_name("DIR")
+                .help("If present, every engine API request and response is recorded to a \
+                       rotating set of JSONL files in this directory. A recording can be \
+                       replayed with `lcli mock-el --replay` to reproduce an execution engine \
+                       session offline.")
+                .requires("execution-endpoint")
+                .takes_value(true)
+        )
         /* Deneb settings */
         .arg(
             Arg::with_name("trusted-setup-file-override")
@@ -955,6 +1020,19 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("180")
         )
+        .arg(
+            Arg::with_name("checkpoint-sync-peers")
+                .long("checkpoint-sync-peers")
+                .help("One or more comma-delimited multiaddrs of trusted peers to download the \
+                       finalized state and block from over libp2p. The multiaddrs must include a \
+                       /p2p/ peer ID component. The downloaded state is verified against \
+                       --wss-checkpoint, which is required.")
+                .value_name("MULTIADDRS")
+                .takes_value(true)
+                .requires("wss-checkpoint")
+                .conflicts_with("checkpoint-state")
+                .conflicts_with("checkpoint-sync-url")
+        )
         .arg(
             Arg::with_name("allow-insecure-genesis-sync")
                 .long("allow-insecure-genesis-sync")
@@ -962,6 +1040,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     Checkpoint syncing is the preferred method for syncing a node. \
                     Only use this flag when testing. DO NOT use on mainnet!")
                 .conflicts_with("checkpoint-sync-url")
+                .conflicts_with("checkpoint-sync-peers")
                 .conflicts_with("checkpoint-state")
                 .takes_value(false)
         )
@@ -1153,6 +1232,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("builder")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("builder-header-timeout")
+                .long("builder-header-timeout")
+                .value_name("MILLISECONDS")
+                .help("The timeout in milliseconds for each builder to respond to a request for \
+                       a bid. Builders which do not respond in time are ignored for that \
+                       proposal. The default is 1000ms.")
+                .requires("builder")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("reset-payload-statuses")
                 .long("reset-payload-statuses")
@@ -1183,6 +1272,13 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        [experimental]")
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("serve-finalized-state")
+                .long("serve-finalized-state")
+                .help("Serve the finalized state to trusted peers over the p2p network so that \
+                       they can checkpoint sync from this node using --checkpoint-sync-peers.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("gui")
                 .long("gui")
@@ -1199,8 +1295,12 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("invalid-gossip-verified-blocks-path")
             .long("invalid-gossip-verified-blocks-path")
             .value_name("PATH")
-            .help("If a block succeeds gossip validation whilst failing full validation, store \
-                    the block SSZ as a file at this path. This feature is only recommended for \
+            .help("Store some invalid gossip objects as SSZ alongside a JSON file describing \
+                    the rejection (peer, topic, error and time) in a subdirectory of this path. \
+                    Blocks are only stored if they are rejected by gossip verification, or fail \
+                    to import after passing it. Blobs, attestations and BLS to execution changes are only stored if they are \
+                    rejected. Ignored objects are never stored. Stored objects can be replayed \
+                    with `lcli replay-invalid`. This feature is only recommended for \
                     developers. This directory is not pruned, users should be careful to avoid \
                     filling up their disks.")
         )
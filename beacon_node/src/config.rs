@@ -6,7 +6,7 @@ use beacon_chain::TrustedSetup;
 use clap::ArgMatches;
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
 use clap_utils::parse_required;
-use client::{ClientConfig, ClientGenesis};
+use client::{ClientConfig, ClientGenesis, DepositSnapshotSource};
 use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
 use environment::RuntimeContext;
 use execution_layer::DEFAULT_JWT_FILE;
@@ -253,6 +253,16 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.purge_cache = true;
     }
 
+    if let Some(path) = cli_args.value_of("deposit-snapshot") {
+        let bytes = fs::read(path)
+            .map_err(|e| format!("Unable to read deposit snapshot {}: {:?}", path, e))?;
+        client_config.deposit_snapshot = Some(DepositSnapshotSource::SszBytes(bytes));
+    } else if let Some(url) = cli_args.value_of("deposit-snapshot-url") {
+        let url = SensitiveUrl::parse(url)
+            .map_err(|e| format!("Invalid deposit snapshot URL: {:?}", e))?;
+        client_config.deposit_snapshot = Some(DepositSnapshotSource::Url(url));
+    }
+
     if let Some(follow_distance) =
         clap_utils::parse_optional(cli_args, "eth1-cache-follow-distance")?
     {
@@ -273,25 +283,33 @@ pub fn get_config<E: EthSpec>(
         // stakers. The merge is already complicated enough.
         client_config.sync_eth1_chain = true;
 
-        // Parse a single execution endpoint, logging warnings if multiple endpoints are supplied.
-        let execution_endpoint =
-            parse_only_one_value(endpoints, SensitiveUrl::parse, "--execution-endpoint", log)?;
+        // Parse the execution endpoints. Payload statuses from multiple endpoints are combined
+        // according to `--execution-status-policy`.
+        let execution_endpoints =
+            parse_comma_separated(endpoints, SensitiveUrl::parse, "--execution-endpoint")?;
 
         // JWTs are required if `--execution-endpoint` is supplied. They can be either passed via
         // file_path or directly as string.
 
-        let secret_file: PathBuf;
-        // Parse a single JWT secret from a given file_path, logging warnings if multiple are supplied.
-        if let Some(secret_files) = cli_args.value_of("execution-jwt") {
-            secret_file =
-                parse_only_one_value(secret_files, PathBuf::from_str, "--execution-jwt", log)?;
+        let secret_files: Vec<PathBuf>;
+        // Parse either a single JWT secret shared by all endpoints, or one per endpoint.
+        if let Some(paths) = cli_args.value_of("execution-jwt") {
+            secret_files = parse_comma_separated(paths, PathBuf::from_str, "--execution-jwt")?;
+            if secret_files.len() != 1 && secret_files.len() != execution_endpoints.len() {
+                return Err(format!(
+                    "--execution-jwt must contain either one path or one path per execution \
+                     endpoint, found {} paths for {} endpoints",
+                    secret_files.len(),
+                    execution_endpoints.len()
+                ));
+            }
 
         // Check if the JWT secret key is passed directly via cli flag and persist it to the default
         // file location.
         } else if let Some(jwt_secret_key) = cli_args.value_of("execution-jwt-secret-key") {
             use std::fs::File;
             use std::io::Write;
-            secret_file = client_config.data_dir().join(DEFAULT_JWT_FILE);
+            let secret_file = client_config.data_dir().join(DEFAULT_JWT_FILE);
             let mut jwt_secret_key_file = File::create(secret_file.clone())
                 .map_err(|e| format!("Error while creating jwt_secret_key file: {:?}", e))?;
             jwt_secret_key_file
@@ -302,18 +320,30 @@ pub fn get_config<E: EthSpec>(
                         e
                     )
                 })?;
+            secret_files = vec![secret_file];
+        } else if execution_endpoints
+            .iter()
+            .all(|endpoint| execution_layer::ipc::ipc_path(endpoint).is_some())
+        {
+            // IPC endpoints are protected by the permissions of the socket file.
+            secret_files = vec![];
         } else {
             return Err("Error! Please set either --execution-jwt file_path or --execution-jwt-secret-key directly via cli when using --execution-endpoint".to_string());
         }
 
-        // Parse and set the payload builder, if any.
-        if let Some(endpoint) = cli_args.value_of("builder") {
-            let payload_builder =
-                parse_only_one_value(endpoint, SensitiveUrl::parse, "--builder", log)?;
-            el_config.builder_url = Some(payload_builder);
+        // Parse and set the payload builders, if any.
+        if let Some(endpoints) = cli_args.value_of("builder") {
+            el_config.builder_urls = endpoints
+                .split(',')
+                .map(SensitiveUrl::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("--builder contains an invalid value {:?}", e))?;
 
             el_config.builder_user_agent =
                 clap_utils::parse_optional(cli_args, "builder-user-agent")?;
+            el_config.builder_header_timeout =
+                clap_utils::parse_optional(cli_args, "builder-header-timeout")?
+                    .map(Duration::from_millis);
         }
 
         if cli_args.is_present("builder-profit-threshold") {
@@ -332,8 +362,10 @@ pub fn get_config<E: EthSpec>(
         }
 
         // Set config values from parse values.
-        el_config.secret_files = vec![secret_file.clone()];
-        el_config.execution_endpoints = vec![execution_endpoint.clone()];
+        el_config.secret_files = secret_files.clone();
+        el_config.execution_endpoints = execution_endpoints.clone();
+        el_config.payload_status_policy =
+            clap_utils::parse_required(cli_args, "execution-status-policy")?;
         el_config.suggested_fee_recipient =
             clap_utils::parse_optional(cli_args, "suggested-fee-recipient")?;
         el_config.jwt_id = clap_utils::parse_optional(cli_args, "execution-jwt-id")?;
@@ -342,12 +374,20 @@ pub fn get_config<E: EthSpec>(
         let execution_timeout_multiplier =
             clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
         el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
+        el_config.engine_traffic_dir =
+            clap_utils::parse_optional(cli_args, "execution-traffic-record-dir")?;
 
-        client_config.eth1.endpoint = Eth1Endpoint::Auth {
-            endpoint: execution_endpoint,
-            jwt_path: secret_file,
-            jwt_id: el_config.jwt_id.clone(),
-            jwt_version: el_config.jwt_version.clone(),
+        // The deposit cache is populated from the first execution endpoint.
+        let eth1_endpoint = execution_endpoints[0].clone();
+        client_config.eth1.endpoint = if execution_layer::ipc::ipc_path(&eth1_endpoint).is_some() {
+            Eth1Endpoint::NoAuth(eth1_endpoint)
+        } else {
+            Eth1Endpoint::Auth {
+                endpoint: eth1_endpoint,
+                jwt_path: secret_files[0].clone(),
+                jwt_id: el_config.jwt_id.clone(),
+                jwt_version: el_config.jwt_version.clone(),
+            }
         };
 
         // Store the EL config in the client config.
@@ -536,11 +576,25 @@ pub fn get_config<E: EthSpec>(
                 .map_err(|e| format!("Invalid checkpoint sync URL: {:?}", e))?;
 
             ClientGenesis::CheckpointSyncUrl { url }
+        } else if let Some(peers_str) = cli_args.value_of("checkpoint-sync-peers") {
+            let peers = peers_str
+                .split(',')
+                .map(|multiaddr| {
+                    multiaddr
+                        .parse()
+                        .map_err(|_| format!("Invalid Multiaddr: {}", multiaddr))
+                })
+                .collect::<Result<Vec<Multiaddr>, _>>()?;
+
+            ClientGenesis::CheckpointSyncPeers { peers }
         } else {
             ClientGenesis::GenesisState
         }
     } else {
-        if cli_args.is_present("checkpoint-state") || cli_args.is_present("checkpoint-sync-url") {
+        if cli_args.is_present("checkpoint-state")
+            || cli_args.is_present("checkpoint-sync-url")
+            || cli_args.is_present("checkpoint-sync-peers")
+        {
             return Err(
                 "Checkpoint sync is not available for this network as no genesis state is known"
                     .to_string(),
@@ -1383,6 +1437,7 @@ pub fn set_network_config(
 
     // Light client server config.
     config.enable_light_client_server = cli_args.is_present("light-client-server");
+    config.enable_finalized_state_server = cli_args.is_present("serve-finalized-state");
 
     // The self limiter is disabled by default.
     // This flag can be used both with or without a value. Try to parse it first with a value, if
@@ -1422,6 +1477,20 @@ pub fn set_network_config(
         }
     };
 
+    config.trusted_peers_inbound_rate_limiter_config =
+        clap_utils::parse_optional(cli_args, "inbound-rate-limiter-trusted-peers")?;
+    if let Some(trusted_peers_config) = &config.trusted_peers_inbound_rate_limiter_config {
+        trusted_peers_config.validate()?;
+    }
+    if config.trusted_peers_inbound_rate_limiter_config.is_some()
+        && config.inbound_rate_limiter_config.is_none()
+    {
+        return Err(
+            "--inbound-rate-limiter-trusted-peers requires the inbound rate limiter to be enabled"
+                .into(),
+        );
+    }
+
     config.disable_duplicate_warn_logs = cli_args.is_present("disable-duplicate-warn-logs");
 
     Ok(())
@@ -1466,15 +1535,12 @@ pub fn get_slots_per_restore_point<E: EthSpec>(
     }
 }
 
-/// Parses the `cli_value` as a comma-separated string of values to be parsed with `parser`.
-///
-/// If there is more than one value, log a warning. If there are no values, return an error.
-pub fn parse_only_one_value<F, T, E>(
+/// Parses a comma-separated list of values, requiring at least one value.
+pub fn parse_comma_separated<F, T, E>(
     cli_value: &str,
     parser: F,
     flag_name: &str,
-    log: &Logger,
-) -> Result<T, String>
+) -> Result<Vec<T>, String>
 where
     F: Fn(&str) -> Result<T, E>,
     E: Debug,
@@ -1485,18 +1551,9 @@ where
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| format!("{} contains an invalid value {:?}", flag_name, e))?;
 
-    if values.len() > 1 {
-        warn!(
-            log,
-            "Multiple values provided";
-            "info" => "multiple values are deprecated, only the first value will be used",
-            "count" => values.len(),
-            "flag" => flag_name
-        );
+    if values.is_empty() {
+        return Err(format!("Must provide at least one value to {}", flag_name));
     }
 
-    values
-        .into_iter()
-        .next()
-        .ok_or(format!("Must provide at least one value to {}", flag_name))
+    Ok(values)
 }
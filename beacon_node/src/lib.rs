@@ -12,7 +12,7 @@ use beacon_chain::{
 };
 use clap::ArgMatches;
 pub use cli::cli_app;
-pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis};
+pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis, DepositSnapshotSource};
 pub use config::{get_config, get_data_dir, get_slots_per_restore_point, set_network_config};
 use environment::RuntimeContext;
 pub use eth2_config::Eth2Config;
@@ -142,9 +142,14 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
                 "endpoint" => format!("{:?}", &client_config.eth1.endpoint),
                 "method" => "json rpc via http"
             );
-            builder
+            let builder = builder
                 .caching_eth1_backend(client_config.eth1.clone())
-                .await?
+                .await?;
+            if let Some(source) = &client_config.deposit_snapshot {
+                builder.import_deposit_snapshot(source).await?
+            } else {
+                builder
+            }
         } else if client_config.dummy_eth1_backend {
             warn!(
                 log,
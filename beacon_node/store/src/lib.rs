@@ -253,6 +253,9 @@ pub enum DBColumn {
     BeaconHistoricalSummaries,
     #[strum(serialize = "olc")]
     OverflowLRUCache,
+    /// For the rewards of blocks proposed via this node, keyed by slot.
+    #[strum(serialize = "bpr")]
+    BeaconProposalRewards,
 }
 
 /// A block from the database, which might have an execution payload or not.
@@ -295,7 +298,8 @@ impl DBColumn {
             | Self::BeaconStateRoots
             | Self::BeaconHistoricalRoots
             | Self::BeaconHistoricalSummaries
-            | Self::BeaconRandaoMixes => 8,
+            | Self::BeaconRandaoMixes
+            | Self::BeaconProposalRewards => 8,
         }
     }
 }
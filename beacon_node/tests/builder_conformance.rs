@@ -0,0 +1,433 @@
+//! Builder API conformance tests.
+//!
+//! Each test runs a beacon node and a validator client with `--builder-proposals` against a
+//! single mock relay which misbehaves in one specific way. The validator client proposes blocks
+//! until `RUN_SLOTS` have passed, after which the proposals recorded by the beacon node are
+//! checked to have used the relay's payloads, or local payloads, as appropriate.
+#![cfg(test)]
+
+use eth2_network_config::TRUSTED_SETUP_BYTES;
+use execution_layer::auth::JwtKey;
+use execution_layer::test_utils::{
+    MockBuilder, Operation, DEFAULT_JWT_SECRET, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
+    DEFAULT_TERMINAL_BLOCK,
+};
+use node_test_rig::{
+    environment::EnvironmentBuilder,
+    eth2::lighthouse::{BuilderRelayStatus, PayloadSource},
+    testing_client_config, testing_validator_config, ClientGenesis, LocalBeaconNode,
+    LocalExecutionNode, LocalValidatorClient, MockExecutionConfig, ValidatorFiles,
+};
+use sensitive_url::SensitiveUrl;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{Address, Epoch, EthSpec, ForkName, Hash256, MinimalEthSpec, Slot, Uint256};
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 8;
+const SECONDS_PER_SLOT: u64 = 3;
+/// The delay before genesis, which leaves time for the nodes to start.
+const GENESIS_DELAY: u64 = 6;
+/// The number of slots for which the validator client proposes blocks.
+const RUN_SLOTS: u64 = 12;
+const FEE_RECIPIENT: Address = Address::repeat_byte(0x11);
+
+/// The ways in which the mock relay can misbehave.
+#[derive(Debug, Clone, Copy)]
+enum Misbehaviour {
+    /// The relay behaves correctly.
+    None,
+    /// Bids are signed with an invalid signature.
+    BadSignature,
+    /// Bids build on the wrong parent.
+    WrongParentHash,
+    /// Bids are worth less than the local payload.
+    LowValue,
+    /// Payloads pay a fee recipient other than the registered one.
+    WrongFeeRecipient,
+    /// The relay refuses to reveal the payload once a blinded block is signed.
+    WithheldPayload,
+    /// The relay reveals blobs which do not match the commitments in the blinded block.
+    MismatchedBlobsBundle,
+}
+
+/// The configuration of a single conformance run.
+struct ConformanceCase {
+    fork: ForkName,
+    misbehaviour: Misbehaviour,
+    builder_boost_factor: Option<u64>,
+    prefer_builder_proposals: bool,
+    offline_primary_beacon_node: bool,
+}
+
+/// The state of the beacon node and relay once the run has finished.
+struct ConformanceResult {
+    /// The payload sources of the proposals recorded by the beacon node, in slot order.
+    proposals: Vec<(Slot, PayloadSource)>,
+    relay: BuilderRelayStatus,
+    blinded_blocks_received: usize,
+    /// The slot at which the relay was first sent a signed blinded block.
+    first_blinded_block_slot: Option<Slot>,
+}
+
+impl ConformanceCase {
+    fn new(fork: ForkName, misbehaviour: Misbehaviour) -> Self {
+        Self {
+            fork,
+            misbehaviour,
+            builder_boost_factor: None,
+            prefer_builder_proposals: false,
+            offline_primary_beacon_node: false,
+        }
+    }
+
+    /// Set `--builder-boost-factor` on the validator client.
+    fn builder_boost_factor(mut self, builder_boost_factor: u64) -> Self {
+        self.builder_boost_factor = Some(builder_boost_factor);
+        self
+    }
+
+    /// Set `--prefer-builder-proposals` on the validator client.
+    fn prefer_builder_proposals(mut self) -> Self {
+        self.prefer_builder_proposals = true;
+        self
+    }
+
+    /// Configure the validator client with an unreachable beacon node ahead of the real one.
+    fn offline_primary_beacon_node(mut self) -> Self {
+        self.offline_primary_beacon_node = true;
+        self
+    }
+
+    fn misbehave(&self, mock_builder: &MockBuilder<E>) {
+        match self.misbehaviour {
+            Misbehaviour::None => {}
+            Misbehaviour::BadSignature => mock_builder.invalid_signatures(),
+            Misbehaviour::WrongParentHash => mock_builder
+                .add_persistent_operation(Operation::ParentHash(Hash256::repeat_byte(0x42))),
+            Misbehaviour::LowValue => mock_builder.add_persistent_operation(Operation::Value(
+                Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1),
+            )),
+            Misbehaviour::WrongFeeRecipient => mock_builder
+                .add_persistent_operation(Operation::FeeRecipient(Address::repeat_byte(0x42))),
+            Misbehaviour::WithheldPayload => mock_builder.withhold_payloads(),
+            Misbehaviour::MismatchedBlobsBundle => mock_builder.mismatch_blobs_bundles(),
+        }
+    }
+
+    /// Start an execution node, beacon node, mock relay and validator client, then let the
+    /// validator client propose blocks for `RUN_SLOTS`.
+    fn run(self) -> ConformanceResult {
+        let mut env = EnvironmentBuilder::minimal()
+            .null_logger()
+            .expect("should build env logger")
+            .multi_threaded_tokio_runtime()
+            .expect("should start tokio runtime")
+            .build()
+            .expect("environment should build");
+
+        let mut spec = self.fork.make_genesis_spec(env.eth2_config.spec.clone());
+        spec.seconds_per_slot = SECONDS_PER_SLOT;
+        env.eth2_config.spec = spec.clone();
+
+        let indices = (0..VALIDATOR_COUNT).collect::<Vec<_>>();
+        let validator_files =
+            ValidatorFiles::with_keystores(&indices).expect("should build keystores");
+
+        let genesis_time = now().as_secs() + GENESIS_DELAY;
+        let fork_time = |epoch: Option<Epoch>| {
+            epoch.map(|epoch| {
+                genesis_time + epoch.as_u64() * E::slots_per_epoch() * SECONDS_PER_SLOT
+            })
+        };
+        let context = env.core_context();
+
+        let execution_node = LocalExecutionNode::new(
+            context.service_context("execution_node".into()),
+            MockExecutionConfig {
+                // The relay authenticates with the default secret.
+                jwt_key: JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap(),
+                terminal_difficulty: spec.terminal_total_difficulty,
+                terminal_block: DEFAULT_TERMINAL_BLOCK,
+                shanghai_time: fork_time(spec.capella_fork_epoch),
+                cancun_time: fork_time(spec.deneb_fork_epoch),
+                ..Default::default()
+            },
+        );
+        let execution_url = SensitiveUrl::parse(&execution_node.server.url()).unwrap();
+
+        let mut client_config = testing_client_config();
+        client_config.genesis = ClientGenesis::InteropMerge {
+            validator_count: VALIDATOR_COUNT,
+            genesis_time,
+        };
+        client_config.http_api.allow_sync_stalled = true;
+        client_config.chain.builder_fallback_disable_checks = true;
+        client_config.trusted_setup = Some(serde_json::from_reader(TRUSTED_SETUP_BYTES).unwrap());
+        client_config.execution_layer = Some(execution_layer::Config {
+            execution_endpoints: vec![execution_url.clone()],
+            secret_files: vec![execution_node.datadir.path().join("jwt.hex")],
+            default_datadir: execution_node.datadir.path().to_path_buf(),
+            ..Default::default()
+        });
+
+        let beacon_node = env
+            .runtime()
+            .block_on(LocalBeaconNode::production(
+                context.service_context("beacon_node".into()),
+                client_config,
+            ))
+            .expect("should start beacon node");
+        let remote_node = beacon_node.remote_node().unwrap();
+        let beacon_url = SensitiveUrl::parse(remote_node.as_ref()).unwrap();
+
+        // Start the relay now that the beacon node's port is known.
+        let mock_builder = {
+            let _guard = env.runtime().enter();
+            let (mock_builder, (addr, mock_builder_server)) = MockBuilder::new_for_testing(
+                execution_url,
+                beacon_url.clone(),
+                spec.clone(),
+                context.executor.clone(),
+            );
+            context
+                .executor
+                .spawn(mock_builder_server, "mock_builder_server");
+            let builder_url =
+                SensitiveUrl::parse(&format!("http://127.0.0.1:{}", addr.port())).unwrap();
+            beacon_node
+                .client
+                .beacon_chain()
+                .unwrap()
+                .execution_layer
+                .as_ref()
+                .unwrap()
+                .set_builder_urls(vec![builder_url], None, None)
+                .unwrap();
+            mock_builder
+        };
+        self.misbehave(&mock_builder);
+
+        let mut validator_config = testing_validator_config();
+        validator_config.beacon_nodes = if self.offline_primary_beacon_node {
+            let port = unused_port::unused_tcp4_port().unwrap();
+            let offline_url = SensitiveUrl::parse(&format!("http://127.0.0.1:{port}")).unwrap();
+            vec![offline_url, beacon_url]
+        } else {
+            vec![beacon_url]
+        };
+        validator_config.fee_recipient = Some(FEE_RECIPIENT);
+        validator_config.builder_proposals = true;
+        validator_config.produce_block_v3 = true;
+        validator_config.builder_boost_factor = self.builder_boost_factor;
+        validator_config.prefer_builder_proposals = self.prefer_builder_proposals;
+        let _validator_client = env
+            .runtime()
+            .block_on(LocalValidatorClient::production_with_insecure_keypairs(
+                context.service_context("validator_client".into()),
+                validator_config,
+                validator_files,
+            ))
+            .expect("should start validator client");
+
+        // Let the validator client propose, noting when the relay is first asked to reveal a
+        // payload.
+        let current_slot =
+            || Slot::new(now().as_secs().saturating_sub(genesis_time) / SECONDS_PER_SLOT);
+        let mut first_blinded_block_slot = None;
+        while current_slot() <= RUN_SLOTS {
+            std::thread::sleep(Duration::from_secs(SECONDS_PER_SLOT) / 2);
+            if first_blinded_block_slot.is_none() && mock_builder.blinded_blocks_received() > 0 {
+                first_blinded_block_slot = Some(current_slot());
+            }
+        }
+
+        let proposals = env
+            .runtime()
+            .block_on(
+                remote_node.get_lighthouse_validator_proposals(Slot::new(0), Slot::new(RUN_SLOTS)),
+            )
+            .expect("should get proposals")
+            .data
+            .into_iter()
+            .map(|proposal| (proposal.slot, proposal.payload_source))
+            .collect();
+        let mut relays = env
+            .runtime()
+            .block_on(remote_node.get_lighthouse_builder_relays())
+            .expect("should get relay status")
+            .data;
+        assert_eq!(relays.len(), 1);
+
+        let result = ConformanceResult {
+            proposals,
+            relay: relays.remove(0),
+            blinded_blocks_received: mock_builder.blinded_blocks_received(),
+            first_blinded_block_slot,
+        };
+
+        env.fire_signal();
+        result
+    }
+}
+
+impl ConformanceResult {
+    fn count(&self, source: PayloadSource) -> usize {
+        self.proposals
+            .iter()
+            .filter(|(_, payload_source)| *payload_source == source)
+            .count()
+    }
+
+    /// Check that the relay's payloads were used for every proposal once the validators were
+    /// registered with it.
+    fn assert_builder_used(&self) {
+        let first_builder_proposal = self
+            .proposals
+            .iter()
+            .position(|(_, source)| *source == PayloadSource::Builder)
+            .expect("no proposal used the relay's payload");
+        assert!(
+            self.proposals[first_builder_proposal..]
+                .iter()
+                .all(|(_, source)| *source == PayloadSource::Builder),
+            "local payload used after the relay's: {:?}",
+            self.proposals
+        );
+        assert_eq!(
+            self.blinded_blocks_received,
+            self.count(PayloadSource::Builder)
+        );
+        assert!(self.relay.enabled);
+    }
+
+    /// Check that only local payloads were used, despite the relay offering bids.
+    fn assert_builder_unused(&self) {
+        assert_eq!(
+            self.count(PayloadSource::Builder),
+            0,
+            "{:?}",
+            self.proposals
+        );
+        assert!(self.count(PayloadSource::Local) > 0);
+        assert_eq!(self.blinded_blocks_received, 0);
+        assert!(self.relay.valid_bids + self.relay.invalid_bids > 0);
+    }
+
+    /// Check that the relay caused a single failed proposal, after which it was disabled and
+    /// local payloads were used.
+    fn assert_relay_disabled(&self) {
+        assert_eq!(self.blinded_blocks_received, 1);
+        assert_eq!(
+            self.count(PayloadSource::Builder),
+            0,
+            "{:?}",
+            self.proposals
+        );
+        assert!(!self.relay.enabled);
+        assert_eq!(self.relay.times_disabled, 1);
+
+        let failed_slot = self.first_blinded_block_slot.unwrap();
+        assert!(
+            self.proposals
+                .iter()
+                .any(|(slot, source)| *slot > failed_slot && *source == PayloadSource::Local),
+            "no local proposal after the failure at slot {failed_slot}: {:?}",
+            self.proposals
+        );
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+}
+
+#[test]
+fn builder_conformance_honest_relay() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::None).run();
+    result.assert_builder_used();
+}
+
+#[test]
+fn builder_conformance_honest_relay_deneb() {
+    let result = ConformanceCase::new(ForkName::Deneb, Misbehaviour::None).run();
+    result.assert_builder_used();
+}
+
+#[test]
+fn builder_conformance_bad_signature() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::BadSignature).run();
+    result.assert_builder_unused();
+    assert!(result.relay.invalid_bids > 0);
+}
+
+#[test]
+fn builder_conformance_wrong_parent_hash() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::WrongParentHash).run();
+    result.assert_builder_unused();
+    assert!(result.relay.invalid_bids > 0);
+}
+
+#[test]
+fn builder_conformance_low_value() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::LowValue).run();
+    result.assert_builder_unused();
+    assert!(result.relay.valid_bids > 0);
+}
+
+/// The builder may pay the proposer with a transaction at the end of the block, so a payload with
+/// a different fee recipient is accepted.
+#[test]
+fn builder_conformance_wrong_fee_recipient() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::WrongFeeRecipient).run();
+    result.assert_builder_used();
+}
+
+#[test]
+fn builder_conformance_withheld_payload() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::WithheldPayload).run();
+    result.assert_relay_disabled();
+    assert_eq!(result.relay.withheld_payloads, 1);
+}
+
+#[test]
+fn builder_conformance_mismatched_blobs_bundle() {
+    let result = ConformanceCase::new(ForkName::Deneb, Misbehaviour::MismatchedBlobsBundle).run();
+    result.assert_relay_disabled();
+    assert_eq!(result.relay.payload_mismatches, 1);
+}
+
+/// The validator client falls back to the second beacon node, which still uses the relay.
+#[test]
+fn builder_conformance_validator_client_fallback() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::None)
+        .offline_primary_beacon_node()
+        .run();
+    result.assert_builder_used();
+}
+
+/// A boost factor of zero makes the validator client always prefer local payloads.
+#[test]
+fn builder_conformance_zero_boost_factor() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::None)
+        .builder_boost_factor(0)
+        .run();
+    result.assert_builder_unused();
+}
+
+/// Boosting the bids makes a relay which bids slightly less than the local payload preferable.
+#[test]
+fn builder_conformance_boost_factor_low_value() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::LowValue)
+        .builder_boost_factor(200)
+        .run();
+    result.assert_builder_used();
+}
+
+#[test]
+fn builder_conformance_prefer_builder_low_value() {
+    let result = ConformanceCase::new(ForkName::Merge, Misbehaviour::LowValue)
+        .prefer_builder_proposals()
+        .run();
+    result.assert_builder_used();
+}
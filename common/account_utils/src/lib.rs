@@ -18,6 +18,7 @@ use std::thread::sleep;
 use std::time::Duration;
 use zeroize::Zeroize;
 
+pub mod password_source;
 pub mod validator_definitions;
 
 pub use eth2_keystore;
@@ -0,0 +1,483 @@
+//! Provides sources of keystore passwords other than the validator definitions file and the
+//! secrets directory, so that passwords do not need to be stored on the disk of the validator
+//! client.
+//!
+//! A source is defined in `validator_definitions.yml` using the `voting_keystore_password_source`
+//! field of a `local_keystore` validator, e.g.:
+//!
+//! ```yaml
+//! voting_keystore_password_source:
+//!   type: vault
+//!   url: "https://vault.example.com:8200"
+//!   path: validators/0xa5566f9ec3c6e1fdf362634ebec9ef7aceb0e460e5079714808388e5d48f4ae1e12897fed1bea951c17fa389d511e477
+//! ```
+//!
+//! Fetching a password may block (e.g. waiting for a command or HTTP request), so it should not be
+//! performed on an async executor thread.
+
+use crate::{strip_off_newlines, ZeroizeString};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// The environment variable used for the Vault token if `token_path` is not specified.
+pub const VAULT_TOKEN_ENV: &str = "VAULT_TOKEN";
+/// The default mount path of the Vault KV secrets engine.
+pub const DEFAULT_VAULT_MOUNT: &str = "secret";
+/// The default field of a Vault secret which contains the password.
+pub const DEFAULT_VAULT_FIELD: &str = "password";
+/// The default timeout for requests to Vault.
+pub const DEFAULT_VAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum Error {
+    /// The environment variable is not set or is not valid unicode.
+    UnableToReadEnvironmentVariable(String, std::env::VarError),
+    /// The command could not be executed.
+    UnableToRunCommand(PathBuf, io::Error),
+    /// The command exited with a non-zero status.
+    CommandFailed(PathBuf, std::process::ExitStatus),
+    /// The file descriptor could not be read.
+    UnableToReadFileDescriptor(i32, io::Error),
+    /// File descriptors are not supported on this platform.
+    FileDescriptorUnsupported,
+    /// The password is not valid UTF-8.
+    InvalidUtf8,
+    /// The password is empty.
+    EmptyPassword,
+    /// The Vault token could not be read from a file or the environment.
+    UnableToReadVaultToken(String),
+    /// The root certificate for Vault could not be read or parsed.
+    InvalidVaultCertificate(String),
+    /// The request to Vault failed.
+    VaultRequestFailed(String),
+    /// The response from Vault does not contain the password as a string.
+    MissingVaultField(String),
+}
+
+/// A secret stored in a Vault-compatible key/value secrets engine.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultDefinition {
+    /// The base URL of the Vault server, e.g. `https://vault.example.com:8200`.
+    pub url: String,
+    /// The path at which the KV secrets engine is mounted.
+    #[serde(default = "default_vault_mount")]
+    pub mount: String,
+    /// The path of the secret within the secrets engine.
+    pub path: String,
+    /// The field of the secret which contains the password.
+    #[serde(default = "default_vault_field")]
+    pub field: String,
+    /// The version of the KV secrets engine, either `1` or `2`.
+    #[serde(default = "default_vault_kv_version")]
+    pub kv_version: u8,
+    /// Path to a file containing the Vault token. The `VAULT_TOKEN` environment variable is used
+    /// if this is omitted.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_path: Option<PathBuf>,
+    /// The Vault Enterprise namespace of the secret.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// Path to a .pem file.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_certificate_path: Option<PathBuf>,
+    /// Specifies a request timeout.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_ms: Option<u64>,
+}
+
+fn default_vault_mount() -> String {
+    DEFAULT_VAULT_MOUNT.to_string()
+}
+
+fn default_vault_field() -> String {
+    DEFAULT_VAULT_FIELD.to_string()
+}
+
+fn default_vault_kv_version() -> u8 {
+    2
+}
+
+/// Defines where the password for a keystore is fetched from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PasswordSource {
+    /// The value of an environment variable of the validator client.
+    #[serde(rename = "env")]
+    Env { name: String },
+    /// The standard output of a command, e.g. a password manager CLI.
+    ///
+    /// The command is run directly rather than via a shell.
+    #[serde(rename = "command")]
+    Command {
+        program: PathBuf,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+    /// The contents of a file descriptor inherited by the validator client, e.g. a pipe set up by
+    /// a process supervisor.
+    ///
+    /// The file descriptor is read to its end the first time the password is fetched and the
+    /// password is cached for the lifetime of the process. The descriptor itself is never closed.
+    #[serde(rename = "fd")]
+    Fd { fd: i32 },
+    /// A secret in a Vault-compatible key/value secrets engine.
+    #[serde(rename = "vault")]
+    Vault(VaultDefinition),
+}
+
+impl PasswordSource {
+    /// Fetches the password, with any trailing new-lines removed.
+    pub fn fetch(&self) -> Result<ZeroizeString, Error> {
+        match self {
+            PasswordSource::Env { name } => std::env::var(name)
+                .map_err(|e| Error::UnableToReadEnvironmentVariable(name.clone(), e))
+                .map(String::into_bytes)
+                .and_then(bytes_to_password),
+            PasswordSource::Command { program, args } => {
+                let output = Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::null())
+                    .stderr(Stdio::inherit())
+                    .output()
+                    .map_err(|e| Error::UnableToRunCommand(program.clone(), e))?;
+                if !output.status.success() {
+                    return Err(Error::CommandFailed(program.clone(), output.status));
+                }
+                bytes_to_password(output.stdout)
+            }
+            PasswordSource::Fd { fd } => fetch_from_fd(*fd),
+            PasswordSource::Vault(vault) => fetch_from_vault(vault),
+        }
+    }
+}
+
+fn bytes_to_password(bytes: Vec<u8>) -> Result<ZeroizeString, Error> {
+    let password: ZeroizeString = String::from_utf8(strip_off_newlines(bytes))
+        .map_err(|_| Error::InvalidUtf8)?
+        .into();
+    if password.as_ref().is_empty() {
+        return Err(Error::EmptyPassword);
+    }
+    Ok(password)
+}
+
+/// Passwords which have been read from file descriptors, keyed by descriptor.
+///
+/// A descriptor such as a pipe can only be read once, but the password of a validator may be
+/// fetched several times (e.g. when the validator definitions are reloaded).
+fn fd_passwords() -> &'static Mutex<HashMap<i32, ZeroizeString>> {
+    static FD_PASSWORDS: OnceLock<Mutex<HashMap<i32, ZeroizeString>>> = OnceLock::new();
+    FD_PASSWORDS.get_or_init(<_>::default)
+}
+
+fn fetch_from_fd(fd: i32) -> Result<ZeroizeString, Error> {
+    // Hold the lock whilst reading so that the descriptor is only ever read once.
+    let mut passwords = fd_passwords()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(password) = passwords.get(&fd) {
+        return Ok(password.clone());
+    }
+    let password = read_fd(fd).and_then(bytes_to_password)?;
+    passwords.insert(fd, password.clone());
+    Ok(password)
+}
+
+#[cfg(unix)]
+fn read_fd(fd: i32) -> Result<Vec<u8>, Error> {
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::unix::io::BorrowedFd;
+
+    // Safety: the descriptor was supplied by the user and is only borrowed for the duration of
+    // `try_clone_to_owned`, which fails if it is not open. Reading via a duplicate means that the
+    // user's descriptor is never closed by this process.
+    let borrowed = unsafe { BorrowedFd::borrow_raw(fd) };
+    let owned = borrowed
+        .try_clone_to_owned()
+        .map_err(|e| Error::UnableToReadFileDescriptor(fd, e))?;
+    let mut file = File::from(owned);
+    let mut bytes = vec![];
+    file.read_to_end(&mut bytes)
+        .map_err(|e| Error::UnableToReadFileDescriptor(fd, e))?;
+    Ok(bytes)
+}
+
+#[cfg(not(unix))]
+fn read_fd(_fd: i32) -> Result<Vec<u8>, Error> {
+    Err(Error::FileDescriptorUnsupported)
+}
+
+impl VaultDefinition {
+    /// Returns the URL of the secret, according to the KV secrets engine version.
+    fn secret_url(&self) -> String {
+        let url = self.url.trim_end_matches('/');
+        let mount = self.mount.trim_matches('/');
+        let path = self.path.trim_start_matches('/');
+        if self.kv_version == 1 {
+            format!("{}/v1/{}/{}", url, mount, path)
+        } else {
+            format!("{}/v1/{}/data/{}", url, mount, path)
+        }
+    }
+
+    fn token(&self) -> Result<ZeroizeString, Error> {
+        let token = if let Some(path) = &self.token_path {
+            fs::read(path).map_err(|e| {
+                Error::UnableToReadVaultToken(format!("unable to read {:?}: {:?}", path, e))
+            })?
+        } else {
+            std::env::var(VAULT_TOKEN_ENV)
+                .map_err(|e| {
+                    Error::UnableToReadVaultToken(format!("{}: {:?}", VAULT_TOKEN_ENV, e))
+                })?
+                .into_bytes()
+        };
+        String::from_utf8(strip_off_newlines(token))
+            .map(Into::into)
+            .map_err(|_| Error::UnableToReadVaultToken("token is not valid UTF-8".into()))
+    }
+}
+
+fn fetch_from_vault(vault: &VaultDefinition) -> Result<ZeroizeString, Error> {
+    let timeout = vault
+        .request_timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_VAULT_TIMEOUT);
+    let mut builder = reqwest::blocking::Client::builder().timeout(timeout);
+    if let Some(path) = &vault.root_certificate_path {
+        let pem = fs::read(path)
+            .map_err(|e| Error::InvalidVaultCertificate(format!("{:?}: {:?}", path, e)))?;
+        let certificate = reqwest::Certificate::from_pem(&pem)
+            .map_err(|e| Error::InvalidVaultCertificate(format!("{:?}: {:?}", path, e)))?;
+        builder = builder.add_root_certificate(certificate);
+    }
+    let client = builder
+        .build()
+        .map_err(|e| Error::VaultRequestFailed(format!("unable to build client: {:?}", e)))?;
+
+    let token = vault.token()?;
+    let mut request = client
+        .get(vault.secret_url())
+        .header("X-Vault-Token", token.as_str());
+    if let Some(namespace) = &vault.namespace {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+    let response: serde_json::Value = request
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.json())
+        .map_err(|e| Error::VaultRequestFailed(format!("{:?}", e)))?;
+
+    // KV version 2 nests the secret within a further `data` object, alongside its metadata.
+    let mut secret = &response["data"];
+    if vault.kv_version != 1 {
+        secret = &secret["data"];
+    }
+    secret[&vault.field]
+        .as_str()
+        .ok_or_else(|| Error::MissingVaultField(vault.field.clone()))
+        .and_then(|password| bytes_to_password(password.as_bytes().to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    const PASSWORD: &str = "hunter2hunter2";
+
+    /// Serves a single HTTP request with `body`, returning the request line and headers.
+    fn mock_vault(body: String) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![];
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                request.push(line.trim().to_string());
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+        (url, handle)
+    }
+
+    fn vault_definition(url: String, token_path: PathBuf) -> VaultDefinition {
+        VaultDefinition {
+            url,
+            mount: default_vault_mount(),
+            path: "validators/0x01".into(),
+            field: default_vault_field(),
+            kv_version: default_vault_kv_version(),
+            token_path: Some(token_path),
+            namespace: None,
+            root_certificate_path: None,
+            request_timeout_ms: None,
+        }
+    }
+
+    #[test]
+    fn env_source() {
+        let name = "LIGHTHOUSE_PASSWORD_SOURCE_TEST";
+        std::env::set_var(name, format!("{}\n", PASSWORD));
+        let source = PasswordSource::Env { name: name.into() };
+        assert_eq!(source.fetch().unwrap().as_str(), PASSWORD);
+
+        let missing = PasswordSource::Env {
+            name: "LIGHTHOUSE_PASSWORD_SOURCE_TEST_MISSING".into(),
+        };
+        assert!(matches!(
+            missing.fetch(),
+            Err(Error::UnableToReadEnvironmentVariable(..))
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn command_source() {
+        let source = PasswordSource::Command {
+            program: "echo".into(),
+            args: vec![PASSWORD.into()],
+        };
+        assert_eq!(source.fetch().unwrap().as_str(), PASSWORD);
+
+        let failing = PasswordSource::Command {
+            program: "false".into(),
+            args: vec![],
+        };
+        assert!(matches!(failing.fetch(), Err(Error::CommandFailed(..))));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn fd_source() {
+        use std::os::unix::io::{FromRawFd, IntoRawFd};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("password");
+        fs::write(&path, PASSWORD).unwrap();
+        let fd = File::open(&path).unwrap().into_raw_fd();
+
+        let source = PasswordSource::Fd { fd };
+        assert_eq!(source.fetch().unwrap().as_str(), PASSWORD);
+
+        // The descriptor has been read to its end, so fetching again must use the cached value.
+        assert_eq!(source.fetch().unwrap().as_str(), PASSWORD);
+
+        // The descriptor must still be open and owned by the caller.
+        let file = unsafe { File::from_raw_fd(fd) };
+        assert!(file.metadata().is_ok());
+    }
+
+    #[test]
+    fn vault_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("token");
+        fs::write(&token_path, "s.token\n").unwrap();
+
+        let body = format!(
+            r#"{{"data":{{"data":{{"password":"{}"}},"metadata":{{"version":1}}}}}}"#,
+            PASSWORD
+        );
+        let (url, handle) = mock_vault(body);
+        let source = PasswordSource::Vault(vault_definition(url, token_path.clone()));
+        assert_eq!(source.fetch().unwrap().as_str(), PASSWORD);
+
+        let request = handle.join().unwrap();
+        assert_eq!(request[0], "GET /v1/secret/data/validators/0x01 HTTP/1.1");
+        assert!(request
+            .iter()
+            .any(|header| header.eq_ignore_ascii_case("x-vault-token: s.token")));
+
+        // KV version 1 does not nest the secret.
+        let body = format!(r#"{{"data":{{"key":"{}"}}}}"#, PASSWORD);
+        let (url, handle) = mock_vault(body);
+        let source = PasswordSource::Vault(VaultDefinition {
+            kv_version: 1,
+            field: "key".into(),
+            ..vault_definition(url, token_path)
+        });
+        assert_eq!(source.fetch().unwrap().as_str(), PASSWORD);
+        let request = handle.join().unwrap();
+        assert_eq!(request[0], "GET /v1/secret/validators/0x01 HTTP/1.1");
+    }
+
+    #[test]
+    fn vault_source_missing_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("token");
+        fs::write(&token_path, "s.token").unwrap();
+
+        let (url, handle) = mock_vault(r#"{"data":{"data":{}}}"#.into());
+        let source = PasswordSource::Vault(vault_definition(url, token_path));
+        assert!(matches!(source.fetch(), Err(Error::MissingVaultField(_))));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn deserialize_sources() {
+        let source: PasswordSource = serde_yaml::from_str(
+            r#"
+            type: vault
+            url: "https://vault:8200"
+            path: validators/0x01
+            "#,
+        )
+        .unwrap();
+        match source {
+            PasswordSource::Vault(vault) => {
+                assert_eq!(vault.mount, DEFAULT_VAULT_MOUNT);
+                assert_eq!(vault.field, DEFAULT_VAULT_FIELD);
+                assert_eq!(vault.kv_version, 2);
+                assert_eq!(
+                    vault.secret_url(),
+                    "https://vault:8200/v1/secret/data/validators/0x01"
+                );
+            }
+            _ => panic!("expected a vault source"),
+        }
+
+        let source: PasswordSource = serde_yaml::from_str(
+            r#"
+            type: command
+            program: pass
+            args: ["show", "validators/0x01"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            source,
+            PasswordSource::Command {
+                program: "pass".into(),
+                args: vec!["show".into(), "validators/0x01".into()],
+            }
+        );
+    }
+}
@@ -4,7 +4,8 @@
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
 use crate::{
-    default_keystore_password_path, read_password_string, write_file_via_temporary, ZeroizeString,
+    default_keystore_password_path, password_source::PasswordSource, read_password_string,
+    write_file_via_temporary, ZeroizeString,
 };
 use directory::ensure_dir_exists;
 use eth2_keystore::Keystore;
@@ -46,6 +47,7 @@ pub enum Error {
     /// The validator directory could not be created.
     UnableToCreateValidatorDir(PathBuf),
     UnableToReadKeystorePassword(String),
+    UnableToFetchKeystorePassword(crate::password_source::Error),
     KeystoreWithoutPassword,
 }
 
@@ -82,11 +84,34 @@ pub struct Web3SignerDefinition {
     pub client_identity_password: Option<String>,
 }
 
+/// A remote signer holding one share of a threshold validator's key.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Hash, Eq)]
+pub struct ThresholdShareDefinition {
+    /// The non-zero index of the share, as used when the key was split.
+    pub index: u64,
+    /// The public key of the share, used to request and verify partial signatures.
+    pub public_key: PublicKey,
+    /// The Web3Signer-compatible server holding the share.
+    #[serde(flatten)]
+    pub signer: Web3SignerDefinition,
+}
+
+/// A validator whose key is split into shares held by several remote signers.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdDefinition {
+    /// The number of partial signatures required to produce a signature.
+    pub threshold: usize,
+    pub shares: Vec<ThresholdShareDefinition>,
+}
+
 /// Defines how the validator client should attempt to sign messages for this validator.
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum SigningDefinition {
     /// A validator that is defined by an EIP-2335 keystore on the local filesystem.
+    ///
+    /// The password is taken from `voting_keystore_password`, `voting_keystore_password_source`
+    /// or `voting_keystore_password_path`, in that order of preference.
     #[serde(rename = "local_keystore")]
     LocalKeystore {
         voting_keystore_path: PathBuf,
@@ -94,12 +119,19 @@ pub enum SigningDefinition {
         voting_keystore_password_path: Option<PathBuf>,
         #[serde(skip_serializing_if = "Option::is_none")]
         voting_keystore_password: Option<ZeroizeString>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        voting_keystore_password_source: Option<PasswordSource>,
     },
     /// A validator that defers to a Web3Signer HTTP server for signing.
     ///
     /// https://github.com/ConsenSys/web3signer
     #[serde(rename = "web3signer")]
     Web3Signer(Web3SignerDefinition),
+    /// A validator whose signatures are combined from the partial signatures of `threshold` of
+    /// its share holders.
+    #[serde(rename = "threshold")]
+    Threshold(ThresholdDefinition),
 }
 
 impl SigningDefinition {
@@ -107,12 +139,22 @@ impl SigningDefinition {
         matches!(self, SigningDefinition::LocalKeystore { .. })
     }
 
+    /// Returns the password of a local keystore.
+    ///
+    /// This may block whilst fetching the password from a `PasswordSource`.
     pub fn voting_keystore_password(&self) -> Result<Option<ZeroizeString>, Error> {
         match self {
             SigningDefinition::LocalKeystore {
                 voting_keystore_password: Some(password),
                 ..
             } => Ok(Some(password.clone())),
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_source: Some(source),
+                ..
+            } => source
+                .fetch()
+                .map(Option::Some)
+                .map_err(Error::UnableToFetchKeystorePassword),
             SigningDefinition::LocalKeystore {
                 voting_keystore_password_path: Some(path),
                 ..
@@ -121,7 +163,7 @@ impl SigningDefinition {
                 .map(Option::Some)
                 .map_err(Error::UnableToReadKeystorePassword),
             SigningDefinition::LocalKeystore { .. } => Err(Error::KeystoreWithoutPassword),
-            SigningDefinition::Web3Signer(_) => Ok(None),
+            SigningDefinition::Web3Signer(_) | SigningDefinition::Threshold(_) => Ok(None),
         }
     }
 
@@ -211,6 +253,7 @@ impl ValidatorDefinition {
                 voting_keystore_path,
                 voting_keystore_password_path,
                 voting_keystore_password,
+                voting_keystore_password_source: None,
             },
         })
     }
@@ -283,8 +326,8 @@ impl ValidatorDefinitions {
                     voting_keystore_path,
                     ..
                 } => Some(voting_keystore_path),
-                // A Web3Signer validator does not use a local keystore file.
-                SigningDefinition::Web3Signer { .. } => None,
+                // Remote signer validators do not use a local keystore file.
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold(_) => None,
             })
             .collect();
 
@@ -361,6 +404,7 @@ impl ValidatorDefinitions {
                         voting_keystore_path,
                         voting_keystore_password_path,
                         voting_keystore_password: None,
+                        voting_keystore_password_source: None,
                     },
                 })
             })
@@ -687,4 +731,98 @@ mod tests {
         let def: ValidatorDefinition = serde_yaml::from_str(valid_builder_proposals).unwrap();
         assert_eq!(def.builder_proposals, Some(true));
     }
+
+    #[test]
+    fn password_source_checks() {
+        let no_password_source = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(no_password_source).unwrap();
+        assert!(matches!(
+            def.signing_definition,
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_source: None,
+                ..
+            }
+        ));
+
+        let env_password_source = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_keystore_password_source:
+          type: env
+          name: VALIDATOR_PASSWORD
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(env_password_source).unwrap();
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_source: Some(source),
+                ..
+            } => assert_eq!(
+                *source,
+                PasswordSource::Env {
+                    name: "VALIDATOR_PASSWORD".into()
+                }
+            ),
+            _ => panic!("expected a password source"),
+        }
+
+        let encoded = serde_yaml::to_string(&def).unwrap();
+        let decoded: ValidatorDefinition = serde_yaml::from_str(&encoded).unwrap();
+        assert!(decoded == def);
+
+        let invalid_password_source = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_keystore_password_source:
+          type: carrier_pigeon
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: Result<ValidatorDefinition, _> = serde_yaml::from_str(invalid_password_source);
+        assert!(def.is_err());
+    }
+
+    #[test]
+    fn threshold_definition() {
+        let threshold = r#"---
+        description: ""
+        enabled: true
+        type: threshold
+        threshold: 2
+        shares:
+          - index: 1
+            public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+            url: "https://signer-1:9000"
+          - index: 2
+            public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+            url: "https://signer-2:9000"
+            request_timeout_ms: 5000
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+
+        let def: ValidatorDefinition = serde_yaml::from_str(threshold).unwrap();
+        match &def.signing_definition {
+            SigningDefinition::Threshold(definition) => {
+                assert_eq!(definition.threshold, 2);
+                assert_eq!(definition.shares.len(), 2);
+                assert_eq!(definition.shares[1].index, 2);
+                assert_eq!(definition.shares[1].signer.url, "https://signer-2:9000");
+                assert_eq!(definition.shares[1].signer.request_timeout_ms, Some(5000));
+            }
+            _ => panic!("expected a threshold definition"),
+        }
+
+        let encoded = serde_yaml::to_string(&def).unwrap();
+        let decoded: ValidatorDefinition = serde_yaml::from_str(&encoded).unwrap();
+        assert!(decoded == def);
+    }
 }
@@ -4,14 +4,15 @@ mod attestation_performance;
 pub mod attestation_rewards;
 mod block_packing_efficiency;
 mod block_rewards;
+mod proposal_rewards;
 mod standard_block_rewards;
 mod sync_committee_rewards;
 
 use crate::{
     ok_or_error,
     types::{
-        BeaconState, ChainSpec, DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock,
-        GenericResponse, ValidatorId,
+        BeaconState, ChainSpec, DepositTreeSnapshot, Epoch, EthSpec, ExecutionBlockHash,
+        FinalizedExecutionBlock, GenericResponse, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot, StateId, StatusCode,
 };
@@ -20,6 +21,7 @@ use reqwest::IntoUrl;
 use serde::{Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
+use std::collections::HashMap;
 use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
 
 pub use attestation_performance::{
@@ -30,7 +32,15 @@ pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use lighthouse_network::{
+    types::{
+        BackFillReport, BatchReport, IpFamily, LookupsReport, ObservedAddress, ParentLookupReport,
+        RangeChainReport, RateLimiterReport, ReachabilityReport, SingleLookupReport, SyncProgress,
+        SyncState, SyncStatusReport, Transport, TransportReachability,
+    },
+    PeerInfo,
+};
+pub use proposal_rewards::{PayloadSource, ProposalReward, ProposalRewardsQuery};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
 
@@ -314,6 +324,24 @@ pub struct Eth1SyncStatusData {
     pub lighthouse_is_cached_and_ready: bool,
 }
 
+/// A comparison of the local deposit tree with the `eth1_data` of the finalized state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositSnapshotConsistency {
+    /// The deposit count of the finalized state's `eth1_data`.
+    pub finalized_deposit_count: u64,
+    /// The deposit root of the finalized state's `eth1_data`.
+    pub finalized_deposit_root: Hash256,
+    /// The deposit count of our finalized deposit tree snapshot, if any.
+    pub snapshot_deposit_count: Option<u64>,
+    /// The deposit root of our finalized deposit tree snapshot, if any.
+    pub snapshot_deposit_root: Option<Hash256>,
+    /// Our deposit root at `finalized_deposit_count`, if it is known.
+    pub local_deposit_root: Option<Hash256>,
+    /// True if `local_deposit_root` matches `finalized_deposit_root`, or `None` if our deposit
+    /// tree does not cover `finalized_deposit_count`.
+    pub consistent: Option<bool>,
+}
+
 /// A fully parsed eth1 deposit contract log.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct DepositLog {
@@ -361,6 +389,73 @@ impl From<Eth1Block> for FinalizedExecutionBlock {
     }
 }
 
+/// A request to change the quotas of the RPC rate limiters at runtime.
+///
+/// Quotas use the format of the `--inbound-rate-limiter` flag, protocols which are not specified
+/// use the default quotas. An empty string removes an override.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitsUpdate {
+    /// The quotas applied to peers without an override.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// The quotas applied to trusted peers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trusted_peers: Option<String>,
+    /// Peer specific quotas, keyed by peer id.
+    #[serde(default)]
+    pub peers: HashMap<String, String>,
+    /// The quotas applied to our own requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outbound: Option<String>,
+}
+
+/// The payload status returned by a single execution engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineStatusVote {
+    /// The URL of the engine, with any credentials redacted.
+    pub engine: String,
+    /// One of `valid`, `invalid`, `syncing` or `error`.
+    pub status: String,
+}
+
+/// Emitted when redundant execution engines return conflicting payload statuses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineDisagreement {
+    /// Either `new_payload` or `forkchoice_updated`.
+    pub method: String,
+    pub block_hash: ExecutionBlockHash,
+    pub votes: Vec<EngineStatusVote>,
+    /// The status used by the beacon node after applying the configured policy.
+    pub outcome: String,
+}
+
+/// The reputation of a single builder relay, as tracked by the beacon node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuilderRelayStatus {
+    /// The URL of the relay, with any credentials redacted.
+    pub relay: String,
+    /// False if the relay has been disabled by the circuit breaker.
+    pub enabled: bool,
+    /// The number of seconds until the relay is re-enabled, if it is disabled.
+    pub disabled_for_secs: Option<u64>,
+    /// The number of faults within the current fault window.
+    pub recent_faults: u64,
+    /// The number of times the circuit breaker has disabled the relay.
+    pub times_disabled: u64,
+    /// The number of valid bids received from the relay.
+    pub valid_bids: u64,
+    /// The number of times the relay failed to reveal a payload for a signed blinded block.
+    pub withheld_payloads: u64,
+    /// The number of bids which failed verification.
+    pub invalid_bids: u64,
+    /// The number of requests for a bid which timed out.
+    pub late_responses: u64,
+    /// The number of revealed payloads or blobs which did not match the bid.
+    pub payload_mismatches: u64,
+    /// The number of imported blocks which paid the fee recipient less than their bid.
+    pub value_shortfalls: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabaseInfo {
     pub schema_version: u64,
@@ -416,6 +511,21 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/sync/status`
+    pub async fn get_lighthouse_sync_status(
+        &self,
+    ) -> Result<GenericResponse<SyncStatusReport>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("sync")
+            .push("status");
+
+        self.get(path).await
+    }
+
     /*
      * Note:
      *
@@ -516,6 +626,59 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `POST lighthouse/eth1/deposit_snapshot`
+    pub async fn post_lighthouse_eth1_deposit_snapshot(
+        &self,
+        snapshot: &DepositTreeSnapshot,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("deposit_snapshot");
+
+        self.post(path, snapshot).await
+    }
+
+    /// `GET lighthouse/eth1/deposit_snapshot/consistency`
+    pub async fn get_lighthouse_eth1_deposit_snapshot_consistency(
+        &self,
+    ) -> Result<GenericResponse<DepositSnapshotConsistency>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("deposit_snapshot")
+            .push("consistency");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/validator/proposals?from,to`
+    pub async fn get_lighthouse_validator_proposals(
+        &self,
+        from: Slot,
+        to: Slot,
+    ) -> Result<GenericResponse<Vec<ProposalReward>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator")
+            .push("proposals");
+
+        path.query_pairs_mut()
+            .append_pair("from", &from.to_string())
+            .append_pair("to", &to.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/beacon/states/{state_id}/ssz`
     pub async fn get_lighthouse_beacon_states_ssz<E: EthSpec>(
         &self,
@@ -563,6 +726,67 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/builder/relays`
+    pub async fn get_lighthouse_builder_relays(
+        &self,
+    ) -> Result<GenericResponse<Vec<BuilderRelayStatus>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("builder")
+            .push("relays");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/nat/reachability`
+    pub async fn get_lighthouse_nat_reachability(
+        &self,
+    ) -> Result<GenericResponse<ReachabilityReport>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("nat")
+            .push("reachability");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/rate_limits`
+    pub async fn get_lighthouse_network_rate_limits(
+        &self,
+    ) -> Result<GenericResponse<RateLimiterReport>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("rate_limits");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/network/rate_limits`
+    pub async fn post_lighthouse_network_rate_limits(
+        &self,
+        update: &RateLimitsUpdate,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("rate_limits");
+
+        self.post(path, update).await
+    }
+
     /// `POST lighthouse/database/reconstruct`
     pub async fn post_lighthouse_database_reconstruct(&self) -> Result<String, Error> {
         let mut path = self.server.full.clone();
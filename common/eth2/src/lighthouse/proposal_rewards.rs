@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use ssz::four_byte_option_impl;
+use ssz_derive::{Decode, Encode};
+use types::{Address, ExecutionBlockHash, Hash256, Slot, Uint256};
+
+four_byte_option_impl!(four_byte_option_u64, u64);
+four_byte_option_impl!(four_byte_option_uint256, Uint256);
+
+/// Where the execution payload of a proposed block came from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "lowercase")]
+#[ssz(enum_behaviour = "tag")]
+pub enum PayloadSource {
+    /// The payload was built by the local execution node.
+    Local,
+    /// The payload was revealed by a builder (e.g. via a mev-boost compatible relay).
+    Builder,
+}
+
+/// The rewards earned by a block which was proposed via this beacon node.
+///
+/// Execution rewards are in wei, consensus rewards are in gwei.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct ProposalReward {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: u64,
+    pub payload_source: PayloadSource,
+    /// The fee recipient of the proposal.
+    ///
+    /// This is the fee recipient of the payload for local payloads. For builder payloads it is
+    /// the fee recipient registered for the proposer when the block was produced, which may
+    /// differ from the fee recipient of the payload.
+    pub fee_recipient: Address,
+    pub execution_block_hash: ExecutionBlockHash,
+    /// The value of the payload according to the execution node or the builder's bid.
+    ///
+    /// This is `None` if the block was not produced by this beacon node.
+    #[ssz(with = "four_byte_option_uint256")]
+    pub bid_value: Option<Uint256>,
+    /// The increase in the balance of `fee_recipient` across the execution block.
+    ///
+    /// This is `None` if the execution node could not provide the balances, or if the balance
+    /// decreased.
+    #[ssz(with = "four_byte_option_uint256")]
+    pub paid_value: Option<Uint256>,
+    /// The consensus reward to the proposer.
+    ///
+    /// This is `None` if the block was not produced by this beacon node.
+    #[ssz(with = "four_byte_option_u64")]
+    pub consensus_reward: Option<u64>,
+    /// The decrease in the balance of `fee_recipient` across the execution block.
+    ///
+    /// This is only `Some` if `fee_recipient` transacted in the block and spent more than it was
+    /// paid, in which case the value paid by the proposal cannot be determined.
+    #[ssz(with = "four_byte_option_uint256")]
+    pub balance_decrease: Option<Uint256>,
+}
+
+/// Query parameters for the `/lighthouse/validator/proposals` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProposalRewardsQuery {
+    /// Lower slot limit for proposals returned (inclusive).
+    pub from: Slot,
+    /// Upper slot limit for proposals returned (inclusive).
+    pub to: Slot,
+}
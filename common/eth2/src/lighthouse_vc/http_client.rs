@@ -375,6 +375,68 @@ impl ValidatorClientHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/beacon/health`
+    pub async fn get_lighthouse_beacon_health(
+        &self,
+    ) -> Result<GenericResponse<Vec<BeaconNodeHealthData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("health");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/scheduled_exits`
+    pub async fn get_lighthouse_scheduled_exits(
+        &self,
+    ) -> Result<GenericResponse<Vec<ScheduledExitData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("scheduled_exits");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/scheduled_exits/{pubkey}`
+    pub async fn post_lighthouse_scheduled_exits(
+        &self,
+        pubkey: &PublicKeyBytes,
+        request: &ScheduleExitRequest,
+    ) -> Result<GenericResponse<ScheduledExitData>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("scheduled_exits")
+            .push(&pubkey.to_string());
+
+        self.post(path, request).await
+    }
+
+    /// `DELETE lighthouse/scheduled_exits/{pubkey}`
+    pub async fn delete_lighthouse_scheduled_exits(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("scheduled_exits")
+            .push(&pubkey.to_string());
+
+        self.delete(path).await
+    }
+
     /// `GET lighthouse/spec`
     pub async fn get_lighthouse_spec<T: Serialize + DeserializeOwned>(
         &self,
@@ -434,6 +496,44 @@ impl ValidatorClientHttpClient {
         self.post(path, &validators).await
     }
 
+    /// `POST lighthouse/validators/reload`
+    pub async fn post_lighthouse_validators_reload(
+        &self,
+    ) -> Result<GenericResponse<ValidatorDefinitionsDiff>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("reload");
+
+        self.post(path, &()).await
+    }
+
+    /// `GET lighthouse/validators/{pubkey}/duties`
+    pub async fn get_lighthouse_validators_duties(
+        &self,
+        pubkey: &PublicKeyBytes,
+        epochs: Option<u64>,
+    ) -> Result<GenericResponse<Vec<DutyRecord>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&pubkey.to_string())
+            .push("duties");
+
+        if let Some(epochs) = epochs {
+            path.query_pairs_mut()
+                .append_pair("epochs", &epochs.to_string());
+        }
+
+        self.get(path).await
+    }
+
     /// `POST lighthouse/validators/mnemonic`
     pub async fn post_lighthouse_validators_mnemonic(
         &self,
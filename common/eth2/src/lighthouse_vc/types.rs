@@ -197,3 +197,155 @@ pub struct SingleExportKeystoresResponse {
 pub struct SetGraffitiRequest {
     pub graffiti: GraffitiString,
 }
+
+/// The status of a beacon node, as used by the validator client to select a beacon node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeaconNodeStatus {
+    Synced,
+    NotSynced,
+    Offline,
+    Incompatible,
+    Uninitialized,
+}
+
+/// The health of a beacon node, as returned by `GET lighthouse/beacon/health`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct BeaconNodeHealthData {
+    /// The position of the beacon node in the `--beacon-nodes` flag.
+    pub index: usize,
+    pub endpoint: String,
+    /// `true` if requests are sent to this beacon node before any other.
+    pub is_primary: bool,
+    pub status: BeaconNodeStatus,
+    /// A score between zero and `10000`, where higher is healthier.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub score: u64,
+    pub head_slot: Option<Slot>,
+    pub sync_distance: Option<Slot>,
+    /// The number of slots the head of this beacon node is behind the most advanced head amongst
+    /// all beacon nodes.
+    pub head_lag: Option<Slot>,
+    pub el_offline: Option<bool>,
+    pub is_optimistic: Option<bool>,
+    pub latency_ms: Option<u64>,
+}
+
+/// The conditions under which the validator client should exit a validator.
+///
+/// The validator is exited once any of the supplied conditions is met.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ScheduleExitRequest {
+    /// Exit once this epoch is reached.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+    /// Exit once the balance of the validator falls below this amount.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_below_gwei: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledExitStatus {
+    /// The conditions of the exit have not been met.
+    Pending,
+    /// The voluntary exit has been signed and broadcast, but not yet processed.
+    Submitted,
+    /// The beacon chain has processed the exit of the validator.
+    Completed,
+}
+
+/// A scheduled exit, as returned by `GET lighthouse/scheduled_exits`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ScheduledExitData {
+    pub pubkey: PublicKeyBytes,
+    pub epoch: Option<Epoch>,
+    pub balance_below_gwei: Option<u64>,
+    pub status: ScheduledExitStatus,
+    /// The epoch of the signed voluntary exit message, once submitted.
+    pub exit_message_epoch: Option<Epoch>,
+    /// The epoch at which the validator exits, once processed.
+    pub exit_epoch: Option<Epoch>,
+    /// The reason the exit could not be submitted when its conditions were last checked.
+    pub last_error: Option<String>,
+}
+
+/// The changes applied by `POST lighthouse/validators/reload`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ValidatorDefinitionsDiff {
+    /// Definitions which were not previously known, whether enabled or not.
+    pub added: Vec<PublicKeyBytes>,
+    /// Definitions which are no longer present in the file.
+    pub removed: Vec<PublicKeyBytes>,
+    /// Validators which started performing duties, including newly added ones.
+    pub enabled: Vec<PublicKeyBytes>,
+    /// Validators which stopped performing duties, including removed ones.
+    pub disabled: Vec<PublicKeyBytes>,
+    /// Definitions whose enabled status is unchanged but whose other fields have changed.
+    pub updated: Vec<PublicKeyBytes>,
+}
+
+impl ValidatorDefinitionsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.enabled.is_empty()
+            && self.disabled.is_empty()
+            && self.updated.is_empty()
+    }
+}
+
+/// A duty performed by a validator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DutyKind {
+    Attestation,
+    Aggregate,
+    BlockProposal,
+    SyncCommitteeMessage,
+    SyncCommitteeContribution,
+}
+
+/// The reason a duty could not be completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DutyErrorCategory {
+    /// No beacon node was able to produce or publish the duty.
+    BeaconNodeUnavailable,
+    /// The remote signer did not respond in time.
+    SignerTimeout,
+    /// The signer returned an error or an invalid response.
+    SignerError,
+    /// Slashing protection refused to sign the message.
+    SlashingProtection,
+    /// The validator is not yet permitted to sign by doppelganger protection.
+    Doppelganger,
+    /// This validator client does not hold the high-availability lease.
+    Standby,
+    /// The validator is no longer known to this validator client.
+    UnknownValidator,
+    Other,
+}
+
+/// A single attempt at a duty, as returned by `GET lighthouse/validators/{pubkey}/duties`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DutyRecord {
+    pub kind: DutyKind,
+    pub slot: Slot,
+    pub success: bool,
+    /// The beacon node which produced the data for the duty, if any.
+    pub beacon_node: Option<String>,
+    /// The time between starting the duty and it completing or failing.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub latency_ms: u64,
+    pub error_category: Option<DutyErrorCategory>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct DutyHistoryQuery {
+    /// The number of epochs of history to return, including the current epoch.
+    pub epochs: Option<u64>,
+}
@@ -21,7 +21,7 @@ use types::beacon_block_body::KzgCommitments;
 pub use types::*;
 
 #[cfg(feature = "lighthouse")]
-use crate::lighthouse::BlockReward;
+use crate::lighthouse::{BlockReward, EngineDisagreement};
 
 /// An API error serializable to JSON.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1077,6 +1077,8 @@ pub enum EventKind<T: EthSpec> {
     LightClientOptimisticUpdate(Box<LightClientOptimisticUpdate<T>>),
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
+    #[cfg(feature = "lighthouse")]
+    EngineDisagreement(EngineDisagreement),
     PayloadAttributes(VersionedSsePayloadAttributes),
 }
 
@@ -1097,6 +1099,8 @@ impl<T: EthSpec> EventKind<T> {
             EventKind::LightClientOptimisticUpdate(_) => "light_client_optimistic_update",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
+            #[cfg(feature = "lighthouse")]
+            EventKind::EngineDisagreement(_) => "engine_disagreement",
         }
     }
 
@@ -1177,6 +1181,12 @@ impl<T: EthSpec> EventKind<T> {
             "block_reward" => Ok(EventKind::BlockReward(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Reward: {:?}", e)),
             )?)),
+            #[cfg(feature = "lighthouse")]
+            "engine_disagreement" => Ok(EventKind::EngineDisagreement(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Engine Disagreement: {:?}", e))
+                })?,
+            )),
             _ => Err(ServerError::InvalidServerSentEvent(
                 "Could not parse event tag".to_string(),
             )),
@@ -1208,6 +1218,8 @@ pub enum EventTopic {
     LightClientOptimisticUpdate,
     #[cfg(feature = "lighthouse")]
     BlockReward,
+    #[cfg(feature = "lighthouse")]
+    EngineDisagreement,
 }
 
 impl FromStr for EventTopic {
@@ -1229,6 +1241,8 @@ impl FromStr for EventTopic {
             "light_client_optimistic_update" => Ok(EventTopic::LightClientOptimisticUpdate),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
+            #[cfg(feature = "lighthouse")]
+            "engine_disagreement" => Ok(EventTopic::EngineDisagreement),
             _ => Err("event topic cannot be parsed.".to_string()),
         }
     }
@@ -1251,6 +1265,8 @@ impl fmt::Display for EventTopic {
             EventTopic::LightClientOptimisticUpdate => write!(f, "light_client_optimistic_update"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
+            #[cfg(feature = "lighthouse")]
+            EventTopic::EngineDisagreement => write!(f, "engine_disagreement"),
         }
     }
 }
@@ -36,6 +36,11 @@ pub trait TSignature<GenericPublicKey>: Sized + Clone {
 
     /// Returns `true` if `self` is a signature across `msg` by `pubkey`.
     fn verify(&self, pubkey: &GenericPublicKey, msg: Hash256) -> bool;
+
+    /// Returns the sum of each signature multiplied by its scalar.
+    ///
+    /// Scalars are big-endian and less than the order of the BLS12-381 scalar field.
+    fn linear_combination(terms: &[([u8; 32], &Self)]) -> Result<Self, Error>;
 }
 
 /// A BLS signature that is generic across:
@@ -180,6 +180,44 @@ impl TSignature<blst_core::PublicKey> for blst_core::Signature {
         // Check Signature inside function for subgroup
         self.verify(true, msg.as_bytes(), DST, &[], pubkey, false) == BLST_ERROR::BLST_SUCCESS
     }
+
+    fn linear_combination(terms: &[([u8; 32], &Self)]) -> Result<Self, Error> {
+        let mut sum: Option<blst::blst_p2> = None;
+
+        for (scalar, signature) in terms {
+            // `blst` expects scalars in little-endian byte order.
+            let mut scalar_le = *scalar;
+            scalar_le.reverse();
+
+            let compressed = signature.compress();
+            let mut affine = blst::blst_p2_affine::default();
+            let mut point = blst::blst_p2::default();
+            let mut product = blst::blst_p2::default();
+
+            // `blst` does not provide a safe API for scalar multiplication of signatures.
+            unsafe {
+                let err = blst::blst_p2_uncompress(&mut affine, compressed.as_ptr());
+                if err != BLST_ERROR::BLST_SUCCESS {
+                    return Err(err.into());
+                }
+                blst::blst_p2_from_affine(&mut point, &affine);
+                blst::blst_p2_mult(&mut product, &point, scalar_le.as_ptr(), 255);
+                if let Some(sum) = sum.as_mut() {
+                    let previous = *sum;
+                    blst::blst_p2_add_or_double(sum, &previous, &product);
+                } else {
+                    sum = Some(product);
+                }
+            }
+        }
+
+        let sum = sum.ok_or_else(|| Error::InvalidThresholdShares("no shares provided".into()))?;
+        let mut compressed = [0; SIGNATURE_BYTES_LEN];
+        unsafe {
+            blst::blst_p2_compress(compressed.as_mut_ptr(), &sum);
+        }
+        Self::from_bytes(&compressed).map_err(Into::into)
+    }
 }
 
 /// A wrapper that allows for `PartialEq` and `Clone` impls.
@@ -105,6 +105,13 @@ impl TSignature<PublicKey> for Signature {
     fn verify(&self, _pubkey: &PublicKey, _msg: Hash256) -> bool {
         true
     }
+
+    fn linear_combination(terms: &[([u8; 32], &Self)]) -> Result<Self, Error> {
+        terms
+            .first()
+            .map(|(_, signature)| (*signature).clone())
+            .ok_or_else(|| Error::InvalidThresholdShares("no shares provided".into()))
+    }
 }
 
 impl PartialEq for Signature {
@@ -115,6 +115,11 @@ impl TSignature<milagro::PublicKey> for milagro::Signature {
     fn verify(&self, pubkey: &milagro::PublicKey, msg: Hash256) -> bool {
         self.verify(msg.as_bytes(), pubkey)
     }
+
+    /// Threshold signatures are only supported by the `supranational` backend.
+    fn linear_combination(_terms: &[([u8; 32], &Self)]) -> Result<Self, Error> {
+        Err(Error::UnsupportedOperation)
+    }
 }
 
 impl TAggregateSignature<milagro::PublicKey, milagro::AggregatePublicKey, milagro::Signature>
@@ -31,6 +31,8 @@ mod generic_signature;
 mod generic_signature_bytes;
 mod generic_signature_set;
 mod get_withdrawal_credentials;
+#[cfg(feature = "supranational")]
+mod threshold;
 mod zeroize_hash;
 
 pub mod impls;
@@ -64,6 +66,10 @@ pub enum Error {
     InvalidInfinityPublicKey,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
+    /// The shares or parameters of a threshold signature were invalid.
+    InvalidThresholdShares(String),
+    /// The operation is not supported by the selected BLS backend.
+    UnsupportedOperation,
 }
 
 #[cfg(feature = "milagro")]
@@ -0,0 +1,295 @@
+//! Provides M-of-N threshold signatures using Shamir secret sharing of a secret key.
+//!
+//! A secret key is split into `N` shares by evaluating a random polynomial of degree `M - 1` at
+//! the share indices `1..=N`. Any `M` signatures produced by the shares over the same message can
+//! be combined with Lagrange interpolation at zero into the signature of the original secret key.
+//!
+//! The scalar arithmetic is performed by `blst` in constant time, since the polynomial is derived
+//! from a secret key. The linear combination of signature points is deferred to the BLS backend
+//! via `TSignature::linear_combination`.
+use crate::{
+    generic_public_key::TPublicKey,
+    generic_secret_key::{GenericSecretKey, TSecretKey},
+    generic_signature::{GenericSignature, TSignature},
+    Error,
+};
+use blst::{blst_fr, blst_scalar};
+use rand::Rng;
+use std::collections::HashSet;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// The number of random bytes reduced into a random scalar, enough to make the bias negligible.
+const RANDOM_SCALAR_BYTES_LEN: usize = 64;
+
+/// An element of the BLS12-381 scalar field.
+///
+/// Scalars may be derived from a secret key, so they are zeroized on drop and deliberately not
+/// `Copy`.
+#[derive(Clone, PartialEq, Eq)]
+struct Scalar(blst_fr);
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.l.zeroize();
+    }
+}
+
+impl Drop for Scalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Scalar {}
+
+impl Scalar {
+    fn zero() -> Self {
+        Scalar(blst_fr::default())
+    }
+
+    fn one() -> Self {
+        Self::from_u64(1)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        let limbs = [value, 0, 0, 0];
+        let mut fr = blst_fr::default();
+        unsafe {
+            blst::blst_fr_from_uint64(&mut fr, limbs.as_ptr());
+        }
+        Scalar(fr)
+    }
+
+    /// Interprets `bytes` as a big-endian integer, reducing it modulo the field order.
+    fn from_be_bytes(bytes: &[u8]) -> Self {
+        let mut scalar = blst_scalar::default();
+        let mut fr = blst_fr::default();
+        unsafe {
+            blst::blst_scalar_from_be_bytes(&mut scalar, bytes.as_ptr(), bytes.len());
+            blst::blst_fr_from_scalar(&mut fr, &scalar);
+        }
+        scalar.b.zeroize();
+        Scalar(fr)
+    }
+
+    /// Returns a uniformly random non-zero scalar.
+    fn random() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut bytes = Zeroizing::new([0; RANDOM_SCALAR_BYTES_LEN]);
+        loop {
+            rng.fill(&mut bytes[..]);
+            let scalar = Self::from_be_bytes(&bytes[..]);
+            if scalar != Self::zero() {
+                return scalar;
+            }
+        }
+    }
+
+    fn to_be_bytes(&self) -> Zeroizing<[u8; 32]> {
+        let mut scalar = blst_scalar::default();
+        let mut bytes = Zeroizing::new([0; 32]);
+        unsafe {
+            blst::blst_scalar_from_fr(&mut scalar, &self.0);
+            blst::blst_bendian_from_scalar(bytes.as_mut_ptr(), &scalar);
+        }
+        scalar.b.zeroize();
+        bytes
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut sum = blst_fr::default();
+        unsafe {
+            blst::blst_fr_add(&mut sum, &self.0, &other.0);
+        }
+        Scalar(sum)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let mut difference = blst_fr::default();
+        unsafe {
+            blst::blst_fr_sub(&mut difference, &self.0, &other.0);
+        }
+        Scalar(difference)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut product = blst_fr::default();
+        unsafe {
+            blst::blst_fr_mul(&mut product, &self.0, &other.0);
+        }
+        Scalar(product)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        if *self == Self::zero() {
+            return None;
+        }
+        let mut inverse = blst_fr::default();
+        unsafe {
+            blst::blst_fr_inverse(&mut inverse, &self.0);
+        }
+        Some(Scalar(inverse))
+    }
+}
+
+/// Returns the Lagrange coefficients for interpolating at zero from the given share indices.
+fn lagrange_coefficients(indices: &[u64]) -> Result<Vec<Scalar>, Error> {
+    let mut seen = HashSet::with_capacity(indices.len());
+    for index in indices {
+        if *index == 0 {
+            return Err(Error::InvalidThresholdShares(
+                "share index must be non-zero".into(),
+            ));
+        }
+        if !seen.insert(*index) {
+            return Err(Error::InvalidThresholdShares(format!(
+                "duplicate share index {}",
+                index
+            )));
+        }
+    }
+
+    indices
+        .iter()
+        .map(|i| {
+            let x_i = Scalar::from_u64(*i);
+            let mut numerator = Scalar::one();
+            let mut denominator = Scalar::one();
+            for j in indices.iter().filter(|j| *j != i) {
+                let x_j = Scalar::from_u64(*j);
+                numerator = numerator.mul(&x_j);
+                denominator = denominator.mul(&x_j.sub(&x_i));
+            }
+            let inverse = denominator.invert().ok_or_else(|| {
+                Error::InvalidThresholdShares("share indices are not distinct".into())
+            })?;
+            Ok(numerator.mul(&inverse))
+        })
+        .collect()
+}
+
+impl<Sig, Pub, Sec> GenericSecretKey<Sig, Pub, Sec>
+where
+    Sig: TSignature<Pub>,
+    Pub: TPublicKey,
+    Sec: TSecretKey<Sig, Pub>,
+{
+    /// Splits `self` into `share_count` shares, any `threshold` of which can produce signatures
+    /// that combine into a signature by `self`.
+    ///
+    /// Returns `(index, share)` pairs. The index of a share is required to combine its signatures.
+    pub fn split_shares(
+        &self,
+        threshold: usize,
+        share_count: usize,
+    ) -> Result<Vec<(u64, Self)>, Error> {
+        if threshold == 0 || threshold > share_count {
+            return Err(Error::InvalidThresholdShares(format!(
+                "threshold {} must be between 1 and the share count {}",
+                threshold, share_count
+            )));
+        }
+
+        // Allocate the coefficients up front so that they are never copied by a reallocation;
+        // each one is zeroized when it is dropped.
+        let mut coefficients = Vec::with_capacity(threshold);
+        coefficients.push(Scalar::from_be_bytes(self.serialize().as_bytes()));
+        coefficients.extend((1..threshold).map(|_| Scalar::random()));
+
+        (1..=share_count as u64)
+            .map(|index| {
+                // Evaluate the polynomial at `index` using Horner's method.
+                let x = Scalar::from_u64(index);
+                let share = coefficients
+                    .iter()
+                    .rev()
+                    .fold(Scalar::zero(), |acc, coefficient| {
+                        acc.mul(&x).add(coefficient)
+                    });
+                Ok((index, Self::deserialize(&share.to_be_bytes()[..])?))
+            })
+            .collect()
+    }
+}
+
+impl<Pub, Sig> GenericSignature<Pub, Sig>
+where
+    Sig: TSignature<Pub>,
+{
+    /// Combines `(index, signature)` pairs produced by distinct secret key shares into the
+    /// signature of the original secret key.
+    ///
+    /// At least `threshold` valid shares must be supplied for the result to be valid. The shares
+    /// are not verified here, callers should verify each share and the combined signature.
+    pub fn combine_shares(shares: &[(u64, Self)]) -> Result<Self, Error> {
+        if shares.is_empty() {
+            return Err(Error::InvalidThresholdShares("no shares provided".into()));
+        }
+
+        let indices = shares.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+        let coefficients = lagrange_coefficients(&indices)?;
+
+        let terms = shares
+            .iter()
+            .zip(coefficients)
+            .map(|((index, signature), coefficient)| {
+                signature
+                    .point()
+                    .map(|point| (*coefficient.to_be_bytes(), point))
+                    .ok_or_else(|| {
+                        Error::InvalidThresholdShares(format!("share {} is empty", index))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let point = Sig::linear_combination(&terms)?;
+        let is_infinity = point.serialize() == crate::INFINITY_SIGNATURE;
+        Ok(Self::from_point(point, is_infinity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_arithmetic() {
+        let a = Scalar::from_u64(7);
+        let b = Scalar::from_u64(5);
+        assert!(a.add(&b) == Scalar::from_u64(12));
+        assert!(a.sub(&b) == Scalar::from_u64(2));
+        assert!(a.mul(&b) == Scalar::from_u64(35));
+        assert!(b.sub(&a).add(&Scalar::from_u64(2)) == Scalar::zero());
+
+        let inverse = a.invert().unwrap();
+        assert!(a.mul(&inverse) == Scalar::one());
+        assert!(Scalar::zero().invert().is_none());
+
+        let random = Scalar::random();
+        assert!(random.mul(&random.invert().unwrap()) == Scalar::one());
+        assert!(Scalar::from_be_bytes(&random.to_be_bytes()[..]) == random);
+    }
+
+    #[test]
+    fn lagrange_coefficients_sum_to_one() {
+        // Interpolating the constant polynomial `f(x) = 1` at zero yields one.
+        let coefficients = lagrange_coefficients(&[1, 3, 4]).unwrap();
+        let sum = coefficients
+            .iter()
+            .fold(Scalar::zero(), |acc, coefficient| acc.add(coefficient));
+        assert!(sum == Scalar::one());
+    }
+
+    #[test]
+    fn scalars_are_zeroized() {
+        let mut scalar = Scalar::random();
+        scalar.zeroize();
+        assert!(scalar == Scalar::zero());
+    }
+
+    #[test]
+    fn invalid_share_indices() {
+        assert!(lagrange_coefficients(&[0, 1]).is_err());
+        assert!(lagrange_coefficients(&[2, 2]).is_err());
+    }
+}
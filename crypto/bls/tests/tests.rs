@@ -508,6 +508,46 @@ macro_rules! test_suite {
 
 mod blst {
     test_suite!(blst_implementations);
+
+    #[test]
+    fn threshold_signature_from_any_m_shares() {
+        let secret = SecretKey::random();
+        let public = secret.public_key();
+        let msg = Hash256::from_low_u64_be(42);
+        let shares = secret.split_shares(3, 5).unwrap();
+
+        let partials = shares
+            .iter()
+            .map(|(index, share)| (*index, share.sign(msg)))
+            .collect::<Vec<_>>();
+
+        for subset in [&[0, 1, 2][..], &[4, 2, 0], &[1, 3, 4, 2]] {
+            let chosen = subset
+                .iter()
+                .map(|i| partials[*i].clone())
+                .collect::<Vec<_>>();
+            let combined = Signature::combine_shares(&chosen).unwrap();
+            assert!(combined.verify(&public, msg));
+            assert_eq!(combined, secret.sign(msg));
+        }
+
+        // Fewer than the threshold does not produce a valid signature.
+        let combined = Signature::combine_shares(&partials[..2]).unwrap();
+        assert!(!combined.verify(&public, msg));
+    }
+
+    #[test]
+    fn threshold_invalid_parameters() {
+        let secret = SecretKey::random();
+        assert!(secret.split_shares(0, 3).is_err());
+        assert!(secret.split_shares(4, 3).is_err());
+
+        let msg = Hash256::from_low_u64_be(1);
+        let sig = secret.sign(msg);
+        assert!(Signature::combine_shares(&[]).is_err());
+        assert!(Signature::combine_shares(&[(0, sig.clone())]).is_err());
+        assert!(Signature::combine_shares(&[(1, sig.clone()), (1, sig)]).is_err());
+    }
 }
 
 #[cfg(all(feature = "milagro", not(debug_assertions)))]
@@ -15,6 +15,7 @@ mod mock_el;
 mod new_testnet;
 mod parse_ssz;
 mod replace_state_pubkeys;
+mod replay_invalid;
 mod skip_slots;
 mod state_root;
 mod transition_blocks;
@@ -892,6 +893,67 @@ fn main() {
                         .help("Number of repeat runs, useful for benchmarking."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("replay-invalid")
+                .about("Re-runs verification of an invalid gossip object stored by a beacon node \
+                    using --invalid-gossip-verified-blocks-path against a given pre-state, and \
+                    prints the reason it is rejected.")
+                .arg(
+                    Arg::with_name("object-path")
+                        .long("object-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the stored object, either its .ssz or .json file."),
+                )
+                .arg(
+                    Arg::with_name("pre-state-path")
+                        .long("pre-state-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the BeaconState of a checkpoint from which to verify the \
+                            object, as SSZ. The object must be from a slot after the first epoch \
+                            boundary at or after this state. The finalized state is a good choice."),
+                )
+                .arg(
+                    Arg::with_name("pre-block-path")
+                        .long("pre-block-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the SignedBeaconBlock of the checkpoint, as SSZ. The \
+                            pre-state must be the post-state of this block."),
+                )
+                .arg(
+                    Arg::with_name("block-path")
+                        .long("block-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Path to a SignedBeaconBlock to import after the checkpoint and \
+                            before verifying the object, as SSZ. May be supplied multiple times."),
+                )
+                .arg(
+                    Arg::with_name("blobs-path")
+                        .long("blobs-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Path to the list of BlobSidecars of a block supplied with \
+                            --block-path, as SSZ. May be supplied multiple times."),
+                )
+                .arg(
+                    Arg::with_name("genesis-state-path")
+                        .long("genesis-state-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to the genesis BeaconState, as SSZ. Defaults to the genesis \
+                            state of the network, which may be downloaded."),
+                )
+        )
         .subcommand(
             SubCommand::with_name("mock-el")
                 .about("Creates a mock execution layer server. This is NOT SAFE and should only \
@@ -921,6 +983,40 @@ fn main() {
                         .help("The server will listen on this port.")
                         .default_value("8551")
                 )
+                .arg(
+                    Arg::with_name("ipc-path")
+                        .long("ipc-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("If provided, the server will also listen for IPC connections on \
+                            a Unix domain socket at this path.")
+                )
+                .arg(
+                    Arg::with_name("replay")
+                        .long("replay")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Path to an engine API traffic recording, as written by the beacon \
+                            node's --execution-traffic-record-dir flag. Responses are served from \
+                            the recording rather than generated.")
+                )
+                .arg(
+                    Arg::with_name("replay-timing")
+                        .long("replay-timing")
+                        .takes_value(false)
+                        .requires("replay")
+                        .help("Delay each replayed response by the time the original response \
+                            took.")
+                )
+                .arg(
+                    Arg::with_name("scenario")
+                        .long("scenario")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Path to a YAML or JSON fault-injection scenario. Its rules can \
+                            override or delay responses and re-org the execution chain, selected \
+                            by method, block hash, time window and request count.")
+                )
                 .arg(
                     Arg::with_name("all-payloads-valid")
                         .long("all-payloads-valid")
@@ -1088,6 +1184,11 @@ fn run<T: EthSpec>(
             state_root::run::<T>(env, network_config, matches)
                 .map_err(|e| format!("Failed to run state-root command: {}", e))
         }
+        ("replay-invalid", Some(matches)) => {
+            let network_config = get_network_config()?;
+            replay_invalid::run::<T>(env, network_config, matches)
+                .map_err(|e| format!("Failed to run replay-invalid command: {}", e))
+        }
         ("mock-el", Some(matches)) => mock_el::run::<T>(env, matches)
             .map_err(|e| format!("Failed to run mock-el command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
@@ -4,7 +4,8 @@ use environment::Environment;
 use execution_layer::{
     auth::JwtKey,
     test_utils::{
-        Config, MockExecutionConfig, MockServer, DEFAULT_JWT_SECRET, DEFAULT_TERMINAL_BLOCK,
+        Config, MockExecutionConfig, MockServer, Replay, Scenario, DEFAULT_JWT_SECRET,
+        DEFAULT_TERMINAL_BLOCK,
     },
 };
 use std::net::Ipv4Addr;
@@ -15,6 +16,10 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<
     let jwt_path: PathBuf = parse_required(matches, "jwt-output-path")?;
     let listen_addr: Ipv4Addr = parse_required(matches, "listen-address")?;
     let listen_port: u16 = parse_required(matches, "listen-port")?;
+    let ipc_path: Option<PathBuf> = parse_optional(matches, "ipc-path")?;
+    let replay_path: Option<PathBuf> = parse_optional(matches, "replay")?;
+    let replay_timing = matches.is_present("replay-timing");
+    let scenario_path: Option<PathBuf> = parse_optional(matches, "scenario")?;
     let all_payloads_valid: bool = parse_required(matches, "all-payloads-valid")?;
     let shanghai_time = parse_required(matches, "shanghai-time")?;
     let cancun_time = parse_optional(matches, "cancun-time")?;
@@ -28,6 +33,7 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<
         server_config: Config {
             listen_addr,
             listen_port,
+            ipc_path: ipc_path.clone(),
         },
         jwt_key,
         terminal_difficulty: spec.terminal_total_difficulty,
@@ -48,11 +54,34 @@ pub fn run<T: EthSpec>(mut env: Environment<T>, matches: &ArgMatches) -> Result<
         server.all_payloads_valid();
     }
 
+    if let Some(replay_path) = replay_path {
+        let replay = Replay::from_file(&replay_path, replay_timing)?;
+        eprintln!(
+            "Replaying {} engine API exchanges from {}",
+            replay.remaining(),
+            replay_path.display()
+        );
+        server.set_replay(replay);
+    }
+
+    if let Some(scenario_path) = scenario_path {
+        let scenario = Scenario::from_file(&scenario_path)?;
+        eprintln!(
+            "Injecting faults from {} rules in {}",
+            scenario.rules.len(),
+            scenario_path.display()
+        );
+        server.set_scenario(scenario);
+    }
+
     eprintln!(
         "This tool is for TESTING PURPOSES ONLY. Do not use in production or on mainnet. \
         It cannot perform validator duties. It may cause nodes to follow an invalid chain."
     );
     eprintln!("Server listening on {}:{}", listen_addr, listen_port);
+    if let Some(ipc_path) = ipc_path {
+        eprintln!("Server listening on ipc://{}", ipc_path.display());
+    }
 
     let shutdown_reason = env.block_until_shutdown_requested()?;
 
@@ -0,0 +1,554 @@
+//! # Replay Invalid
+//!
+//! Use this tool to re-run verification of a gossip object that was archived by a beacon node
+//! started with `--invalid-gossip-verified-blocks-path`, printing the reason it is rejected.
+//!
+//! The object is verified by the same gossip (and, for blocks, full block) verification that the
+//! beacon node runs, against a temporary in-memory `BeaconChain`. The chain is started from a
+//! checkpoint given by `--pre-state-path` and `--pre-block-path`, such as the finalized state and
+//! block served by `/eth/v2/debug/beacon/states/finalized` and `/eth/v2/beacon/blocks/finalized`.
+//! The blocks between the checkpoint and the object may be supplied with `--block-path` (and their
+//! blobs with `--blobs-path`), and are imported before the object is verified.
+//!
+//! Since the chain is anchored at the checkpoint, the object (and any supplied blocks) must be from
+//! a slot after the first epoch boundary at or after the checkpoint state.
+//!
+//! The chain uses a mock execution engine which considers every payload valid, so rejections by
+//! the execution engine are not reproduced. The chain's observation caches are empty, so objects
+//! ignored as duplicates by the beacon node will be verified in full.
+//!
+//! Logging output is controlled via the `RUST_LOG` environment variable. For example, `export
+//! RUST_LOG=debug`.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! lcli replay-invalid \
+//!     --object-path /tmp/invalid/block/1234_0x6c69...bec.ssz \
+//!     --pre-state-path /tmp/finalized-state.ssz \
+//!     --pre-block-path /tmp/finalized-block.ssz \
+//!     --block-path /tmp/block-1217.ssz \
+//!     --blobs-path /tmp/blobs-1217.ssz
+//! ```
+use crate::transition_blocks::load_from_ssz_with;
+use beacon_chain::blob_verification::GossipVerifiedBlob;
+use beacon_chain::block_verification_types::RpcBlock;
+use beacon_chain::slot_clock::{SlotClock, TestingSlotClock};
+use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+use beacon_chain::{
+    AvailabilityProcessingStatus, BeaconChain, BeaconChainTypes, GossipVerifiedBlock,
+    NotifyExecutionLayer,
+};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2_network_config::Eth2NetworkConfig;
+use lighthouse_network::types::{
+    InvalidObjectKind, InvalidObjectMetadata, INVALID_OBJECT_METADATA_EXTENSION,
+    INVALID_OBJECT_SSZ_EXTENSION,
+};
+use ssz::Decode;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use types::{
+    Attestation, BeaconState, BlobSidecar, BlobSidecarList, ChainSpec, EthSpec, Hash256,
+    SignedAggregateAndProof, SignedBeaconBlock, SignedBlsToExecutionChange, Slot, SubnetId,
+    VariableList,
+};
+
+const GENESIS_STATE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// The outcome of verifying an object against the replay chain.
+#[derive(Debug, PartialEq)]
+enum ReplayResult {
+    Valid,
+    Rejected(String),
+}
+
+/// The checkpoint from which the replay chain is started, and the blocks to import on top of it.
+struct ReplayAnchor<E: EthSpec> {
+    genesis_state: BeaconState<E>,
+    state: BeaconState<E>,
+    block: SignedBeaconBlock<E>,
+    blocks: Vec<SignedBeaconBlock<E>>,
+    blobs: Vec<Arc<BlobSidecar<E>>>,
+}
+
+pub fn run<T: EthSpec>(
+    env: Environment<T>,
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = network_config.chain_spec::<T>()?;
+
+    let object_path: PathBuf = parse_required(matches, "object-path")?;
+    let pre_state_path: PathBuf = parse_required(matches, "pre-state-path")?;
+    let pre_block_path: PathBuf = parse_required(matches, "pre-block-path")?;
+    let genesis_state_path: Option<PathBuf> = parse_optional(matches, "genesis-state-path")?;
+    let block_paths: Vec<PathBuf> = matches
+        .values_of("block-path")
+        .map_or(vec![], |paths| paths.map(PathBuf::from).collect());
+    let blobs_paths: Vec<PathBuf> = matches
+        .values_of("blobs-path")
+        .map_or(vec![], |paths| paths.map(PathBuf::from).collect());
+
+    info!("Object path: {:?}", object_path);
+    info!("Pre-state path: {:?}", pre_state_path);
+    info!("Pre-block path: {:?}", pre_block_path);
+
+    let metadata = load_metadata(&object_path)?;
+
+    println!("Kind: {}", metadata.kind.as_ref());
+    println!("Root: {:?}", metadata.root);
+    if let Some(slot) = metadata.slot {
+        println!("Slot: {}", slot);
+    }
+    println!("Peer: {}", metadata.peer_id);
+    println!("Topic: {}", metadata.topic);
+    println!("Seen timestamp (ms): {}", metadata.seen_timestamp_ms);
+    println!("Recorded error: {}", metadata.error);
+
+    let state = load_from_ssz_with(&pre_state_path, &spec, BeaconState::from_ssz_bytes)?;
+    let block = load_from_ssz_with(&pre_block_path, &spec, SignedBeaconBlock::from_ssz_bytes)?;
+    let blocks = block_paths
+        .iter()
+        .map(|path| load_from_ssz_with(path, &spec, SignedBeaconBlock::from_ssz_bytes))
+        .collect::<Result<Vec<_>, _>>()?;
+    let blobs = blobs_paths
+        .iter()
+        .map(|path| {
+            load_from_ssz_with(path, &spec, |bytes, _| {
+                BlobSidecarList::<T>::from_ssz_bytes(bytes)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let log = env.core_context().log().clone();
+    let result = env.runtime().block_on(async {
+        let genesis_state = if let Some(path) = genesis_state_path {
+            load_from_ssz_with(&path, &spec, BeaconState::from_ssz_bytes)?
+        } else {
+            network_config
+                .genesis_state::<T>(None, GENESIS_STATE_TIMEOUT, &log)
+                .await?
+                .ok_or("Genesis state is unknown for this network, use --genesis-state-path")?
+        };
+
+        let anchor = ReplayAnchor {
+            genesis_state,
+            state,
+            block,
+            blocks,
+            blobs,
+        };
+        let harness = build_harness(spec, anchor).await?;
+        replay(&harness, &metadata, &object_path).await
+    })?;
+
+    match result {
+        ReplayResult::Valid => println!("Replay result: valid"),
+        ReplayResult::Rejected(e) => println!("Replay result: rejected: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Loads the metadata stored alongside the object at `object_path`.
+fn load_metadata(object_path: &Path) -> Result<InvalidObjectMetadata, String> {
+    let metadata_path = object_path.with_extension(INVALID_OBJECT_METADATA_EXTENSION);
+    info!("Metadata path: {:?}", metadata_path);
+
+    File::open(&metadata_path)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", metadata_path, e))
+        .and_then(|file| {
+            serde_json::from_reader(file)
+                .map_err(|e| format!("Unable to parse {:?}: {:?}", metadata_path, e))
+        })
+}
+
+/// Starts an in-memory chain from `anchor` and imports the blocks it contains.
+async fn build_harness<E: EthSpec>(
+    spec: ChainSpec,
+    anchor: ReplayAnchor<E>,
+) -> Result<BeaconChainHarness<EphemeralHarnessType<E>>, String> {
+    let ReplayAnchor {
+        genesis_state,
+        mut state,
+        block,
+        mut blocks,
+        blobs,
+    } = anchor;
+
+    // Check the anchor here, since the chain builder panics if it is inconsistent.
+    let state_root = state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("Unable to compute pre-state root: {:?}", e))?;
+    if state.get_latest_block_root(state_root) != block.canonical_root() {
+        return Err("The pre-state is not the post-state of the pre-block".into());
+    }
+    if state.genesis_validators_root() != genesis_state.genesis_validators_root() {
+        return Err("The pre-state is not from the same network as the genesis state".into());
+    }
+
+    // The chain starts from the first epoch boundary at or after the pre-state.
+    let slots_per_epoch = E::slots_per_epoch();
+    let anchor_slot = if state.slot() % slots_per_epoch == 0 {
+        state.slot()
+    } else {
+        (state.current_epoch() + 1).start_slot(slots_per_epoch)
+    };
+    let genesis_time = genesis_state.genesis_time();
+    let slot_clock = TestingSlotClock::new(
+        spec.genesis_slot,
+        Duration::from_secs(genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+    slot_clock.set_slot(anchor_slot.as_u64());
+
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .keypairs(vec![])
+        .fresh_ephemeral_store()
+        .override_store_mutator(Box::new(move |builder| {
+            builder
+                .weak_subjectivity_state(state, block, genesis_state)
+                .expect("should start from the pre-state")
+        }))
+        .testing_slot_clock(slot_clock)
+        .mock_execution_layer()
+        .mock_execution_layer_all_payloads_valid()
+        .recalculate_fork_times_with_genesis(genesis_time)
+        .build();
+
+    let mut blobs_by_root: HashMap<Hash256, Vec<Arc<BlobSidecar<E>>>> = HashMap::new();
+    for blob in blobs {
+        blobs_by_root
+            .entry(blob.block_root())
+            .or_default()
+            .push(blob);
+    }
+
+    blocks.sort_by_key(|block| block.slot());
+    for block in blocks {
+        let block_root = block.canonical_root();
+        let block_blobs = blobs_by_root
+            .remove(&block_root)
+            .map(|mut blobs| {
+                blobs.sort_by_key(|blob| blob.index);
+                VariableList::new(blobs)
+            })
+            .transpose()
+            .map_err(|e| format!("Too many blobs for block {:?}: {:?}", block_root, e))?;
+        let rpc_block = RpcBlock::new(Some(block_root), Arc::new(block), block_blobs)
+            .map_err(|e| format!("Invalid blobs for block {:?}: {:?}", block_root, e))?;
+
+        harness.set_current_slot(rpc_block.as_block().slot());
+        match harness
+            .chain
+            .process_block(block_root, rpc_block, NotifyExecutionLayer::Yes, || Ok(()))
+            .await
+        {
+            Ok(AvailabilityProcessingStatus::Imported(_)) => {
+                debug!("Imported block {:?}", block_root);
+            }
+            Ok(AvailabilityProcessingStatus::MissingComponents(..)) => {
+                return Err(format!(
+                    "Unable to import block {:?}, its blobs must be supplied with --blobs-path",
+                    block_root
+                ));
+            }
+            Err(e) => {
+                return Err(format!("Unable to import block {:?}: {:?}", block_root, e));
+            }
+        }
+    }
+    harness.chain.recompute_head_at_current_slot().await;
+
+    Ok(harness)
+}
+
+/// Verifies the object described by `metadata` against the chain of `harness`.
+async fn replay<E: EthSpec>(
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    metadata: &InvalidObjectMetadata,
+    object_path: &Path,
+) -> Result<ReplayResult, String> {
+    let chain = &harness.chain;
+    let spec = &harness.spec;
+    let ssz_path = object_path.with_extension(INVALID_OBJECT_SSZ_EXTENSION);
+    info!("Object SSZ path: {:?}", ssz_path);
+
+    // Verify the object as if it were received during its own slot.
+    if let Some(slot) = metadata.slot {
+        if slot > harness.get_current_slot() {
+            harness.set_current_slot(slot);
+        }
+    }
+
+    let result = match metadata.kind {
+        InvalidObjectKind::Block => {
+            let block = load_from_ssz_with(&ssz_path, spec, SignedBeaconBlock::from_ssz_bytes)?;
+            replay_block(chain, Arc::new(block)).await
+        }
+        InvalidObjectKind::BlobSidecar => {
+            let blob = load_from_ssz_with(&ssz_path, spec, |bytes, _| {
+                BlobSidecar::from_ssz_bytes(bytes)
+            })?;
+            let subnet = topic_index(&metadata.topic)?;
+            match GossipVerifiedBlob::new(Arc::new(blob), subnet, chain) {
+                Ok(_) => ReplayResult::Valid,
+                Err(e) => ReplayResult::Rejected(format!("{:?}", e)),
+            }
+        }
+        InvalidObjectKind::Attestation => {
+            let attestation = load_from_ssz_with(&ssz_path, spec, |bytes, _| {
+                Attestation::from_ssz_bytes(bytes)
+            })?;
+            let subnet_id = SubnetId::new(topic_index(&metadata.topic)?);
+            match chain.verify_unaggregated_attestation_for_gossip(&attestation, Some(subnet_id)) {
+                Ok(_) => ReplayResult::Valid,
+                Err(e) => ReplayResult::Rejected(format!("{:?}", e)),
+            }
+        }
+        InvalidObjectKind::AggregateAndProof => {
+            let aggregate = load_from_ssz_with(&ssz_path, spec, |bytes, _| {
+                SignedAggregateAndProof::from_ssz_bytes(bytes)
+            })?;
+            match chain.verify_aggregated_attestation_for_gossip(&aggregate) {
+                Ok(_) => ReplayResult::Valid,
+                Err(e) => ReplayResult::Rejected(format!("{:?}", e)),
+            }
+        }
+        InvalidObjectKind::BlsToExecutionChange => {
+            let change = load_from_ssz_with(&ssz_path, spec, |bytes, _| {
+                SignedBlsToExecutionChange::from_ssz_bytes(bytes)
+            })?;
+            match chain.verify_bls_to_execution_change_for_gossip(change) {
+                Ok(_) => ReplayResult::Valid,
+                Err(e) => ReplayResult::Rejected(format!("{:?}", e)),
+            }
+        }
+    };
+
+    Ok(result)
+}
+
+/// Runs gossip verification and then full verification of `block`, as the beacon node does.
+async fn replay_block<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+) -> ReplayResult {
+    let block_root = block.canonical_root();
+    let gossip_verified_block = match GossipVerifiedBlock::new(block, chain) {
+        Ok(block) => block,
+        Err(e) => return ReplayResult::Rejected(format!("{:?}", e)),
+    };
+
+    match chain
+        .process_block(
+            block_root,
+            gossip_verified_block,
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+    {
+        Ok(AvailabilityProcessingStatus::Imported(_)) => ReplayResult::Valid,
+        Ok(AvailabilityProcessingStatus::MissingComponents(..)) => {
+            info!("Block is valid but its blobs were not supplied");
+            ReplayResult::Valid
+        }
+        Err(e) => ReplayResult::Rejected(format!("{:?}", e)),
+    }
+}
+
+/// Returns the subnet (or blob index) of a topic such as `beacon_attestation_3`.
+fn topic_index(topic: &str) -> Result<u64, String> {
+    topic
+        .rsplit('_')
+        .next()
+        .and_then(|index| index.parse().ok())
+        .ok_or_else(|| format!("Unable to parse subnet from topic {}", topic))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{AttestationStrategy, BlockStrategy};
+    use beacon_chain::WhenSlotSkipped;
+    use ssz::Encode;
+    use std::fs;
+    use tempfile::tempdir;
+    use tree_hash::TreeHash;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 32;
+
+    /// Stores `object` and its `metadata` in `base_dir` in the beacon node's archive layout,
+    /// returning the path of the object.
+    fn archive<O: Encode>(
+        base_dir: &Path,
+        metadata: &InvalidObjectMetadata,
+        object: &O,
+    ) -> PathBuf {
+        fs::create_dir_all(metadata.dir(base_dir)).unwrap();
+        fs::write(
+            metadata.metadata_path(base_dir),
+            serde_json::to_vec_pretty(metadata).unwrap(),
+        )
+        .unwrap();
+        let ssz_path = metadata.ssz_path(base_dir);
+        fs::write(&ssz_path, object.as_ssz_bytes()).unwrap();
+        ssz_path
+    }
+
+    fn metadata(
+        kind: InvalidObjectKind,
+        slot: Slot,
+        root: Hash256,
+        topic: &str,
+    ) -> InvalidObjectMetadata {
+        InvalidObjectMetadata {
+            kind,
+            slot: Some(slot),
+            root,
+            peer_id: "16Uiu2HAkwG1Fvy1j3LbJjfBT8LPAWhUsY5w6W9VJW6ZJtQmUpnJb".into(),
+            topic: topic.into(),
+            error: "recorded".into(),
+            seen_timestamp_ms: 0,
+        }
+    }
+
+    /// Produces a chain up to slot 10, and a replay chain anchored at the block at slot 8 with
+    /// the block at slot 9 imported on top.
+    async fn harnesses() -> (
+        BeaconChainHarness<EphemeralHarnessType<E>>,
+        BeaconChainHarness<EphemeralHarnessType<E>>,
+    ) {
+        let harness = BeaconChainHarness::builder(E)
+            .default_spec()
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                9,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        let chain = &harness.chain;
+        let block_at_slot = |slot| async move {
+            let block_root = chain
+                .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+                .unwrap()
+                .unwrap();
+            chain.get_block(&block_root).await.unwrap().unwrap()
+        };
+        let anchor_block = block_at_slot(8).await;
+        let anchor_state = chain
+            .get_state(&anchor_block.state_root(), Some(anchor_block.slot()))
+            .unwrap()
+            .unwrap();
+        let genesis_state = chain
+            .get_state(&chain.genesis_state_root, Some(Slot::new(0)))
+            .unwrap()
+            .unwrap();
+
+        let anchor = ReplayAnchor {
+            genesis_state,
+            state: anchor_state,
+            block: anchor_block,
+            blocks: vec![block_at_slot(9).await],
+            blobs: vec![],
+        };
+        let replay_harness = build_harness(harness.spec.clone(), anchor).await.unwrap();
+        assert_eq!(replay_harness.head_slot(), Slot::new(9));
+
+        (harness, replay_harness)
+    }
+
+    #[tokio::test]
+    async fn replay_invalid_block() {
+        let (harness, replay_harness) = harnesses().await;
+        let base_dir = tempdir().unwrap();
+
+        let slot = Slot::new(10);
+        harness.set_current_slot(slot);
+        let ((block, _), _) = harness
+            .make_block_with_modifier(harness.get_current_state(), slot, |block| {
+                *block.state_root_mut() = Hash256::repeat_byte(42)
+            })
+            .await;
+        let metadata = metadata(
+            InvalidObjectKind::Block,
+            slot,
+            block.canonical_root(),
+            "beacon_block",
+        );
+        let object_path = archive(base_dir.path(), &metadata, block.as_ref());
+
+        let loaded = load_metadata(&object_path).unwrap();
+        assert_eq!(loaded, metadata);
+        match replay(&replay_harness, &loaded, &object_path)
+            .await
+            .unwrap()
+        {
+            ReplayResult::Rejected(e) => assert!(e.starts_with("StateRootMismatch"), "{}", e),
+            ReplayResult::Valid => panic!("invalid block should be rejected"),
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_valid_attestation() {
+        let (harness, replay_harness) = harnesses().await;
+        let base_dir = tempdir().unwrap();
+
+        let slot = Slot::new(10);
+        harness.set_current_slot(slot);
+        let (state, state_root) = harness.get_current_state_and_root();
+        let (attestation, subnet_id) = harness
+            .make_unaggregated_attestations(
+                &harness.get_all_validators(),
+                &state,
+                state_root,
+                harness.head_block_root().into(),
+                slot,
+            )
+            .remove(0)
+            .remove(0);
+        let metadata = metadata(
+            InvalidObjectKind::Attestation,
+            slot,
+            attestation.tree_hash_root(),
+            &format!("beacon_attestation_{}", *subnet_id),
+        );
+        let object_path = archive(base_dir.path(), &metadata, &attestation);
+
+        let loaded = load_metadata(&object_path).unwrap();
+        assert_eq!(
+            replay(&replay_harness, &loaded, &object_path)
+                .await
+                .unwrap(),
+            ReplayResult::Valid
+        );
+    }
+
+    #[test]
+    fn parse_topic_index() {
+        assert_eq!(topic_index("beacon_attestation_12"), Ok(12));
+        assert_eq!(topic_index("blob_sidecar_3"), Ok(3));
+        assert!(topic_index("beacon_block").is_err());
+    }
+}
@@ -3,6 +3,7 @@ use account_manager::{
         create::*,
         import::{self, CMD as IMPORT_CMD},
         modify::{ALL, CMD as MODIFY_CMD, DISABLE, ENABLE, PUBKEY_FLAG},
+        slashing_protection::{AUDIT_CMD, CMD as SLASHING_PROTECTION_CMD, PRUNE_CMD},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
@@ -25,7 +26,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Output, Stdio};
 use std::str::from_utf8;
 use tempfile::{tempdir, TempDir};
-use types::{Keypair, PublicKey};
+use types::{Epoch, Hash256, Keypair, PublicKey, Slot};
 use validator_dir::ValidatorDir;
 
 /// Returns the `lighthouse account` command.
@@ -499,6 +500,7 @@ fn validator_import_launchpad() {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
@@ -623,6 +625,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: None,
+            voting_keystore_password_source: None,
         },
     };
 
@@ -651,6 +654,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
             voting_keystore_path: dst_keystore_dir.join(KEYSTORE_NAME),
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
@@ -754,6 +758,7 @@ fn validator_import_launchpad_password_file() {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
@@ -773,3 +778,149 @@ fn check_slashing_protection(validator_dir: &TempDir, pubkeys: impl Iterator<Ite
             .unwrap();
     }
 }
+
+/// Returns the `lighthouse account validator slashing-protection` command for `validator_dir`.
+fn slashing_protection_cmd(validator_dir: &TempDir) -> Command {
+    let mut cmd = validator_cmd();
+    cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(validator_dir.path().as_os_str())
+        .arg(SLASHING_PROTECTION_CMD);
+    cmd
+}
+
+/// Returns a validator definition for `voting_public_key` which is never used to sign.
+fn unused_validator_definition(
+    validator_dir: &TempDir,
+    voting_public_key: PublicKey,
+) -> ValidatorDefinition {
+    ValidatorDefinition {
+        enabled: true,
+        description: "".into(),
+        voting_public_key,
+        graffiti: None,
+        suggested_fee_recipient: None,
+        gas_limit: None,
+        builder_proposals: None,
+        builder_boost_factor: None,
+        prefer_builder_proposals: None,
+        signing_definition: SigningDefinition::LocalKeystore {
+            voting_keystore_path: validator_dir.path().join("voting-keystore.json"),
+            voting_keystore_password_path: None,
+            voting_keystore_password: None,
+            voting_keystore_password_source: None,
+        },
+    }
+}
+
+#[test]
+fn slashing_protection_prune() {
+    let validator_dir = tempdir().unwrap();
+    let slashing_db_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
+    let pubkey = Keypair::random().pk.compress();
+
+    let slashing_db = SlashingDatabase::create(&slashing_db_path).unwrap();
+    slashing_db.register_validator(pubkey).unwrap();
+    for slot in [1, 3, 7] {
+        slashing_db
+            .check_and_insert_block_signing_root(&pubkey, Slot::new(slot), Default::default())
+            .unwrap();
+    }
+    for (source, target) in [(0, 1), (1, 2), (2, 5)] {
+        slashing_db
+            .check_and_insert_attestation_signing_root(
+                &pubkey,
+                Epoch::new(source),
+                Epoch::new(target),
+                Default::default(),
+            )
+            .unwrap();
+    }
+    drop(slashing_db);
+
+    let output = output_result(slashing_protection_cmd(&validator_dir).arg(PRUNE_CMD)).unwrap();
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Retained the latest records of 1 validators"));
+    assert!(stderr.contains("latest proposed block: slot 7"));
+
+    // Only the maximum block and attestation should remain.
+    let slashing_db = SlashingDatabase::open(&slashing_db_path).unwrap();
+    let interchange = slashing_db
+        .export_all_interchange_info(Hash256::zero())
+        .unwrap();
+    assert_eq!(interchange.data.len(), 1);
+    let record = &interchange.data[0];
+    assert_eq!(record.pubkey, pubkey);
+    assert_eq!(
+        record
+            .signed_blocks
+            .iter()
+            .map(|block| block.slot)
+            .collect::<Vec<_>>(),
+        vec![Slot::new(7)]
+    );
+    assert_eq!(
+        record
+            .signed_attestations
+            .iter()
+            .map(|att| (att.source_epoch, att.target_epoch))
+            .collect::<Vec<_>>(),
+        vec![(Epoch::new(2), Epoch::new(5))]
+    );
+}
+
+#[test]
+fn slashing_protection_prune_without_database() {
+    let validator_dir = tempdir().unwrap();
+
+    let error = output_result(slashing_protection_cmd(&validator_dir).arg(PRUNE_CMD)).unwrap_err();
+    assert!(error.contains("No slashing protection database exists"));
+}
+
+#[test]
+fn slashing_protection_audit() {
+    let validator_dir = tempdir().unwrap();
+    let slashing_db_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
+    let registered = Keypair::random().pk;
+
+    let slashing_db = SlashingDatabase::create(&slashing_db_path).unwrap();
+    slashing_db
+        .register_validator(registered.compress())
+        .unwrap();
+    slashing_db
+        .check_and_insert_block_signing_root(
+            &registered.compress(),
+            Slot::new(1),
+            Default::default(),
+        )
+        .unwrap();
+    drop(slashing_db);
+
+    ValidatorDefinitions::from(vec![unused_validator_definition(
+        &validator_dir,
+        registered.clone(),
+    )])
+    .save(validator_dir.path())
+    .unwrap();
+
+    let output = output_result(slashing_protection_cmd(&validator_dir).arg(AUDIT_CMD)).unwrap();
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("No anomalies found"));
+
+    // A validator in the definitions which is not registered is reported as an anomaly.
+    let unregistered = Keypair::random().pk;
+    ValidatorDefinitions::from(vec![
+        unused_validator_definition(&validator_dir, registered),
+        unused_validator_definition(&validator_dir, unregistered.clone()),
+    ])
+    .save(validator_dir.path())
+    .unwrap();
+
+    let error = output_result(slashing_protection_cmd(&validator_dir).arg(AUDIT_CMD)).unwrap_err();
+    assert!(error.contains("Found 1 anomalies"));
+    assert!(error.contains(&format!(
+        "validator {:?} is not registered",
+        unregistered.compress()
+    )));
+    assert!(error.contains("the slashing protection database is inconsistent"));
+}
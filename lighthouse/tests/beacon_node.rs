@@ -1,4 +1,6 @@
 use beacon_node::ClientConfig as Config;
+use beacon_node::ClientGenesis;
+use beacon_node::DepositSnapshotSource;
 
 use crate::exec::{CommandLineTestExec, CompletedTest};
 use beacon_node::beacon_chain::chain_config::{
@@ -365,6 +367,39 @@ fn eth1_purge_cache_flag() {
         .with_config(|config| assert!(config.eth1.purge_cache));
 }
 #[test]
+fn deposit_snapshot_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("deposit_snapshot.ssz");
+    File::create(&path)
+        .and_then(|mut file| file.write_all(&[1, 2, 3]))
+        .expect("Unable to write deposit snapshot");
+    CommandLineTest::new()
+        .flag("deposit-snapshot", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| match &config.deposit_snapshot {
+            Some(DepositSnapshotSource::SszBytes(bytes)) => assert_eq!(bytes, &[1, 2, 3]),
+            other => panic!("unexpected deposit snapshot: {:?}", other),
+        });
+}
+#[test]
+fn deposit_snapshot_url_flag() {
+    CommandLineTest::new()
+        .flag("deposit-snapshot-url", Some("http://localhost:5052/"))
+        .run_with_zero_port()
+        .with_config(|config| match &config.deposit_snapshot {
+            Some(DepositSnapshotSource::Url(url)) => {
+                assert_eq!(url.full.as_str(), "http://localhost:5052/")
+            }
+            other => panic!("unexpected deposit snapshot: {:?}", other),
+        });
+}
+#[test]
+fn deposit_snapshot_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.deposit_snapshot.is_none()));
+}
+#[test]
 fn eth1_cache_follow_distance_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -388,8 +423,6 @@ fn eth1_cache_follow_distance_manual() {
 fn run_merge_execution_endpoints_flag_test(flag: &str) {
     use sensitive_url::SensitiveUrl;
     let urls = vec!["http://sigp.io/no-way:1337", "http://infura.not_real:4242"];
-    // we don't support redundancy for execution-endpoints
-    // only the first provided endpoint is parsed.
 
     let mut endpoint_arg = urls[0].to_string();
     for url in urls.iter().skip(1) {
@@ -418,17 +451,85 @@ fn run_merge_execution_endpoints_flag_test(flag: &str) {
         .flag("execution-jwt", Some(&jwts_arg))
         .run_with_zero_port()
         .with_config(|config| {
+            // The deposit cache follows the first endpoint.
+            assert!(matches!(
+                &config.eth1.endpoint,
+                Eth1Endpoint::Auth { endpoint, jwt_path, .. }
+                    if *endpoint == SensitiveUrl::parse(urls[0]).unwrap() && *jwt_path == jwts[0]
+            ));
             let config = config.execution_layer.as_ref().unwrap();
-            assert_eq!(config.execution_endpoints.len(), 1);
             assert_eq!(
-                config.execution_endpoints[0],
-                SensitiveUrl::parse(&urls[0]).unwrap()
+                config.execution_endpoints,
+                urls.iter()
+                    .map(|url| SensitiveUrl::parse(url).unwrap())
+                    .collect::<Vec<_>>()
             );
-            // Only the first secret file should be used.
-            assert_eq!(config.secret_files, vec![jwts[0].clone()]);
+            assert_eq!(config.secret_files, jwts);
+            assert_eq!(config.payload_status_policy.to_string(), "majority");
         });
 }
 #[test]
+fn execution_endpoint_ipc_without_jwt() {
+    use sensitive_url::SensitiveUrl;
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("ipc:///tmp/engine.ipc"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.eth1.endpoint,
+                Eth1Endpoint::NoAuth(SensitiveUrl::parse("ipc:///tmp/engine.ipc").unwrap())
+            );
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.execution_endpoints.len(), 1);
+            assert!(config.secret_files.is_empty());
+        });
+}
+#[test]
+#[should_panic]
+fn execution_endpoint_http_without_jwt() {
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://localhost:8551/"))
+        .run_with_zero_port();
+}
+#[test]
+fn execution_status_policy_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "execution-endpoint",
+            Some("http://localhost:8551/,http://localhost:8552/"),
+        )
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("execution-status-policy", Some("all"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.execution_endpoints.len(), 2);
+            assert_eq!(config.secret_files.len(), 1);
+            assert_eq!(config.payload_status_policy.to_string(), "all");
+        });
+}
+#[test]
+#[should_panic]
+fn execution_jwt_count_mismatch() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let jwts = format!(
+        "{},{}",
+        dir.path().join("jwt-0").display(),
+        dir.path().join("jwt-1").display()
+    );
+    CommandLineTest::new()
+        .flag(
+            "execution-endpoint",
+            Some("http://localhost:8551/,http://localhost:8552/,http://localhost:8553/"),
+        )
+        .flag("execution-jwt", Some(&jwts))
+        .run_with_zero_port();
+}
+#[test]
 fn run_execution_jwt_secret_key_is_persisted() {
     let jwt_secret_key = "0x3cbc11b0d8fa16f3344eacfd6ff6430b9d30734450e8adcf5400f88d327dcb33";
     CommandLineTest::new()
@@ -466,6 +567,23 @@ fn execution_timeout_multiplier_flag() {
         });
 }
 #[test]
+fn execution_traffic_record_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let record_dir = dir.path().join("engine-traffic");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("execution-traffic-record-dir", record_dir.to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.engine_traffic_dir, Some(record_dir.clone()));
+        });
+}
+#[test]
 fn merge_execution_endpoints_flag() {
     run_merge_execution_endpoints_flag_test("execution-endpoints")
 }
@@ -526,9 +644,7 @@ fn run_payload_builder_flag_test(flag: &str, builders: &str) {
         .collect();
     run_payload_builder_flag_test_with_config(flag, builders, None, None, |config| {
         let config = config.execution_layer.as_ref().unwrap();
-        // Only first provided endpoint is parsed as we don't support
-        // redundancy.
-        assert_eq!(config.builder_url, all_builders.get(0).cloned());
+        assert_eq!(config.builder_urls, all_builders);
     })
 }
 fn run_payload_builder_flag_test_with_config<F: Fn(&Config)>(
@@ -633,6 +749,42 @@ fn builder_user_agent() {
     );
 }
 
+#[test]
+fn builder_header_timeout() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_header_timeout,
+                None
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats,http://woof.dogs",
+        Some("builder-header-timeout"),
+        Some("750"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_header_timeout,
+                Some(Duration::from_millis(750))
+            );
+        },
+    );
+}
+
 fn run_jwt_optional_flags_test(jwt_flag: &str, jwt_id_flag: &str, jwt_version_flag: &str) {
     use sensitive_url::SensitiveUrl;
 
@@ -1525,6 +1677,21 @@ fn disable_inbound_rate_limiter_flag() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
 }
+#[test]
+fn inbound_rate_limiter_trusted_peers_flag() {
+    CommandLineTest::new()
+        .flag(
+            "inbound-rate-limiter-trusted-peers",
+            Some("beacon_blocks_by_range:4096/10"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.trusted_peers_inbound_rate_limiter_config,
+                Some("beacon_blocks_by_range:4096/10".parse().unwrap())
+            )
+        });
+}
 
 #[test]
 fn http_allow_origin_flag() {
@@ -2397,6 +2564,40 @@ fn light_client_http_server_enabled() {
         });
 }
 
+#[test]
+fn serve_finalized_state_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.network.enable_finalized_state_server));
+}
+
+#[test]
+fn serve_finalized_state_enabled() {
+    CommandLineTest::new()
+        .flag("serve-finalized-state", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.enable_finalized_state_server));
+}
+
+#[test]
+fn checkpoint_sync_peers_flag() {
+    let addr = "/ip4/127.0.0.1/tcp/9000/p2p/16Uiu2HAm8SbB6xgmMgxo7ZbMuHH7LMTMrWdgXq6GHz9zrK5Zs7me";
+    CommandLineTest::new()
+        .flag(
+            "wss-checkpoint",
+            Some("0x0000000000000000000000000000000000000000000000000000000000000001:1"),
+        )
+        .flag("checkpoint-sync-peers", Some(addr))
+        .run_with_zero_port()
+        .with_config(|config| match &config.genesis {
+            ClientGenesis::CheckpointSyncPeers { peers } => {
+                assert_eq!(peers.len(), 1);
+                assert_eq!(peers[0].to_string(), addr);
+            }
+            other => panic!("unexpected genesis: {:?}", other),
+        });
+}
+
 #[test]
 fn gui_flag() {
     CommandLineTest::new()
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
 use std::string::ToString;
+use std::time::Duration;
 use tempfile::TempDir;
 use types::Address;
 
@@ -616,6 +617,40 @@ fn latency_measurement_service() {
         });
 }
 
+#[test]
+fn attest_on_block_arrival_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.attest_on_block_arrival);
+    });
+}
+
+#[test]
+fn attest_on_block_arrival_flag() {
+    CommandLineTest::new()
+        .flag("attest-on-block-arrival", None)
+        .run()
+        .with_config(|config| {
+            assert!(config.attest_on_block_arrival);
+        });
+}
+
+#[test]
+fn watch_validator_definitions_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(!config.watch_validator_definitions);
+    });
+}
+
+#[test]
+fn watch_validator_definitions_flag() {
+    CommandLineTest::new()
+        .flag("watch-validator-definitions", None)
+        .run()
+        .with_config(|config| {
+            assert!(config.watch_validator_definitions);
+        });
+}
+
 #[test]
 fn validator_registration_batch_size() {
     CommandLineTest::new().run().with_config(|config| {
@@ -629,6 +664,27 @@ fn validator_registration_batch_size() {
         });
 }
 
+#[test]
+fn scheduled_exit_rebroadcast_epochs() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.scheduled_exit_rebroadcast_epochs, 4);
+    });
+    CommandLineTest::new()
+        .flag("scheduled-exit-rebroadcast-epochs", Some("8"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.scheduled_exit_rebroadcast_epochs, 8);
+        });
+}
+
+#[test]
+#[should_panic]
+fn scheduled_exit_rebroadcast_epochs_zero_value() {
+    CommandLineTest::new()
+        .flag("scheduled-exit-rebroadcast-epochs", Some("0"))
+        .run();
+}
+
 #[test]
 #[should_panic]
 fn validator_registration_batch_size_zero_value() {
@@ -636,3 +692,35 @@ fn validator_registration_batch_size_zero_value() {
         .flag("validator-registration-batch-size", Some("0"))
         .run();
 }
+
+#[test]
+fn ha_lease_flags() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(config.ha_lease.is_none()));
+    CommandLineTest::new()
+        .flag("ha-instance-id", Some("vc-1"))
+        .flag(
+            "ha-slashing-protection-db",
+            Some("/shared/slashing_protection.sqlite"),
+        )
+        .flag("ha-lease-duration", Some("36"))
+        .run()
+        .with_config(|config| {
+            let ha_config = config.ha_lease.as_ref().unwrap();
+            assert_eq!(ha_config.instance_id, "vc-1");
+            assert_eq!(
+                ha_config.slashing_protection_db,
+                PathBuf::from("/shared/slashing_protection.sqlite")
+            );
+            assert_eq!(ha_config.lease_duration, Duration::from_secs(36));
+        });
+}
+
+#[test]
+#[should_panic]
+fn ha_instance_id_requires_shared_db() {
+    CommandLineTest::new()
+        .flag("ha-instance-id", Some("vc-1"))
+        .run();
+}
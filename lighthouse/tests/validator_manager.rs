@@ -8,9 +8,14 @@ use std::str::FromStr;
 use tempfile::{tempdir, TempDir};
 use types::*;
 use validator_manager::{
+    bls_change::BlsChangeConfig,
+    common::ValidatorSelection,
     create_validators::CreateConfig,
+    exit_validators::ExitConfig,
     import_validators::ImportConfig,
     move_validators::{MoveConfig, PasswordSource, Validators},
+    set_fee_recipient::SetFeeRecipientConfig,
+    set_gas_limit::SetGasLimitConfig,
 };
 
 const EXAMPLE_ETH1_ADDRESS: &str = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
@@ -100,6 +105,30 @@ impl CommandLineTest<MoveConfig> {
     }
 }
 
+impl CommandLineTest<ExitConfig> {
+    fn validators_exit() -> Self {
+        Self::default().flag("exit", None)
+    }
+}
+
+impl CommandLineTest<BlsChangeConfig> {
+    fn validators_bls_change() -> Self {
+        Self::default().flag("bls-change", None)
+    }
+}
+
+impl CommandLineTest<SetFeeRecipientConfig> {
+    fn validators_set_fee_recipient() -> Self {
+        Self::default().flag("set-fee-recipient", None)
+    }
+}
+
+impl CommandLineTest<SetGasLimitConfig> {
+    fn validators_set_gas_limit() -> Self {
+        Self::default().flag("set-gas-limit", None)
+    }
+}
+
 #[test]
 pub fn validator_create_without_output_path() {
     CommandLineTest::validators_create().assert_failed();
@@ -389,3 +418,198 @@ pub fn validator_move_count() {
             assert_eq!(expected, config);
         });
 }
+
+#[test]
+pub fn validator_exit_without_output() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_exit_via_vc() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--exit-epoch", Some("42"))
+        .flag("--output-path", Some("./exits.json"))
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: Some(SensitiveUrl::parse("http://localhost:1").unwrap()),
+                vc_token_path: Some(PathBuf::from("./token.json")),
+                validators: ValidatorSelection::Specific(vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ]),
+                exit_epoch: Some(Epoch::new(42)),
+                validator_indices: None,
+                genesis_validators_root: None,
+                bn_url: None,
+                publish: false,
+                output_path: Some(PathBuf::from("./exits.json")),
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_from_file() {
+    CommandLineTest::validators_exit()
+        .flag("--validators-file", Some("./vals.json"))
+        .flag("--beacon-node", Some("http://localhost:2"))
+        .flag("--publish", None)
+        .flag("--no-confirmation", None)
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: None,
+                vc_token_path: None,
+                validators: ValidatorSelection::File(PathBuf::from("./vals.json")),
+                exit_epoch: None,
+                validator_indices: None,
+                genesis_validators_root: None,
+                bn_url: Some(SensitiveUrl::parse("http://localhost:2").unwrap()),
+                publish: true,
+                output_path: None,
+                no_confirmation: true,
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_from_file_without_beacon_node() {
+    CommandLineTest::validators_exit()
+        .flag("--validators-file", Some("./vals.json"))
+        .flag("--output-path", Some("./exits.json"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_exit_from_file_offline() {
+    let genesis_validators_root = Hash256::repeat_byte(42);
+    CommandLineTest::validators_exit()
+        .flag("--validators-file", Some("./vals.json"))
+        .flag("--validator-indices", Some("4, 5"))
+        .flag(
+            "--genesis-validators-root",
+            Some(&format!("{:?}", genesis_validators_root)),
+        )
+        .flag("--exit-epoch", Some("42"))
+        .flag("--output-path", Some("./exits.json"))
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: None,
+                vc_token_path: None,
+                validators: ValidatorSelection::File(PathBuf::from("./vals.json")),
+                exit_epoch: Some(Epoch::new(42)),
+                validator_indices: Some(vec![4, 5]),
+                genesis_validators_root: Some(genesis_validators_root),
+                bn_url: None,
+                publish: false,
+                output_path: Some(PathBuf::from("./exits.json")),
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows) || false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_from_file_offline_without_exit_epoch() {
+    CommandLineTest::validators_exit()
+        .flag("--validators-file", Some("./vals.json"))
+        .flag("--validator-indices", Some("4,5"))
+        .flag(
+            "--genesis-validators-root",
+            Some(&format!("{:?}", Hash256::repeat_byte(42))),
+        )
+        .flag("--output-path", Some("./exits.json"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_bls_change_without_genesis_validators_root() {
+    CommandLineTest::validators_bls_change()
+        .flag("--validator-indices", Some("1,2"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--output-path", Some("./changes.json"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_bls_change_offline() {
+    let genesis_validators_root = Hash256::repeat_byte(42);
+    CommandLineTest::validators_bls_change()
+        .flag("--validator-indices", Some("1,2,3"))
+        .flag("--first-index", Some("7"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag(
+            "--genesis-validators-root",
+            Some(&format!("{:?}", genesis_validators_root)),
+        )
+        .flag("--output-path", Some("./changes.json"))
+        .assert_success(|config| {
+            let expected = BlsChangeConfig {
+                mnemonic_path: None,
+                stdin_inputs: cfg!(windows) || false,
+                first_index: 7,
+                validator_indices: vec![1, 2, 3],
+                execution_address: Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap(),
+                bn_url: None,
+                genesis_validators_root: Some(genesis_validators_root),
+                publish: false,
+                output_path: Some(PathBuf::from("./changes.json")),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_set_fee_recipient_all() {
+    CommandLineTest::validators_set_fee_recipient()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .flag("--suggested-fee-recipient", Some(EXAMPLE_ETH1_ADDRESS))
+        .assert_success(|config| {
+            let expected = SetFeeRecipientConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: ValidatorSelection::All,
+                fee_recipient: Some(Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap()),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_set_fee_recipient_without_value() {
+    CommandLineTest::validators_set_fee_recipient()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_set_gas_limit_from_file() {
+    CommandLineTest::validators_set_gas_limit()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators-file", Some("./vals.json"))
+        .assert_success(|config| {
+            let expected = SetGasLimitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: ValidatorSelection::File(PathBuf::from("./vals.json")),
+                gas_limit: None,
+            };
+            assert_eq!(expected, config);
+        });
+}
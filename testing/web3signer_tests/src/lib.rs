@@ -339,6 +339,7 @@ mod tests {
                 Hash256::repeat_byte(42),
                 spec,
                 None,
+                None,
                 slot_clock,
                 &config,
                 executor,
@@ -398,6 +399,7 @@ mod tests {
                         voting_keystore_path: signer_rig.keystore_path.clone(),
                         voting_keystore_password_path: None,
                         voting_keystore_password: Some(KEYSTORE_PASSWORD.to_string().into()),
+                        voting_keystore_password_source: None,
                     },
                 };
                 ValidatorStoreRig::new(vec![validator_definition], spec.clone()).await
@@ -0,0 +1,358 @@
+//! Consistency checks for the contents of a slashing protection database.
+//!
+//! The database should never contain slashable data, because every block and attestation is
+//! checked before it is inserted. An audit detects databases which have been corrupted or
+//! manually edited.
+//!
+//! Conflicting blocks at the same slot and conflicting attestations with the same target epoch are
+//! prevented by the `UNIQUE` constraints of the schema, so they are not checked here.
+use crate::{InterchangeError, SlashingDatabase};
+use rusqlite::params;
+use std::collections::HashSet;
+use std::fmt;
+use types::{Epoch, PublicKeyBytes, Slot};
+
+/// An inconsistency found by `SlashingDatabase::audit`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anomaly {
+    /// A validator which is expected to be registered is missing from the database.
+    UnregisteredValidator(PublicKeyBytes),
+    /// Blocks or attestations are recorded for a validator ID with no registered public key.
+    OrphanedRecords { validator_id: i64, count: u32 },
+    /// A block was recorded at or below the minimum slot of the blocks recorded before it, i.e. the
+    /// block watermark moved backwards.
+    BlockBelowWatermark {
+        pubkey: PublicKeyBytes,
+        slot: Slot,
+        watermark: Slot,
+    },
+    /// An attestation was recorded with a source epoch greater than its target epoch.
+    SourceExceedsTarget {
+        pubkey: PublicKeyBytes,
+        source: Epoch,
+        target: Epoch,
+    },
+    /// An attestation was recorded which surrounds another recorded attestation, i.e. the
+    /// attestation watermarks do not increase together.
+    SurroundingVote {
+        pubkey: PublicKeyBytes,
+        surrounding: (Epoch, Epoch),
+        surrounded: (Epoch, Epoch),
+    },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Anomaly::UnregisteredValidator(pubkey) => {
+                write!(f, "validator {:?} is not registered", pubkey)
+            }
+            Anomaly::OrphanedRecords {
+                validator_id,
+                count,
+            } => write!(
+                f,
+                "{} records belong to validator ID {} which is not registered",
+                count, validator_id
+            ),
+            Anomaly::BlockBelowWatermark {
+                pubkey,
+                slot,
+                watermark,
+            } => write!(
+                f,
+                "validator {:?} has a block at slot {} which was recorded after a block at slot {}",
+                pubkey, slot, watermark
+            ),
+            Anomaly::SourceExceedsTarget {
+                pubkey,
+                source,
+                target,
+            } => write!(
+                f,
+                "validator {:?} has an attestation with source epoch {} after target epoch {}",
+                pubkey, source, target
+            ),
+            Anomaly::SurroundingVote {
+                pubkey,
+                surrounding,
+                surrounded,
+            } => write!(
+                f,
+                "validator {:?} has an attestation {} => {} surrounding {} => {}",
+                pubkey, surrounding.0, surrounding.1, surrounded.0, surrounded.1
+            ),
+        }
+    }
+}
+
+impl SlashingDatabase {
+    /// Check the internal consistency of the database, returning every anomaly found.
+    ///
+    /// Every public key in `expected_pubkeys` must be registered (enabled or not).
+    pub fn audit(
+        &self,
+        expected_pubkeys: &[PublicKeyBytes],
+    ) -> Result<Vec<Anomaly>, InterchangeError> {
+        self.with_transaction(|txn| {
+            let mut anomalies = vec![];
+
+            let validators = self.list_all_registered_validators(txn)?;
+            let registered = validators
+                .iter()
+                .map(|(_, pubkey)| *pubkey)
+                .collect::<HashSet<_>>();
+            anomalies.extend(
+                expected_pubkeys
+                    .iter()
+                    .filter(|pubkey| !registered.contains(pubkey))
+                    .map(|pubkey| Anomaly::UnregisteredValidator(*pubkey)),
+            );
+
+            let orphans = txn
+                .prepare(
+                    "SELECT validator_id, COUNT(*) FROM (
+                        SELECT validator_id FROM signed_blocks
+                        UNION ALL
+                        SELECT validator_id FROM signed_attestations
+                     )
+                     WHERE validator_id NOT IN (SELECT id FROM validators)
+                     GROUP BY validator_id",
+                )?
+                .query_map(params![], |row| {
+                    Ok(Anomaly::OrphanedRecords {
+                        validator_id: row.get(0)?,
+                        count: row.get(1)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            anomalies.extend(orphans);
+
+            for (validator_id, pubkey) in validators {
+                // Rows without an explicit primary key are ordered by insertion (`VACUUM`
+                // renumbers them, but preserves their order).
+                let slots = txn
+                    .prepare(
+                        "SELECT slot FROM signed_blocks WHERE validator_id = ?1 ORDER BY rowid ASC",
+                    )?
+                    .query_map(params![validator_id], |row| row.get(0))?
+                    .collect::<Result<Vec<Slot>, _>>()?;
+                anomalies.extend(check_blocks(pubkey, &slots));
+
+                let attestations = txn
+                    .prepare(
+                        "SELECT source_epoch, target_epoch
+                         FROM signed_attestations
+                         WHERE validator_id = ?1
+                         ORDER BY source_epoch ASC, target_epoch ASC",
+                    )?
+                    .query_map(params![validator_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<Result<Vec<(Epoch, Epoch)>, _>>()?;
+                anomalies.extend(check_attestations(pubkey, &attestations));
+            }
+
+            Ok(anomalies)
+        })
+    }
+}
+
+/// Check a validator's block slots, which must be in the order in which they were recorded.
+///
+/// A block is only signed above the minimum slot of the blocks already recorded (the minimum slot
+/// rule), so the minimum over the preceding blocks is a lower bound that can never be reached.
+fn check_blocks(pubkey: PublicKeyBytes, slots: &[Slot]) -> Vec<Anomaly> {
+    let mut anomalies = vec![];
+
+    let mut watermark: Option<Slot> = None;
+    for &slot in slots {
+        if let Some(watermark) = watermark {
+            if slot <= watermark {
+                anomalies.push(Anomaly::BlockBelowWatermark {
+                    pubkey,
+                    slot,
+                    watermark,
+                });
+            }
+        }
+        watermark = Some(watermark.map_or(slot, |watermark| watermark.min(slot)));
+    }
+
+    anomalies
+}
+
+/// Check a validator's attestations, which must be sorted by source epoch and then target epoch.
+fn check_attestations(pubkey: PublicKeyBytes, attestations: &[(Epoch, Epoch)]) -> Vec<Anomaly> {
+    let mut anomalies = vec![];
+
+    for &(source, target) in attestations {
+        if source > target {
+            anomalies.push(Anomaly::SourceExceedsTarget {
+                pubkey,
+                source,
+                target,
+            });
+        }
+    }
+
+    // An attestation with a lower source must never have a higher target. Track the attestation
+    // with the highest target amongst those with a strictly lower source.
+    let mut max_prior: Option<(Epoch, Epoch)> = None;
+    let mut max_current: Option<(Epoch, Epoch)> = None;
+    for &(source, target) in attestations {
+        if let Some(current) = max_current {
+            if current.0 < source {
+                max_prior = max_by_target(max_prior, current);
+                max_current = None;
+            }
+        }
+        if let Some(prior) = max_prior {
+            if prior.1 > target {
+                anomalies.push(Anomaly::SurroundingVote {
+                    pubkey,
+                    surrounding: prior,
+                    surrounded: (source, target),
+                });
+            }
+        }
+        max_current = max_by_target(max_current, (source, target));
+    }
+
+    anomalies
+}
+
+fn max_by_target(
+    existing: Option<(Epoch, Epoch)>,
+    other: (Epoch, Epoch),
+) -> Option<(Epoch, Epoch)> {
+    match existing {
+        Some(existing) if existing.1 >= other.1 => Some(existing),
+        _ => Some(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::pubkey;
+    use crate::NotSafe;
+    use tempfile::tempdir;
+
+    fn epochs(attestations: &[(u64, u64)]) -> Vec<(Epoch, Epoch)> {
+        attestations
+            .iter()
+            .map(|&(s, t)| (Epoch::new(s), Epoch::new(t)))
+            .collect()
+    }
+
+    #[test]
+    fn consistent_blocks() {
+        let pk = pubkey(0);
+        let slots = [1, 5, 3, 8, 2].map(Slot::new);
+        assert_eq!(check_blocks(pk, &slots), vec![]);
+    }
+
+    #[test]
+    fn blocks_below_watermark() {
+        let pk = pubkey(0);
+        let slots = [4, 6, 3, 5, 2].map(Slot::new);
+        assert_eq!(
+            check_blocks(pk, &slots),
+            vec![
+                Anomaly::BlockBelowWatermark {
+                    pubkey: pk,
+                    slot: Slot::new(3),
+                    watermark: Slot::new(4),
+                },
+                Anomaly::BlockBelowWatermark {
+                    pubkey: pk,
+                    slot: Slot::new(2),
+                    watermark: Slot::new(3),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn consistent_attestations() {
+        let pk = pubkey(0);
+        let attestations = epochs(&[(0, 1), (0, 2), (1, 3), (2, 4), (2, 5), (4, 6)]);
+        assert_eq!(check_attestations(pk, &attestations), vec![]);
+    }
+
+    #[test]
+    fn surrounding_attestations() {
+        let pk = pubkey(0);
+        let attestations = epochs(&[(0, 1), (1, 10), (2, 3), (5, 6), (5, 12)]);
+        assert_eq!(
+            check_attestations(pk, &attestations),
+            vec![
+                Anomaly::SurroundingVote {
+                    pubkey: pk,
+                    surrounding: (Epoch::new(1), Epoch::new(10)),
+                    surrounded: (Epoch::new(2), Epoch::new(3)),
+                },
+                Anomaly::SurroundingVote {
+                    pubkey: pk,
+                    surrounding: (Epoch::new(1), Epoch::new(10)),
+                    surrounded: (Epoch::new(5), Epoch::new(6)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn audit_database() {
+        let dir = tempdir().unwrap();
+        let db = SlashingDatabase::create(&dir.path().join("db.sqlite")).unwrap();
+        db.register_validator(pubkey(0)).unwrap();
+        db.check_and_insert_attestation_signing_root(
+            &pubkey(0),
+            Epoch::new(1),
+            Epoch::new(2),
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(db.audit(&[pubkey(0)]).unwrap(), vec![]);
+
+        for slot in [2, 4] {
+            db.check_and_insert_block_signing_root(&pubkey(0), Slot::new(slot), Default::default())
+                .unwrap();
+        }
+        assert_eq!(db.audit(&[pubkey(0)]).unwrap(), vec![]);
+
+        // Insert a surrounding attestation and a block below the block watermark directly,
+        // bypassing the slashing checks.
+        db.with_transaction(|txn| {
+            txn.execute(
+                "INSERT INTO signed_attestations
+                    (validator_id, source_epoch, target_epoch, signing_root)
+                 VALUES (1, 0, 3, X'00')",
+                params![],
+            )?;
+            txn.execute(
+                "INSERT INTO signed_blocks (validator_id, slot, signing_root)
+                 VALUES (1, 1, X'00')",
+                params![],
+            )?;
+            Ok::<_, NotSafe>(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            db.audit(&[pubkey(0), pubkey(1)]).unwrap(),
+            vec![
+                Anomaly::UnregisteredValidator(pubkey(1)),
+                Anomaly::BlockBelowWatermark {
+                    pubkey: pubkey(0),
+                    slot: Slot::new(1),
+                    watermark: Slot::new(2),
+                },
+                Anomaly::SurroundingVote {
+                    pubkey: pubkey(0),
+                    surrounding: (Epoch::new(0), Epoch::new(3)),
+                    surrounded: (Epoch::new(1), Epoch::new(2)),
+                },
+            ]
+        );
+    }
+}
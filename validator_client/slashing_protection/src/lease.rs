@@ -0,0 +1,74 @@
+//! A lease which allows several validator clients to share a slashing protection database whilst
+//! only one of them is permitted to sign at a time.
+//!
+//! The lease is stored in the slashing protection database itself. Whilst a database is opened
+//! with a lease holder, every block and attestation is checked against the lease in the same
+//! exclusive transaction that records it. A validator client which has lost the lease (e.g. due to
+//! a long pause) is therefore unable to record, and thus sign, any slashable message even if it
+//! believes it is still the active instance.
+//!
+//! Lease expiry is determined using the system clock of each instance, so all instances sharing a
+//! database must have synchronized clocks.
+use crate::NotSafe;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The holder of the lease and the time at which it expires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lease {
+    /// The identifier of the validator client instance holding the lease.
+    pub holder: String,
+    /// Incremented each time the lease is taken over after it was released or expired.
+    pub term: u64,
+    /// Expiry time of the lease, as milliseconds since the UNIX epoch.
+    pub expires_at_millis: u64,
+}
+
+impl Lease {
+    /// Returns `true` if the lease has expired at `now`.
+    pub fn is_expired(&self, now: Duration) -> bool {
+        self.expires_at_millis <= duration_to_millis(now)
+    }
+
+    /// Returns the time remaining before the lease expires, if any.
+    pub fn remaining(&self, now: Duration) -> Option<Duration> {
+        Duration::from_millis(self.expires_at_millis).checked_sub(now)
+    }
+}
+
+/// The outcome of trying to acquire or renew the lease.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LeaseStatus {
+    /// The lease was free or expired and has been taken over with a new term.
+    Acquired(Lease),
+    /// The lease was already held by this instance and has been extended.
+    Renewed(Lease),
+    /// The lease is held by another instance and has not expired.
+    HeldByOther(Lease),
+}
+
+impl LeaseStatus {
+    /// Returns the current lease, regardless of who holds it.
+    pub fn lease(&self) -> &Lease {
+        match self {
+            LeaseStatus::Acquired(lease)
+            | LeaseStatus::Renewed(lease)
+            | LeaseStatus::HeldByOther(lease) => lease,
+        }
+    }
+
+    /// Returns `true` if this instance holds the lease.
+    pub fn is_held(&self) -> bool {
+        !matches!(self, LeaseStatus::HeldByOther(_))
+    }
+}
+
+/// Returns the duration since the UNIX epoch according to the system clock.
+pub fn unix_time_now() -> Result<Duration, NotSafe> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| NotSafe::ClockError)
+}
+
+pub(crate) fn duration_to_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
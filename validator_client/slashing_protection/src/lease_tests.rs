@@ -0,0 +1,174 @@
+#![cfg(test)]
+
+use crate::attestation_tests::attestation_data_builder;
+use crate::block_tests::block;
+use crate::test_utils::*;
+use crate::*;
+use std::time::Duration;
+use tempfile::tempdir;
+use types::{Epoch, Slot};
+
+const LEASE_DURATION: Duration = Duration::from_secs(24);
+
+fn now() -> Duration {
+    unix_time_now().unwrap()
+}
+
+#[test]
+fn lease_takeover_after_expiry() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.sqlite");
+    let db_a = SlashingDatabase::create_shared(&path, "a".into()).unwrap();
+    let db_b = SlashingDatabase::open_shared(&path, "b".into()).unwrap();
+    let start = now();
+
+    let lease = match db_a.acquire_lease(LEASE_DURATION, start).unwrap() {
+        LeaseStatus::Acquired(lease) => lease,
+        status => panic!("unexpected status {:?}", status),
+    };
+    assert_eq!(lease.holder, "a");
+    assert_eq!(lease.term, 0);
+
+    assert!(matches!(
+        db_a.acquire_lease(LEASE_DURATION, start + Duration::from_secs(1)),
+        Ok(LeaseStatus::Renewed(Lease { term: 0, .. }))
+    ));
+    assert_eq!(
+        db_b.acquire_lease(LEASE_DURATION, start + Duration::from_secs(2))
+            .unwrap(),
+        LeaseStatus::HeldByOther(Lease {
+            holder: "a".into(),
+            term: 0,
+            expires_at_millis: (start + Duration::from_secs(25)).as_millis() as u64,
+        })
+    );
+
+    // Once expired the lease can be taken over with a new term.
+    let later = start + LEASE_DURATION * 2;
+    match db_b.acquire_lease(LEASE_DURATION, later).unwrap() {
+        LeaseStatus::Acquired(lease) => {
+            assert_eq!(lease.holder, "b");
+            assert_eq!(lease.term, 1);
+        }
+        status => panic!("unexpected status {:?}", status),
+    }
+    assert!(!db_a.acquire_lease(LEASE_DURATION, later).unwrap().is_held());
+}
+
+#[test]
+fn release_lease() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.sqlite");
+    let db_a = SlashingDatabase::create_shared(&path, "a".into()).unwrap();
+    let db_b = SlashingDatabase::open_shared(&path, "b".into()).unwrap();
+
+    assert!(db_a.acquire_lease(LEASE_DURATION, now()).unwrap().is_held());
+    assert!(!db_b.acquire_lease(LEASE_DURATION, now()).unwrap().is_held());
+
+    // Releasing a lease held by another instance has no effect.
+    db_b.release_lease().unwrap();
+    assert!(!db_b.acquire_lease(LEASE_DURATION, now()).unwrap().is_held());
+
+    db_a.release_lease().unwrap();
+    assert!(matches!(
+        db_b.acquire_lease(LEASE_DURATION, now()),
+        Ok(LeaseStatus::Acquired(Lease { term: 1, .. }))
+    ));
+}
+
+#[test]
+fn exclusive_database_has_no_lease() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.sqlite");
+    let db = SlashingDatabase::create(&path).unwrap();
+
+    assert_eq!(
+        db.acquire_lease(LEASE_DURATION, now()),
+        Err(NotSafe::NoLeaseHolder)
+    );
+    assert_eq!(db.current_lease(), Ok(None));
+}
+
+#[test]
+fn signing_requires_lease() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.sqlite");
+    let db_a = SlashingDatabase::create_shared(&path, "a".into()).unwrap();
+    let db_b = SlashingDatabase::open_shared(&path, "b".into()).unwrap();
+    let pk = pubkey(0);
+    db_a.register_validator(pk).unwrap();
+
+    // No instance holds the lease.
+    assert_eq!(
+        db_a.check_and_insert_block_proposal(&pk, &block(1), DEFAULT_DOMAIN),
+        Err(NotSafe::LeaseNotHeld(None))
+    );
+
+    db_a.acquire_lease(LEASE_DURATION, now()).unwrap();
+    assert_eq!(
+        db_a.check_and_insert_block_proposal(&pk, &block(1), DEFAULT_DOMAIN),
+        Ok(Safe::Valid)
+    );
+    assert!(matches!(
+        db_b.check_and_insert_attestation(&pk, &attestation_data_builder(0, 1), DEFAULT_DOMAIN),
+        Err(NotSafe::LeaseNotHeld(Some(Lease { ref holder, .. }))) if holder == "a"
+    ));
+
+    // An expired lease fences off its previous holder.
+    db_a.acquire_lease(Duration::ZERO, now()).unwrap();
+    assert!(matches!(
+        db_a.check_and_insert_attestation(&pk, &attestation_data_builder(0, 1), DEFAULT_DOMAIN),
+        Err(NotSafe::LeaseNotHeld(Some(_)))
+    ));
+}
+
+#[test]
+fn shared_database_high_watermark() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("slashing_protection.sqlite");
+    let db_a = SlashingDatabase::create_shared(&path, "a".into()).unwrap();
+    let db_b = SlashingDatabase::open_shared(&path, "b".into()).unwrap();
+    let pk = pubkey(0);
+    db_a.register_validator(pk).unwrap();
+
+    db_a.acquire_lease(LEASE_DURATION, now()).unwrap();
+    db_a.check_and_insert_block_proposal(&pk, &block(2), DEFAULT_DOMAIN)
+        .unwrap();
+    db_a.check_and_insert_block_proposal(&pk, &block(5), DEFAULT_DOMAIN)
+        .unwrap();
+    db_a.check_and_insert_attestation(&pk, &attestation_data_builder(1, 2), DEFAULT_DOMAIN)
+        .unwrap();
+    db_a.check_and_insert_attestation(&pk, &attestation_data_builder(2, 4), DEFAULT_DOMAIN)
+        .unwrap();
+    db_a.release_lease().unwrap();
+
+    // The standby takes over and refuses anything below the messages signed by the previous
+    // holder, even if it would not be slashable.
+    db_b.acquire_lease(LEASE_DURATION, now()).unwrap();
+    assert_eq!(
+        db_b.check_and_insert_block_proposal(&pk, &block(3), DEFAULT_DOMAIN),
+        Err(NotSafe::InvalidBlock(
+            InvalidBlock::SlotBelowHighWatermark {
+                block_slot: Slot::new(3),
+                watermark_slot: Slot::new(5),
+            }
+        ))
+    );
+    assert_eq!(
+        db_b.check_and_insert_attestation(&pk, &attestation_data_builder(2, 3), DEFAULT_DOMAIN),
+        Err(NotSafe::InvalidAttestation(
+            InvalidAttestation::TargetBelowHighWatermark {
+                target_epoch: Epoch::new(3),
+                watermark_epoch: Epoch::new(4),
+            }
+        ))
+    );
+    assert_eq!(
+        db_b.check_and_insert_block_proposal(&pk, &block(6), DEFAULT_DOMAIN),
+        Ok(Safe::Valid)
+    );
+    assert_eq!(
+        db_b.check_and_insert_attestation(&pk, &attestation_data_builder(4, 5), DEFAULT_DOMAIN),
+        Ok(Safe::Valid)
+    );
+}
@@ -1,8 +1,11 @@
 mod attestation_tests;
+mod audit;
 mod block_tests;
 mod extra_interchange_tests;
 pub mod interchange;
 pub mod interchange_test;
+mod lease;
+mod lease_tests;
 mod parallel_tests;
 mod registration_tests;
 mod signed_attestation;
@@ -10,6 +13,8 @@ mod signed_block;
 mod slashing_database;
 pub mod test_utils;
 
+pub use crate::audit::Anomaly;
+pub use crate::lease::{unix_time_now, Lease, LeaseStatus};
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::{
@@ -38,6 +43,14 @@ pub enum NotSafe {
     SQLError(String),
     SQLPoolError(String),
     ConsistencyError,
+    /// The database is shared via a lease which is not held by this instance.
+    ///
+    /// Contains the current lease, if any.
+    LeaseNotHeld(Option<Lease>),
+    /// The database was not opened with a lease holder.
+    NoLeaseHolder,
+    /// The system clock is before the UNIX epoch.
+    ClockError,
 }
 
 /// The attestation or block is safe to sign, and will not cause the signer to be slashed.
@@ -32,6 +32,12 @@ pub enum InvalidAttestation {
         target_epoch: Epoch,
         bound_epoch: Epoch,
     },
+    /// The attestation is invalid because its target epoch is less than the highest target epoch
+    /// signed for this validator, which is not permitted for databases shared via a lease.
+    TargetBelowHighWatermark {
+        target_epoch: Epoch,
+        watermark_epoch: Epoch,
+    },
 }
 
 impl SignedAttestation {
@@ -12,7 +12,16 @@ pub struct SignedBlock {
 #[derive(PartialEq, Debug)]
 pub enum InvalidBlock {
     DoubleBlockProposal(SignedBlock),
-    SlotViolatesLowerBound { block_slot: Slot, bound_slot: Slot },
+    SlotViolatesLowerBound {
+        block_slot: Slot,
+        bound_slot: Slot,
+    },
+    /// The block is below the highest slot signed for this validator, which is not permitted for
+    /// databases shared via a lease.
+    SlotBelowHighWatermark {
+        block_slot: Slot,
+        watermark_slot: Slot,
+    },
 }
 
 impl SignedBlock {
@@ -2,6 +2,7 @@ use crate::interchange::{
     Interchange, InterchangeData, InterchangeMetadata, SignedAttestation as InterchangeAttestation,
     SignedBlock as InterchangeBlock,
 };
+use crate::lease::{duration_to_millis, unix_time_now, Lease, LeaseStatus};
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
 use crate::{signing_root_from_row, NotSafe, Safe, SignedAttestation, SignedBlock, SigningRoot};
@@ -34,6 +35,8 @@ pub const VALIDATORS_ENABLED_CID: i64 = 2;
 #[derive(Debug, Clone)]
 pub struct SlashingDatabase {
     conn_pool: Pool,
+    /// The identifier of this instance if the database is shared with other instances via a lease.
+    lease_holder: Option<String>,
 }
 
 impl SlashingDatabase {
@@ -46,10 +49,36 @@ impl SlashingDatabase {
         }
     }
 
+    /// Open an existing database at the given `path` which is shared with other validator
+    /// clients, or create one if none exists.
+    ///
+    /// Unlike `Self::open_or_create`, the database is not locked exclusively by this process.
+    /// Instead, blocks and attestations can only be recorded whilst `lease_holder` holds the lease
+    /// (see `Self::acquire_lease`).
+    pub fn open_or_create_shared(path: &Path, lease_holder: String) -> Result<Self, NotSafe> {
+        if path.exists() {
+            Self::open_shared(path, lease_holder)
+        } else {
+            Self::create_shared(path, lease_holder)
+        }
+    }
+
     /// Create a slashing database at the given path.
     ///
     /// Error if a database (or any file) already exists at `path`.
     pub fn create(path: &Path) -> Result<Self, NotSafe> {
+        Self::create_with_lease_holder(path, None)
+    }
+
+    /// Create a slashing database at the given path, which is shared via a lease.
+    pub fn create_shared(path: &Path, lease_holder: String) -> Result<Self, NotSafe> {
+        Self::create_with_lease_holder(path, Some(lease_holder))
+    }
+
+    fn create_with_lease_holder(
+        path: &Path,
+        lease_holder: Option<String>,
+    ) -> Result<Self, NotSafe> {
         let _file = File::options()
             .write(true)
             .read(true)
@@ -57,7 +86,7 @@ impl SlashingDatabase {
             .open(path)?;
 
         restrict_file_permissions(path).map_err(|_| NotSafe::PermissionsError)?;
-        let conn_pool = Self::open_conn_pool(path)?;
+        let conn_pool = Self::open_conn_pool(path, lease_holder.is_some())?;
         let mut conn = conn_pool.get()?;
 
         conn.execute(
@@ -97,15 +126,30 @@ impl SlashingDatabase {
         Self::apply_schema_migrations(&txn)?;
         txn.commit()?;
 
-        Ok(Self { conn_pool })
+        Ok(Self {
+            conn_pool,
+            lease_holder,
+        })
     }
 
     /// Open an existing `SlashingDatabase` from disk.
     ///
     /// This will automatically check for and apply the latest schema migrations.
     pub fn open(path: &Path) -> Result<Self, NotSafe> {
-        let conn_pool = Self::open_conn_pool(path)?;
-        let db = Self { conn_pool };
+        Self::open_with_lease_holder(path, None)
+    }
+
+    /// Open an existing `SlashingDatabase` from disk, which is shared via a lease.
+    pub fn open_shared(path: &Path, lease_holder: String) -> Result<Self, NotSafe> {
+        Self::open_with_lease_holder(path, Some(lease_holder))
+    }
+
+    fn open_with_lease_holder(path: &Path, lease_holder: Option<String>) -> Result<Self, NotSafe> {
+        let conn_pool = Self::open_conn_pool(path, lease_holder.is_some())?;
+        let db = Self {
+            conn_pool,
+            lease_holder,
+        };
         db.with_transaction(Self::apply_schema_migrations)?;
         Ok(db)
     }
@@ -139,14 +183,32 @@ impl SlashingDatabase {
             )?;
         }
 
+        // Add the single-row `ha_lease` table, used when the database is shared.
+        txn.execute(
+            "CREATE TABLE IF NOT EXISTS ha_lease (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                holder TEXT NOT NULL,
+                term INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+            params![],
+        )?;
+
         Ok(())
     }
 
     /// Open a new connection pool with all of the necessary settings and tweaks.
-    fn open_conn_pool(path: &Path) -> Result<Pool, NotSafe> {
+    ///
+    /// If `shared` is `true` the database is not locked exclusively, see `apply_shared_pragmas`.
+    fn open_conn_pool(path: &Path, shared: bool) -> Result<Pool, NotSafe> {
+        let init: fn(&mut rusqlite::Connection) -> Result<(), rusqlite::Error> = if shared {
+            Self::apply_shared_pragmas
+        } else {
+            Self::apply_pragmas
+        };
         let manager = SqliteConnectionManager::file(path)
             .with_flags(rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE)
-            .with_init(Self::apply_pragmas);
+            .with_init(init);
         let conn_pool = Pool::builder()
             .max_size(POOL_SIZE)
             .connection_timeout(CONNECTION_TIMEOUT)
@@ -167,6 +229,17 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Apply the necessary settings to an SQLite connection to a database shared between processes.
+    ///
+    /// The database remains in the normal locking mode so that the lock is released at the end of
+    /// each transaction. Exclusive transactions still serialise all checks and inserts across
+    /// processes, waiting up to `CONNECTION_TIMEOUT` for other processes to finish.
+    fn apply_shared_pragmas(conn: &mut rusqlite::Connection) -> Result<(), rusqlite::Error> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.busy_timeout(CONNECTION_TIMEOUT)?;
+        Ok(())
+    }
+
     /// Creates an empty transaction and drops it. Used to test whether the database is locked.
     pub fn test_transaction(&self) -> Result<(), NotSafe> {
         let mut conn = self.conn_pool.get()?;
@@ -367,6 +440,24 @@ impl SlashingDatabase {
             }
         }
 
+        // When the database is shared, never sign below a block signed by any instance.
+        if self.lease_holder.is_some() {
+            let max_slot = txn
+                .prepare("SELECT MAX(slot) FROM signed_blocks WHERE validator_id = ?1")?
+                .query_row(params![validator_id], |row| row.get(0))?;
+
+            if let Some(max_slot) = max_slot {
+                if slot < max_slot {
+                    return Err(NotSafe::InvalidBlock(
+                        InvalidBlock::SlotBelowHighWatermark {
+                            block_slot: slot,
+                            watermark_slot: max_slot,
+                        },
+                    ));
+                }
+            }
+        }
+
         Ok(Safe::Valid)
     }
 
@@ -493,6 +584,26 @@ impl SlashingDatabase {
             }
         }
 
+        // When the database is shared, never sign below an attestation signed by any instance.
+        if self.lease_holder.is_some() {
+            let max_target = txn
+                .prepare(
+                    "SELECT MAX(target_epoch) FROM signed_attestations WHERE validator_id = ?1",
+                )?
+                .query_row(params![validator_id], |row| row.get(0))?;
+
+            if let Some(max_target) = max_target {
+                if att_target_epoch < max_target {
+                    return Err(NotSafe::InvalidAttestation(
+                        InvalidAttestation::TargetBelowHighWatermark {
+                            target_epoch: att_target_epoch,
+                            watermark_epoch: max_target,
+                        },
+                    ));
+                }
+            }
+        }
+
         // Everything has been checked, return Valid
         Ok(Safe::Valid)
     }
@@ -591,6 +702,7 @@ impl SlashingDatabase {
         signing_root: SigningRoot,
         txn: &Transaction,
     ) -> Result<Safe, NotSafe> {
+        self.check_lease_in_txn(txn)?;
         let safe = self.check_block_proposal(txn, validator_pubkey, slot, signing_root)?;
 
         if safe != Safe::SameData {
@@ -650,6 +762,7 @@ impl SlashingDatabase {
         att_signing_root: SigningRoot,
         txn: &Transaction,
     ) -> Result<Safe, NotSafe> {
+        self.check_lease_in_txn(txn)?;
         let safe = self.check_attestation(
             txn,
             validator_pubkey,
@@ -670,6 +783,114 @@ impl SlashingDatabase {
         Ok(safe)
     }
 
+    /// Try to acquire the lease for `duration` as of `now` (the time since the UNIX epoch), or to
+    /// extend it if it is already held by this instance.
+    ///
+    /// Error if the database was not opened with a lease holder.
+    pub fn acquire_lease(&self, duration: Duration, now: Duration) -> Result<LeaseStatus, NotSafe> {
+        let holder = self.lease_holder.as_ref().ok_or(NotSafe::NoLeaseHolder)?;
+        let expires_at_millis = duration_to_millis(now.saturating_add(duration));
+
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+        let status = match Self::lease_in_txn(&txn)? {
+            Some(lease) if !lease.is_expired(now) && lease.holder != *holder => {
+                LeaseStatus::HeldByOther(lease)
+            }
+            Some(lease) if !lease.is_expired(now) => LeaseStatus::Renewed(Lease {
+                expires_at_millis,
+                ..lease
+            }),
+            // Taking over a free or expired lease always starts a new term, even for the previous
+            // holder, so that the caller can tell that it may not have been active in between.
+            lease => LeaseStatus::Acquired(Lease {
+                holder: holder.clone(),
+                term: lease.map_or(0, |lease| lease.term.saturating_add(1)),
+                expires_at_millis,
+            }),
+        };
+
+        if status.is_held() {
+            Self::store_lease_in_txn(status.lease(), &txn)?;
+        }
+        txn.commit()?;
+        Ok(status)
+    }
+
+    /// Expire the lease immediately if it is held by this instance, allowing another instance to
+    /// take over without waiting for the lease to expire.
+    pub fn release_lease(&self) -> Result<(), NotSafe> {
+        let holder = self.lease_holder.as_ref().ok_or(NotSafe::NoLeaseHolder)?;
+
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        if let Some(lease) = Self::lease_in_txn(&txn)?.filter(|lease| lease.holder == *holder) {
+            Self::store_lease_in_txn(
+                &Lease {
+                    expires_at_millis: 0,
+                    ..lease
+                },
+                &txn,
+            )?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Return the current lease, whether or not it has expired.
+    pub fn current_lease(&self) -> Result<Option<Lease>, NotSafe> {
+        self.with_transaction(Self::lease_in_txn)
+    }
+
+    fn lease_in_txn(txn: &Transaction) -> Result<Option<Lease>, NotSafe> {
+        txn.query_row(
+            "SELECT holder, term, expires_at FROM ha_lease WHERE id = 0",
+            params![],
+            |row| {
+                let term: i64 = row.get(1)?;
+                let expires_at: i64 = row.get(2)?;
+                Ok((row.get(0)?, term, expires_at))
+            },
+        )
+        .optional()?
+        .map(|(holder, term, expires_at)| {
+            Ok(Lease {
+                holder,
+                term: u64::try_from(term).map_err(|_| NotSafe::ConsistencyError)?,
+                expires_at_millis: u64::try_from(expires_at)
+                    .map_err(|_| NotSafe::ConsistencyError)?,
+            })
+        })
+        .transpose()
+    }
+
+    fn store_lease_in_txn(lease: &Lease, txn: &Transaction) -> Result<(), NotSafe> {
+        let term = i64::try_from(lease.term).map_err(|_| NotSafe::ConsistencyError)?;
+        let expires_at =
+            i64::try_from(lease.expires_at_millis).map_err(|_| NotSafe::ConsistencyError)?;
+        txn.execute(
+            "INSERT OR REPLACE INTO ha_lease (id, holder, term, expires_at) VALUES (0, ?1, ?2, ?3)",
+            params![lease.holder, term, expires_at],
+        )?;
+        Ok(())
+    }
+
+    /// Check that this instance holds an unexpired lease, if the database is shared.
+    ///
+    /// This must be called in the same exclusive transaction that records a signed message, so
+    /// that the lease cannot be taken over between the check and the insert.
+    fn check_lease_in_txn(&self, txn: &Transaction) -> Result<(), NotSafe> {
+        let Some(holder) = &self.lease_holder else {
+            return Ok(());
+        };
+        let now = unix_time_now()?;
+        match Self::lease_in_txn(txn)? {
+            Some(lease) if lease.holder == *holder && !lease.is_expired(now) => Ok(()),
+            lease => Err(NotSafe::LeaseNotHeld(lease)),
+        }
+    }
+
     /// Import slashing protection from another client in the interchange format.
     ///
     /// This function will atomically import the entire interchange, failing if *any*
@@ -903,7 +1124,15 @@ impl SlashingDatabase {
         txn: &Transaction,
     ) -> Result<(), NotSafe> {
         let validator_id = self.get_validator_id_in_txn(txn, public_key)?;
+        Self::prune_signed_blocks_for_id(validator_id, new_min_slot, txn)
+    }
 
+    /// As for `prune_signed_blocks`, but for a validator ID regardless of its status.
+    fn prune_signed_blocks_for_id(
+        validator_id: i64,
+        new_min_slot: Slot,
+        txn: &Transaction,
+    ) -> Result<(), NotSafe> {
         txn.execute(
             "DELETE FROM signed_blocks
              WHERE
@@ -943,7 +1172,15 @@ impl SlashingDatabase {
         txn: &Transaction,
     ) -> Result<(), NotSafe> {
         let validator_id = self.get_validator_id_in_txn(txn, public_key)?;
+        Self::prune_signed_attestations_for_id(validator_id, new_min_target, txn)
+    }
 
+    /// As for `prune_signed_attestations`, but for a validator ID regardless of its status.
+    fn prune_signed_attestations_for_id(
+        validator_id: i64,
+        new_min_target: Epoch,
+        txn: &Transaction,
+    ) -> Result<(), NotSafe> {
         // The following holds, because we never store mutually slashable attestations:
         //   a.target < new_min_target --> a.source <= new_min_source
         //
@@ -1012,6 +1249,64 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Prune every block and attestation which is not required to enforce the minimum slot and
+    /// minimum epoch rules, leaving each registered validator (enabled or not) with only its
+    /// maximum block and its maximum attestation.
+    ///
+    /// The watermarks to retain are computed by minifying an export of the database (see
+    /// `Interchange::minify`) and are checked against the pruned database before the changes are
+    /// committed. The database file is compacted afterwards.
+    pub fn prune_to_watermarks(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<Vec<(PublicKeyBytes, ValidatorSummary)>, InterchangeError> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+
+        let minified = self
+            .export_interchange_info_in_txn(genesis_validators_root, None, &txn)?
+            .minify()?;
+
+        let mut summaries = Vec::with_capacity(minified.data.len());
+        for record in &minified.data {
+            let validator_id = self.get_validator_id_ignoring_status(&txn, &record.pubkey)?;
+
+            let max_block_slot = record.signed_blocks.iter().map(|block| block.slot).max();
+            let max_attestation = record
+                .signed_attestations
+                .iter()
+                .map(|att| (att.source_epoch, att.target_epoch))
+                .max_by_key(|(_, target)| *target);
+
+            if let Some(slot) = max_block_slot {
+                Self::prune_signed_blocks_for_id(validator_id, slot, &txn)?;
+            }
+            if let Some((_, target)) = max_attestation {
+                Self::prune_signed_attestations_for_id(validator_id, target, &txn)?;
+            }
+
+            // Exactly the watermarks computed by minification should remain.
+            let summary = Self::validator_summary_for_id(validator_id, &txn)?;
+            let (max_source, max_target) = max_attestation.unzip();
+            if summary.min_block_slot != max_block_slot
+                || summary.max_block_slot != max_block_slot
+                || summary.min_attestation_source != max_source
+                || summary.max_attestation_source != max_source
+                || summary.min_attestation_target != max_target
+                || summary.max_attestation_target != max_target
+            {
+                return Err(InterchangeError::SummaryInconsistent);
+            }
+            summaries.push((record.pubkey, summary));
+        }
+
+        txn.commit()?;
+
+        conn.execute("VACUUM", params![])?;
+
+        Ok(summaries)
+    }
+
     pub fn num_validator_rows(&self) -> Result<u32, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
@@ -1028,6 +1323,14 @@ impl SlashingDatabase {
         txn: &Transaction,
     ) -> Result<ValidatorSummary, NotSafe> {
         let validator_id = self.get_validator_id_in_txn(txn, public_key)?;
+        Self::validator_summary_for_id(validator_id, txn)
+    }
+
+    /// As for `validator_summary`, but for a validator ID regardless of its status.
+    fn validator_summary_for_id(
+        validator_id: i64,
+        txn: &Transaction,
+    ) -> Result<ValidatorSummary, NotSafe> {
         let (min_block_slot, max_block_slot) = txn
             .prepare(
                 "SELECT MIN(slot), MAX(slot)
@@ -1250,4 +1553,85 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn prune_to_watermarks() {
+        use crate::test_utils::pubkey;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        let (pk0, pk1) = (pubkey(0), pubkey(1));
+        db.register_validators([pk0, pk1].iter()).unwrap();
+
+        for (source, target) in [(0, 1), (1, 2), (2, 5)] {
+            db.check_and_insert_attestation_signing_root(
+                &pk0,
+                Epoch::new(source),
+                Epoch::new(target),
+                SigningRoot::default(),
+            )
+            .unwrap();
+        }
+        for slot in [1, 3, 7] {
+            db.check_and_insert_block_signing_root(&pk0, Slot::new(slot), SigningRoot::default())
+                .unwrap();
+        }
+        for (source, target) in [(0, 1), (1, 3)] {
+            db.check_and_insert_attestation_signing_root(
+                &pk1,
+                Epoch::new(source),
+                Epoch::new(target),
+                SigningRoot::default(),
+            )
+            .unwrap();
+        }
+
+        // Disabled validators are pruned too.
+        db.with_transaction(|txn| {
+            let id = db.get_validator_id_in_txn(txn, &pk1)?;
+            db.update_validator_status(txn, id, false)
+        })
+        .unwrap();
+
+        let mut summaries = db.prune_to_watermarks(Hash256::zero()).unwrap();
+        summaries.sort_by_key(|(pubkey, _)| *pubkey != pk0);
+        assert_eq!(summaries.len(), 2);
+
+        let (_, summary) = &summaries[0];
+        assert_eq!(summary.min_block_slot, Some(Slot::new(7)));
+        assert_eq!(summary.max_block_slot, Some(Slot::new(7)));
+        assert_eq!(summary.min_attestation_source, Some(Epoch::new(2)));
+        assert_eq!(summary.min_attestation_target, Some(Epoch::new(5)));
+
+        let (_, summary) = &summaries[1];
+        assert_eq!(summary.max_block_slot, None);
+        assert_eq!(summary.min_attestation_source, Some(Epoch::new(1)));
+        assert_eq!(summary.min_attestation_target, Some(Epoch::new(3)));
+
+        let interchange = db.export_all_interchange_info(Hash256::zero()).unwrap();
+        assert_eq!(interchange.len(), 2);
+        assert!(interchange
+            .data
+            .iter()
+            .all(|data| data.signed_blocks.len() <= 1 && data.signed_attestations.len() == 1));
+
+        // The minimum slot and minimum epoch rules still apply.
+        db.with_transaction(|txn| {
+            let id = db.get_validator_id_ignoring_status(txn, &pk1)?;
+            db.update_validator_status(txn, id, true)
+        })
+        .unwrap();
+        assert!(db
+            .check_and_insert_block_signing_root(&pk0, Slot::new(6), SigningRoot::default())
+            .is_err());
+        assert!(db
+            .check_and_insert_attestation_signing_root(
+                &pk1,
+                Epoch::new(0),
+                Epoch::new(2),
+                SigningRoot::default()
+            )
+            .is_err());
+    }
 }
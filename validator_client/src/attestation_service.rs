@@ -1,17 +1,23 @@
 use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::{DutiesService, DutyAndProof},
+    duty_history::{error_category, DutyAttempt},
     http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{DutyErrorCategory, DutyKind};
+use eth2::types::{EventKind, EventTopic};
+use eth2::BeaconNodeHttpClient;
 use futures::future::join_all;
+use futures::StreamExt;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
+use tokio::sync::watch;
 use tokio::time::{sleep, sleep_until, Duration, Instant};
 use tree_hash::TreeHash;
 use types::{
@@ -19,6 +25,14 @@ use types::{
     Slot,
 };
 
+/// The most recent head observed via the `head` event stream of a beacon node.
+#[derive(Clone, Default)]
+struct HeadEvent {
+    slot: Slot,
+    /// The beacon node which emitted the event.
+    beacon_node: Option<BeaconNodeHttpClient>,
+}
+
 /// Builds an `AttestationService`.
 pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     duties_service: Option<Arc<DutiesService<T, E>>>,
@@ -26,6 +40,7 @@ pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     slot_clock: Option<T>,
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
+    attest_on_block_arrival: bool,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
@@ -36,6 +51,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             slot_clock: None,
             beacon_nodes: None,
             context: None,
+            attest_on_block_arrival: false,
         }
     }
 
@@ -64,6 +80,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    pub fn attest_on_block_arrival(mut self, attest_on_block_arrival: bool) -> Self {
+        self.attest_on_block_arrival = attest_on_block_arrival;
+        self
+    }
+
     pub fn build(self) -> Result<AttestationService<T, E>, String> {
         Ok(AttestationService {
             inner: Arc::new(Inner {
@@ -82,6 +103,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or("Cannot build AttestationService without runtime_context")?,
+                attest_on_block_arrival: self.attest_on_block_arrival,
             }),
         })
     }
@@ -94,6 +116,9 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: T,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
+    /// Attest as soon as the block for the slot becomes the head of the beacon node, rather than
+    /// always waiting until 1/3rd of the way through the slot.
+    attest_on_block_arrival: bool,
 }
 
 /// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
@@ -101,6 +126,11 @@ pub struct Inner<T, E: EthSpec> {
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
 /// validators.
+///
+/// If `attest_on_block_arrival` is set, attestations are produced as soon as a beacon node
+/// emits a `head` event for the slot's block, with 1/3rd of the way through the slot remaining
+/// the deadline when the block is late or missing. The attestation data is then requested from
+/// the beacon node which emitted the event.
 pub struct AttestationService<T, E: EthSpec> {
     inner: Arc<Inner<T, E>>,
 }
@@ -140,13 +170,31 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 
         let executor = self.context.executor.clone();
 
+        let mut head_rx = if self.attest_on_block_arrival {
+            info!(log, "Attesting on block arrival");
+            let (head_tx, head_rx) = watch::channel(HeadEvent::default());
+            executor.spawn(
+                self.clone().head_event_listener(head_tx),
+                "attestation_head",
+            );
+            Some(head_rx)
+        } else {
+            None
+        };
+
         let interval_fut = async move {
             loop {
                 if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
-                    sleep(duration_to_next_slot + slot_duration / 3).await;
+                    let deadline = sleep(duration_to_next_slot + slot_duration / 3);
+                    let head_beacon_node = if let Some(head_rx) = head_rx.as_mut() {
+                        self.wait_for_block_or_deadline(head_rx, deadline).await
+                    } else {
+                        deadline.await;
+                        None
+                    };
                     let log = self.context.log();
 
-                    if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
+                    if let Err(e) = self.spawn_attestation_tasks(slot_duration, head_beacon_node) {
                         crit!(
                             log,
                             "Failed to spawn attestation tasks";
@@ -171,9 +219,121 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         Ok(())
     }
 
+    /// Wait until either the block for the next slot becomes the head of a beacon node, or
+    /// `deadline` elapses.
+    ///
+    /// Returns the beacon node which emitted the head event if the block arrived first.
+    async fn wait_for_block_or_deadline(
+        &self,
+        head_rx: &mut watch::Receiver<HeadEvent>,
+        deadline: tokio::time::Sleep,
+    ) -> Option<BeaconNodeHttpClient> {
+        let Some(slot) = self.slot_clock.now().map(|slot| slot + 1) else {
+            deadline.await;
+            return None;
+        };
+
+        match wait_for_head(head_rx, slot, deadline).await {
+            Some(beacon_node) => {
+                metrics::inc_counter_vec(&metrics::ATTESTATION_TRIGGERS, &[metrics::BLOCK_ARRIVAL]);
+                debug!(
+                    self.context.log(),
+                    "Attesting on block arrival";
+                    "beacon_node" => %beacon_node,
+                    "slot" => slot,
+                );
+                // The beacon node's clock may be slightly ahead of ours, ensure that the
+                // attestations are produced for `slot`.
+                if let Some(duration) = self.slot_clock.duration_to_slot(slot) {
+                    sleep(duration).await;
+                }
+                Some(beacon_node)
+            }
+            None => {
+                metrics::inc_counter_vec(&metrics::ATTESTATION_TRIGGERS, &[metrics::DEADLINE]);
+                None
+            }
+        }
+    }
+
+    /// Listen for `head` events from the beacon nodes, sending each new head and the beacon node
+    /// which emitted it to `head_tx`. Reconnects to the next available beacon node when the stream
+    /// fails.
+    async fn head_event_listener(self, head_tx: watch::Sender<HeadEvent>) {
+        let log = self.context.log().clone();
+        let slot_duration = self.slot_clock.slot_duration();
+
+        loop {
+            let events = self
+                .beacon_nodes
+                .first_success(
+                    RequireSynced::Yes,
+                    OfflineOnFailure::Yes,
+                    |beacon_node| async move {
+                        beacon_node
+                            .get_events::<E>(&[EventTopic::Head])
+                            .await
+                            .map(|events| (beacon_node.clone(), events))
+                    },
+                )
+                .await;
+
+            match events {
+                Ok((beacon_node, events)) => {
+                    futures::pin_mut!(events);
+                    while let Some(event) = events.next().await {
+                        match event {
+                            Ok(EventKind::Head(head)) => {
+                                head_tx.send_if_modified(|current| {
+                                    if head.slot > current.slot {
+                                        *current = HeadEvent {
+                                            slot: head.slot,
+                                            beacon_node: Some(beacon_node.clone()),
+                                        };
+                                        true
+                                    } else {
+                                        false
+                                    }
+                                });
+                            }
+                            Ok(_) => (),
+                            Err(e) => {
+                                warn!(
+                                    log,
+                                    "Error from head event stream";
+                                    "error" => ?e,
+                                );
+                                break;
+                            }
+                        }
+                    }
+                    debug!(log, "Head event stream ended");
+                }
+                Err(e) => {
+                    warn!(
+                        log,
+                        "Unable to subscribe to head events";
+                        "error" => %e,
+                        "msg" => "attestations will be produced 1/3rd of the way through the slot",
+                    );
+                }
+            }
+
+            // Avoid hammering the beacon nodes when reconnecting.
+            sleep(slot_duration).await;
+        }
+    }
+
     /// For each each required attestation, spawn a new task that downloads, signs and uploads the
     /// attestation to the beacon node.
-    fn spawn_attestation_tasks(&self, slot_duration: Duration) -> Result<(), String> {
+    ///
+    /// If attestation production was triggered by a head event, `head_beacon_node` is the beacon
+    /// node which emitted it.
+    fn spawn_attestation_tasks(
+        &self,
+        slot_duration: Duration,
+        head_beacon_node: Option<BeaconNodeHttpClient>,
+    ) -> Result<(), String> {
         let slot = self.slot_clock.now().ok_or("Failed to read slot clock")?;
         let duration_to_next_slot = self
             .slot_clock
@@ -212,6 +372,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         committee_index,
                         validator_duties,
                         aggregate_production_instant,
+                        head_beacon_node.clone(),
                     ),
                     "attestation publish",
                 );
@@ -240,6 +401,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         committee_index: CommitteeIndex,
         validator_duties: Vec<DutyAndProof>,
         aggregate_production_instant: Instant,
+        head_beacon_node: Option<BeaconNodeHttpClient>,
     ) -> Result<(), ()> {
         let log = self.context.log();
         let attestations_timer = metrics::start_timer_vec(
@@ -257,7 +419,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         //
         // Download, sign and publish an `Attestation` for each validator.
         let attestation_opt = self
-            .produce_and_publish_attestations(slot, committee_index, &validator_duties)
+            .produce_and_publish_attestations(
+                slot,
+                committee_index,
+                &validator_duties,
+                head_beacon_node.as_ref(),
+            )
             .await
             .map_err(move |e| {
                 crit!(
@@ -306,6 +473,74 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         Ok(())
     }
 
+    /// Download the attestation data for `slot` and `committee_index`, returning it along with
+    /// the beacon node which provided it.
+    ///
+    /// When attestation production was triggered by a head event, the data is requested from
+    /// `head_beacon_node`, since the other beacon nodes may not have imported the block yet. If
+    /// that request fails, the other beacon nodes are only tried once the usual deadline 1/3rd of
+    /// the way through the slot has passed.
+    async fn get_attestation_data(
+        &self,
+        slot: Slot,
+        committee_index: CommitteeIndex,
+        head_beacon_node: Option<&BeaconNodeHttpClient>,
+    ) -> Result<(String, AttestationData), String> {
+        if let Some(beacon_node) = head_beacon_node {
+            let result = {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::ATTESTATION_SERVICE_TIMES,
+                    &[metrics::ATTESTATIONS_HTTP_GET],
+                );
+                beacon_node
+                    .get_validator_attestation_data(slot, committee_index)
+                    .await
+            };
+            match result {
+                Ok(response) => return Ok((beacon_node.to_string(), response.data)),
+                Err(e) => {
+                    warn!(
+                        self.context.log(),
+                        "Failed to produce attestation data on block arrival";
+                        "error" => ?e,
+                        "beacon_node" => %beacon_node,
+                        "msg" => "trying other beacon nodes 1/3rd of the way through the slot",
+                        "slot" => slot,
+                    );
+                    let deadline = self
+                        .slot_clock
+                        .start_of(slot)
+                        .map(|start| start + self.slot_clock.unagg_attestation_production_delay());
+                    if let Some(duration) = deadline
+                        .zip(self.slot_clock.now_duration())
+                        .and_then(|(deadline, now)| deadline.checked_sub(now))
+                    {
+                        sleep(duration).await;
+                    }
+                }
+            }
+        }
+
+        self.beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::Yes,
+                |beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::ATTESTATION_SERVICE_TIMES,
+                        &[metrics::ATTESTATIONS_HTTP_GET],
+                    );
+                    beacon_node
+                        .get_validator_attestation_data(slot, committee_index)
+                        .await
+                        .map_err(|e| format!("Failed to produce attestation data: {:?}", e))
+                        .map(|result| (beacon_node.to_string(), result.data))
+                },
+            )
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     /// Performs the first step of the attesting process: downloading `Attestation` objects,
     /// signing them and returning them to the validator.
     ///
@@ -323,6 +558,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         slot: Slot,
         committee_index: CommitteeIndex,
         validator_duties: &[DutyAndProof],
+        head_beacon_node: Option<&BeaconNodeHttpClient>,
     ) -> Result<Option<AttestationData>, String> {
         let log = self.context.log();
 
@@ -335,29 +571,29 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .now()
             .ok_or("Unable to determine current slot from clock")?
             .epoch(E::slots_per_epoch());
+        let duty_history = self.validator_store.duty_history();
+        let mut attempt = DutyAttempt::new(DutyKind::Attestation, slot);
 
-        let attestation_data = self
-            .beacon_nodes
-            .first_success(
-                RequireSynced::No,
-                OfflineOnFailure::Yes,
-                |beacon_node| async move {
-                    let _timer = metrics::start_timer_vec(
-                        &metrics::ATTESTATION_SERVICE_TIMES,
-                        &[metrics::ATTESTATIONS_HTTP_GET],
-                    );
-                    beacon_node
-                        .get_validator_attestation_data(slot, committee_index)
-                        .await
-                        .map_err(|e| format!("Failed to produce attestation data: {:?}", e))
-                        .map(|result| result.data)
-                },
-            )
+        let (beacon_node, attestation_data) = match self
+            .get_attestation_data(slot, committee_index, head_beacon_node)
             .await
-            .map_err(|e| e.to_string())?;
+        {
+            Ok(response) => response,
+            Err(error) => {
+                for duty_and_proof in validator_duties {
+                    duty_history.record(
+                        duty_and_proof.duty.pubkey,
+                        attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, error.clone()),
+                    );
+                }
+                return Err(error);
+            }
+        };
+        attempt.set_beacon_node(beacon_node);
 
         // Create futures to produce signed `Attestation` objects.
         let attestation_data_ref = &attestation_data;
+        let attempt_ref = &attempt;
         let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
             let duty = &duty_and_proof.duty;
             let attestation_data = attestation_data_ref;
@@ -384,7 +620,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 signature: AggregateSignature::infinity(),
             };
 
-            match self
+            let result = self
                 .validator_store
                 .sign_attestation(
                     duty.pubkey,
@@ -392,9 +628,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     &mut attestation,
                     current_epoch,
                 )
-                .await
-            {
-                Ok(()) => Some((attestation, duty.validator_index)),
+                .await;
+
+            if let Err(e) = &result {
+                duty_history.record(
+                    duty.pubkey,
+                    attempt_ref.failure(error_category(e), format!("{:?}", e)),
+                );
+            }
+
+            match result {
+                Ok(()) => Some((attestation, duty)),
                 Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                     // A pubkey can be missing when a validator was recently
                     // removed via the API.
@@ -424,14 +668,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = join_all(signing_futures)
+        let (ref attestations, duties): (Vec<_>, Vec<_>) = join_all(signing_futures)
             .await
             .into_iter()
             .flatten()
             .unzip();
+        let validator_indices = duties
+            .iter()
+            .map(|duty| duty.validator_index)
+            .collect::<Vec<_>>();
 
         // Post the attestations to the BN.
-        match self
+        let result = self
             .beacon_nodes
             .request(
                 RequireSynced::No,
@@ -447,8 +695,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         .await
                 },
             )
-            .await
-        {
+            .await;
+
+        for duty in &duties {
+            let record = match &result {
+                Ok(()) => attempt.success(),
+                Err(e) => attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, e.to_string()),
+            };
+            duty_history.record(duty.pubkey, record);
+        }
+
+        match result {
             Ok(()) => info!(
                 log,
                 "Successfully published attestations";
@@ -500,7 +757,10 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             return Ok(());
         }
 
-        let aggregated_attestation = &self
+        let duty_history = self.validator_store.duty_history();
+        let mut attempt = DutyAttempt::new(DutyKind::Aggregate, attestation_data.slot);
+
+        let (beacon_node, aggregated_attestation) = match self
             .beacon_nodes
             .first_success(
                 RequireSynced::No,
@@ -520,11 +780,29 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                             format!("Failed to produce an aggregate attestation: {:?}", e)
                         })?
                         .ok_or_else(|| format!("No aggregate available for {:?}", attestation_data))
-                        .map(|result| result.data)
+                        .map(|result| (beacon_node.to_string(), result.data))
                 },
             )
             .await
-            .map_err(|e| e.to_string())?;
+        {
+            Ok(response) => response,
+            Err(e) => {
+                let error = e.to_string();
+                for duty_and_proof in validator_duties
+                    .iter()
+                    .filter(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+                {
+                    duty_history.record(
+                        duty_and_proof.duty.pubkey,
+                        attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, error.clone()),
+                    );
+                }
+                return Err(error);
+            }
+        };
+        attempt.set_beacon_node(beacon_node);
+        let aggregated_attestation = &aggregated_attestation;
+        let attempt_ref = &attempt;
 
         // Create futures to produce the signed aggregated attestations.
         let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
@@ -539,7 +817,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 return None;
             }
 
-            match self
+            let result = self
                 .validator_store
                 .produce_signed_aggregate_and_proof(
                     duty.pubkey,
@@ -547,9 +825,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     aggregated_attestation.clone(),
                     selection_proof.clone(),
                 )
-                .await
-            {
-                Ok(aggregate) => Some(aggregate),
+                .await;
+
+            if let Err(e) = &result {
+                duty_history.record(
+                    duty.pubkey,
+                    attempt_ref.failure(error_category(e), format!("{:?}", e)),
+                );
+            }
+
+            match result {
+                Ok(aggregate) => Some((aggregate, duty.pubkey)),
                 Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                     // A pubkey can be missing when a validator was recently
                     // removed via the API.
@@ -573,11 +859,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let signed_aggregate_and_proofs = join_all(signing_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
+        let (signed_aggregate_and_proofs, aggregators): (Vec<_>, Vec<_>) =
+            join_all(signing_futures)
+                .await
+                .into_iter()
+                .flatten()
+                .unzip();
 
         if !signed_aggregate_and_proofs.is_empty() {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
@@ -599,6 +886,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 .await
             {
                 Ok(()) => {
+                    for pubkey in aggregators {
+                        duty_history.record(pubkey, attempt.success());
+                    }
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = &signed_aggregate_and_proof.message.aggregate;
                         info!(
@@ -614,6 +904,13 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     }
                 }
                 Err(e) => {
+                    for pubkey in aggregators {
+                        duty_history.record(
+                            pubkey,
+                            attempt
+                                .failure(DutyErrorCategory::BeaconNodeUnavailable, e.to_string()),
+                        );
+                    }
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = &signed_aggregate_and_proof.message.aggregate;
                         crit!(
@@ -660,11 +957,49 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     }
 }
 
+/// Wait until `head_rx` reports a head at or after `slot`, or `deadline` elapses.
+///
+/// Returns the beacon node which emitted the head event if it arrived before the deadline.
+async fn wait_for_head(
+    head_rx: &mut watch::Receiver<HeadEvent>,
+    slot: Slot,
+    deadline: tokio::time::Sleep,
+) -> Option<BeaconNodeHttpClient> {
+    let head_arrival = async {
+        loop {
+            {
+                let head = head_rx.borrow_and_update();
+                if head.slot >= slot {
+                    return head.beacon_node.clone();
+                }
+            }
+            if head_rx.changed().await.is_err() {
+                // The listener has stopped, rely on the deadline.
+                futures::future::pending::<()>().await;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = deadline => None,
+        beacon_node = head_arrival => beacon_node,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use eth2::Timeouts;
     use futures::future::FutureExt;
     use parking_lot::RwLock;
+    use sensitive_url::SensitiveUrl;
+
+    fn beacon_node(url: &str) -> BeaconNodeHttpClient {
+        BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(url).unwrap(),
+            Timeouts::set_all(Duration::from_secs(1)),
+        )
+    }
 
     /// This test is to ensure that a `tokio_timer::Sleep` with an instant in the past will still
     /// trigger.
@@ -683,4 +1018,53 @@ mod tests {
             "state should have been updated"
         );
     }
+
+    /// Attestation production is triggered as soon as a beacon node emits the head event for the
+    /// slot, rather than at the deadline, and the emitting beacon node is returned.
+    #[tokio::test]
+    async fn head_event_triggers_attestation_before_deadline() {
+        let slot = Slot::new(1);
+        let deadline = Duration::from_secs(4);
+        let head_node = beacon_node("http://head-node:5052");
+        let (head_tx, mut head_rx) = watch::channel(HeadEvent::default());
+
+        let sender_head_node = head_node.clone();
+        let sender = tokio::spawn(async move {
+            // A head from an earlier slot doesn't trigger attestation production.
+            sleep(Duration::from_millis(50)).await;
+            head_tx.send_replace(HeadEvent {
+                slot: slot - 1,
+                beacon_node: Some(beacon_node("http://other-node:5052")),
+            });
+            sleep(Duration::from_millis(50)).await;
+            head_tx.send_replace(HeadEvent {
+                slot,
+                beacon_node: Some(sender_head_node),
+            });
+            head_tx
+        });
+
+        let start = Instant::now();
+        let triggered_by = wait_for_head(&mut head_rx, slot, sleep(deadline)).await;
+        assert!(start.elapsed() < deadline / 2);
+        assert_eq!(
+            triggered_by.map(|beacon_node| beacon_node.to_string()),
+            Some(head_node.to_string())
+        );
+        drop(sender.await.unwrap());
+    }
+
+    /// Without a head event for the slot, attestation production waits for the deadline.
+    #[tokio::test]
+    async fn deadline_triggers_attestation_without_head_event() {
+        let deadline = Duration::from_millis(100);
+        let (head_tx, mut head_rx) = watch::channel(HeadEvent::default());
+
+        let start = Instant::now();
+        assert!(wait_for_head(&mut head_rx, Slot::new(1), sleep(deadline))
+            .await
+            .is_none());
+        assert!(start.elapsed() >= deadline);
+        drop(head_tx);
+    }
 }
@@ -1,15 +1,25 @@
 //! Allows for a list of `BeaconNodeHttpClient` to appear as a single entity which will exhibits
 //! "fallback" behaviour; it will try a request on all of the nodes until one or none of them
 //! succeed.
-
-use crate::check_synced::check_synced;
-use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+//!
+//! Nodes are tried in order of their health score (see `BeaconNodeHealth::score`), which is
+//! re-computed each time the status of the nodes is updated. The node tried first is referred to as
+//! the *primary*.
+
+use crate::check_synced::{check_synced, get_sync_status};
+use crate::http_metrics::metrics::{
+    inc_counter, inc_counter_vec, set_int_gauge, BEACON_NODE_HEALTH_SCORE,
+    BEACON_NODE_PRIMARY_SWITCHES, ENDPOINT_ERRORS, ENDPOINT_REQUESTS,
+};
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{BeaconNodeHealthData, BeaconNodeStatus};
+use eth2::types::SyncingData;
 use eth2::BeaconNodeHttpClient;
 use futures::future;
 use serde::{Deserialize, Serialize};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
+use std::cmp::Reverse;
 use std::fmt;
 use std::fmt::Debug;
 use std::future::Future;
@@ -18,7 +28,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use strum::{EnumString, EnumVariantNames};
 use tokio::{sync::RwLock, time::sleep};
-use types::{ChainSpec, Config, EthSpec};
+use types::{ChainSpec, Config, EthSpec, Slot};
 
 /// Message emitted when the VC detects the BN is using a different spec.
 const UPDATE_REQUIRED_LOG_HINT: &str = "this VC or the remote BN may need updating";
@@ -32,6 +42,40 @@ const UPDATE_REQUIRED_LOG_HINT: &str = "this VC or the remote BN may need updati
 /// having the correct nodes up and running prior to the start of the slot.
 const SLOT_LOOKAHEAD: Duration = Duration::from_secs(2);
 
+/// The health score of a beacon node without any penalties.
+pub const MAX_HEALTH_SCORE: u64 = 10_000;
+
+/// Penalty applied for each slot that a beacon node reports it is behind the current slot.
+const SYNC_DISTANCE_PENALTY: u64 = 1_000;
+
+/// Penalty applied for each slot that the head of a beacon node is behind the most advanced head
+/// amongst all beacon nodes.
+const HEAD_LAG_PENALTY: u64 = 1_000;
+
+/// Penalty applied when a beacon node reports that its execution layer is offline.
+const EL_OFFLINE_PENALTY: u64 = 5_000;
+
+/// Penalty applied when the head of a beacon node is optimistic (i.e. not fully verified by its
+/// execution layer).
+const OPTIMISTIC_PENALTY: u64 = 2_500;
+
+/// The maximum penalty applied for latency, at a rate of one point per millisecond.
+const MAX_LATENCY_PENALTY: u64 = 2_000;
+
+/// The score by which another node must exceed the primary before it replaces the primary.
+///
+/// This prevents flapping between nodes with similar scores, e.g. due to latency jitter. The
+/// hysteresis is not applied to a primary whose head is more than `PRIMARY_MAX_HEAD_LAG` slots
+/// behind that of the best candidate, so such a primary will always be replaced.
+const PRIMARY_SWITCH_HYSTERESIS: u64 = 1_500;
+
+/// The number of slots the head of the primary may lag the head of the best candidate whilst the
+/// hysteresis still applies.
+///
+/// Nodes commonly import a block at slightly different times, so a primary which is a single slot
+/// behind at the time of a refresh is likely to catch up and should not be replaced for it alone.
+const PRIMARY_MAX_HEAD_LAG: u64 = 1;
+
 /// Indicates a measurement of latency between the VC and a BN.
 pub struct LatencyMeasurement {
     /// An identifier for the beacon node (e.g. the URL).
@@ -143,11 +187,66 @@ pub enum CandidateError {
     NotSynced,
 }
 
+/// The health of a beacon node, as observed when its status was last refreshed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeaconNodeHealth {
+    pub head_slot: Slot,
+    pub sync_distance: Slot,
+    pub el_offline: bool,
+    pub is_optimistic: bool,
+    /// The round-trip latency of the most recent request used to measure latency.
+    pub latency: Option<Duration>,
+}
+
+impl BeaconNodeHealth {
+    fn from_sync_status(sync_status: &SyncingData, latency: Duration) -> Self {
+        Self {
+            head_slot: sync_status.head_slot,
+            sync_distance: sync_status.sync_distance,
+            el_offline: sync_status.el_offline.unwrap_or(false),
+            is_optimistic: sync_status.is_optimistic.unwrap_or(false),
+            latency: Some(latency),
+        }
+    }
+
+    /// Returns a score between zero and `MAX_HEALTH_SCORE`, where higher is healthier.
+    ///
+    /// `best_head_slot` is the most advanced head amongst all available beacon nodes, which
+    /// penalizes a node that is lagging even if it believes it is synced.
+    pub fn score(&self, best_head_slot: Slot) -> u64 {
+        let head_lag = best_head_slot.saturating_sub(self.head_slot).as_u64();
+        let latency_millis = self
+            .latency
+            .map_or(0, |latency| latency.as_millis() as u64)
+            .min(MAX_LATENCY_PENALTY);
+
+        let penalty = self
+            .sync_distance
+            .as_u64()
+            .saturating_mul(SYNC_DISTANCE_PENALTY)
+            .saturating_add(head_lag.saturating_mul(HEAD_LAG_PENALTY))
+            .saturating_add(if self.el_offline {
+                EL_OFFLINE_PENALTY
+            } else {
+                0
+            })
+            .saturating_add(if self.is_optimistic {
+                OPTIMISTIC_PENALTY
+            } else {
+                0
+            })
+            .saturating_add(latency_millis);
+
+        MAX_HEALTH_SCORE.saturating_sub(penalty)
+    }
+}
+
 /// Represents a `BeaconNodeHttpClient` inside a `BeaconNodeFallback` that may or may not be used
 /// for a query.
 pub struct CandidateBeaconNode<E> {
     beacon_node: BeaconNodeHttpClient,
     status: RwLock<Result<(), CandidateError>>,
+    health: RwLock<Option<BeaconNodeHealth>>,
     _phantom: PhantomData<E>,
 }
 
@@ -157,10 +256,16 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Self {
             beacon_node,
             status: RwLock::new(Err(CandidateError::Uninitialized)),
+            health: RwLock::new(None),
             _phantom: PhantomData,
         }
     }
 
+    /// Returns the health of `self` as observed when its status was last refreshed, if known.
+    pub async fn health(&self) -> Option<BeaconNodeHealth> {
+        *self.health.read().await
+    }
+
     /// Returns the status of `self`.
     ///
     /// If `RequiredSynced::No`, any `NotSynced` node will be ignored and mapped to `Ok(())`.
@@ -202,6 +307,12 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         // status. I deem this edge-case acceptable in return for the concurrency benefits of not
         // holding a write-lock whilst we check the online status of the node.
         *self.status.write().await = new_status;
+        if matches!(
+            new_status,
+            Err(CandidateError::Offline | CandidateError::Incompatible)
+        ) {
+            *self.health.write().await = None;
+        }
 
         new_status
     }
@@ -311,14 +422,20 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Ok(())
     }
 
-    /// Checks if the beacon node is synced.
+    /// Checks if the beacon node is synced, updating `self.health`.
     async fn is_synced<T: SlotClock>(
         &self,
         slot_clock: Option<&T>,
         log: &Logger,
     ) -> Result<(), CandidateError> {
         if let Some(slot_clock) = slot_clock {
-            check_synced(&self.beacon_node, slot_clock, Some(log)).await
+            let request_instant = Instant::now();
+            let sync_status = get_sync_status(&self.beacon_node, Some(log)).await?;
+            *self.health.write().await = Some(BeaconNodeHealth::from_sync_status(
+                &sync_status,
+                request_instant.elapsed(),
+            ));
+            check_synced(&self.beacon_node, &sync_status, slot_clock, Some(log))
         } else {
             // Skip this check if we don't supply a slot clock.
             Ok(())
@@ -331,6 +448,8 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 /// identical query.
 pub struct BeaconNodeFallback<T, E> {
     candidates: Vec<CandidateBeaconNode<E>>,
+    /// Indices of `candidates` in the order in which they are tried, starting with the primary.
+    ranking: RwLock<Vec<usize>>,
     slot_clock: Option<T>,
     broadcast_topics: Vec<ApiTopic>,
     spec: ChainSpec,
@@ -344,8 +463,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         spec: ChainSpec,
         log: Logger,
     ) -> Self {
+        let ranking = RwLock::new((0..candidates.len()).collect());
         Self {
             candidates,
+            ranking,
             slot_clock: None,
             broadcast_topics,
             spec,
@@ -381,7 +502,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     /// The count of synced and ready fallbacks excluding the primary beacon node candidate.
     pub async fn num_synced_fallback(&self) -> usize {
         let mut n = 0;
-        for candidate in self.candidates.iter().skip(1) {
+        for candidate in self.ranked_candidates().await.into_iter().skip(1) {
             if candidate.status(RequireSynced::Yes).await.is_ok() {
                 n += 1
             }
@@ -416,6 +537,143 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
         // run all updates concurrently and ignore errors
         let _ = future::join_all(futures).await;
+
+        self.update_ranking().await;
+    }
+
+    /// Set the status and health of the candidate at `index` and re-rank all candidates, as if its
+    /// status had been refreshed.
+    #[cfg(test)]
+    pub(crate) async fn set_candidate_health(
+        &self,
+        index: usize,
+        status: Result<(), CandidateError>,
+        health: Option<BeaconNodeHealth>,
+    ) {
+        let candidate = &self.candidates[index];
+        *candidate.status.write().await = status;
+        *candidate.health.write().await = health;
+        self.update_ranking().await;
+    }
+
+    /// Returns the candidates in the order in which they should be tried.
+    async fn ranked_candidates(&self) -> Vec<&CandidateBeaconNode<E>> {
+        self.ranking
+            .read()
+            .await
+            .iter()
+            .map(|&index| &self.candidates[index])
+            .collect()
+    }
+
+    /// Returns the status and score of each candidate, in the configured order.
+    async fn score_candidates(&self) -> Vec<ScoredCandidate> {
+        let mut statuses = Vec::with_capacity(self.candidates.len());
+        for candidate in &self.candidates {
+            statuses.push((
+                candidate.status(RequireSynced::Yes).await,
+                candidate.health().await,
+            ));
+        }
+
+        let best_head_slot = statuses
+            .iter()
+            .filter(|(status, _)| status_tier(status) < UNAVAILABLE_TIER)
+            .filter_map(|(_, health)| health.map(|health| health.head_slot))
+            .max();
+
+        statuses
+            .into_iter()
+            .enumerate()
+            .map(|(index, (status, health))| {
+                let score = match (best_head_slot, health) {
+                    (Some(best_head_slot), Some(health))
+                        if status_tier(&status) < UNAVAILABLE_TIER =>
+                    {
+                        health.score(best_head_slot)
+                    }
+                    _ => 0,
+                };
+                ScoredCandidate {
+                    index,
+                    status,
+                    health,
+                    best_head_slot,
+                    score,
+                }
+            })
+            .collect()
+    }
+
+    /// Re-ranks the candidates by their status and health score, switching the primary if it has
+    /// become sufficiently less healthy than another candidate.
+    async fn update_ranking(&self) {
+        let scored = self.score_candidates().await;
+        for candidate in &scored {
+            set_int_gauge(
+                &BEACON_NODE_HEALTH_SCORE,
+                &[self.candidates[candidate.index].beacon_node.as_ref()],
+                candidate.score as i64,
+            );
+        }
+
+        let mut ranking = self.ranking.write().await;
+        let previous_primary = ranking.first().copied();
+        let new_ranking = rank_candidates(&scored, previous_primary);
+
+        if let (Some(previous), Some(&new)) = (previous_primary, new_ranking.first()) {
+            if previous != new {
+                info!(
+                    self.log,
+                    "Switched primary beacon node";
+                    "previous" => %self.candidates[previous].beacon_node,
+                    "previous_score" => scored[previous].score,
+                    "new" => %self.candidates[new].beacon_node,
+                    "new_score" => scored[new].score,
+                );
+                inc_counter(&BEACON_NODE_PRIMARY_SWITCHES);
+            }
+        }
+
+        *ranking = new_ranking;
+    }
+
+    /// Returns the health of each candidate, in the order in which they are tried.
+    pub async fn health(&self) -> Vec<BeaconNodeHealthData> {
+        let scored = self.score_candidates().await;
+        let ranking = self.ranking.read().await.clone();
+
+        ranking
+            .into_iter()
+            .enumerate()
+            .map(|(rank, index)| {
+                let candidate = &scored[index];
+                let health = candidate.health;
+                BeaconNodeHealthData {
+                    index,
+                    endpoint: self.candidates[index].beacon_node.to_string(),
+                    is_primary: rank == 0,
+                    status: match candidate.status {
+                        Ok(()) => BeaconNodeStatus::Synced,
+                        Err(CandidateError::NotSynced) => BeaconNodeStatus::NotSynced,
+                        Err(CandidateError::Offline) => BeaconNodeStatus::Offline,
+                        Err(CandidateError::Incompatible) => BeaconNodeStatus::Incompatible,
+                        Err(CandidateError::Uninitialized) => BeaconNodeStatus::Uninitialized,
+                    },
+                    score: candidate.score,
+                    head_slot: health.map(|health| health.head_slot),
+                    sync_distance: health.map(|health| health.sync_distance),
+                    head_lag: health.zip(candidate.best_head_slot).map(
+                        |(health, best_head_slot)| best_head_slot.saturating_sub(health.head_slot),
+                    ),
+                    el_offline: health.map(|health| health.el_offline),
+                    is_optimistic: health.map(|health| health.is_optimistic),
+                    latency_ms: health
+                        .and_then(|health| health.latency)
+                        .map(|latency| latency.as_millis() as u64),
+                }
+            })
+            .collect()
     }
 
     /// Concurrently send a request to all candidates (regardless of
@@ -425,7 +683,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         let futures: Vec<_> = self
             .candidates
             .iter()
-            .map(|candidate| async {
+            .map(|candidate| async move {
                 let beacon_node_id = candidate.beacon_node.to_string();
                 // The `node/version` endpoint is used since I imagine it would
                 // require the least processing in the BN and therefore measure
@@ -439,7 +697,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                     .await
                     .ok()
                     .map(|_| Instant::now());
-                (beacon_node_id, response_instant)
+                (candidate, beacon_node_id, response_instant)
             })
             .collect();
 
@@ -448,15 +706,25 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // Send the request to all BNs at the same time. This might involve some
         // queueing on the sending host, however I hope it will avoid bias
         // caused by sending requests at different times.
-        future::join_all(futures)
-            .await
-            .into_iter()
-            .map(|(beacon_node_id, response_instant)| LatencyMeasurement {
+        let mut measurements = Vec::with_capacity(self.candidates.len());
+        for (candidate, beacon_node_id, response_instant) in future::join_all(futures).await {
+            let latency = response_instant
+                .and_then(|response| response.checked_duration_since(request_instant));
+
+            // Use the measurement when ranking the candidate. A failed request is reflected in
+            // the status of the candidate instead.
+            if let Some(latency) = latency {
+                if let Some(health) = candidate.health.write().await.as_mut() {
+                    health.latency = Some(latency);
+                }
+            }
+
+            measurements.push(LatencyMeasurement {
                 beacon_node_id,
-                latency: response_instant
-                    .and_then(|response| response.checked_duration_since(request_instant)),
-            })
-            .collect()
+                latency,
+            });
+        }
+        measurements
     }
 
     /// Run `func` against each candidate in `self`, returning immediately if a result is found.
@@ -515,10 +783,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             }};
         }
 
-        // First pass: try `func` on all synced and ready candidates.
+        // First pass: try `func` on all synced and ready candidates, in order of their health.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates().await {
             match candidate.status(RequireSynced::Yes).await {
                 Err(e @ CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -629,7 +897,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // First pass: try `func` on all synced and ready candidates.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates().await {
             match candidate.status(RequireSynced::Yes).await {
                 Err(CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -713,6 +981,82 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     }
 }
 
+/// The tier of candidates which are offline, incompatible or uninitialized.
+const UNAVAILABLE_TIER: u8 = 2;
+
+/// Candidates are ranked by tier before their score, so that a synced candidate is always preferred
+/// over an unsynced or unavailable one.
+fn status_tier(status: &Result<(), CandidateError>) -> u8 {
+    match status {
+        Ok(()) => 0,
+        Err(CandidateError::NotSynced) => 1,
+        Err(_) => UNAVAILABLE_TIER,
+    }
+}
+
+/// The status and health score of a candidate at the time of ranking.
+#[derive(Debug, Clone, Copy)]
+struct ScoredCandidate {
+    /// The index of the candidate in the configured order.
+    index: usize,
+    status: Result<(), CandidateError>,
+    health: Option<BeaconNodeHealth>,
+    best_head_slot: Option<Slot>,
+    score: u64,
+}
+
+impl ScoredCandidate {
+    /// Returns `true` if the head of `self` is known to be more than `PRIMARY_MAX_HEAD_LAG` slots
+    /// behind the head of `other`.
+    fn head_is_behind(&self, other: &Self) -> bool {
+        match (self.health, other.health) {
+            (Some(health), Some(other_health)) => {
+                health.head_slot + PRIMARY_MAX_HEAD_LAG < other_health.head_slot
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Returns the indices of `candidates` ordered from most to least preferred.
+///
+/// Candidates are ordered by the tier of their status, then by descending score and finally by
+/// their configured order. The `previous_primary` remains first unless another candidate in the
+/// same tier has a score more than `PRIMARY_SWITCH_HYSTERESIS` greater, its head is more than
+/// `PRIMARY_MAX_HEAD_LAG` slots behind that of the best candidate, or it has dropped to a lower
+/// tier.
+fn rank_candidates(candidates: &[ScoredCandidate], previous_primary: Option<usize>) -> Vec<usize> {
+    let mut ranked = candidates.to_vec();
+    ranked.sort_by_key(|candidate| {
+        (
+            status_tier(&candidate.status),
+            Reverse(candidate.score),
+            candidate.index,
+        )
+    });
+
+    let previous_position = previous_primary.and_then(|previous| {
+        ranked
+            .iter()
+            .position(|candidate| candidate.index == previous)
+    });
+    if let (Some(position), Some(best)) = (previous_position, ranked.first()) {
+        let previous = &ranked[position];
+        if status_tier(&previous.status) == status_tier(&best.status)
+            && best.score <= previous.score.saturating_add(PRIMARY_SWITCH_HYSTERESIS)
+            && !previous.head_is_behind(best)
+        {
+            let previous = ranked.remove(position);
+            ranked.insert(0, previous);
+        }
+    }
+
+    ranked
+        .into_iter()
+        .map(|candidate| candidate.index)
+        .collect()
+}
+
 /// Serves as a cue for `BeaconNodeFallback` to tell which requests need to be broadcasted.
 #[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, EnumString, EnumVariantNames)]
 #[strum(serialize_all = "kebab-case")]
@@ -736,6 +1080,134 @@ mod test {
     use std::str::FromStr;
     use strum::VariantNames;
 
+    fn health(head_slot: u64, latency_millis: u64) -> BeaconNodeHealth {
+        BeaconNodeHealth {
+            head_slot: Slot::new(head_slot),
+            sync_distance: Slot::new(0),
+            el_offline: false,
+            is_optimistic: false,
+            latency: Some(Duration::from_millis(latency_millis)),
+        }
+    }
+
+    fn scored(index: usize, status: Result<(), CandidateError>, score: u64) -> ScoredCandidate {
+        ScoredCandidate {
+            index,
+            status,
+            health: None,
+            best_head_slot: None,
+            score,
+        }
+    }
+
+    #[test]
+    fn health_score() {
+        let best_head_slot = Slot::new(10);
+        assert_eq!(health(10, 0).score(best_head_slot), MAX_HEALTH_SCORE);
+        assert_eq!(health(10, 20).score(best_head_slot), MAX_HEALTH_SCORE - 20);
+        assert_eq!(
+            health(10, 60_000).score(best_head_slot),
+            MAX_HEALTH_SCORE - MAX_LATENCY_PENALTY
+        );
+        assert_eq!(
+            health(9, 0).score(best_head_slot),
+            MAX_HEALTH_SCORE - HEAD_LAG_PENALTY
+        );
+        assert_eq!(health(0, 0).score(best_head_slot), 0);
+
+        let lagging = BeaconNodeHealth {
+            sync_distance: Slot::new(2),
+            ..health(10, 0)
+        };
+        assert_eq!(
+            lagging.score(best_head_slot),
+            MAX_HEALTH_SCORE - 2 * SYNC_DISTANCE_PENALTY
+        );
+
+        let optimistic = BeaconNodeHealth {
+            is_optimistic: true,
+            ..health(10, 0)
+        };
+        let el_offline = BeaconNodeHealth {
+            el_offline: true,
+            ..health(10, 0)
+        };
+        assert!(el_offline.score(best_head_slot) < optimistic.score(best_head_slot));
+        assert!(optimistic.score(best_head_slot) < health(10, 0).score(best_head_slot));
+    }
+
+    #[test]
+    fn rank_candidates_by_tier_then_score() {
+        let candidates = vec![
+            scored(0, Err(CandidateError::Offline), 0),
+            scored(1, Err(CandidateError::NotSynced), 9_000),
+            scored(2, Ok(()), 7_000),
+            scored(3, Ok(()), 8_000),
+            scored(4, Ok(()), 8_000),
+        ];
+        assert_eq!(rank_candidates(&candidates, None), vec![3, 4, 2, 1, 0]);
+    }
+
+    #[test]
+    fn rank_candidates_with_hysteresis() {
+        // The primary is retained whilst the best candidate is within the hysteresis.
+        let candidates = vec![
+            scored(0, Ok(()), 8_000),
+            scored(1, Ok(()), 8_000 + PRIMARY_SWITCH_HYSTERESIS),
+        ];
+        assert_eq!(rank_candidates(&candidates, Some(0)), vec![0, 1]);
+
+        // The primary is replaced once the best candidate exceeds the hysteresis.
+        let candidates = vec![
+            scored(0, Ok(()), 8_000),
+            scored(1, Ok(()), 8_001 + PRIMARY_SWITCH_HYSTERESIS),
+        ];
+        assert_eq!(rank_candidates(&candidates, Some(0)), vec![1, 0]);
+
+        // The primary is replaced immediately once it is no longer synced.
+        let candidates = vec![
+            scored(0, Err(CandidateError::NotSynced), 9_000),
+            scored(1, Ok(()), 1_000),
+        ];
+        assert_eq!(rank_candidates(&candidates, Some(0)), vec![1, 0]);
+
+        // A recovered node does not replace a healthy primary of similar score.
+        let candidates = vec![
+            scored(0, Ok(()), 9_900),
+            scored(1, Ok(()), 9_000),
+            scored(2, Ok(()), 8_500),
+        ];
+        assert_eq!(rank_candidates(&candidates, Some(1)), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn rank_candidates_replaces_lagging_primary() {
+        let best_head_slot = Slot::new(10);
+        let candidate = |index, health: BeaconNodeHealth| ScoredCandidate {
+            index,
+            status: Ok(()),
+            health: Some(health),
+            best_head_slot: Some(best_head_slot),
+            score: health.score(best_head_slot),
+        };
+
+        // A primary two slots behind is replaced, even though the score of the best candidate is
+        // within the hysteresis.
+        let candidates = vec![candidate(0, health(8, 0)), candidate(1, health(10, 1_500))];
+        assert!(candidates[1].score <= candidates[0].score + PRIMARY_SWITCH_HYSTERESIS);
+        assert_eq!(rank_candidates(&candidates, Some(0)), vec![1, 0]);
+
+        // A primary exactly one slot behind is retained whilst the best candidate is within the
+        // hysteresis.
+        let candidates = vec![candidate(0, health(9, 0)), candidate(1, health(10, 500))];
+        assert!(candidates[1].score <= candidates[0].score + PRIMARY_SWITCH_HYSTERESIS);
+        assert_eq!(rank_candidates(&candidates, Some(0)), vec![0, 1]);
+
+        // A primary at the best head is retained against a candidate of similar score.
+        let candidates = vec![candidate(0, health(10, 500)), candidate(1, health(10, 0))];
+        assert_eq!(rank_candidates(&candidates, Some(0)), vec![0, 1]);
+    }
+
     #[test]
     fn api_topic_all() {
         let all = ApiTopic::all();
@@ -6,11 +6,13 @@ use crate::{
     OfflineOnFailure,
 };
 use crate::{
+    duty_history::{error_category, DutyAttempt},
     http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
 };
 use bls::SignatureBytes;
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{DutyErrorCategory, DutyKind};
 use eth2::types::{FullBlockContents, PublishBlockRequest};
 use eth2::{BeaconNodeHttpClient, StatusCode};
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -433,6 +435,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         graffiti: Option<Graffiti>,
         validator_pubkey: &PublicKeyBytes,
         unsigned_block: UnsignedBlock<E>,
+        attempt: DutyAttempt,
     ) -> Result<(), BlockError> {
         let log = self.context.log();
         let signing_timer = metrics::start_timer(&metrics::BLOCK_SIGNING_TIMES);
@@ -453,6 +456,13 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 .map(SignedBlock::Blinded),
         };
 
+        if let Err(e) = &res {
+            self.validator_store.duty_history().record(
+                *validator_pubkey,
+                attempt.failure(error_category(e), format!("{:?}", e)),
+            );
+        }
+
         let signed_block = match res {
             Ok(block) => block,
             Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
@@ -499,7 +509,19 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                         .await
                 },
             )
-            .await?;
+            .await
+            .map_err(|e| {
+                self.block_duty_failed(
+                    *validator_pubkey,
+                    &attempt,
+                    DutyErrorCategory::BeaconNodeUnavailable,
+                    e.into(),
+                )
+            })?;
+
+        self.validator_store
+            .duty_history()
+            .record(*validator_pubkey, attempt.success());
 
         info!(
             log,
@@ -523,11 +545,21 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let _timer =
             metrics::start_timer_vec(&metrics::BLOCK_SERVICE_TIMES, &[metrics::BEACON_BLOCK]);
 
-        let randao_reveal = match self
+        let mut attempt = DutyAttempt::new(DutyKind::BlockProposal, slot);
+
+        let randao_result = self
             .validator_store
             .randao_reveal(validator_pubkey, slot.epoch(E::slots_per_epoch()))
-            .await
-        {
+            .await;
+
+        if let Err(e) = &randao_result {
+            self.validator_store.duty_history().record(
+                validator_pubkey,
+                attempt.failure(error_category(e), format!("{:?}", e)),
+            );
+        }
+
+        let randao_reveal = match randao_result {
             Ok(signature) => signature.into(),
             Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                 // A pubkey can be missing when a validator was recently removed
@@ -575,7 +607,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         //
         // Try the proposer nodes last, since it's likely that they don't have a
         // great view of attestations on the network.
-        let unsigned_block = proposer_fallback
+        let (beacon_node, unsigned_block) = proposer_fallback
             .request_proposers_last(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
@@ -599,12 +631,24 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                             "Error from beacon node when producing block: {:?}",
                             e
                         ))
-                    });
+                    })
+                    .map(|block| (beacon_node.to_string(), block));
 
                     Ok::<_, BlockError>(block_response)
                 },
             )
-            .await??;
+            .await
+            .map_err(BlockError::from)
+            .and_then(|block_response| block_response)
+            .map_err(|e| {
+                self.block_duty_failed(
+                    validator_pubkey,
+                    &attempt,
+                    DutyErrorCategory::BeaconNodeUnavailable,
+                    e,
+                )
+            })?;
+        attempt.set_beacon_node(beacon_node);
 
         self_ref
             .sign_and_publish_block(
@@ -613,6 +657,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 graffiti,
                 &validator_pubkey,
                 unsigned_block,
+                attempt,
             )
             .await?;
 
@@ -630,11 +675,21 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let _timer =
             metrics::start_timer_vec(&metrics::BLOCK_SERVICE_TIMES, &[metrics::BEACON_BLOCK]);
 
-        let randao_reveal = match self
+        let mut attempt = DutyAttempt::new(DutyKind::BlockProposal, slot);
+
+        let randao_result = self
             .validator_store
             .randao_reveal(validator_pubkey, slot.epoch(E::slots_per_epoch()))
-            .await
-        {
+            .await;
+
+        if let Err(e) = &randao_result {
+            self.validator_store.duty_history().record(
+                validator_pubkey,
+                attempt.failure(error_category(e), format!("{:?}", e)),
+            );
+        }
+
+        let randao_reveal = match randao_result {
             Ok(signature) => signature.into(),
             Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                 // A pubkey can be missing when a validator was recently removed
@@ -682,11 +737,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         //
         // Try the proposer nodes last, since it's likely that they don't have a
         // great view of attestations on the network.
-        let unsigned_block = proposer_fallback
+        let (beacon_node, unsigned_block) = proposer_fallback
             .request_proposers_last(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
-                move |beacon_node| {
+                move |beacon_node| async move {
                     Self::get_validator_block(
                         beacon_node,
                         slot,
@@ -696,9 +751,20 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                         builder_proposal,
                         log,
                     )
+                    .await
+                    .map(|block| (beacon_node.to_string(), block))
                 },
             )
-            .await?;
+            .await
+            .map_err(|e| {
+                self.block_duty_failed(
+                    validator_pubkey,
+                    &attempt,
+                    DutyErrorCategory::BeaconNodeUnavailable,
+                    e.into(),
+                )
+            })?;
+        attempt.set_beacon_node(beacon_node);
 
         self_ref
             .sign_and_publish_block(
@@ -707,12 +773,30 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 graffiti,
                 &validator_pubkey,
                 unsigned_block,
+                attempt,
             )
             .await?;
 
         Ok(())
     }
 
+    /// Record a failed block proposal in the duty history, returning `error`.
+    fn block_duty_failed(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        attempt: &DutyAttempt,
+        category: DutyErrorCategory,
+        error: BlockError,
+    ) -> BlockError {
+        let message = match &error {
+            BlockError::Recoverable(e) | BlockError::Irrecoverable(e) => e.clone(),
+        };
+        self.validator_store
+            .duty_history()
+            .record(validator_pubkey, attempt.failure(category, message));
+        error
+    }
+
     async fn publish_signed_block_contents(
         &self,
         signed_block: &SignedBlock<E>,
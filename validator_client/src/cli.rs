@@ -339,6 +339,26 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("true")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("attest-on-block-arrival")
+                .long("attest-on-block-arrival")
+                .help("If present, the validator client subscribes to `head` events from the \
+                    beacon node and produces attestations as soon as the block for the current \
+                    slot becomes the head, rather than always waiting until 1/3rd of the way \
+                    through the slot. Attestations are still produced 1/3rd of the way through \
+                    the slot if the block is late or missing.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("watch-validator-definitions")
+                .long("watch-validator-definitions")
+                .help("If present, the validator definitions file is reloaded whenever it is \
+                    modified, enabling, disabling, adding or removing validators without a \
+                    restart. The file should be written atomically. Regardless of this flag, \
+                    the file can be reloaded via the `POST /lighthouse/validators/reload` \
+                    endpoint of the HTTP API.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("validator-registration-batch-size")
                 .long("validator-registration-batch-size")
@@ -367,4 +387,46 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     constructed by builders, regardless of payload value.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("ha-instance-id")
+                .long("ha-instance-id")
+                .value_name("ID")
+                .help("Enables active/passive high availability. Several validator clients with \
+                    the same validators and distinct instance IDs share the slashing protection \
+                    database given by --ha-slashing-protection-db, and only the instance which \
+                    holds a lease recorded in that database performs duties. The other instances \
+                    remain on standby and take over once the lease expires.")
+                .requires("ha-slashing-protection-db")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("ha-slashing-protection-db")
+                .long("ha-slashing-protection-db")
+                .value_name("PATH")
+                .help("Path to the slashing protection database shared by all high availability \
+                    instances. Replaces the slashing protection database in the validators \
+                    directory.")
+                .requires("ha-instance-id")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("ha-lease-duration")
+                .long("ha-lease-duration")
+                .value_name("SECONDS")
+                .help("The duration of the high availability lease. The active instance renews \
+                    the lease several times per duration, and a standby instance takes over at \
+                    most this long after the active instance stops renewing it.")
+                .requires("ha-instance-id")
+                .default_value("24")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("scheduled-exit-rebroadcast-epochs")
+                .long("scheduled-exit-rebroadcast-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs to wait for a scheduled exit to be included in the \
+                    beacon chain before broadcasting it again.")
+                .default_value("4")
+                .takes_value(true),
+        )
 }
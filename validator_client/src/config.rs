@@ -1,6 +1,6 @@
 use crate::beacon_node_fallback::ApiTopic;
 use crate::graffiti_file::GraffitiFile;
-use crate::{http_api, http_metrics};
+use crate::{ha_lease, http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
 use directory::{
@@ -14,6 +14,7 @@ use slog::{info, warn, Logger};
 use std::fs;
 use std::net::IpAddr;
 use std::path::PathBuf;
+use std::time::Duration;
 use types::{Address, GRAFFITI_BYTES_LEN};
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
@@ -73,6 +74,11 @@ pub struct Config {
     pub broadcast_topics: Vec<ApiTopic>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
+    /// Reload the validator definitions file whenever it is modified.
+    pub watch_validator_definitions: bool,
+    /// Attest as soon as the block for the slot becomes head, rather than 1/3rd of the way through
+    /// the slot.
+    pub attest_on_block_arrival: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
     /// Enables block production via the block v3 endpoint. This configuration option can be removed post deneb.
@@ -81,6 +87,11 @@ pub struct Config {
     pub builder_boost_factor: Option<u64>,
     /// If true, Lighthouse will prefer builder proposals, if available.
     pub prefer_builder_proposals: bool,
+    /// Configuration for active/passive high availability, if enabled.
+    pub ha_lease: Option<ha_lease::Config>,
+    /// The number of epochs to wait for a scheduled exit to be included before broadcasting it
+    /// again.
+    pub scheduled_exit_rebroadcast_epochs: u64,
 }
 
 impl Default for Config {
@@ -120,10 +131,14 @@ impl Default for Config {
             gas_limit: None,
             broadcast_topics: vec![ApiTopic::Subscriptions],
             enable_latency_measurement_service: true,
+            watch_validator_definitions: false,
+            attest_on_block_arrival: false,
             validator_registration_batch_size: 500,
             produce_block_v3: false,
             builder_boost_factor: None,
             prefer_builder_proposals: false,
+            ha_lease: None,
+            scheduled_exit_rebroadcast_epochs: 4,
         }
     }
 }
@@ -380,12 +395,38 @@ impl Config {
         config.enable_latency_measurement_service =
             parse_optional(cli_args, "latency-measurement-service")?.unwrap_or(true);
 
+        config.watch_validator_definitions = cli_args.is_present("watch-validator-definitions");
+
+        config.attest_on_block_arrival = cli_args.is_present("attest-on-block-arrival");
+
         config.validator_registration_batch_size =
             parse_required(cli_args, "validator-registration-batch-size")?;
         if config.validator_registration_batch_size == 0 {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        config.scheduled_exit_rebroadcast_epochs =
+            parse_required(cli_args, "scheduled-exit-rebroadcast-epochs")?;
+        if config.scheduled_exit_rebroadcast_epochs == 0 {
+            return Err("scheduled-exit-rebroadcast-epochs cannot be 0".to_string());
+        }
+
+        /*
+         * High availability
+         */
+        if let Some(instance_id) = cli_args.value_of("ha-instance-id") {
+            let lease_duration =
+                Duration::from_secs(parse_required(cli_args, "ha-lease-duration")?);
+            if lease_duration.is_zero() {
+                return Err("ha-lease-duration cannot be 0".to_string());
+            }
+            config.ha_lease = Some(ha_lease::Config {
+                instance_id: instance_id.to_string(),
+                slashing_protection_db: parse_required(cli_args, "ha-slashing-protection-db")?,
+                lease_duration,
+            });
+        }
+
         Ok(config)
     }
 }
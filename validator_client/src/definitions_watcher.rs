@@ -0,0 +1,103 @@
+//! Reloads the validator definitions file whenever it is modified on disk, allowing external
+//! tooling to add, remove, enable or disable validators without restarting the validator client.
+
+use crate::validator_store::ValidatorStore;
+use account_utils::validator_definitions::CONFIG_FILENAME;
+use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::ValidatorDefinitionsDiff;
+use slog::{debug, error, info};
+use slot_clock::SlotClock;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::EthSpec;
+
+/// The interval at which the modification time of the definitions file is checked.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Starts a service which reloads the validator definitions file in `validators_dir` each time
+/// its modification time changes.
+///
+/// Writes to the file should be atomic (e.g., write to a temporary file and rename), otherwise a
+/// partially written file may be read and rejected.
+pub fn start_definitions_watcher_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    validators_dir: PathBuf,
+) {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    let path = validators_dir.join(CONFIG_FILENAME);
+
+    info!(
+        log,
+        "Watching validator definitions";
+        "path" => %path.display(),
+    );
+
+    let future = async move {
+        let mut last_modified = modified_time(&path);
+        loop {
+            sleep(POLL_INTERVAL).await;
+
+            let modified = modified_time(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            match reload_definitions(&executor, &validator_store).await {
+                Ok(diff) if diff.is_empty() => {
+                    debug!(
+                        log,
+                        "Validator definitions unchanged";
+                        "path" => %path.display(),
+                    );
+                }
+                // The changes are logged by the validator store.
+                Ok(_) => (),
+                Err(e) => {
+                    error!(
+                        log,
+                        "Unable to reload validator definitions";
+                        "error" => e,
+                        "path" => %path.display(),
+                    );
+                }
+            }
+        }
+    };
+
+    context.executor.spawn(future, "definitions_watcher");
+}
+
+/// Reloads the validator definitions on a blocking thread.
+///
+/// The validators lock is held whilst any new validators are initialized, so the reload must not
+/// run on the async executor.
+async fn reload_definitions<T: SlotClock + 'static, E: EthSpec>(
+    executor: &TaskExecutor,
+    validator_store: &Arc<ValidatorStore<T, E>>,
+) -> Result<ValidatorDefinitionsDiff, String> {
+    let handle = executor
+        .handle()
+        .ok_or_else(|| "Lighthouse shutting down".to_string())?;
+    let validator_store = validator_store.clone();
+    executor
+        .spawn_blocking_handle(
+            move || handle.block_on(validator_store.reload_validator_definitions()),
+            "reload_validator_definitions",
+        )
+        .ok_or_else(|| "Lighthouse shutting down".to_string())?
+        .await
+        .map_err(|e| format!("Reload task failed: {:?}", e))?
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,221 @@
+//! Keeps a short, per-validator history of the duties attempted by this validator client.
+//!
+//! Each attempt is recorded along with the beacon node which served it, its latency and, upon
+//! failure, the reason it failed. The history is exposed via the HTTP API so that operators can
+//! determine why a validator missed a duty without searching the logs.
+use crate::http_metrics::metrics;
+use crate::signing_method::Error as SigningError;
+use crate::validator_store::Error as ValidatorStoreError;
+use eth2::lighthouse_vc::types::{DutyErrorCategory, DutyKind, DutyRecord};
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use types::{Epoch, PublicKeyBytes, Slot};
+
+/// The number of epochs of history retained for each validator.
+pub const DUTY_HISTORY_EPOCHS: u64 = 64;
+
+/// A single in-progress attempt at a duty.
+///
+/// Latency is measured from the creation of the attempt until it is converted into a
+/// `DutyRecord`.
+#[derive(Clone)]
+pub struct DutyAttempt {
+    kind: DutyKind,
+    slot: Slot,
+    started: Instant,
+    beacon_node: Option<String>,
+}
+
+impl DutyAttempt {
+    pub fn new(kind: DutyKind, slot: Slot) -> Self {
+        Self {
+            kind,
+            slot,
+            started: Instant::now(),
+            beacon_node: None,
+        }
+    }
+
+    /// Set the beacon node which served the data for this duty.
+    pub fn set_beacon_node(&mut self, beacon_node: String) {
+        self.beacon_node = Some(beacon_node);
+    }
+
+    pub fn success(&self) -> DutyRecord {
+        self.record(None)
+    }
+
+    pub fn failure(&self, category: DutyErrorCategory, error: String) -> DutyRecord {
+        self.record(Some((category, error)))
+    }
+
+    fn record(&self, failure: Option<(DutyErrorCategory, String)>) -> DutyRecord {
+        let (error_category, error) = failure.unzip();
+        DutyRecord {
+            kind: self.kind,
+            slot: self.slot,
+            success: error_category.is_none(),
+            beacon_node: self.beacon_node.clone(),
+            latency_ms: self.started.elapsed().as_millis() as u64,
+            error_category,
+            error,
+        }
+    }
+}
+
+/// Returns the category of a failure to sign a message with the `ValidatorStore`.
+pub fn error_category(e: &ValidatorStoreError) -> DutyErrorCategory {
+    match e {
+        ValidatorStoreError::DoppelgangerProtected(_)
+        | ValidatorStoreError::UnknownToDoppelgangerService(_) => DutyErrorCategory::Doppelganger,
+        ValidatorStoreError::HaStandby => DutyErrorCategory::Standby,
+        ValidatorStoreError::UnknownPubkey(_) => DutyErrorCategory::UnknownValidator,
+        ValidatorStoreError::Slashable(_) | ValidatorStoreError::SameData => {
+            DutyErrorCategory::SlashingProtection
+        }
+        ValidatorStoreError::UnableToSign(SigningError::Web3SignerRequestTimedOut) => {
+            DutyErrorCategory::SignerTimeout
+        }
+        ValidatorStoreError::UnableToSign(_) => DutyErrorCategory::SignerError,
+        ValidatorStoreError::GreaterThanCurrentSlot { .. }
+        | ValidatorStoreError::GreaterThanCurrentEpoch { .. }
+        | ValidatorStoreError::UnableToSignAttestation(_) => DutyErrorCategory::Other,
+    }
+}
+
+/// The history of duties attempted by each validator, bounded to `DUTY_HISTORY_EPOCHS`.
+pub struct DutyHistory {
+    records: RwLock<HashMap<PublicKeyBytes, VecDeque<DutyRecord>>>,
+    slots_per_epoch: u64,
+}
+
+impl DutyHistory {
+    pub fn new(slots_per_epoch: u64) -> Self {
+        Self {
+            records: RwLock::new(HashMap::new()),
+            slots_per_epoch,
+        }
+    }
+
+    /// Add `record` to the history of `pubkey`, pruning any records which are too old.
+    pub fn record(&self, pubkey: PublicKeyBytes, record: DutyRecord) {
+        let duty = duty_label(record.kind);
+        let outcome = record
+            .error_category
+            .map_or(metrics::SUCCESS, category_label);
+        metrics::inc_counter_vec(&metrics::DUTY_OUTCOMES_TOTAL, &[duty, outcome]);
+        metrics::observe_timer_vec(
+            &metrics::DUTY_LATENCY,
+            &[duty],
+            Duration::from_millis(record.latency_ms),
+        );
+
+        let oldest_slot = record
+            .slot
+            .epoch(self.slots_per_epoch)
+            .saturating_sub(DUTY_HISTORY_EPOCHS - 1)
+            .start_slot(self.slots_per_epoch);
+
+        let mut records = self.records.write();
+        let history = records.entry(pubkey).or_default();
+        history.push_back(record);
+        history.retain(|record| record.slot >= oldest_slot);
+    }
+
+    /// Returns the records for `pubkey` at or after `epoch`, in the order they were recorded.
+    pub fn get(&self, pubkey: &PublicKeyBytes, epoch: Epoch) -> Vec<DutyRecord> {
+        let start_slot = epoch.start_slot(self.slots_per_epoch);
+        self.records
+            .read()
+            .get(pubkey)
+            .map(|history| {
+                history
+                    .iter()
+                    .filter(|record| record.slot >= start_slot)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+fn duty_label(kind: DutyKind) -> &'static str {
+    match kind {
+        DutyKind::Attestation => "attestation",
+        DutyKind::Aggregate => "aggregate",
+        DutyKind::BlockProposal => "block_proposal",
+        DutyKind::SyncCommitteeMessage => "sync_committee_message",
+        DutyKind::SyncCommitteeContribution => "sync_committee_contribution",
+    }
+}
+
+fn category_label(category: DutyErrorCategory) -> &'static str {
+    match category {
+        DutyErrorCategory::BeaconNodeUnavailable => "beacon_node_unavailable",
+        DutyErrorCategory::SignerTimeout => "signer_timeout",
+        DutyErrorCategory::SignerError => "signer_error",
+        DutyErrorCategory::SlashingProtection => "slashing_protection",
+        DutyErrorCategory::Doppelganger => "doppelganger",
+        DutyErrorCategory::Standby => "standby",
+        DutyErrorCategory::UnknownValidator => "unknown_validator",
+        DutyErrorCategory::Other => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOTS_PER_EPOCH: u64 = 32;
+
+    fn record(history: &DutyHistory, pubkey: PublicKeyBytes, slot: u64) {
+        let attempt = DutyAttempt::new(DutyKind::Attestation, Slot::new(slot));
+        history.record(pubkey, attempt.success());
+    }
+
+    #[test]
+    fn prunes_old_records() {
+        let history = DutyHistory::new(SLOTS_PER_EPOCH);
+        let pubkey = PublicKeyBytes::empty();
+
+        for epoch in 0..DUTY_HISTORY_EPOCHS * 2 {
+            record(&history, pubkey, epoch * SLOTS_PER_EPOCH);
+        }
+
+        let records = history.get(&pubkey, Epoch::new(0));
+        assert_eq!(records.len() as u64, DUTY_HISTORY_EPOCHS);
+        assert_eq!(
+            records[0].slot,
+            Epoch::new(DUTY_HISTORY_EPOCHS).start_slot(SLOTS_PER_EPOCH)
+        );
+
+        let recent = history.get(&pubkey, Epoch::new(DUTY_HISTORY_EPOCHS * 2 - 2));
+        assert_eq!(recent.len(), 2);
+    }
+
+    #[test]
+    fn records_failures() {
+        let history = DutyHistory::new(SLOTS_PER_EPOCH);
+        let pubkey = PublicKeyBytes::empty();
+
+        let mut attempt = DutyAttempt::new(DutyKind::BlockProposal, Slot::new(1));
+        attempt.set_beacon_node("http://localhost:5052/".into());
+        let category = error_category(&ValidatorStoreError::UnableToSign(
+            SigningError::Web3SignerRequestTimedOut,
+        ));
+        history.record(pubkey, attempt.failure(category, "timed out".into()));
+
+        let records = history.get(&pubkey, Epoch::new(0));
+        assert_eq!(records.len(), 1);
+        assert!(!records[0].success);
+        assert_eq!(
+            records[0].beacon_node.as_deref(),
+            Some("http://localhost:5052/")
+        );
+        assert_eq!(
+            records[0].error_category,
+            Some(DutyErrorCategory::SignerTimeout)
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Active/passive high availability for several validator clients managing the same validators.
+//!
+//! All instances share a slashing protection database which stores a lease (see
+//! `slashing_protection::Lease`). The instance holding the lease is *active* and performs duties,
+//! whilst the others remain on *standby*. The active instance renews the lease several times per
+//! lease duration. If it stops doing so (e.g. because it crashed or lost access to the database),
+//! a standby instance takes over once the lease has expired.
+//!
+//! Fencing relies on the slashing protection database rather than on this service: blocks and
+//! attestations can only be recorded (and therefore signed) whilst the lease is held, and never
+//! below the highest slot or target epoch recorded by any instance. On takeover, all validators
+//! are re-registered with doppelganger protection (if enabled) before they are permitted to sign.
+
+use crate::http_metrics::metrics;
+use crate::validator_store::ValidatorStore;
+use environment::RuntimeContext;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use slashing_protection::{unix_time_now, Lease, LeaseStatus, NotSafe, SlashingDatabase};
+use slog::{crit, debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::EthSpec;
+
+/// The number of times the active instance renews the lease per lease duration.
+const RENEWALS_PER_LEASE_DURATION: u32 = 4;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    /// Identifies this instance amongst all instances sharing the slashing protection database.
+    pub instance_id: String,
+    /// Path to the slashing protection database shared by all instances.
+    pub slashing_protection_db: PathBuf,
+    /// The duration for which the lease is acquired or renewed.
+    pub lease_duration: Duration,
+}
+
+/// The lease held by this instance, if any.
+///
+/// Shared between the `LeaseService` and the `ValidatorStore`, which refuses to sign whilst this
+/// instance is on standby.
+#[derive(Default)]
+pub struct HaLease {
+    lease: RwLock<Option<Lease>>,
+}
+
+impl HaLease {
+    /// Returns `true` if this instance holds an unexpired lease.
+    ///
+    /// The lease stops being active once it expires, even if the `LeaseService` is unable to
+    /// reach the database to find out that another instance has taken over.
+    pub fn is_active(&self) -> bool {
+        let Ok(now) = unix_time_now() else {
+            return false;
+        };
+        self.lease
+            .read()
+            .as_ref()
+            .map_or(false, |lease| !lease.is_expired(now))
+    }
+
+    fn set(&self, lease: Option<Lease>) {
+        *self.lease.write() = lease;
+    }
+}
+
+/// Periodically acquires or renews the lease in the shared slashing protection database.
+#[derive(Clone)]
+pub struct LeaseService<T, E: EthSpec> {
+    config: Config,
+    ha_lease: Arc<HaLease>,
+    slashing_protection: SlashingDatabase,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    context: RuntimeContext<E>,
+}
+
+impl<T: SlotClock + 'static, E: EthSpec> LeaseService<T, E> {
+    pub fn new(
+        config: Config,
+        ha_lease: Arc<HaLease>,
+        slashing_protection: SlashingDatabase,
+        validator_store: Arc<ValidatorStore<T, E>>,
+        context: RuntimeContext<E>,
+    ) -> Self {
+        Self {
+            config,
+            ha_lease,
+            slashing_protection,
+            validator_store,
+            context,
+        }
+    }
+
+    /// Starts the service which keeps the lease updated, releasing it on shutdown so that a
+    /// standby instance can take over immediately.
+    pub fn start_update_service(self) {
+        let log = self.context.log().clone();
+        let executor = self.context.executor.clone();
+        let renewal_interval = self.config.lease_duration / RENEWALS_PER_LEASE_DURATION;
+
+        info!(
+            log,
+            "High availability lease service started";
+            "instance_id" => &self.config.instance_id,
+            "lease_duration" => ?self.config.lease_duration,
+        );
+
+        let future = async move {
+            let mut last_holder = None;
+            loop {
+                last_holder = self.update_lease(last_holder, &log).await;
+
+                tokio::select! {
+                    () = sleep(renewal_interval) => (),
+                    () = self.context.executor.exit() => break,
+                }
+            }
+
+            self.ha_lease.set(None);
+            metrics::set_gauge(&metrics::HA_LEASE_ACTIVE, 0);
+            match self.slashing_protection.release_lease() {
+                Ok(()) => info!(log, "Released high availability lease"),
+                Err(e) => error!(
+                    log,
+                    "Failed to release high availability lease";
+                    "error" => ?e,
+                ),
+            }
+        };
+
+        executor.spawn_without_exit(future, "ha_lease");
+    }
+
+    /// Tries to acquire or renew the lease, returning the current holder.
+    async fn update_lease(&self, last_holder: Option<String>, log: &Logger) -> Option<String> {
+        let slashing_protection = self.slashing_protection.clone();
+        let lease_duration = self.config.lease_duration;
+        let Some(handle) = self.context.executor.spawn_blocking_handle(
+            move || {
+                let now = unix_time_now()?;
+                slashing_protection.acquire_lease(lease_duration, now)
+            },
+            "ha_lease_update",
+        ) else {
+            // The executor is shutting down.
+            return last_holder;
+        };
+        let result = handle
+            .await
+            .map_err(|e| NotSafe::SQLError(format!("lease update task failed: {:?}", e)))
+            .and_then(|result| result);
+
+        let holder = match result {
+            Ok(status) => {
+                metrics::set_gauge(&metrics::HA_LEASE_TERM, status.lease().term as i64);
+                let holder = status.lease().holder.clone();
+                match status {
+                    LeaseStatus::Acquired(lease) => self.take_over(lease, log),
+                    LeaseStatus::Renewed(lease) => {
+                        debug!(
+                            log,
+                            "Renewed high availability lease";
+                            "term" => lease.term,
+                        );
+                        self.ha_lease.set(Some(lease));
+                    }
+                    LeaseStatus::HeldByOther(lease) => {
+                        if last_holder.as_ref() == Some(&self.config.instance_id) {
+                            crit!(
+                                log,
+                                "Lost high availability lease";
+                                "msg" => "another instance has taken over, standing by",
+                                "holder" => &lease.holder,
+                                "term" => lease.term,
+                            );
+                        } else if last_holder.as_ref() != Some(&lease.holder) {
+                            info!(
+                                log,
+                                "Standing by for high availability lease";
+                                "holder" => &lease.holder,
+                                "term" => lease.term,
+                            );
+                        }
+                        self.ha_lease.set(None);
+                    }
+                }
+                Some(holder)
+            }
+            Err(e) => {
+                // Keep any lease we hold, it stops being active once it expires.
+                error!(
+                    log,
+                    "Failed to update high availability lease";
+                    "error" => ?e,
+                );
+                metrics::inc_counter(&metrics::HA_LEASE_ERRORS);
+                last_holder
+            }
+        };
+
+        metrics::set_gauge(&metrics::HA_LEASE_ACTIVE, self.ha_lease.is_active() as i64);
+        holder
+    }
+
+    /// Becomes the active instance after acquiring a new lease.
+    ///
+    /// Another instance may have been signing until the lease expired, so all validators must pass
+    /// doppelganger protection again before signing.
+    fn take_over(&self, lease: Lease, log: &Logger) {
+        if let Err(e) = self
+            .validator_store
+            .register_all_in_doppelganger_protection_if_enabled()
+        {
+            crit!(
+                log,
+                "Unable to take over high availability lease";
+                "msg" => "failed to register validators with doppelganger protection",
+                "error" => e,
+            );
+            if let Err(e) = self.slashing_protection.release_lease() {
+                error!(
+                    log,
+                    "Failed to release high availability lease";
+                    "error" => ?e,
+                );
+            }
+            self.ha_lease.set(None);
+            return;
+        }
+
+        if !self.validator_store.doppelganger_protection_enabled() {
+            warn!(
+                log,
+                "Taking over without doppelganger protection";
+                "msg" => "consider using --enable-doppelganger-protection with high availability",
+            );
+        }
+
+        info!(
+            log,
+            "Acquired high availability lease";
+            "msg" => "this instance is now active",
+            "term" => lease.term,
+        );
+        metrics::inc_counter(&metrics::HA_LEASE_TAKEOVERS);
+        self.ha_lease.set(Some(lease));
+    }
+}
@@ -10,8 +10,10 @@ pub mod test_utils;
 
 use crate::http_api::graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
+use crate::duty_history::DUTY_HISTORY_EPOCHS;
 use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
-use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
+use crate::scheduled_exits::{Error as ScheduledExitsError, ScheduledExits};
+use crate::{determine_graffiti, BeaconNodeFallback, GraffitiFile, ValidatorStore};
 use account_utils::{
     mnemonic_from_phrase,
     validator_definitions::{SigningDefinition, ValidatorDefinition, Web3SignerDefinition},
@@ -80,7 +82,9 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub task_executor: TaskExecutor,
     pub api_secret: ApiSecret,
     pub validator_store: Option<Arc<ValidatorStore<T, E>>>,
+    pub beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     pub validator_dir: Option<PathBuf>,
+    pub scheduled_exits: Option<Arc<ScheduledExits>>,
     pub secrets_dir: Option<PathBuf>,
     pub graffiti_file: Option<GraffitiFile>,
     pub graffiti_flag: Option<Graffiti>,
@@ -190,6 +194,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let inner_beacon_nodes = ctx.beacon_nodes.clone();
+    let beacon_nodes_filter = warp::any()
+        .map(move || inner_beacon_nodes.clone())
+        .and_then(|beacon_nodes: Option<_>| async move {
+            beacon_nodes.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "beacon nodes are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_task_executor = ctx.task_executor.clone();
     let task_executor_filter = warp::any().map(move || inner_task_executor.clone());
 
@@ -204,6 +219,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let inner_scheduled_exits = ctx.scheduled_exits.clone();
+    let scheduled_exits_filter = warp::any()
+        .map(move || inner_scheduled_exits.clone())
+        .and_then(|scheduled_exits: Option<_>| async move {
+            scheduled_exits.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "scheduled exits are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_secrets_dir = ctx.secrets_dir.clone();
     let secrets_dir_filter = warp::any().map(move || inner_secrets_dir.clone()).and_then(
         |secrets_dir: Option<_>| async move {
@@ -362,6 +388,154 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // GET lighthouse/validators/{validator_pubkey}/duties
+    let get_lighthouse_validators_pubkey_duties = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("duties"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::DutyHistoryQuery>())
+        .and(validator_store_filter.clone())
+        .and(slot_clock_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             query: api_types::DutyHistoryQuery,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             slot_clock: T,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    let epochs = query.epochs.unwrap_or(DUTY_HISTORY_EPOCHS);
+                    if epochs == 0 {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "epochs must be greater than zero".to_string(),
+                        ));
+                    }
+
+                    if !validator_store
+                        .initialized_validators()
+                        .read()
+                        .validator_definitions()
+                        .iter()
+                        .any(|def| def.voting_public_key == validator_pubkey)
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator for {:?}",
+                            validator_pubkey
+                        )));
+                    }
+
+                    let current_epoch = slot_clock
+                        .now()
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_server_error(
+                                "unable to read slot clock".to_string(),
+                            )
+                        })?
+                        .epoch(E::slots_per_epoch());
+                    let records = validator_store.duty_history().get(
+                        &PublicKeyBytes::from(&validator_pubkey),
+                        current_epoch.saturating_sub(epochs - 1),
+                    );
+
+                    Ok(api_types::GenericResponse::from(records))
+                })
+            },
+        );
+
+    // GET lighthouse/beacon/health
+    let get_lighthouse_beacon_health = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("health"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter)
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |beacon_nodes: Arc<BeaconNodeFallback<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let health = handle.block_on(beacon_nodes.health());
+                        Ok(api_types::GenericResponse::from(health))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
+    // GET lighthouse/scheduled_exits
+    let get_lighthouse_scheduled_exits = warp::path("lighthouse")
+        .and(warp::path("scheduled_exits"))
+        .and(warp::path::end())
+        .and(scheduled_exits_filter.clone())
+        .and(signer.clone())
+        .and_then(|scheduled_exits: Arc<ScheduledExits>, signer| {
+            blocking_signed_json_task(signer, move || {
+                let exits = scheduled_exits
+                    .list()
+                    .iter()
+                    .map(api_types::ScheduledExitData::from)
+                    .collect::<Vec<_>>();
+                Ok(api_types::GenericResponse::from(exits))
+            })
+        });
+
+    // POST lighthouse/scheduled_exits/{pubkey}
+    let post_lighthouse_scheduled_exits = warp::path("lighthouse")
+        .and(warp::path("scheduled_exits"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .and(scheduled_exits_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             request: api_types::ScheduleExitRequest,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             scheduled_exits: Arc<ScheduledExits>,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    let pubkey = PublicKeyBytes::from(&validator_pubkey);
+                    if !validator_store.has_validator(&pubkey) {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    scheduled_exits
+                        .schedule(pubkey, &request)
+                        .map(|exit| {
+                            api_types::GenericResponse::from(api_types::ScheduledExitData::from(
+                                &exit,
+                            ))
+                        })
+                        .map_err(scheduled_exits_rejection)
+                })
+            },
+        );
+
+    // DELETE lighthouse/scheduled_exits/{pubkey}
+    let delete_lighthouse_scheduled_exits = warp::path("lighthouse")
+        .and(warp::path("scheduled_exits"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path::end())
+        .and(scheduled_exits_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey, scheduled_exits: Arc<ScheduledExits>, signer| {
+                blocking_signed_json_task(signer, move || {
+                    scheduled_exits
+                        .cancel(&PublicKeyBytes::from(&validator_pubkey))
+                        .map_err(scheduled_exits_rejection)
+                })
+            },
+        )
+        .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::NO_CONTENT));
+
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
         .and(warp::path("ui"))
@@ -512,6 +686,31 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // POST lighthouse/validators/reload
+    let post_validators_reload = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("reload"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |validator_store: Arc<ValidatorStore<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let diff = handle
+                            .block_on(validator_store.reload_validator_definitions())
+                            .map_err(warp_utils::reject::custom_server_error)?;
+                        Ok(api_types::GenericResponse::from(diff))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // POST lighthouse/validators/keystore
     let post_validators_keystore = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -1269,6 +1468,9 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
                         .or(get_lighthouse_validators_pubkey)
+                        .or(get_lighthouse_validators_pubkey_duties)
+                        .or(get_lighthouse_beacon_health)
+                        .or(get_lighthouse_scheduled_exits)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_fee_recipient)
@@ -1282,6 +1484,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                     post_validators
                         .or(post_validators_keystore)
                         .or(post_validators_mnemonic)
+                        .or(post_validators_reload)
                         .or(post_validators_web3signer)
                         .or(post_validators_voluntary_exits)
                         .or(post_fee_recipient)
@@ -1289,6 +1492,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_std_keystores)
                         .or(post_std_remotekeys)
                         .or(post_graffiti)
+                        .or(post_lighthouse_scheduled_exits)
                         .recover(warp_utils::reject::handle_rejection),
                 ))
                 .or(warp::patch()
@@ -1297,6 +1501,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                     delete_lighthouse_keystores
                         .or(delete_fee_recipient)
                         .or(delete_gas_limit)
+                        .or(delete_lighthouse_scheduled_exits)
                         .or(delete_std_keystores)
                         .or(delete_std_remotekeys)
                         .or(delete_graffiti)
@@ -1328,6 +1533,29 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     Ok((listening_socket, server))
 }
 
+/// Maps errors from the scheduled exits store to an HTTP rejection.
+fn scheduled_exits_rejection(e: ScheduledExitsError) -> warp::Rejection {
+    match e {
+        ScheduledExitsError::NoExitCondition => warp_utils::reject::custom_bad_request(
+            "an exit epoch or balance threshold is required".to_string(),
+        ),
+        ScheduledExitsError::ExitAlreadySubmitted(pubkey) => {
+            warp_utils::reject::custom_bad_request(format!(
+                "the exit of {:?} has already been submitted",
+                pubkey
+            ))
+        }
+        ScheduledExitsError::ExitNotFound(pubkey) => warp_utils::reject::custom_not_found(format!(
+            "no scheduled exit found for {:?}",
+            pubkey
+        )),
+        e => warp_utils::reject::custom_server_error(format!(
+            "unable to persist scheduled exits: {:?}",
+            e
+        )),
+    }
+}
+
 /// Executes `func` in blocking tokio task (i.e., where long-running tasks are permitted).
 /// JSON-encodes the return value of `func`, using the `signer` function to produce a signature of
 /// those bytes.
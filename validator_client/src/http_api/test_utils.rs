@@ -3,7 +3,7 @@ use crate::key_cache::{KeyCache, CACHE_FILENAME};
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
     initialized_validators::{InitializedValidators, OnDecryptFailure},
-    Config, ValidatorDefinitions, ValidatorStore,
+    Config, ScheduledExits, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
     eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
@@ -111,6 +111,7 @@ impl ApiTester {
             Hash256::repeat_byte(42),
             spec,
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
+            None,
             slot_clock.clone(),
             &config,
             test_runtime.task_executor.clone(),
@@ -127,8 +128,12 @@ impl ApiTester {
             task_executor: test_runtime.task_executor.clone(),
             api_secret,
             validator_dir: Some(validator_dir.path().into()),
+            scheduled_exits: Some(Arc::new(
+                ScheduledExits::open_or_create(validator_dir.path()).unwrap(),
+            )),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            beacon_nodes: None,
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
@@ -3,21 +3,25 @@
 
 mod keystores;
 
+use crate::beacon_node_fallback::{
+    BeaconNodeFallback, BeaconNodeHealth, CandidateBeaconNode, CandidateError, MAX_HEALTH_SCORE,
+};
 use crate::doppelganger_service::DoppelgangerService;
+use crate::duty_history::DutyAttempt;
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
     initialized_validators::InitializedValidators,
-    Config, ValidatorDefinitions, ValidatorStore,
+    Config, ScheduledExits, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
     eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    random_password_string, ZeroizeString,
+    random_password_string, validator_definitions::ValidatorDefinition, ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
 use eth2::{
     lighthouse_vc::{http_client::ValidatorClientHttpClient, types::*},
     types::ErrorMessage as ApiErrorMessage,
-    Error as ApiError,
+    BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
 use eth2_keystore::KeystoreBuilder;
 use logging::test_logger;
@@ -46,7 +50,7 @@ struct ApiTester {
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
     slot_clock: TestingSlotClock,
-    _validator_dir: TempDir,
+    validator_dir: TempDir,
     _test_runtime: TestRuntime,
 }
 
@@ -57,7 +61,14 @@ impl ApiTester {
         Self::new_with_config(config).await
     }
 
-    pub async fn new_with_config(mut config: Config) -> Self {
+    pub async fn new_with_config(config: Config) -> Self {
+        Self::new_with_beacon_nodes(config, None).await
+    }
+
+    pub async fn new_with_beacon_nodes(
+        mut config: Config,
+        beacon_nodes: Option<Arc<BeaconNodeFallback<TestingSlotClock, E>>>,
+    ) -> Self {
         let log = test_logger();
 
         let validator_dir = tempdir().unwrap();
@@ -99,6 +110,7 @@ impl ApiTester {
             Hash256::repeat_byte(42),
             spec.clone(),
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
+            None,
             slot_clock.clone(),
             &config,
             test_runtime.task_executor.clone(),
@@ -115,8 +127,12 @@ impl ApiTester {
             task_executor: test_runtime.task_executor.clone(),
             api_secret,
             validator_dir: Some(validator_dir.path().into()),
+            scheduled_exits: Some(Arc::new(
+                ScheduledExits::open_or_create(validator_dir.path()).unwrap(),
+            )),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            beacon_nodes,
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
@@ -154,7 +170,7 @@ impl ApiTester {
             validator_store,
             url,
             slot_clock,
-            _validator_dir: validator_dir,
+            validator_dir,
             _test_runtime: test_runtime,
         }
     }
@@ -809,6 +825,242 @@ impl ApiTester {
 
         self
     }
+
+    pub async fn test_scheduled_exits(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let pubkey = validator.voting_pubkey;
+
+        // An exit requires at least one condition.
+        let empty_request = ScheduleExitRequest {
+            epoch: None,
+            balance_below_gwei: None,
+        };
+        assert!(self
+            .client
+            .post_lighthouse_scheduled_exits(&pubkey, &empty_request)
+            .await
+            .is_err());
+
+        // Exits can only be scheduled for known validators.
+        let request = ScheduleExitRequest {
+            epoch: Some(Epoch::new(1024)),
+            balance_below_gwei: Some(31_000_000_000),
+        };
+        assert!(self
+            .client
+            .post_lighthouse_scheduled_exits(&PublicKeyBytes::empty(), &request)
+            .await
+            .is_err());
+
+        let scheduled = self
+            .client
+            .post_lighthouse_scheduled_exits(&pubkey, &request)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(scheduled.pubkey, pubkey);
+        assert_eq!(scheduled.status, ScheduledExitStatus::Pending);
+
+        let exits = self
+            .client
+            .get_lighthouse_scheduled_exits()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(exits, vec![scheduled]);
+
+        self.client
+            .delete_lighthouse_scheduled_exits(&pubkey)
+            .await
+            .unwrap();
+        assert!(self
+            .client
+            .get_lighthouse_scheduled_exits()
+            .await
+            .unwrap()
+            .data
+            .is_empty());
+        assert!(self
+            .client
+            .delete_lighthouse_scheduled_exits(&pubkey)
+            .await
+            .is_err());
+
+        self
+    }
+
+    pub async fn test_get_validator_duties(self, index: usize) -> Self {
+        let pubkey =
+            self.client.get_lighthouse_validators().await.unwrap().data[index].voting_pubkey;
+        let slots_per_epoch = E::slots_per_epoch();
+        let duty_history = self.validator_store.duty_history();
+
+        duty_history.record(
+            pubkey,
+            DutyAttempt::new(DutyKind::Attestation, Slot::new(1)).success(),
+        );
+        let block_slot = Epoch::new(2).start_slot(slots_per_epoch);
+        duty_history.record(
+            pubkey,
+            DutyAttempt::new(DutyKind::BlockProposal, block_slot).failure(
+                DutyErrorCategory::SlashingProtection,
+                "slashable".to_string(),
+            ),
+        );
+        self.slot_clock.set_slot(block_slot.as_u64() + 1);
+
+        let duties = self
+            .client
+            .get_lighthouse_validators_duties(&pubkey, None)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(duties.len(), 2);
+        assert_eq!(duties[0].kind, DutyKind::Attestation);
+        assert!(duties[0].success);
+
+        // Only the current epoch.
+        let duties = self
+            .client
+            .get_lighthouse_validators_duties(&pubkey, Some(1))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(duties.len(), 1);
+        assert_eq!(duties[0].kind, DutyKind::BlockProposal);
+        assert!(!duties[0].success);
+        assert_eq!(
+            duties[0].error_category,
+            Some(DutyErrorCategory::SlashingProtection)
+        );
+
+        assert!(self
+            .client
+            .get_lighthouse_validators_duties(&pubkey, Some(0))
+            .await
+            .is_err());
+        assert!(self
+            .client
+            .get_lighthouse_validators_duties(&PublicKeyBytes::empty(), None)
+            .await
+            .is_err());
+
+        self
+    }
+
+    pub async fn test_reload_validator_definitions(self) -> Self {
+        let dir = self.validator_dir.path();
+        let pubkeys = self
+            .client
+            .get_lighthouse_validators()
+            .await
+            .unwrap()
+            .data
+            .into_iter()
+            .map(|v| v.voting_pubkey)
+            .collect::<Vec<_>>();
+        let find = |defs: &mut ValidatorDefinitions, pubkey: &PublicKeyBytes| {
+            defs.as_mut_slice()
+                .iter_mut()
+                .position(|def| def.voting_public_key.compress() == *pubkey)
+                .unwrap()
+        };
+
+        // Reloading an unmodified file changes nothing.
+        let diff = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap();
+        assert!(diff.data.is_empty());
+
+        // Disable one validator and change the graffiti of another.
+        let mut defs = ValidatorDefinitions::open(dir).unwrap();
+        let i = find(&mut defs, &pubkeys[0]);
+        defs.as_mut_slice()[i].enabled = false;
+        let i = find(&mut defs, &pubkeys[1]);
+        defs.as_mut_slice()[i].graffiti = Some(GraffitiString::from_str("reloaded").unwrap());
+        defs.save(dir).unwrap();
+
+        let diff = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap();
+        assert_eq!(
+            diff.data,
+            ValidatorDefinitionsDiff {
+                disabled: vec![pubkeys[0]],
+                updated: vec![pubkeys[1]],
+                ..<_>::default()
+            }
+        );
+        assert_eq!(self.vals_enabled(), pubkeys.len() - 1);
+        assert_eq!(
+            self.client
+                .get_graffiti(&pubkeys[1])
+                .await
+                .unwrap()
+                .graffiti,
+            GraffitiString::from_str("reloaded").unwrap().into()
+        );
+
+        // Remove the disabled validator.
+        let mut defs = ValidatorDefinitions::open(dir).unwrap();
+        let removed = defs.as_mut_slice()[find(&mut defs, &pubkeys[0])].clone();
+        defs.retain(|def| def.voting_public_key != removed.voting_public_key);
+        defs.save(dir).unwrap();
+
+        let diff = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap();
+        assert_eq!(
+            diff.data,
+            ValidatorDefinitionsDiff {
+                removed: vec![pubkeys[0]],
+                ..<_>::default()
+            }
+        );
+        assert_eq!(self.vals_total(), pubkeys.len() - 1);
+
+        // Add it back, enabled.
+        let mut defs = ValidatorDefinitions::open(dir).unwrap();
+        defs.push(ValidatorDefinition {
+            enabled: true,
+            ..removed
+        });
+        defs.save(dir).unwrap();
+
+        let diff = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap();
+        assert_eq!(
+            diff.data,
+            ValidatorDefinitionsDiff {
+                added: vec![pubkeys[0]],
+                enabled: vec![pubkeys[0]],
+                ..<_>::default()
+            }
+        );
+
+        // A file with duplicate public keys is rejected without modifying any validators.
+        let mut defs = ValidatorDefinitions::open(dir).unwrap();
+        let duplicate = defs.as_slice()[0].clone();
+        defs.push(duplicate);
+        defs.save(dir).unwrap();
+        assert!(self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .is_err());
+
+        self.assert_enabled_validators_count(pubkeys.len())
+            .assert_validators_count(pubkeys.len())
+    }
 }
 
 struct HdValidatorScenario {
@@ -873,6 +1125,16 @@ async fn routes_with_invalid_auth() {
                 .await
         })
         .await
+        .test_with_invalid_auth(
+            |client| async move { client.post_lighthouse_validators_reload().await },
+        )
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .get_lighthouse_validators_duties(&PublicKeyBytes::empty(), None)
+                .await
+        })
+        .await
         .test_with_invalid_auth(|client| async move {
             client
                 .post_lighthouse_validators_mnemonic(&CreateValidatorsMnemonicRequest {
@@ -960,6 +1222,28 @@ async fn routes_with_invalid_auth() {
                 .set_graffiti(&PublicKeyBytes::empty(), GraffitiString::default())
                 .await
         })
+        .await
+        .test_with_invalid_auth(
+            |client| async move { client.get_lighthouse_scheduled_exits().await },
+        )
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_scheduled_exits(
+                    &PublicKeyBytes::empty(),
+                    &ScheduleExitRequest {
+                        epoch: Some(Epoch::new(0)),
+                        balance_below_gwei: None,
+                    },
+                )
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .delete_lighthouse_scheduled_exits(&PublicKeyBytes::empty())
+                .await
+        })
         .await;
 }
 
@@ -975,6 +1259,90 @@ async fn simple_getters() {
         .await;
 }
 
+#[tokio::test]
+async fn beacon_node_health() {
+    let candidates = ["http://127.0.0.1:5052", "http://127.0.0.1:5053"]
+        .iter()
+        .map(|url| {
+            CandidateBeaconNode::new(BeaconNodeHttpClient::new(
+                SensitiveUrl::parse(url).unwrap(),
+                Timeouts::set_all(Duration::from_secs(1)),
+            ))
+        })
+        .collect();
+    let beacon_nodes = Arc::new(BeaconNodeFallback::new(
+        candidates,
+        vec![],
+        E::default_spec(),
+        test_logger(),
+    ));
+
+    // The first node starts as the primary but its head is a slot behind the second node.
+    let lagging = BeaconNodeHealth {
+        head_slot: Slot::new(9),
+        sync_distance: Slot::new(0),
+        el_offline: false,
+        is_optimistic: false,
+        latency: Some(Duration::from_millis(10)),
+    };
+    let best = BeaconNodeHealth {
+        head_slot: Slot::new(10),
+        latency: Some(Duration::from_millis(200)),
+        ..lagging
+    };
+    beacon_nodes
+        .set_candidate_health(0, Ok(()), Some(lagging))
+        .await;
+    beacon_nodes
+        .set_candidate_health(1, Ok(()), Some(best))
+        .await;
+
+    let tester =
+        ApiTester::new_with_beacon_nodes(Config::default(), Some(beacon_nodes.clone())).await;
+    let health = tester
+        .client
+        .get_lighthouse_beacon_health()
+        .await
+        .unwrap()
+        .data;
+
+    assert_eq!(health.len(), 2);
+
+    assert_eq!(health[0].index, 1);
+    assert_eq!(health[0].endpoint, "http://127.0.0.1:5053/");
+    assert!(health[0].is_primary);
+    assert_eq!(health[0].status, BeaconNodeStatus::Synced);
+    assert_eq!(health[0].score, MAX_HEALTH_SCORE - 200);
+    assert_eq!(health[0].head_lag, Some(Slot::new(0)));
+    assert_eq!(health[0].latency_ms, Some(200));
+
+    assert_eq!(health[1].index, 0);
+    assert!(!health[1].is_primary);
+    assert_eq!(health[1].status, BeaconNodeStatus::Synced);
+    assert_eq!(health[1].score, lagging.score(Slot::new(10)));
+    assert_eq!(health[1].head_slot, Some(Slot::new(9)));
+    assert_eq!(health[1].head_lag, Some(Slot::new(1)));
+
+    // A primary which goes offline is replaced by the synced node.
+    beacon_nodes
+        .set_candidate_health(1, Err(CandidateError::Offline), None)
+        .await;
+    let health = tester
+        .client
+        .get_lighthouse_beacon_health()
+        .await
+        .unwrap()
+        .data;
+
+    assert_eq!(health[0].index, 0);
+    assert!(health[0].is_primary);
+    assert_eq!(health[0].head_lag, Some(Slot::new(0)));
+    assert_eq!(health[1].index, 1);
+    assert_eq!(health[1].status, BeaconNodeStatus::Offline);
+    assert_eq!(health[1].score, 0);
+    assert_eq!(health[1].head_slot, None);
+}
+
 #[tokio::test]
 async fn hd_validator_creation() {
     ApiTester::new()
@@ -1262,6 +1630,54 @@ async fn validator_graffiti_api() {
         .await;
 }
 
+#[tokio::test]
+async fn validator_scheduled_exits() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_enabled_validators_count(2)
+        .test_scheduled_exits(1)
+        .await;
+}
+
+#[tokio::test]
+async fn validator_duties() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_enabled_validators_count(2)
+        .test_get_validator_duties(1)
+        .await;
+}
+
+#[tokio::test]
+async fn validator_definitions_reload() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 3,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_enabled_validators_count(3)
+        .test_reload_validator_definitions()
+        .await;
+}
+
 #[tokio::test]
 async fn keystore_validator_creation() {
     ApiTester::new()
@@ -8,6 +8,7 @@ pub const SUCCESS: &str = "success";
 pub const SLASHABLE: &str = "slashable";
 pub const SAME_DATA: &str = "same_data";
 pub const UNREGISTERED: &str = "unregistered";
+pub const LEASE_NOT_HELD: &str = "lease_not_held";
 pub const FULL_UPDATE: &str = "full_update";
 pub const BEACON_BLOCK: &str = "beacon_block";
 pub const BEACON_BLOCK_HTTP_GET: &str = "beacon_block_http_get";
@@ -18,6 +19,8 @@ pub const ATTESTATIONS: &str = "attestations";
 pub const ATTESTATIONS_HTTP_GET: &str = "attestations_http_get";
 pub const ATTESTATIONS_HTTP_POST: &str = "attestations_http_post";
 pub const AGGREGATES: &str = "aggregates";
+pub const BLOCK_ARRIVAL: &str = "block_arrival";
+pub const DEADLINE: &str = "deadline";
 pub const AGGREGATES_HTTP_GET: &str = "aggregates_http_get";
 pub const AGGREGATES_HTTP_POST: &str = "aggregates_http_post";
 pub const CURRENT_EPOCH: &str = "current_epoch";
@@ -37,6 +40,7 @@ pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
 pub const LOCAL_KEYSTORE: &str = "local_keystore";
 pub const WEB3SIGNER: &str = "web3signer";
+pub const THRESHOLD: &str = "threshold";
 
 pub use lighthouse_metrics::*;
 
@@ -109,6 +113,11 @@ lazy_static::lazy_static! {
         "Duration to perform attestation service tasks",
         &["task"]
     );
+    pub static ref ATTESTATION_TRIGGERS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_attestation_triggers_total",
+        "Count of attestation rounds by what triggered them when attesting on block arrival",
+        &["trigger"]
+    );
     pub static ref SLASHING_PROTECTION_PRUNE_TIMES: Result<Histogram> = try_create_histogram(
         "vc_slashing_protection_prune_times_seconds",
         "Time required to prune the slashing protection DB",
@@ -161,6 +170,15 @@ lazy_static::lazy_static! {
         "vc_beacon_nodes_total_count",
         "Total number of beacon nodes",
     );
+    pub static ref BEACON_NODE_HEALTH_SCORE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "vc_beacon_node_health_score",
+        "Health score of each beacon node used to rank them, higher is healthier",
+        &["endpoint"]
+    );
+    pub static ref BEACON_NODE_PRIMARY_SWITCHES: Result<IntCounter> = try_create_int_counter(
+        "vc_beacon_node_primary_switches_total",
+        "Number of times a different beacon node was ranked as the primary",
+    );
 
     pub static ref ETH2_FALLBACK_CONFIGURED: Result<IntGauge> = try_create_int_gauge(
         "sync_eth2_fallback_configured",
@@ -189,6 +207,19 @@ lazy_static::lazy_static! {
         "Attestation duty slot for all managed validators",
         &["validator"]
     );
+    /*
+     * Duty outcomes
+     */
+    pub static ref DUTY_OUTCOMES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_duty_outcomes_total",
+        "Count of attempted duties by duty and outcome, where failures are labelled by reason",
+        &["duty", "outcome"]
+    );
+    pub static ref DUTY_LATENCY: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_duty_latency_seconds",
+        "Duration between starting a duty and it completing or failing",
+        &["duty"]
+    );
     /*
      * BN latency
      */
@@ -201,6 +232,25 @@ lazy_static::lazy_static! {
         "vc_beacon_node_latency_primary_endpoint",
         "Round-trip latency for the primary BN endpoint",
     );
+    /*
+     * High availability
+     */
+    pub static ref HA_LEASE_ACTIVE: Result<IntGauge> = try_create_int_gauge(
+        "vc_ha_lease_active",
+        "Set to 1 if this instance holds the high availability lease, otherwise set to 0",
+    );
+    pub static ref HA_LEASE_TERM: Result<IntGauge> = try_create_int_gauge(
+        "vc_ha_lease_term",
+        "Term of the high availability lease, regardless of which instance holds it",
+    );
+    pub static ref HA_LEASE_TAKEOVERS: Result<IntCounter> = try_create_int_counter(
+        "vc_ha_lease_takeovers_total",
+        "Number of times this instance has taken over the high availability lease",
+    );
+    pub static ref HA_LEASE_ERRORS: Result<IntCounter> = try_create_int_counter(
+        "vc_ha_lease_errors_total",
+        "Number of failed attempts to acquire or renew the high availability lease",
+    );
 }
 
 pub fn gather_prometheus_metrics<T: EthSpec>(
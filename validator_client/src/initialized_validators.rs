@@ -6,15 +6,17 @@
 //! The `InitializedValidators` struct in this file serves as the source-of-truth of which
 //! validators are managed by this validator client.
 
-use crate::signing_method::SigningMethod;
+use crate::signing_method::{ShareSigner, SigningMethod};
 use account_utils::{
+    password_source::{self, PasswordSource},
     read_password, read_password_from_user, read_password_string,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, Web3SignerDefinition,
-        CONFIG_FILENAME,
+        self, SigningDefinition, ThresholdDefinition, ValidatorDefinition, ValidatorDefinitions,
+        Web3SignerDefinition, CONFIG_FILENAME,
     },
     ZeroizeString,
 };
+use eth2::lighthouse_vc::types::ValidatorDefinitionsDiff;
 use eth2_keystore::Keystore;
 use lighthouse_metrics::set_gauge;
 use lockfile::{Lockfile, LockfileError};
@@ -80,8 +82,14 @@ pub enum Error {
     UnableToDecryptKeystore(eth2_keystore::Error),
     /// There was a filesystem error when reading the keystore password from disk.
     UnableToReadVotingKeystorePassword(io::Error),
+    /// The keystore password could not be fetched from its `PasswordSource`.
+    UnableToFetchVotingKeystorePassword(password_source::Error),
     /// There was an error updating the on-disk validator definitions file.
     UnableToSaveDefinitions(validator_definitions::Error),
+    /// There was an error reading the on-disk validator definitions file.
+    UnableToReadDefinitions(validator_definitions::Error),
+    /// A reloaded definition would require prompting the user for a keystore password.
+    ReloadRequiresPassword(PublicKey),
     /// It is not legal to try and initialize a disabled validator definition.
     UnableToInitializeDisabledValidator,
     /// There was an error while deleting a keystore file.
@@ -109,6 +117,8 @@ pub enum Error {
     InvalidWeb3SignerClientIdentityCertificateFile(io::Error),
     InvalidWeb3SignerClientIdentityCertificate(ReqwestError),
     UnableToBuildWeb3SignerClient(ReqwestError),
+    /// The threshold or shares of a threshold validator definition are invalid.
+    InvalidThresholdDefinition(String),
     /// Unable to apply an action to a validator.
     InvalidActionOnValidator,
     UnableToReadValidatorPassword(String),
@@ -148,8 +158,8 @@ impl InitializedValidator {
                 option_lockfile.as_mut()
             })
             .ok(),
-            // Web3Signer validators do not have any lockfiles.
-            SigningMethod::Web3Signer { .. } => None,
+            // Remote signer validators do not have any lockfiles.
+            SigningMethod::Web3Signer { .. } | SigningMethod::Threshold { .. } => None,
         }
     }
 
@@ -194,6 +204,14 @@ fn get_lockfile_path(file_path: &Path) -> Option<PathBuf> {
         .map(|filename| file_path.with_file_name(format!("{}.lock", filename)))
 }
 
+/// Fetches a keystore password from `source` without blocking the async executor.
+async fn fetch_password_from_source(source: PasswordSource) -> Result<ZeroizeString, Error> {
+    tokio::task::spawn_blocking(move || source.fetch())
+        .await
+        .map_err(Error::TokioJoin)?
+        .map_err(Error::UnableToFetchVotingKeystorePassword)
+}
+
 impl InitializedValidator {
     /// Instantiate `self` from a `ValidatorDefinition`.
     ///
@@ -220,6 +238,7 @@ impl InitializedValidator {
                 voting_keystore_path,
                 voting_keystore_password_path,
                 voting_keystore_password,
+                voting_keystore_password_source,
             } => {
                 use std::collections::hash_map::Entry::*;
                 let voting_keystore = match key_stores.entry(voting_keystore_path.clone()) {
@@ -235,19 +254,36 @@ impl InitializedValidator {
                     // Decoding a local keystore can take several seconds, therefore it's best
                     // to keep if off the core executor. This also has the fortunate effect of
                     // interrupting the potentially long-running task during shut down.
+                    //
+                    // Fetching the password from a `PasswordSource` may also block.
                     let (password, keypair) = tokio::task::spawn_blocking(move || {
                         Result::<_, Error>::Ok(
-                            match (voting_keystore_password_path, voting_keystore_password) {
+                            match (
+                                voting_keystore_password_path,
+                                voting_keystore_password,
+                                voting_keystore_password_source,
+                            ) {
                                 // If the password is supplied, use it and ignore the path
                                 // (if supplied).
-                                (_, Some(password)) => (
+                                (_, Some(password), _) => (
                                     password.as_ref().to_vec().into(),
                                     keystore
                                         .decrypt_keypair(password.as_ref())
                                         .map_err(Error::UnableToDecryptKeystore)?,
                                 ),
+                                // If a password source is supplied, fetch the password from it
+                                // and ignore the path (if supplied).
+                                (_, None, Some(source)) => {
+                                    let password = source
+                                        .fetch()
+                                        .map_err(Error::UnableToFetchVotingKeystorePassword)?;
+                                    let keypair = keystore
+                                        .decrypt_keypair(password.as_ref())
+                                        .map_err(Error::UnableToDecryptKeystore)?;
+                                    (password.as_ref().to_vec().into(), keypair)
+                                }
                                 // If only the path is supplied, use the path.
-                                (Some(path), None) => {
+                                (Some(path), None, None) => {
                                     let password = read_password(path)
                                         .map_err(Error::UnableToReadVotingKeystorePassword)?;
                                     let keypair = keystore
@@ -256,7 +292,7 @@ impl InitializedValidator {
                                     (password, keypair)
                                 }
                                 // If there is no password available, maybe prompt for a password.
-                                (None, None) => {
+                                (None, None, None) => {
                                     let (password, keypair) = unlock_keystore_via_stdin_password(
                                         &keystore,
                                         &keystore_path,
@@ -295,41 +331,8 @@ impl InitializedValidator {
             SigningDefinition::Web3Signer(web3_signer) => {
                 let signing_url = build_web3_signer_url(&web3_signer.url, &def.voting_public_key)
                     .map_err(|e| Error::InvalidWeb3SignerUrl(e.to_string()))?;
-
-                let request_timeout = web3_signer
-                    .request_timeout_ms
-                    .map(Duration::from_millis)
-                    .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT);
-
-                // Check if a client has already been initialized for this remote signer url.
-                let http_client = if let Some(client_map) = web3_signer_client_map {
-                    match client_map.get(&web3_signer) {
-                        Some(client) => client.clone(),
-                        None => {
-                            let client = build_web3_signer_client(
-                                web3_signer.root_certificate_path.clone(),
-                                web3_signer.client_identity_path.clone(),
-                                web3_signer.client_identity_password.clone(),
-                                request_timeout,
-                            )?;
-                            client_map.insert(web3_signer, client.clone());
-                            client
-                        }
-                    }
-                } else {
-                    // There are no clients in the map.
-                    let mut new_web3_signer_client_map: HashMap<Web3SignerDefinition, Client> =
-                        HashMap::new();
-                    let client = build_web3_signer_client(
-                        web3_signer.root_certificate_path.clone(),
-                        web3_signer.client_identity_path.clone(),
-                        web3_signer.client_identity_password.clone(),
-                        request_timeout,
-                    )?;
-                    new_web3_signer_client_map.insert(web3_signer, client.clone());
-                    *web3_signer_client_map = Some(new_web3_signer_client_map);
-                    client
-                };
+                let http_client =
+                    get_or_build_web3_signer_client(web3_signer, web3_signer_client_map)?;
 
                 SigningMethod::Web3Signer {
                     signing_url,
@@ -337,6 +340,49 @@ impl InitializedValidator {
                     voting_public_key: def.voting_public_key,
                 }
             }
+            SigningDefinition::Threshold(threshold_definition) => {
+                let ThresholdDefinition { threshold, shares } = threshold_definition;
+                if threshold == 0 || threshold > shares.len() {
+                    return Err(Error::InvalidThresholdDefinition(format!(
+                        "threshold {} must be between 1 and the number of shares {}",
+                        threshold,
+                        shares.len()
+                    )));
+                }
+                let mut indices = HashSet::new();
+                if let Some(share) = shares
+                    .iter()
+                    .find(|share| share.index == 0 || !indices.insert(share.index))
+                {
+                    return Err(Error::InvalidThresholdDefinition(format!(
+                        "share indices must be unique and non-zero, found {}",
+                        share.index
+                    )));
+                }
+
+                let share_signers = shares
+                    .into_iter()
+                    .map(|share| {
+                        let signing_url =
+                            build_web3_signer_url(&share.signer.url, &share.public_key)
+                                .map_err(|e| Error::InvalidWeb3SignerUrl(e.to_string()))?;
+                        let http_client =
+                            get_or_build_web3_signer_client(share.signer, web3_signer_client_map)?;
+                        Ok(ShareSigner {
+                            index: share.index,
+                            public_key: share.public_key,
+                            signing_url,
+                            http_client,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                SigningMethod::Threshold {
+                    threshold,
+                    share_signers,
+                    voting_public_key: def.voting_public_key,
+                }
+            }
         };
 
         Ok(Self {
@@ -357,6 +403,9 @@ impl InitializedValidator {
             SigningMethod::LocalKeystore { voting_keypair, .. } => &voting_keypair.pk,
             SigningMethod::Web3Signer {
                 voting_public_key, ..
+            }
+            | SigningMethod::Threshold {
+                voting_public_key, ..
             } => voting_public_key,
         }
     }
@@ -384,6 +433,33 @@ pub fn load_pkcs12_identity<P: AsRef<Path>>(
         .map_err(Error::InvalidWeb3SignerClientIdentityCertificate)
 }
 
+/// Returns the client for `web3_signer` from `web3_signer_client_map`, building and inserting it
+/// if it does not exist yet.
+fn get_or_build_web3_signer_client(
+    web3_signer: Web3SignerDefinition,
+    web3_signer_client_map: &mut Option<HashMap<Web3SignerDefinition, Client>>,
+) -> Result<Client, Error> {
+    let client_map = web3_signer_client_map.get_or_insert_with(HashMap::new);
+
+    // Check if a client has already been initialized for this remote signer url.
+    if let Some(client) = client_map.get(&web3_signer) {
+        return Ok(client.clone());
+    }
+
+    let request_timeout = web3_signer
+        .request_timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT);
+    let client = build_web3_signer_client(
+        web3_signer.root_certificate_path.clone(),
+        web3_signer.client_identity_path.clone(),
+        web3_signer.client_identity_password.clone(),
+        request_timeout,
+    )?;
+    client_map.insert(web3_signer, client.clone());
+    Ok(client)
+}
+
 fn build_web3_signer_url(base_url: &str, voting_public_key: &PublicKey) -> Result<Url, ParseError> {
     Url::parse(base_url)?.join(&format!("api/v1/eth2/sign/{}", voting_public_key))
 }
@@ -433,6 +509,7 @@ fn unlock_keystore_via_stdin_password(
     );
     eprintln!();
     eprintln!(" - voting_keystore_password");
+    eprintln!(" - voting_keystore_password_source");
     eprintln!(" - voting_keystore_password_path");
     eprintln!();
     eprintln!(
@@ -587,17 +664,24 @@ impl InitializedValidators {
                     voting_keystore_path,
                     voting_keystore_password,
                     voting_keystore_password_path,
-                    ..
+                    voting_keystore_password_source,
                 } if is_local_keystore => {
-                    let password = match (voting_keystore_password, voting_keystore_password_path) {
-                        (Some(password), _) => Some(password.clone()),
-                        (_, Some(path)) => {
+                    let password = match (
+                        voting_keystore_password,
+                        voting_keystore_password_source,
+                        voting_keystore_password_path,
+                    ) {
+                        (Some(password), _, _) => Some(password.clone()),
+                        (None, Some(source), _) => {
+                            fetch_password_from_source(source.clone()).await.map(Some)?
+                        }
+                        (None, None, Some(path)) => {
                             password_path_opt = Some(path.clone());
                             read_password_string(path)
                                 .map(Option::Some)
                                 .map_err(Error::UnableToReadValidatorPassword)?
                         }
-                        (None, None) => None,
+                        (None, None, None) => None,
                     };
                     let keystore = Keystore::from_json_file(voting_keystore_path)
                         .map_err(Error::UnableToReadKeystoreFile)?;
@@ -610,7 +694,9 @@ impl InitializedValidators {
 
                     Some(KeystoreAndPassword { keystore, password })
                 }
-                SigningDefinition::Web3Signer(_) if !is_local_keystore => {
+                SigningDefinition::Web3Signer(_) | SigningDefinition::Threshold(_)
+                    if !is_local_keystore =>
+                {
                     def.enabled = false;
                     None
                 }
@@ -1110,7 +1196,7 @@ impl InitializedValidators {
                     definitions_map.insert(*key_store.uuid(), def);
                 }
                 // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold(_) => (),
             }
         }
 
@@ -1137,9 +1223,16 @@ impl InitializedValidators {
                     voting_keystore_password_path,
                     voting_keystore_password,
                     voting_keystore_path,
+                    voting_keystore_password_source,
                 } => {
                     let pw = if let Some(p) = voting_keystore_password {
                         p.as_ref().to_vec().into()
+                    } else if let Some(source) = voting_keystore_password_source {
+                        fetch_password_from_source(source.clone())
+                            .await?
+                            .as_ref()
+                            .to_vec()
+                            .into()
                     } else if let Some(path) = voting_keystore_password_path {
                         read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?
                     } else {
@@ -1154,7 +1247,7 @@ impl InitializedValidators {
                     public_keys.push(def.voting_public_key.clone());
                 }
                 // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold(_) => (),
             };
         }
 
@@ -1278,7 +1371,13 @@ impl InitializedValidators {
                             }
                         }
                     }
-                    SigningDefinition::Web3Signer(Web3SignerDefinition { .. }) => {
+                    SigningDefinition::Web3Signer(_) | SigningDefinition::Threshold(_) => {
+                        let signing_method =
+                            if matches!(def.signing_definition, SigningDefinition::Threshold(_)) {
+                                "threshold_signer"
+                            } else {
+                                "remote_signer"
+                            };
                         match InitializedValidator::from_definition(
                             def.clone(),
                             &mut key_cache,
@@ -1294,7 +1393,7 @@ impl InitializedValidators {
                                 info!(
                                     self.log,
                                     "Enabled validator";
-                                    "signing_method" => "remote_signer",
+                                    "signing_method" => signing_method,
                                     "voting_pubkey" => format!("{:?}", def.voting_public_key),
                                 );
                             }
@@ -1303,7 +1402,7 @@ impl InitializedValidators {
                                     self.log,
                                     "Failed to initialize validator";
                                     "error" => format!("{:?}", e),
-                                    "signing_method" => "remote_signer",
+                                    "signing_method" => signing_method,
                                     "validator" => format!("{:?}", def.voting_public_key)
                                 );
 
@@ -1325,7 +1424,7 @@ impl InitializedValidators {
                         }
                     }
                     // Remote signers do not interact with the key cache.
-                    SigningDefinition::Web3Signer { .. } => (),
+                    SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold(_) => (),
                 }
 
                 info!(
@@ -1374,6 +1473,176 @@ impl InitializedValidators {
         Ok(())
     }
 
+    /// Read the validator definitions file from disk, without applying it.
+    pub fn read_definitions_from_disk(&self) -> Result<ValidatorDefinitions, Error> {
+        ValidatorDefinitions::open(&self.validators_dir).map_err(Error::UnableToReadDefinitions)
+    }
+
+    /// Compare `definitions` against the definitions currently held by `self`.
+    ///
+    /// Returns an error if `definitions` could not be applied safely, i.e. if it contains duplicate
+    /// public keys or a local keystore which needs to be unlocked but has no password configured.
+    pub fn diff_definitions(
+        &self,
+        definitions: &ValidatorDefinitions,
+    ) -> Result<ValidatorDefinitionsDiff, Error> {
+        let current = self
+            .definitions
+            .as_slice()
+            .iter()
+            .map(|def| (def.voting_public_key.compress(), def))
+            .collect::<HashMap<_, _>>();
+
+        let mut diff = ValidatorDefinitionsDiff::default();
+        let mut seen = HashSet::new();
+        for def in definitions.as_slice() {
+            let pubkey = def.voting_public_key.compress();
+            if !seen.insert(pubkey) {
+                return Err(Error::DuplicatePublicKey);
+            }
+
+            let needs_init = match current.get(&pubkey) {
+                None => {
+                    diff.added.push(pubkey);
+                    if def.enabled {
+                        diff.enabled.push(pubkey);
+                    }
+                    def.enabled
+                }
+                Some(existing) if existing.enabled != def.enabled => {
+                    if def.enabled {
+                        diff.enabled.push(pubkey);
+                    } else {
+                        diff.disabled.push(pubkey);
+                    }
+                    def.enabled
+                }
+                Some(existing) if *existing != def => {
+                    diff.updated.push(pubkey);
+                    def.enabled && existing.signing_definition != def.signing_definition
+                }
+                Some(_) => false,
+            };
+
+            // Never prompt for a password on stdin whilst the validator client is running.
+            if needs_init {
+                if let SigningDefinition::LocalKeystore {
+                    voting_keystore_password_path: None,
+                    voting_keystore_password: None,
+                    voting_keystore_password_source: None,
+                    ..
+                } = def.signing_definition
+                {
+                    return Err(Error::ReloadRequiresPassword(def.voting_public_key.clone()));
+                }
+            }
+        }
+
+        for (pubkey, existing) in current {
+            if !seen.contains(&pubkey) {
+                diff.removed.push(pubkey);
+                if existing.enabled {
+                    diff.disabled.push(pubkey);
+                }
+            }
+        }
+
+        Ok(diff)
+    }
+
+    /// Replace the definitions held by `self` with `definitions`, enabling, disabling, adding and
+    /// removing validators as described by `diff`.
+    ///
+    /// The `diff` must have been produced by `Self::diff_definitions` for the same `definitions`
+    /// without any intermediate modification of `self`. The on-disk definitions file is not
+    /// written, since `definitions` are expected to have been read from it.
+    ///
+    /// If any validator fails to initialize the previous definitions are restored.
+    pub async fn apply_definitions(
+        &mut self,
+        definitions: ValidatorDefinitions,
+        diff: &ValidatorDefinitionsDiff,
+    ) -> Result<(), Error> {
+        let previous = std::mem::replace(&mut self.definitions, definitions);
+
+        for pubkey in &diff.removed {
+            if self.validators.remove(pubkey).is_some() {
+                info!(
+                    self.log,
+                    "Removed validator";
+                    "voting_pubkey" => ?pubkey,
+                );
+            }
+        }
+
+        // Validators with a new signing definition are re-initialized, keeping their index.
+        let mut indices = HashMap::new();
+        for def in self.definitions.as_slice() {
+            let pubkey = def.voting_public_key.compress();
+            if !def.enabled || !diff.updated.contains(&pubkey) {
+                continue;
+            }
+            let signing_definition_changed = previous.as_slice().iter().any(|prev| {
+                prev.voting_public_key == def.voting_public_key
+                    && prev.signing_definition != def.signing_definition
+            });
+            if signing_definition_changed {
+                if let Some(validator) = self.validators.remove(&pubkey) {
+                    if let Some(index) = validator.index {
+                        indices.insert(pubkey, index);
+                    }
+                }
+            }
+        }
+
+        if let Err(e) = self.update_validators().await {
+            error!(
+                self.log,
+                "Failed to apply validator definitions";
+                "error" => ?e,
+                "msg" => "restoring previous definitions",
+            );
+            self.definitions = previous;
+            let enabled = self
+                .definitions
+                .as_slice()
+                .iter()
+                .filter(|def| def.enabled)
+                .map(|def| def.voting_public_key.compress())
+                .collect::<HashSet<_>>();
+            self.validators.retain(|pubkey, _| enabled.contains(pubkey));
+            self.update_validators().await?;
+            return Err(e);
+        }
+
+        for (pubkey, index) in indices {
+            self.set_index(&pubkey, index);
+        }
+
+        // Apply any changes to the per-validator settings of validators which remained enabled.
+        for def in self.definitions.as_slice() {
+            let pubkey = def.voting_public_key.compress();
+            if !diff.updated.contains(&pubkey) {
+                continue;
+            }
+            if let Some(validator) = self.validators.get_mut(&pubkey) {
+                validator.graffiti = def.graffiti.clone().map(Into::into);
+                validator.suggested_fee_recipient = def.suggested_fee_recipient;
+                validator.gas_limit = def.gas_limit;
+                validator.builder_proposals = def.builder_proposals;
+                validator.builder_boost_factor = def.builder_boost_factor;
+                validator.prefer_builder_proposals = def.prefer_builder_proposals;
+            }
+            info!(
+                self.log,
+                "Updated validator definition";
+                "voting_pubkey" => ?pubkey,
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn get_index(&self, pubkey: &PublicKeyBytes) -> Option<u64> {
         self.validators.get(pubkey).and_then(|val| val.index)
     }
@@ -1404,7 +1673,7 @@ impl InitializedValidators {
                     }
                 }
                 // Remote signers don't have passwords.
-                SigningDefinition::Web3Signer { .. } => (),
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Threshold(_) => (),
             };
         }
 
@@ -4,13 +4,17 @@ mod block_service;
 mod check_synced;
 mod cli;
 mod config;
+mod definitions_watcher;
 mod duties_service;
+mod duty_history;
 mod graffiti_file;
+mod ha_lease;
 mod http_metrics;
 mod key_cache;
 mod latency;
 mod notifier;
 mod preparation_service;
+mod scheduled_exits;
 mod signing_method;
 mod sync_committee_service;
 
@@ -19,21 +23,22 @@ pub mod http_api;
 pub mod initialized_validators;
 pub mod validator_store;
 
-pub use beacon_node_fallback::ApiTopic;
+pub use beacon_node_fallback::{ApiTopic, BeaconNodeFallback};
 pub use cli::cli_app;
 pub use config::Config;
 use initialized_validators::InitializedValidators;
 use lighthouse_metrics::set_gauge;
 use monitoring_api::{MonitoringHttpClient, ProcessType};
+pub use scheduled_exits::ScheduledExits;
 use sensitive_url::SensitiveUrl;
 pub use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 
 use crate::beacon_node_fallback::{
-    start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode, OfflineOnFailure,
-    RequireSynced,
+    start_fallback_updater_service, CandidateBeaconNode, OfflineOnFailure, RequireSynced,
 };
 use crate::doppelganger_service::DoppelgangerService;
 use crate::graffiti_file::GraffitiFile;
+use crate::ha_lease::{HaLease, LeaseService};
 use crate::initialized_validators::Error::UnableToOpenVotingKeystore;
 use account_utils::validator_definitions::ValidatorDefinitions;
 use attestation_service::{AttestationService, AttestationServiceBuilder};
@@ -47,6 +52,7 @@ use notifier::spawn_notifier;
 use parking_lot::RwLock;
 use preparation_service::{PreparationService, PreparationServiceBuilder};
 use reqwest::Certificate;
+use scheduled_exits::{ScheduledExitService, ScheduledExitServiceBuilder, ScheduledExits};
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
@@ -95,7 +101,10 @@ pub struct ProductionValidatorClient<T: EthSpec> {
     attestation_service: AttestationService<SystemTimeSlotClock, T>,
     sync_committee_service: SyncCommitteeService<SystemTimeSlotClock, T>,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
+    lease_service: Option<LeaseService<SystemTimeSlotClock, T>>,
     preparation_service: PreparationService<SystemTimeSlotClock, T>,
+    scheduled_exits: Arc<ScheduledExits>,
+    scheduled_exit_service: ScheduledExitService<SystemTimeSlotClock, T>,
     validator_store: Arc<ValidatorStore<SystemTimeSlotClock, T>>,
     slot_clock: SystemTimeSlotClock,
     http_api_listen_addr: Option<SocketAddr>,
@@ -228,22 +237,47 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // Create the slashing database if there are no validators, even if
         // `init_slashing_protection` is not supplied. There is no risk in creating a slashing
         // database without any validators in it.
-        let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
-        let slashing_protection = if config.init_slashing_protection || voting_pubkeys.is_empty() {
-            SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
-                format!(
-                    "Failed to open or create slashing protection database: {:?}",
-                    e
+        //
+        // With high availability the database is shared with the other instances rather than
+        // being locked by this process.
+        let slashing_protection = if let Some(ha_config) = &config.ha_lease {
+            let slashing_db_path = &ha_config.slashing_protection_db;
+            let lease_holder = ha_config.instance_id.clone();
+            if config.init_slashing_protection || voting_pubkeys.is_empty() {
+                SlashingDatabase::open_or_create_shared(slashing_db_path, lease_holder).map_err(
+                    |e| {
+                        format!(
+                            "Failed to open or create shared slashing protection database: {:?}",
+                            e
+                        )
+                    },
                 )
-            })
+            } else {
+                SlashingDatabase::open_shared(slashing_db_path, lease_holder).map_err(|e| {
+                    format!(
+                        "Failed to open shared slashing protection database {:?}: {:?}",
+                        slashing_db_path, e
+                    )
+                })
+            }
         } else {
-            SlashingDatabase::open(&slashing_db_path).map_err(|e| {
-                format!(
-                    "Failed to open slashing protection database: {:?}.\n\
-                     Ensure that `slashing_protection.sqlite` is in {:?} folder",
-                    e, config.validator_dir
-                )
-            })
+            let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
+            if config.init_slashing_protection || voting_pubkeys.is_empty() {
+                SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
+                    format!(
+                        "Failed to open or create slashing protection database: {:?}",
+                        e
+                    )
+                })
+            } else {
+                SlashingDatabase::open(&slashing_db_path).map_err(|e| {
+                    format!(
+                        "Failed to open slashing protection database: {:?}.\n\
+                         Ensure that `slashing_protection.sqlite` is in {:?} folder",
+                        e, config.validator_dir
+                    )
+                })
+            }
         }?;
 
         // Check validator registration with slashing protection, or auto-register all validators.
@@ -418,12 +452,19 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             None
         };
 
+        // All instances start on standby when high availability is enabled.
+        let ha_lease = config
+            .ha_lease
+            .as_ref()
+            .map(|_| Arc::new(HaLease::default()));
+
         let validator_store = Arc::new(ValidatorStore::new(
             validators,
-            slashing_protection,
+            slashing_protection.clone(),
             genesis_validators_root,
             context.eth2_config.spec.clone(),
             doppelganger_service.clone(),
+            ha_lease.clone(),
             slot_clock.clone(),
             &config,
             context.executor.clone(),
@@ -433,6 +474,20 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // Ensure all validators are registered in doppelganger protection.
         validator_store.register_all_in_doppelganger_protection_if_enabled()?;
 
+        let lease_service = config
+            .ha_lease
+            .clone()
+            .zip(ha_lease)
+            .map(|(ha_config, ha_lease)| {
+                LeaseService::new(
+                    ha_config,
+                    ha_lease,
+                    slashing_protection,
+                    validator_store.clone(),
+                    context.service_context("ha_lease".into()),
+                )
+            });
+
         info!(
             log,
             "Loaded validator keypair store";
@@ -486,6 +541,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("attestation".into()))
+            .attest_on_block_arrival(config.attest_on_block_arrival)
             .build()?;
 
         let preparation_service = PreparationServiceBuilder::new()
@@ -497,6 +553,20 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .validator_registration_batch_size(config.validator_registration_batch_size)
             .build()?;
 
+        let scheduled_exits = Arc::new(
+            ScheduledExits::open_or_create(&config.validator_dir)
+                .map_err(|e| format!("Unable to open scheduled exits: {:?}", e))?,
+        );
+
+        let scheduled_exit_service = ScheduledExitServiceBuilder::new()
+            .slot_clock(slot_clock.clone())
+            .validator_store(validator_store.clone())
+            .beacon_nodes(beacon_nodes.clone())
+            .runtime_context(context.service_context("scheduled_exits".into()))
+            .scheduled_exits(scheduled_exits.clone())
+            .rebroadcast_epochs(config.scheduled_exit_rebroadcast_epochs)
+            .build()?;
+
         let sync_committee_service = SyncCommitteeService::new(
             duties_service.clone(),
             validator_store.clone(),
@@ -512,7 +582,10 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             attestation_service,
             sync_committee_service,
             doppelganger_service,
+            lease_service,
             preparation_service,
+            scheduled_exits,
+            scheduled_exit_service,
             validator_store,
             config,
             slot_clock,
@@ -537,7 +610,9 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 task_executor: self.context.executor.clone(),
                 api_secret,
                 validator_store: Some(self.validator_store.clone()),
+                beacon_nodes: Some(self.duties_service.beacon_nodes.clone()),
                 validator_dir: Some(self.config.validator_dir.clone()),
+                scheduled_exits: Some(self.scheduled_exits.clone()),
                 secrets_dir: Some(self.config.secrets_dir.clone()),
                 graffiti_file: self.config.graffiti_file.clone(),
                 graffiti_flag: self.config.graffiti,
@@ -565,6 +640,11 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             None
         };
 
+        // Start competing for the lease before genesis so that an instance is active in time.
+        if let Some(lease_service) = self.lease_service.clone() {
+            lease_service.start_update_service();
+        }
+
         // Wait until genesis has occurred.
         wait_for_genesis(&self.beacon_nodes, self.genesis_time, &self.context).await?;
 
@@ -590,6 +670,11 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .start_update_service(&self.context.eth2_config.spec)
             .map_err(|e| format!("Unable to start preparation service: {}", e))?;
 
+        self.scheduled_exit_service
+            .clone()
+            .start_update_service(&self.context.eth2_config.spec)
+            .map_err(|e| format!("Unable to start scheduled exit service: {}", e))?;
+
         if let Some(doppelganger_service) = self.doppelganger_service.clone() {
             DoppelgangerService::start_update_service(
                 doppelganger_service,
@@ -614,6 +699,14 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             );
         }
 
+        if self.config.watch_validator_definitions {
+            definitions_watcher::start_definitions_watcher_service(
+                self.context.clone(),
+                self.validator_store.clone(),
+                self.config.validator_dir.clone(),
+            );
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,879 @@
+//! Provides voluntary exits which are signed and broadcast by the validator client once a
+//! condition is met (e.g. an epoch is reached or the balance drops below a threshold).
+//!
+//! Scheduled exits are persisted to disk so they survive restarts. Once an exit has been signed
+//! the signed message is persisted *before* it is broadcast, so a restart never causes a second,
+//! different exit message to be signed for the same validator.
+//!
+//! A signed exit is broadcast once, and only broadcast again if it hasn't been included in the
+//! beacon chain after a configurable number of epochs.
+
+use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::validator_store::ValidatorStore;
+use crate::OfflineOnFailure;
+use account_utils::write_file_via_temporary;
+use bls::PublicKeyBytes;
+use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{ScheduleExitRequest, ScheduledExitData, ScheduledExitStatus};
+use eth2::types::{StateId, ValidatorData, ValidatorId};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use slog::{debug, error, info, warn};
+use slot_clock::SlotClock;
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use types::{ChainSpec, Epoch, EthSpec, SignedVoluntaryExit, VoluntaryExit};
+
+/// The file name for the serialized scheduled exits.
+pub const SCHEDULED_EXITS_FILENAME: &str = "scheduled_exits.json";
+
+/// The file name for the temporary scheduled exits file.
+pub const TEMP_SCHEDULED_EXITS_FILENAME: &str = ".scheduled_exits.json.tmp";
+
+#[derive(Debug)]
+pub enum Error {
+    UnableToOpenFile(io::Error),
+    UnableToParseFile(serde_json::Error),
+    UnableToEncodeFile(serde_json::Error),
+    UnableToWriteFile(account_utils::filesystem::Error),
+    /// A scheduled exit must have at least one condition.
+    NoExitCondition,
+    /// The exit of the validator has already been signed, it can no longer be changed.
+    ExitAlreadySubmitted(PublicKeyBytes),
+    /// There is no scheduled exit for the validator.
+    ExitNotFound(PublicKeyBytes),
+}
+
+/// A voluntary exit that will be signed once any of its conditions is met.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledExit {
+    pub pubkey: PublicKeyBytes,
+    pub epoch: Option<Epoch>,
+    pub balance_below_gwei: Option<u64>,
+    pub status: ScheduledExitStatus,
+    /// The signed exit, present once the exit has been submitted.
+    pub signed_exit: Option<SignedVoluntaryExit>,
+    /// The epoch at which `signed_exit` was last broadcast, or at which broadcasting it failed.
+    pub broadcast_epoch: Option<Epoch>,
+    pub exit_epoch: Option<Epoch>,
+    pub last_error: Option<String>,
+}
+
+impl ScheduledExit {
+    fn new(pubkey: PublicKeyBytes, request: &ScheduleExitRequest) -> Self {
+        Self {
+            pubkey,
+            epoch: request.epoch,
+            balance_below_gwei: request.balance_below_gwei,
+            status: ScheduledExitStatus::Pending,
+            signed_exit: None,
+            broadcast_epoch: None,
+            exit_epoch: None,
+            last_error: None,
+        }
+    }
+
+    /// Returns `true` if any of the conditions of the exit is met.
+    pub fn conditions_met(&self, current_epoch: Epoch, balance: u64) -> bool {
+        self.epoch.map_or(false, |epoch| current_epoch >= epoch)
+            || self
+                .balance_below_gwei
+                .map_or(false, |threshold| balance < threshold)
+    }
+}
+
+impl From<&ScheduledExit> for ScheduledExitData {
+    fn from(exit: &ScheduledExit) -> Self {
+        Self {
+            pubkey: exit.pubkey,
+            epoch: exit.epoch,
+            balance_below_gwei: exit.balance_below_gwei,
+            status: exit.status,
+            exit_message_epoch: exit
+                .signed_exit
+                .as_ref()
+                .map(|signed_exit| signed_exit.message.epoch),
+            exit_epoch: exit.exit_epoch,
+            last_error: exit.last_error.clone(),
+        }
+    }
+}
+
+/// The on-disk collection of scheduled exits.
+pub struct ScheduledExits {
+    path: PathBuf,
+    temp_path: PathBuf,
+    exits: Mutex<Vec<ScheduledExit>>,
+}
+
+impl ScheduledExits {
+    /// Open the scheduled exits in `validators_dir`, or start with none if the file doesn't exist.
+    pub fn open_or_create<P: AsRef<Path>>(validators_dir: P) -> Result<Self, Error> {
+        let path = validators_dir.as_ref().join(SCHEDULED_EXITS_FILENAME);
+        let temp_path = validators_dir.as_ref().join(TEMP_SCHEDULED_EXITS_FILENAME);
+
+        let exits = if path.exists() {
+            let file = File::open(&path).map_err(Error::UnableToOpenFile)?;
+            serde_json::from_reader(file).map_err(Error::UnableToParseFile)?
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            path,
+            temp_path,
+            exits: Mutex::new(exits),
+        })
+    }
+
+    pub fn list(&self) -> Vec<ScheduledExit> {
+        self.exits.lock().clone()
+    }
+
+    /// Returns `true` if there is any exit which hasn't been processed by the beacon chain.
+    pub fn has_unfinished(&self) -> bool {
+        self.exits
+            .lock()
+            .iter()
+            .any(|exit| exit.status != ScheduledExitStatus::Completed)
+    }
+
+    /// Schedule an exit, replacing the conditions of any pending exit for the same validator.
+    pub fn schedule(
+        &self,
+        pubkey: PublicKeyBytes,
+        request: &ScheduleExitRequest,
+    ) -> Result<ScheduledExit, Error> {
+        if request.epoch.is_none() && request.balance_below_gwei.is_none() {
+            return Err(Error::NoExitCondition);
+        }
+
+        let mut exits = self.exits.lock();
+        let exit = ScheduledExit::new(pubkey, request);
+        match exits.iter_mut().find(|exit| exit.pubkey == pubkey) {
+            Some(existing) if existing.status != ScheduledExitStatus::Pending => {
+                return Err(Error::ExitAlreadySubmitted(pubkey))
+            }
+            Some(existing) => *existing = exit.clone(),
+            None => exits.push(exit.clone()),
+        }
+        self.save(&exits)?;
+
+        Ok(exit)
+    }
+
+    /// Cancel the pending exit of a validator.
+    pub fn cancel(&self, pubkey: &PublicKeyBytes) -> Result<(), Error> {
+        let mut exits = self.exits.lock();
+        let position = exits
+            .iter()
+            .position(|exit| exit.pubkey == *pubkey)
+            .ok_or(Error::ExitNotFound(*pubkey))?;
+        if exits[position].status != ScheduledExitStatus::Pending {
+            return Err(Error::ExitAlreadySubmitted(*pubkey));
+        }
+        exits.remove(position);
+        self.save(&exits)
+    }
+
+    /// Record the signed exit of a validator, transitioning it from pending to submitted.
+    ///
+    /// Returns `false` if the exit was cancelled or changed whilst it was being signed, in which
+    /// case the signed exit must not be broadcast.
+    fn submit(
+        &self,
+        pubkey: &PublicKeyBytes,
+        signed_exit: SignedVoluntaryExit,
+    ) -> Result<bool, Error> {
+        let mut exits = self.exits.lock();
+        match exits.iter_mut().find(|exit| exit.pubkey == *pubkey) {
+            Some(exit) if exit.status == ScheduledExitStatus::Pending => {
+                exit.status = ScheduledExitStatus::Submitted;
+                exit.signed_exit = Some(signed_exit);
+                exit.last_error = None;
+            }
+            _ => return Ok(false),
+        }
+        self.save(&exits)?;
+        Ok(true)
+    }
+
+    /// Apply `update` to the exit of a validator, persisting the result.
+    fn update<F: FnOnce(&mut ScheduledExit)>(
+        &self,
+        pubkey: &PublicKeyBytes,
+        update: F,
+    ) -> Result<(), Error> {
+        let mut exits = self.exits.lock();
+        if let Some(exit) = exits.iter_mut().find(|exit| exit.pubkey == *pubkey) {
+            let before = exit.clone();
+            update(exit);
+            if *exit != before {
+                self.save(&exits)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn save(&self, exits: &[ScheduledExit]) -> Result<(), Error> {
+        let bytes = serde_json::to_vec_pretty(exits).map_err(Error::UnableToEncodeFile)?;
+        write_file_via_temporary(&self.path, &self.temp_path, &bytes)
+            .map_err(Error::UnableToWriteFile)
+    }
+}
+
+/// What the `ScheduledExitService` should do with a scheduled exit.
+#[derive(Debug, PartialEq)]
+enum ExitAction {
+    /// Nothing to do at this time.
+    Wait,
+    /// The conditions are met, but the beacon chain won't accept an exit until the given epoch.
+    NotYetEligible(Epoch),
+    Sign,
+    /// The exit has been signed but hasn't been broadcast, the last broadcast failed or the exit
+    /// hasn't been included since it was last broadcast.
+    Broadcast,
+    /// The beacon chain has processed the exit, the validator exits at the given epoch.
+    Complete(Epoch),
+}
+
+fn next_action(
+    exit: &ScheduledExit,
+    validator: &ValidatorData,
+    current_epoch: Epoch,
+    rebroadcast_epochs: u64,
+    spec: &ChainSpec,
+) -> ExitAction {
+    if validator.validator.exit_epoch != spec.far_future_epoch {
+        return ExitAction::Complete(validator.validator.exit_epoch);
+    }
+
+    match exit.status {
+        ScheduledExitStatus::Completed => ExitAction::Wait,
+        ScheduledExitStatus::Submitted => match exit.broadcast_epoch {
+            None => ExitAction::Broadcast,
+            // A failed broadcast is retried in the next epoch.
+            Some(broadcast_epoch) if exit.last_error.is_some() => {
+                if broadcast_epoch < current_epoch {
+                    ExitAction::Broadcast
+                } else {
+                    ExitAction::Wait
+                }
+            }
+            Some(broadcast_epoch) => {
+                if current_epoch >= broadcast_epoch.saturating_add(rebroadcast_epochs) {
+                    ExitAction::Broadcast
+                } else {
+                    ExitAction::Wait
+                }
+            }
+        },
+        ScheduledExitStatus::Pending => {
+            if !exit.conditions_met(current_epoch, validator.balance) {
+                return ExitAction::Wait;
+            }
+
+            let eligible_epoch = validator
+                .validator
+                .activation_epoch
+                .saturating_add(spec.shard_committee_period);
+            if validator.validator.activation_epoch == spec.far_future_epoch
+                || current_epoch < eligible_epoch
+            {
+                ExitAction::NotYetEligible(eligible_epoch)
+            } else {
+                ExitAction::Sign
+            }
+        }
+    }
+}
+
+/// Builds a `ScheduledExitService`.
+pub struct ScheduledExitServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
+    validator_store: Option<Arc<ValidatorStore<T, E>>>,
+    slot_clock: Option<T>,
+    beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+    context: Option<RuntimeContext<E>>,
+    scheduled_exits: Option<Arc<ScheduledExits>>,
+    rebroadcast_epochs: Option<u64>,
+}
+
+impl<T: SlotClock + 'static, E: EthSpec> ScheduledExitServiceBuilder<T, E> {
+    pub fn new() -> Self {
+        Self {
+            validator_store: None,
+            slot_clock: None,
+            beacon_nodes: None,
+            context: None,
+            scheduled_exits: None,
+            rebroadcast_epochs: None,
+        }
+    }
+
+    pub fn validator_store(mut self, store: Arc<ValidatorStore<T, E>>) -> Self {
+        self.validator_store = Some(store);
+        self
+    }
+
+    pub fn slot_clock(mut self, slot_clock: T) -> Self {
+        self.slot_clock = Some(slot_clock);
+        self
+    }
+
+    pub fn beacon_nodes(mut self, beacon_nodes: Arc<BeaconNodeFallback<T, E>>) -> Self {
+        self.beacon_nodes = Some(beacon_nodes);
+        self
+    }
+
+    pub fn runtime_context(mut self, context: RuntimeContext<E>) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    pub fn scheduled_exits(mut self, scheduled_exits: Arc<ScheduledExits>) -> Self {
+        self.scheduled_exits = Some(scheduled_exits);
+        self
+    }
+
+    pub fn rebroadcast_epochs(mut self, rebroadcast_epochs: u64) -> Self {
+        self.rebroadcast_epochs = Some(rebroadcast_epochs);
+        self
+    }
+
+    pub fn build(self) -> Result<ScheduledExitService<T, E>, String> {
+        Ok(ScheduledExitService {
+            inner: Arc::new(Inner {
+                validator_store: self
+                    .validator_store
+                    .ok_or("Cannot build ScheduledExitService without validator_store")?,
+                slot_clock: self
+                    .slot_clock
+                    .ok_or("Cannot build ScheduledExitService without slot_clock")?,
+                beacon_nodes: self
+                    .beacon_nodes
+                    .ok_or("Cannot build ScheduledExitService without beacon_nodes")?,
+                context: self
+                    .context
+                    .ok_or("Cannot build ScheduledExitService without runtime_context")?,
+                scheduled_exits: self
+                    .scheduled_exits
+                    .ok_or("Cannot build ScheduledExitService without scheduled_exits")?,
+                rebroadcast_epochs: self
+                    .rebroadcast_epochs
+                    .ok_or("Cannot build ScheduledExitService without rebroadcast_epochs")?,
+            }),
+        })
+    }
+}
+
+/// Helper to minimise `Arc` usage.
+pub struct Inner<T, E: EthSpec> {
+    validator_store: Arc<ValidatorStore<T, E>>,
+    slot_clock: T,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    context: RuntimeContext<E>,
+    scheduled_exits: Arc<ScheduledExits>,
+    rebroadcast_epochs: u64,
+}
+
+/// Signs and broadcasts scheduled voluntary exits once their conditions are met.
+pub struct ScheduledExitService<T, E: EthSpec> {
+    inner: Arc<Inner<T, E>>,
+}
+
+impl<T, E: EthSpec> Clone for ScheduledExitService<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T, E: EthSpec> Deref for ScheduledExitService<T, E> {
+    type Target = Inner<T, E>;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.deref()
+    }
+}
+
+impl<T: SlotClock + 'static, E: EthSpec> ScheduledExitService<T, E> {
+    /// Starts the service which checks the conditions of scheduled exits every slot.
+    pub fn start_update_service(self, spec: &ChainSpec) -> Result<(), String> {
+        let log = self.context.log().clone();
+
+        info!(
+            log,
+            "Scheduled exit service started";
+        );
+
+        let executor = self.context.executor.clone();
+        let spec = spec.clone();
+        let slot_duration = Duration::from_secs(spec.seconds_per_slot);
+
+        let interval_fut = async move {
+            loop {
+                if self.scheduled_exits.has_unfinished() {
+                    self.process_scheduled_exits(&spec)
+                        .await
+                        .map_err(|e| {
+                            error!(
+                                log,
+                                "Error during scheduled exits";
+                                "error" => e,
+                            )
+                        })
+                        .unwrap_or(());
+                }
+
+                if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
+                    sleep(duration_to_next_slot).await;
+                } else {
+                    error!(log, "Failed to read slot clock");
+                    // If we can't read the slot clock, just wait another slot.
+                    sleep(slot_duration).await;
+                }
+            }
+        };
+
+        executor.spawn(interval_fut, "scheduled_exit_service");
+        Ok(())
+    }
+
+    async fn process_scheduled_exits(&self, spec: &ChainSpec) -> Result<(), String> {
+        let log = self.context.log();
+
+        // Only the active instance of a high-availability pair may sign exits.
+        if !self.validator_store.ha_lease_allows_signing() {
+            return Ok(());
+        }
+
+        let current_epoch = self
+            .slot_clock
+            .now()
+            .ok_or("Unable to read slot clock")?
+            .epoch(E::slots_per_epoch());
+
+        let exits = self
+            .scheduled_exits
+            .list()
+            .into_iter()
+            .filter(|exit| exit.status != ScheduledExitStatus::Completed)
+            .collect::<Vec<_>>();
+        let validator_ids = exits
+            .iter()
+            .map(|exit| ValidatorId::PublicKey(exit.pubkey))
+            .collect::<Vec<_>>();
+
+        let validators = self
+            .beacon_nodes
+            .first_success(RequireSynced::Yes, OfflineOnFailure::Yes, |beacon_node| {
+                let validator_ids = validator_ids.clone();
+                async move {
+                    beacon_node
+                        .post_beacon_states_validators(StateId::Head, Some(validator_ids), None)
+                        .await
+                }
+            })
+            .await
+            .map_err(|e| format!("Failed to query validators: {}", e))?
+            .ok_or("Head state not found")?
+            .data;
+
+        for exit in exits {
+            let Some(validator) = validators
+                .iter()
+                .find(|validator| validator.validator.pubkey == exit.pubkey)
+            else {
+                debug!(
+                    log,
+                    "Scheduled exit for unknown validator";
+                    "pubkey" => ?exit.pubkey,
+                );
+                continue;
+            };
+
+            match next_action(
+                &exit,
+                validator,
+                current_epoch,
+                self.rebroadcast_epochs,
+                spec,
+            ) {
+                ExitAction::Wait => {}
+                ExitAction::Complete(exit_epoch) => {
+                    info!(
+                        log,
+                        "Scheduled exit processed";
+                        "exit_epoch" => exit_epoch,
+                        "pubkey" => ?exit.pubkey,
+                    );
+                    self.update(&exit.pubkey, move |exit| {
+                        exit.status = ScheduledExitStatus::Completed;
+                        exit.exit_epoch = Some(exit_epoch);
+                        exit.last_error = None;
+                    })
+                    .await;
+                }
+                ExitAction::NotYetEligible(eligible_epoch) => {
+                    let message = format!("validator cannot exit before epoch {}", eligible_epoch);
+                    debug!(
+                        log,
+                        "Scheduled exit not yet eligible";
+                        "eligible_epoch" => eligible_epoch,
+                        "pubkey" => ?exit.pubkey,
+                    );
+                    self.update(&exit.pubkey, move |exit| exit.last_error = Some(message))
+                        .await;
+                }
+                ExitAction::Sign => {
+                    let voluntary_exit = VoluntaryExit {
+                        epoch: current_epoch,
+                        validator_index: validator.index,
+                    };
+                    let signed_exit = match self
+                        .validator_store
+                        .sign_voluntary_exit(exit.pubkey, voluntary_exit)
+                        .await
+                    {
+                        Ok(signed_exit) => signed_exit,
+                        Err(e) => {
+                            let message = format!("unable to sign exit: {:?}", e);
+                            warn!(
+                                log,
+                                "Failed to sign scheduled exit";
+                                "error" => &message,
+                                "pubkey" => ?exit.pubkey,
+                            );
+                            self.update(&exit.pubkey, move |exit| exit.last_error = Some(message))
+                                .await;
+                            continue;
+                        }
+                    };
+
+                    let pubkey = exit.pubkey;
+                    let exit_to_submit = signed_exit.clone();
+                    match self
+                        .with_scheduled_exits(
+                            move |scheduled_exits| scheduled_exits.submit(&pubkey, exit_to_submit),
+                            "scheduled_exits_submit",
+                        )
+                        .await
+                    {
+                        Ok(true) => {
+                            info!(
+                                log,
+                                "Signed scheduled exit";
+                                "epoch" => current_epoch,
+                                "validator_index" => validator.index,
+                                "pubkey" => ?exit.pubkey,
+                            );
+                            self.broadcast(&exit.pubkey, &signed_exit, current_epoch)
+                                .await;
+                        }
+                        Ok(false) => debug!(
+                            log,
+                            "Scheduled exit changed whilst signing";
+                            "pubkey" => ?exit.pubkey,
+                        ),
+                        Err(e) => error!(
+                            log,
+                            "Failed to persist signed exit";
+                            "error" => e,
+                            "pubkey" => ?exit.pubkey,
+                        ),
+                    }
+                }
+                ExitAction::Broadcast => {
+                    if let Some(signed_exit) = &exit.signed_exit {
+                        self.broadcast(&exit.pubkey, signed_exit, current_epoch)
+                            .await;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Publish a signed exit, recording the outcome against the scheduled exit.
+    async fn broadcast(
+        &self,
+        pubkey: &PublicKeyBytes,
+        signed_exit: &SignedVoluntaryExit,
+        current_epoch: Epoch,
+    ) {
+        let result = self
+            .beacon_nodes
+            .first_success(
+                RequireSynced::Yes,
+                OfflineOnFailure::No,
+                |beacon_node| async move {
+                    beacon_node
+                        .post_beacon_pool_voluntary_exits(signed_exit)
+                        .await
+                },
+            )
+            .await;
+
+        match result {
+            Ok(()) => {
+                self.update(pubkey, move |exit| {
+                    exit.broadcast_epoch = Some(current_epoch);
+                    exit.last_error = None;
+                })
+                .await
+            }
+            Err(e) => {
+                let message = format!("unable to publish exit: {}", e);
+                warn!(
+                    self.context.log(),
+                    "Failed to publish scheduled exit";
+                    "error" => &message,
+                    "pubkey" => ?pubkey,
+                );
+                self.update(pubkey, move |exit| {
+                    exit.broadcast_epoch = Some(current_epoch);
+                    exit.last_error = Some(message);
+                })
+                .await;
+            }
+        }
+    }
+
+    async fn update<F>(&self, pubkey: &PublicKeyBytes, update: F)
+    where
+        F: FnOnce(&mut ScheduledExit) + Send + 'static,
+    {
+        let pubkey = *pubkey;
+        if let Err(e) = self
+            .with_scheduled_exits(
+                move |scheduled_exits| scheduled_exits.update(&pubkey, update),
+                "scheduled_exits_update",
+            )
+            .await
+        {
+            error!(
+                self.context.log(),
+                "Failed to persist scheduled exit";
+                "error" => e,
+                "pubkey" => ?pubkey,
+            );
+        }
+    }
+
+    /// Run `task` on a blocking thread, since changes to the scheduled exits are written to disk.
+    async fn with_scheduled_exits<F, R>(&self, task: F, name: &'static str) -> Result<R, String>
+    where
+        F: FnOnce(&ScheduledExits) -> Result<R, Error> + Send + 'static,
+        R: Send + 'static,
+    {
+        let scheduled_exits = self.scheduled_exits.clone();
+        self.context
+            .executor
+            .spawn_blocking_handle(move || task(&scheduled_exits), name)
+            .ok_or("Shutting down")?
+            .await
+            .map_err(|e| format!("Task failed: {:?}", e))?
+            .map_err(|e| format!("{:?}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::types::ValidatorStatus;
+    use tempfile::tempdir;
+    use types::{Hash256, MainnetEthSpec, Validator};
+
+    fn pubkey(byte: u8) -> PublicKeyBytes {
+        let mut bytes = [0; 48];
+        bytes[0] = byte;
+        PublicKeyBytes::deserialize(&bytes).unwrap()
+    }
+
+    fn validator_data(activation_epoch: Epoch, exit_epoch: Epoch, balance: u64) -> ValidatorData {
+        ValidatorData {
+            index: 0,
+            balance,
+            status: ValidatorStatus::ActiveOngoing,
+            validator: Validator {
+                pubkey: pubkey(1),
+                withdrawal_credentials: Hash256::zero(),
+                effective_balance: balance,
+                slashed: false,
+                activation_eligibility_epoch: Epoch::new(0),
+                activation_epoch,
+                exit_epoch,
+                withdrawable_epoch: exit_epoch,
+            },
+        }
+    }
+
+    #[test]
+    fn schedule_and_cancel() {
+        let dir = tempdir().unwrap();
+        let exits = ScheduledExits::open_or_create(dir.path()).unwrap();
+
+        assert!(matches!(
+            exits.schedule(
+                pubkey(1),
+                &ScheduleExitRequest {
+                    epoch: None,
+                    balance_below_gwei: None
+                }
+            ),
+            Err(Error::NoExitCondition)
+        ));
+
+        let request = ScheduleExitRequest {
+            epoch: Some(Epoch::new(10)),
+            balance_below_gwei: None,
+        };
+        exits.schedule(pubkey(1), &request).unwrap();
+        exits.schedule(pubkey(2), &request).unwrap();
+
+        // Scheduled exits survive a restart.
+        let reopened = ScheduledExits::open_or_create(dir.path()).unwrap();
+        assert_eq!(reopened.list(), exits.list());
+
+        exits.cancel(&pubkey(2)).unwrap();
+        assert!(matches!(
+            exits.cancel(&pubkey(2)),
+            Err(Error::ExitNotFound(_))
+        ));
+
+        // Once signed, an exit can be neither changed nor cancelled.
+        let signed_exit = SignedVoluntaryExit {
+            message: VoluntaryExit {
+                epoch: Epoch::new(10),
+                validator_index: 0,
+            },
+            signature: bls::Signature::empty(),
+        };
+        assert!(exits.submit(&pubkey(1), signed_exit.clone()).unwrap());
+        assert!(!exits.submit(&pubkey(1), signed_exit).unwrap());
+        assert!(matches!(
+            exits.schedule(pubkey(1), &request),
+            Err(Error::ExitAlreadySubmitted(_))
+        ));
+        assert!(matches!(
+            exits.cancel(&pubkey(1)),
+            Err(Error::ExitAlreadySubmitted(_))
+        ));
+
+        let reopened = ScheduledExits::open_or_create(dir.path()).unwrap();
+        assert_eq!(reopened.list()[0].status, ScheduledExitStatus::Submitted);
+        assert!(reopened.list()[0].signed_exit.is_some());
+    }
+
+    #[test]
+    fn exit_actions() {
+        let spec = MainnetEthSpec::default_spec();
+        let far_future = spec.far_future_epoch;
+        let activation_epoch = Epoch::new(0);
+        let eligible_epoch = activation_epoch.saturating_add(spec.shard_committee_period);
+        let rebroadcast_epochs = 4;
+
+        let mut exit = ScheduledExit::new(
+            pubkey(1),
+            &ScheduleExitRequest {
+                epoch: Some(eligible_epoch + 10),
+                balance_below_gwei: Some(31_000_000_000),
+            },
+        );
+        let healthy = validator_data(activation_epoch, far_future, 32_000_000_000);
+        let leaking = validator_data(activation_epoch, far_future, 30_000_000_000);
+
+        // Neither condition is met.
+        assert_eq!(
+            next_action(&exit, &healthy, eligible_epoch, rebroadcast_epochs, &spec),
+            ExitAction::Wait
+        );
+        // The epoch condition is met.
+        assert_eq!(
+            next_action(
+                &exit,
+                &healthy,
+                eligible_epoch + 10,
+                rebroadcast_epochs,
+                &spec
+            ),
+            ExitAction::Sign
+        );
+        // The balance condition is met.
+        assert_eq!(
+            next_action(&exit, &leaking, eligible_epoch, rebroadcast_epochs, &spec),
+            ExitAction::Sign
+        );
+        // The validator hasn't been active for long enough.
+        assert_eq!(
+            next_action(
+                &exit,
+                &leaking,
+                eligible_epoch - 1,
+                rebroadcast_epochs,
+                &spec
+            ),
+            ExitAction::NotYetEligible(eligible_epoch)
+        );
+
+        // A signed exit which hasn't been broadcast is broadcast immediately.
+        exit.status = ScheduledExitStatus::Submitted;
+        assert_eq!(
+            next_action(&exit, &healthy, eligible_epoch, rebroadcast_epochs, &spec),
+            ExitAction::Broadcast
+        );
+        // Once broadcast, the exit is only broadcast again if it hasn't been included after
+        // `rebroadcast_epochs`.
+        exit.broadcast_epoch = Some(eligible_epoch);
+        for epoch in 0..rebroadcast_epochs {
+            assert_eq!(
+                next_action(
+                    &exit,
+                    &healthy,
+                    eligible_epoch + epoch,
+                    rebroadcast_epochs,
+                    &spec
+                ),
+                ExitAction::Wait
+            );
+        }
+        assert_eq!(
+            next_action(
+                &exit,
+                &healthy,
+                eligible_epoch + rebroadcast_epochs,
+                rebroadcast_epochs,
+                &spec
+            ),
+            ExitAction::Broadcast
+        );
+        // A failed broadcast is retried in the next epoch.
+        exit.last_error = Some("unable to publish exit".to_string());
+        assert_eq!(
+            next_action(&exit, &healthy, eligible_epoch, rebroadcast_epochs, &spec),
+            ExitAction::Wait
+        );
+        assert_eq!(
+            next_action(
+                &exit,
+                &healthy,
+                eligible_epoch + 1,
+                rebroadcast_epochs,
+                &spec
+            ),
+            ExitAction::Broadcast
+        );
+
+        let exited = validator_data(activation_epoch, eligible_epoch + 5, 32_000_000_000);
+        assert_eq!(
+            next_action(&exit, &exited, eligible_epoch, rebroadcast_epochs, &spec),
+            ExitAction::Complete(eligible_epoch + 5)
+        );
+    }
+}
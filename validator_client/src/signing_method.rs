@@ -2,12 +2,15 @@
 //!
 //! - Via a local `Keypair`.
 //! - Via a remote signer (Web3Signer)
+//! - Via a threshold of remote signers, each holding a share of the key.
 
 use crate::http_metrics::metrics;
 use eth2_keystore::Keystore;
+use futures::stream::{FuturesUnordered, StreamExt};
 use lockfile::Lockfile;
 use parking_lot::Mutex;
 use reqwest::Client;
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
@@ -26,7 +29,14 @@ pub enum Error {
         domain: Domain,
     },
     Web3SignerRequestFailed(String),
+    Web3SignerRequestTimedOut,
     Web3SignerJsonParsingFailed(String),
+    ThresholdNotMet {
+        threshold: usize,
+        valid_shares: usize,
+        errors: Vec<String>,
+    },
+    InvalidThresholdSignature(String),
     ShuttingDown,
     TokioJoin(String),
     MergeForkNotSupported,
@@ -93,6 +103,24 @@ pub enum SigningMethod {
         http_client: Client,
         voting_public_key: PublicKey,
     },
+    /// A validator whose key is split into shares held by several Web3Signer-compatible servers.
+    ///
+    /// Partial signatures are requested from all share holders and any `threshold` valid ones
+    /// are combined into the validator's signature.
+    Threshold {
+        threshold: usize,
+        share_signers: Vec<ShareSigner>,
+        voting_public_key: PublicKey,
+    },
+}
+
+/// A remote signer holding one share of a threshold validator's key.
+pub struct ShareSigner {
+    /// The index of the share, used to combine its partial signatures.
+    pub index: u64,
+    pub public_key: PublicKey,
+    pub signing_url: Url,
+    pub http_client: Client,
 }
 
 /// The additional information used to construct a signature. Mostly used for protection from replay
@@ -176,71 +204,309 @@ impl SigningMethod {
                 let _timer =
                     metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::WEB3SIGNER]);
 
-                // Map the message into a Web3Signer type.
-                let object = match signable_message {
-                    SignableMessage::RandaoReveal(epoch) => {
-                        Web3SignerObject::RandaoReveal { epoch }
-                    }
-                    SignableMessage::BeaconBlock(block) => Web3SignerObject::beacon_block(block)?,
-                    SignableMessage::AttestationData(a) => Web3SignerObject::Attestation(a),
-                    SignableMessage::SignedAggregateAndProof(a) => {
-                        Web3SignerObject::AggregateAndProof(a)
-                    }
-                    SignableMessage::SelectionProof(slot) => {
-                        Web3SignerObject::AggregationSlot { slot }
-                    }
-                    SignableMessage::SyncSelectionProof(s) => {
-                        Web3SignerObject::SyncAggregatorSelectionData(s)
-                    }
-                    SignableMessage::SyncCommitteeSignature {
-                        beacon_block_root,
-                        slot,
-                    } => Web3SignerObject::SyncCommitteeMessage {
-                        beacon_block_root,
-                        slot,
-                    },
-                    SignableMessage::SignedContributionAndProof(c) => {
-                        Web3SignerObject::ContributionAndProof(c)
-                    }
-                    SignableMessage::ValidatorRegistration(v) => {
-                        Web3SignerObject::ValidatorRegistration(v)
-                    }
-                    SignableMessage::VoluntaryExit(e) => Web3SignerObject::VoluntaryExit(e),
-                };
+                let request = web3signer_request(signable_message, signing_root, fork_info)?;
+
+                // Request a signature from the Web3Signer instance via HTTP(S).
+                request_web3signer_signature(http_client, signing_url, &request).await
+            }
+            SigningMethod::Threshold {
+                threshold,
+                share_signers,
+                voting_public_key,
+            } => {
+                let _timer =
+                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::THRESHOLD]);
 
-                // Determine the Web3Signer message type.
-                let message_type = object.message_type();
+                let request = web3signer_request(signable_message, signing_root, fork_info)?;
 
-                if matches!(
-                    object,
-                    Web3SignerObject::Deposit { .. } | Web3SignerObject::ValidatorRegistration(_)
-                ) && fork_info.is_some()
-                {
-                    return Err(Error::GenesisForkVersionRequired);
-                }
+                // Request partial signatures from all share holders concurrently and use the first
+                // `threshold` valid ones, so that slow or offline holders don't delay signing.
+                let partials = share_signers
+                    .iter()
+                    .map(|share_signer| {
+                        let request = &request;
+                        async move {
+                            let response = request_web3signer_signature(
+                                &share_signer.http_client,
+                                &share_signer.signing_url,
+                                request,
+                            )
+                            .await;
+                            (
+                                share_signer.index,
+                                share_signer.public_key.clone(),
+                                response,
+                            )
+                        }
+                    })
+                    .collect::<FuturesUnordered<_>>();
 
-                let request = SigningRequest {
-                    message_type,
-                    fork_info,
-                    signing_root,
-                    object,
+                // Verifying signatures is CPU intensive, so it is kept off the core executor.
+                let verify = |public_key: PublicKey, signature: Signature| async move {
+                    executor
+                        .spawn_blocking_handle(
+                            move || signature.verify(&public_key, signing_root),
+                            "threshold_signer",
+                        )
+                        .ok_or(Error::ShuttingDown)?
+                        .await
+                        .map_err(|e| Error::TokioJoin(e.to_string()))
                 };
+                let valid_shares = collect_partial_signatures(*threshold, partials, verify).await?;
 
-                // Request a signature from the Web3Signer instance via HTTP(S).
-                let response: SigningResponse = http_client
-                    .post(signing_url.clone())
-                    .json(&request)
-                    .send()
-                    .await
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
-                    .error_for_status()
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
-                    .json()
+                let voting_public_key = voting_public_key.clone();
+                executor
+                    .spawn_blocking_handle(
+                        move || {
+                            combine_partial_signatures(
+                                valid_shares,
+                                &voting_public_key,
+                                signing_root,
+                            )
+                        },
+                        "threshold_signer",
+                    )
+                    .ok_or(Error::ShuttingDown)?
                     .await
-                    .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
+                    .map_err(|e| Error::TokioJoin(e.to_string()))?
+            }
+        }
+    }
+}
+
+/// Maps `signable_message` into a request for a Web3Signer-compatible server.
+fn web3signer_request<'a, T: EthSpec, Payload: AbstractExecPayload<T>>(
+    signable_message: SignableMessage<'a, T, Payload>,
+    signing_root: Hash256,
+    fork_info: Option<ForkInfo>,
+) -> Result<SigningRequest<'a, T, Payload>, Error> {
+    // Map the message into a Web3Signer type.
+    let object = match signable_message {
+        SignableMessage::RandaoReveal(epoch) => Web3SignerObject::RandaoReveal { epoch },
+        SignableMessage::BeaconBlock(block) => Web3SignerObject::beacon_block(block)?,
+        SignableMessage::AttestationData(a) => Web3SignerObject::Attestation(a),
+        SignableMessage::SignedAggregateAndProof(a) => Web3SignerObject::AggregateAndProof(a),
+        SignableMessage::SelectionProof(slot) => Web3SignerObject::AggregationSlot { slot },
+        SignableMessage::SyncSelectionProof(s) => Web3SignerObject::SyncAggregatorSelectionData(s),
+        SignableMessage::SyncCommitteeSignature {
+            beacon_block_root,
+            slot,
+        } => Web3SignerObject::SyncCommitteeMessage {
+            beacon_block_root,
+            slot,
+        },
+        SignableMessage::SignedContributionAndProof(c) => Web3SignerObject::ContributionAndProof(c),
+        SignableMessage::ValidatorRegistration(v) => Web3SignerObject::ValidatorRegistration(v),
+        SignableMessage::VoluntaryExit(e) => Web3SignerObject::VoluntaryExit(e),
+    };
+
+    // Determine the Web3Signer message type.
+    let message_type = object.message_type();
+
+    if matches!(
+        object,
+        Web3SignerObject::Deposit { .. } | Web3SignerObject::ValidatorRegistration(_)
+    ) && fork_info.is_some()
+    {
+        return Err(Error::GenesisForkVersionRequired);
+    }
+
+    Ok(SigningRequest {
+        message_type,
+        fork_info,
+        signing_root,
+        object,
+    })
+}
+
+/// Requests a signature from a Web3Signer-compatible server via HTTP(S).
+async fn request_web3signer_signature<T: EthSpec, Payload: AbstractExecPayload<T>>(
+    http_client: &Client,
+    signing_url: &Url,
+    request: &SigningRequest<'_, T, Payload>,
+) -> Result<Signature, Error> {
+    let response: SigningResponse = http_client
+        .post(signing_url.clone())
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                Error::Web3SignerRequestTimedOut
+            } else {
+                Error::Web3SignerRequestFailed(e.to_string())
+            }
+        })?
+        .error_for_status()
+        .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
+
+    Ok(response.signature)
+}
 
-                Ok(response.signature)
+/// Awaits partial signatures as they arrive, returning as soon as `threshold` of them have been
+/// verified against the public keys of their shares.
+///
+/// Each partial signature is verified individually, so that a faulty share holder can't prevent
+/// the others from producing a signature. Requests which are still outstanding once the threshold
+/// is met are dropped.
+async fn collect_partial_signatures<P, V, VF>(
+    threshold: usize,
+    mut partials: FuturesUnordered<P>,
+    verify: V,
+) -> Result<Vec<(u64, Signature)>, Error>
+where
+    P: Future<Output = (u64, PublicKey, Result<Signature, Error>)>,
+    V: Fn(PublicKey, Signature) -> VF,
+    VF: Future<Output = Result<bool, Error>>,
+{
+    let mut valid_shares = Vec::with_capacity(threshold);
+    let mut errors = vec![];
+    while valid_shares.len() < threshold {
+        let Some((index, public_key, response)) = partials.next().await else {
+            return Err(Error::ThresholdNotMet {
+                threshold,
+                valid_shares: valid_shares.len(),
+                errors,
+            });
+        };
+        match response {
+            Ok(signature) => {
+                if verify(public_key, signature.clone()).await? {
+                    valid_shares.push((index, signature));
+                } else {
+                    errors.push(format!("share {}: invalid partial signature", index));
+                }
             }
+            Err(e) => errors.push(format!("share {}: {:?}", index, e)),
+        }
+    }
+    Ok(valid_shares)
+}
+
+/// Combines valid partial signatures into a signature by `voting_public_key`.
+fn combine_partial_signatures(
+    valid_shares: Vec<(u64, Signature)>,
+    voting_public_key: &PublicKey,
+    signing_root: Hash256,
+) -> Result<Signature, Error> {
+    let signature = Signature::combine_shares(&valid_shares)
+        .map_err(|e| Error::InvalidThresholdSignature(format!("{:?}", e)))?;
+    if !signature.verify(voting_public_key, signing_root) {
+        return Err(Error::InvalidThresholdSignature(
+            "combined signature does not match the voting public key".into(),
+        ));
+    }
+
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::{self, BoxFuture, FutureExt};
+    use std::time::Duration;
+
+    type Partial = BoxFuture<'static, (u64, PublicKey, Result<Signature, Error>)>;
+
+    /// Signs `signing_root` with each share of a 2-of-3 split key, as a set of local signers would.
+    fn partial_signatures(signing_root: Hash256) -> (PublicKey, Vec<(u64, PublicKey, Signature)>) {
+        let keypair = Keypair::random();
+        let partials = keypair
+            .sk
+            .split_shares(2, 3)
+            .unwrap()
+            .into_iter()
+            .map(|(index, sk)| (index, sk.public_key(), sk.sign(signing_root)))
+            .collect();
+        (keypair.pk, partials)
+    }
+
+    /// Returns share holders which respond immediately with `partials`.
+    fn ready(partials: Vec<(u64, PublicKey, Signature)>) -> Vec<Partial> {
+        partials
+            .into_iter()
+            .map(|(index, public_key, signature)| {
+                future::ready((index, public_key, Ok(signature))).boxed()
+            })
+            .collect()
+    }
+
+    async fn sign(
+        partials: Vec<Partial>,
+        voting_public_key: &PublicKey,
+        signing_root: Hash256,
+    ) -> Result<Signature, Error> {
+        let verify = |public_key: PublicKey, signature: Signature| async move {
+            Ok(signature.verify(&public_key, signing_root))
+        };
+        let valid_shares =
+            collect_partial_signatures(2, partials.into_iter().collect(), verify).await?;
+        combine_partial_signatures(valid_shares, voting_public_key, signing_root)
+    }
+
+    #[tokio::test]
+    async fn combines_any_threshold_of_shares() {
+        let signing_root = Hash256::repeat_byte(42);
+        let (voting_public_key, partials) = partial_signatures(signing_root);
+
+        for skip in 0..partials.len() {
+            let subset = partials
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != skip)
+                .map(|(_, partial)| partial.clone())
+                .collect();
+            let signature = sign(ready(subset), &voting_public_key, signing_root)
+                .await
+                .unwrap();
+            assert!(signature.verify(&voting_public_key, signing_root));
         }
     }
+
+    #[tokio::test]
+    async fn skips_invalid_partial_signatures() {
+        let signing_root = Hash256::repeat_byte(42);
+        let (voting_public_key, mut partials) = partial_signatures(signing_root);
+
+        // A faulty signer returns a signature over a different message.
+        partials[0].2 = Keypair::random().sk.sign(signing_root);
+
+        let signature = sign(ready(partials.clone()), &voting_public_key, signing_root)
+            .await
+            .unwrap();
+        assert!(signature.verify(&voting_public_key, signing_root));
+
+        // With only one honest signer the threshold is not met.
+        partials.truncate(2);
+        assert_eq!(
+            sign(ready(partials), &voting_public_key, signing_root).await,
+            Err(Error::ThresholdNotMet {
+                threshold: 2,
+                valid_shares: 1,
+                errors: vec!["share 1: invalid partial signature".into()],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn does_not_wait_for_hanging_share_holder() {
+        let signing_root = Hash256::repeat_byte(42);
+        let (voting_public_key, partials) = partial_signatures(signing_root);
+
+        // The first share holder never responds.
+        let mut share_holders = ready(partials);
+        share_holders[0] = future::pending().boxed();
+
+        let signature = tokio::time::timeout(
+            Duration::from_secs(5),
+            sign(share_holders, &voting_public_key, signing_root),
+        )
+        .await
+        .expect("signing should not wait for a hanging share holder")
+        .unwrap();
+        assert!(signature.verify(&voting_public_key, signing_root));
+    }
 }
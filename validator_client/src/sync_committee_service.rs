@@ -1,10 +1,12 @@
 use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::DutiesService,
+    duty_history::{error_category, DutyAttempt},
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{DutyErrorCategory, DutyKind};
 use eth2::types::BlockId;
 use futures::future::join_all;
 use futures::future::FutureExt;
@@ -176,6 +178,18 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             return Ok(());
         }
 
+        if !self.validator_store.ha_lease_allows_signing() {
+            debug!(
+                log,
+                "Not producing sync committee messages on standby";
+                "slot" => slot,
+            );
+            return Ok(());
+        }
+
+        let duty_history = self.validator_store.duty_history();
+        let mut attempt = DutyAttempt::new(DutyKind::SyncCommitteeMessage, slot);
+
         // Fetch `block_root` with non optimistic execution for `SyncCommitteeContribution`.
         let response = self
             .beacon_nodes
@@ -185,7 +199,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 |beacon_node| async move {
                     match beacon_node.get_beacon_blocks_root(BlockId::Head).await {
                         Ok(Some(block)) if block.execution_optimistic == Some(false) => {
-                            Ok(block)
+                            Ok((beacon_node.to_string(), block))
                         }
                         Ok(Some(_)) => {
                             Err(format!("To sign sync committee messages for slot {slot} a non-optimistic head block is required"))
@@ -198,8 +212,26 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .await;
 
         let block_root = match response {
-            Ok(block) => block.data.root,
+            Ok((beacon_node, block)) => {
+                attempt.set_beacon_node(beacon_node);
+                block.data.root
+            }
             Err(errs) => {
+                let contribution_attempt =
+                    DutyAttempt::new(DutyKind::SyncCommitteeContribution, slot);
+                for duty in &slot_duties.duties {
+                    duty_history.record(
+                        duty.pubkey,
+                        attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, errs.to_string()),
+                    );
+                }
+                for (_, pubkey, _) in slot_duties.aggregators.values().flatten() {
+                    duty_history.record(
+                        *pubkey,
+                        contribution_attempt
+                            .failure(DutyErrorCategory::BeaconNodeUnavailable, errs.to_string()),
+                    );
+                }
                 warn!(
                     log,
                     "Refusing to sign sync committee messages for an optimistic head block or \
@@ -217,7 +249,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         self.inner.context.executor.spawn(
             async move {
                 service
-                    .publish_sync_committee_signatures(slot, block_root, validator_duties)
+                    .publish_sync_committee_signatures(slot, block_root, validator_duties, attempt)
                     .map(|_| ())
                     .await
             },
@@ -250,12 +282,15 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         slot: Slot,
         beacon_block_root: Hash256,
         validator_duties: Vec<SyncDuty>,
+        attempt: DutyAttempt,
     ) -> Result<(), ()> {
         let log = self.context.log();
+        let duty_history = self.validator_store.duty_history();
+        let attempt_ref = &attempt;
 
         // Create futures to produce sync committee signatures.
         let signature_futures = validator_duties.iter().map(|duty| async move {
-            match self
+            let result = self
                 .validator_store
                 .produce_sync_committee_signature(
                     slot,
@@ -263,9 +298,17 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     duty.validator_index,
                     &duty.pubkey,
                 )
-                .await
-            {
-                Ok(signature) => Some(signature),
+                .await;
+
+            if let Err(e) = &result {
+                duty_history.record(
+                    duty.pubkey,
+                    attempt_ref.failure(error_category(e), format!("{:?}", e)),
+                );
+            }
+
+            match result {
+                Ok(signature) => Some((signature, duty.pubkey)),
                 Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                     // A pubkey can be missing when a validator was recently
                     // removed via the API.
@@ -292,13 +335,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let committee_signatures = &join_all(signature_futures)
+        let (ref committee_signatures, signers): (Vec<_>, Vec<_>) = join_all(signature_futures)
             .await
             .into_iter()
             .flatten()
-            .collect::<Vec<_>>();
+            .unzip();
 
-        self.beacon_nodes
+        let result = self
+            .beacon_nodes
             .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
@@ -309,15 +353,24 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                         .await
                 },
             )
-            .await
-            .map_err(|e| {
-                error!(
-                    log,
-                    "Unable to publish sync committee messages";
-                    "slot" => slot,
-                    "error" => %e,
-                );
-            })?;
+            .await;
+
+        for pubkey in signers {
+            let record = match &result {
+                Ok(()) => attempt.success(),
+                Err(e) => attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, e.to_string()),
+            };
+            duty_history.record(pubkey, record);
+        }
+
+        result.map_err(|e| {
+            error!(
+                log,
+                "Unable to publish sync committee messages";
+                "slot" => slot,
+                "error" => %e,
+            );
+        })?;
 
         info!(
             log,
@@ -368,8 +421,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         sleep_until(aggregate_instant).await;
 
         let log = self.context.log();
+        let duty_history = self.validator_store.duty_history();
+        let mut attempt = DutyAttempt::new(DutyKind::SyncCommitteeContribution, slot);
 
-        let contribution = &self
+        let response = self
             .beacon_nodes
             .first_success(
                 RequireSynced::No,
@@ -384,32 +439,56 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     beacon_node
                         .get_validator_sync_committee_contribution::<E>(&sync_contribution_data)
                         .await
+                        .map(|response| (beacon_node.to_string(), response))
                 },
             )
-            .await
-            .map_err(|e| {
+            .await;
+
+        let (beacon_node, contribution) = match response {
+            Ok((beacon_node, Some(contribution))) => (beacon_node, contribution.data),
+            Ok((_, None)) => {
                 crit!(
                     log,
-                    "Failed to produce sync contribution";
+                    "No aggregate contribution found";
                     "slot" => slot,
                     "beacon_block_root" => ?beacon_block_root,
-                    "error" => %e,
-                )
-            })?
-            .ok_or_else(|| {
+                );
+                for (_, pubkey, _) in &subnet_aggregators {
+                    duty_history.record(
+                        *pubkey,
+                        attempt.failure(
+                            DutyErrorCategory::BeaconNodeUnavailable,
+                            "No aggregate contribution found".to_string(),
+                        ),
+                    );
+                }
+                return Err(());
+            }
+            Err(e) => {
                 crit!(
                     log,
-                    "No aggregate contribution found";
+                    "Failed to produce sync contribution";
                     "slot" => slot,
                     "beacon_block_root" => ?beacon_block_root,
+                    "error" => %e,
                 );
-            })?
-            .data;
+                for (_, pubkey, _) in &subnet_aggregators {
+                    duty_history.record(
+                        *pubkey,
+                        attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, e.to_string()),
+                    );
+                }
+                return Err(());
+            }
+        };
+        attempt.set_beacon_node(beacon_node);
+        let contribution = &contribution;
+        let attempt_ref = &attempt;
 
         // Create futures to produce signed contributions.
         let signature_futures = subnet_aggregators.into_iter().map(
             |(aggregator_index, aggregator_pk, selection_proof)| async move {
-                match self
+                let result = self
                     .validator_store
                     .produce_signed_contribution_and_proof(
                         aggregator_index,
@@ -417,9 +496,17 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                         contribution.clone(),
                         selection_proof,
                     )
-                    .await
-                {
-                    Ok(signed_contribution) => Some(signed_contribution),
+                    .await;
+
+                if let Err(e) = &result {
+                    duty_history.record(
+                        aggregator_pk,
+                        attempt_ref.failure(error_category(e), format!("{:?}", e)),
+                    );
+                }
+
+                match result {
+                    Ok(signed_contribution) => Some((signed_contribution, aggregator_pk)),
                     Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                         // A pubkey can be missing when a validator was recently
                         // removed via the API.
@@ -445,14 +532,15 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         );
 
         // Execute all the futures in parallel, collecting any successful results.
-        let signed_contributions = &join_all(signature_futures)
+        let (ref signed_contributions, aggregators): (Vec<_>, Vec<_>) = join_all(signature_futures)
             .await
             .into_iter()
             .flatten()
-            .collect::<Vec<_>>();
+            .unzip();
 
         // Publish to the beacon node.
-        self.beacon_nodes
+        let result = self
+            .beacon_nodes
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
@@ -462,15 +550,24 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                         .await
                 },
             )
-            .await
-            .map_err(|e| {
-                error!(
-                    log,
-                    "Unable to publish signed contributions and proofs";
-                    "slot" => slot,
-                    "error" => %e,
-                );
-            })?;
+            .await;
+
+        for pubkey in aggregators {
+            let record = match &result {
+                Ok(()) => attempt.success(),
+                Err(e) => attempt.failure(DutyErrorCategory::BeaconNodeUnavailable, e.to_string()),
+            };
+            duty_history.record(pubkey, record);
+        }
+
+        result.map_err(|e| {
+            error!(
+                log,
+                "Unable to publish signed contributions and proofs";
+                "slot" => slot,
+                "error" => %e,
+            );
+        })?;
 
         info!(
             log,
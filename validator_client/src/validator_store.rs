@@ -1,11 +1,14 @@
 use crate::{
     doppelganger_service::DoppelgangerService,
+    duty_history::DutyHistory,
+    ha_lease::HaLease,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
     Config,
 };
 use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
+use eth2::lighthouse_vc::types::ValidatorDefinitionsDiff;
 use parking_lot::{Mutex, RwLock};
 use slashing_protection::{
     interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
@@ -34,6 +37,7 @@ use crate::preparation_service::ProposalData;
 #[derive(Debug, PartialEq)]
 pub enum Error {
     DoppelgangerProtected(PublicKeyBytes),
+    HaStandby,
     UnknownToDoppelgangerService(PublicKeyBytes),
     UnknownPubkey(PublicKeyBytes),
     Slashable(NotSafe),
@@ -93,6 +97,8 @@ pub struct ValidatorStore<T, E: EthSpec> {
     spec: Arc<ChainSpec>,
     log: Logger,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
+    ha_lease: Option<Arc<HaLease>>,
+    duty_history: DutyHistory,
     slot_clock: T,
     fee_recipient_process: Option<Address>,
     gas_limit: Option<u64>,
@@ -114,6 +120,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         genesis_validators_root: Hash256,
         spec: ChainSpec,
         doppelganger_service: Option<Arc<DoppelgangerService>>,
+        ha_lease: Option<Arc<HaLease>>,
         slot_clock: T,
         config: &Config,
         task_executor: TaskExecutor,
@@ -127,6 +134,8 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             spec: Arc::new(spec),
             log,
             doppelganger_service,
+            ha_lease,
+            duty_history: DutyHistory::new(E::slots_per_epoch()),
             slot_clock,
             fee_recipient_process: config.fee_recipient,
             gas_limit: config.gas_limit,
@@ -162,6 +171,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.clone()
     }
 
+    /// Returns the history of duties attempted by each validator.
+    pub fn duty_history(&self) -> &DutyHistory {
+        &self.duty_history
+    }
+
     /// Indicates if the `voting_public_key` exists in self and is enabled.
     pub fn has_validator(&self, voting_public_key: &PublicKeyBytes) -> bool {
         self.validators
@@ -235,6 +249,58 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(validator_def)
     }
 
+    /// Re-read the validator definitions file and apply any changes made to it since it was last
+    /// read or written by this validator client.
+    ///
+    /// Validators which start performing duties are registered with the slashing protection
+    /// database and the doppelganger service before they are enabled.
+    ///
+    /// The validators lock is held across the initialization of new validators, so this must be
+    /// driven from a blocking thread (e.g. with `Handle::block_on`) rather than the async executor.
+    #[allow(clippy::await_holding_lock)]
+    pub async fn reload_validator_definitions(&self) -> Result<ValidatorDefinitionsDiff, String> {
+        let mut validators = self.validators.write();
+
+        let definitions = validators
+            .read_definitions_from_disk()
+            .map_err(|e| format!("Unable to read validator definitions: {:?}", e))?;
+        let diff = validators
+            .diff_definitions(&definitions)
+            .map_err(|e| format!("Invalid validator definitions: {:?}", e))?;
+
+        if diff.is_empty() {
+            return Ok(diff);
+        }
+
+        for pubkey in &diff.enabled {
+            self.slashing_protection
+                .register_validator(*pubkey)
+                .map_err(|e| format!("failed to register validator: {:?}", e))?;
+
+            if let Some(doppelganger_service) = &self.doppelganger_service {
+                doppelganger_service.register_new_validator::<E, _>(*pubkey, &self.slot_clock)?;
+            }
+        }
+
+        validators
+            .apply_definitions(definitions, &diff)
+            .await
+            .map_err(|e| format!("Unable to apply validator definitions: {:?}", e))?;
+        drop(validators);
+
+        info!(
+            self.log,
+            "Reloaded validator definitions";
+            "added" => ?diff.added,
+            "removed" => ?diff.removed,
+            "enabled" => ?diff.enabled,
+            "disabled" => ?diff.disabled,
+            "updated" => ?diff.updated,
+        );
+
+        Ok(diff)
+    }
+
     /// Returns `ProposalData` for the provided `pubkey` if it exists in `InitializedValidators`.
     /// `ProposalData` fields include defaulting logic described in `get_fee_recipient_defaulting`,
     /// `get_gas_limit_defaulting`, and `get_builder_proposals_defaulting`.
@@ -288,13 +354,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
         pubkeys
             .into_iter()
-            .map(|pubkey| {
-                self.doppelganger_service
-                    .as_ref()
-                    .map(|doppelganger_service| doppelganger_service.validator_status(pubkey))
-                    // Allow signing on all pubkeys if doppelganger protection is disabled.
-                    .unwrap_or_else(|| DoppelgangerStatus::SigningEnabled(pubkey))
-            })
+            .map(|pubkey| self.signing_status(pubkey))
             .filter_map(filter_func)
             .collect()
     }
@@ -313,29 +373,35 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
         pubkeys
             .into_iter()
-            .map(|pubkey| {
-                self.doppelganger_service
-                    .as_ref()
-                    .map(|doppelganger_service| doppelganger_service.validator_status(pubkey))
-                    // Allow signing on all pubkeys if doppelganger protection is disabled.
-                    .unwrap_or_else(|| DoppelgangerStatus::SigningEnabled(pubkey))
-            })
+            .map(|pubkey| self.signing_status(pubkey))
             .collect()
     }
 
-    /// Check if the `validator_pubkey` is permitted by the doppleganger protection to sign
-    /// messages.
-    pub fn doppelganger_protection_allows_signing(&self, validator_pubkey: PublicKeyBytes) -> bool {
+    /// Returns the doppelganger status of `pubkey`, treating every validator as not yet safe to
+    /// sign whilst this instance is on high availability standby.
+    fn signing_status(&self, pubkey: PublicKeyBytes) -> DoppelgangerStatus {
+        if !self.ha_lease_allows_signing() {
+            return DoppelgangerStatus::SigningDisabled(pubkey);
+        }
+
         self.doppelganger_service
             .as_ref()
-            // If there's no doppelganger service then we assume it is purposefully disabled and
-            // declare that all keys are safe with regard to it.
-            .map_or(true, |doppelganger_service| {
-                doppelganger_service
-                    .validator_status(validator_pubkey)
-                    .only_safe()
-                    .is_some()
-            })
+            .map(|doppelganger_service| doppelganger_service.validator_status(pubkey))
+            // Allow signing on all pubkeys if doppelganger protection is disabled.
+            .unwrap_or_else(|| DoppelgangerStatus::SigningEnabled(pubkey))
+    }
+
+    /// Returns `false` if high availability is enabled and this instance does not hold the lease.
+    pub fn ha_lease_allows_signing(&self) -> bool {
+        self.ha_lease
+            .as_ref()
+            .map_or(true, |ha_lease| ha_lease.is_active())
+    }
+
+    /// Check if the `validator_pubkey` is permitted by the doppleganger protection (and the high
+    /// availability lease, if enabled) to sign messages.
+    pub fn doppelganger_protection_allows_signing(&self, validator_pubkey: PublicKeyBytes) -> bool {
+        self.signing_status(validator_pubkey).only_safe().is_some()
     }
 
     pub fn num_voting_validators(&self) -> usize {
@@ -645,6 +711,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::UNREGISTERED]);
                 Err(Error::Slashable(NotSafe::UnregisteredValidator(pk)))
             }
+            Err(NotSafe::LeaseNotHeld(lease)) => {
+                warn!(
+                    self.log,
+                    "Not signing block without high availability lease";
+                    "lease" => ?lease,
+                );
+                metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::LEASE_NOT_HELD]);
+                Err(Error::Slashable(NotSafe::LeaseNotHeld(lease)))
+            }
             Err(e) => {
                 crit!(
                     self.log,
@@ -726,6 +801,18 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 );
                 Err(Error::Slashable(NotSafe::UnregisteredValidator(pk)))
             }
+            Err(NotSafe::LeaseNotHeld(lease)) => {
+                warn!(
+                    self.log,
+                    "Not signing attestation without high availability lease";
+                    "lease" => ?lease,
+                );
+                metrics::inc_counter_vec(
+                    &metrics::SIGNED_ATTESTATIONS_TOTAL,
+                    &[metrics::LEASE_NOT_HELD],
+                );
+                Err(Error::Slashable(NotSafe::LeaseNotHeld(lease)))
+            }
             Err(e) => {
                 crit!(
                     self.log,
@@ -914,6 +1001,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let signing_context = self.signing_context(Domain::SyncCommittee, signing_epoch);
 
         // Bypass `with_validator_signing_method`: sync committee messages are not slashable.
+        // They are still only produced by the active high availability instance.
+        if !self.ha_lease_allows_signing() {
+            return Err(Error::HaStandby);
+        }
         let signing_method = self.doppelganger_bypassed_signing_method(*validator_pubkey)?;
 
         let signature = signing_method
@@ -953,6 +1044,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let signing_context = self.signing_context(Domain::ContributionAndProof, signing_epoch);
 
         // Bypass `with_validator_signing_method`: sync committee messages are not slashable.
+        // They are still only produced by the active high availability instance.
+        if !self.ha_lease_allows_signing() {
+            return Err(Error::HaStandby);
+        }
         let signing_method = self.doppelganger_bypassed_signing_method(aggregator_pubkey)?;
 
         let message = ContributionAndProof {
@@ -0,0 +1,409 @@
+use super::common::*;
+use crate::DumpConfig;
+use account_utils::read_mnemonic_from_cli;
+use clap::{App, Arg, ArgMatches};
+use eth2::{
+    types::{StateId, ValidatorId},
+    SensitiveUrl,
+};
+use eth2_keystore::keypair_from_secret;
+use eth2_wallet::{bip39::Seed, recover_validator_secret_from_mnemonic, KeyType};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use types::*;
+
+pub const CMD: &str = "bls-change";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const VALIDATOR_INDICES_FLAG: &str = "validator-indices";
+pub const EXECUTION_ADDRESS_FLAG: &str = "execution-address";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const GENESIS_VALIDATORS_ROOT_FLAG: &str = "genesis-validators-root";
+pub const PUBLISH_FLAG: &str = "publish";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates messages which change the withdrawal credentials of validators from a BLS \
+                withdrawal key to an execution address. The BLS withdrawal keys are derived from \
+                a BIP-39 mnemonic. The signed messages can be written to a file and/or published \
+                to a beacon node.",
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("If present, the mnemonic will be read in from this file.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help(
+                    "The EIP-2334 derivation index of the first validator in \
+                    --validator-indices. The following validators are assumed to use \
+                    consecutive derivation indices.",
+                )
+                .default_value("0")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_INDICES_FLAG)
+                .long(VALIDATOR_INDICES_FLAG)
+                .value_name("STRING")
+                .help(
+                    "A comma-separated list of the beacon chain indices of the validators, \
+                    ordered by derivation index.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXECUTION_ADDRESS_FLAG)
+                .long(EXECUTION_ADDRESS_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help(
+                    "The execution address to which withdrawals will be sent. This cannot \
+                    be changed once the message is included in the beacon chain.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a beacon node using the beacon-API. If this value is \
+                    provided, the withdrawal credentials of each validator are checked against \
+                    the keys derived from the mnemonic before any message is created.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(GENESIS_VALIDATORS_ROOT_FLAG)
+                .long(GENESIS_VALIDATORS_ROOT_FLAG)
+                .value_name("HASH")
+                .help(
+                    "The genesis validators root of the network, used to create messages \
+                    without a beacon node.",
+                )
+                .required_unless(BEACON_NODE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PUBLISH_FLAG)
+                .long(PUBLISH_FLAG)
+                .help("If present, publish the signed messages to the beacon node.")
+                .requires(BEACON_NODE_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("PATH")
+                .help(
+                    "If present, the signed messages are written to a new JSON file at this \
+                    path. The file must not already exist.",
+                )
+                .takes_value(true),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BlsChangeConfig {
+    pub mnemonic_path: Option<PathBuf>,
+    pub stdin_inputs: bool,
+    pub first_index: u32,
+    pub validator_indices: Vec<u64>,
+    pub execution_address: Address,
+    pub bn_url: Option<SensitiveUrl>,
+    pub genesis_validators_root: Option<Hash256>,
+    pub publish: bool,
+    pub output_path: Option<PathBuf>,
+}
+
+impl BlsChangeConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let publish = matches.is_present(PUBLISH_FLAG);
+        let output_path = clap_utils::parse_optional(matches, OUTPUT_PATH_FLAG)?;
+        if !publish && output_path.is_none() {
+            return Err(format!(
+                "Must supply --{PUBLISH_FLAG} and/or --{OUTPUT_PATH_FLAG}."
+            ));
+        }
+
+        let validator_indices = matches
+            .value_of(VALIDATOR_INDICES_FLAG)
+            .ok_or_else(|| format!("--{VALIDATOR_INDICES_FLAG} is required"))?
+            .split(',')
+            .map(|index| {
+                index
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid validator index {}: {:?}", index, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            mnemonic_path: clap_utils::parse_optional(matches, MNEMONIC_FLAG)?,
+            stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
+            first_index: clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?,
+            validator_indices,
+            execution_address: clap_utils::parse_required(matches, EXECUTION_ADDRESS_FLAG)?,
+            bn_url: clap_utils::parse_optional(matches, BEACON_NODE_FLAG)?,
+            genesis_validators_root: clap_utils::parse_optional(
+                matches,
+                GENESIS_VALIDATORS_ROOT_FLAG,
+            )?,
+            publish,
+            output_path,
+        })
+    }
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    spec: &ChainSpec,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = BlsChangeConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config, spec).await
+    }
+}
+
+async fn run(config: BlsChangeConfig, spec: &ChainSpec) -> Result<(), String> {
+    let BlsChangeConfig {
+        mnemonic_path,
+        stdin_inputs,
+        first_index,
+        validator_indices,
+        execution_address,
+        bn_url,
+        genesis_validators_root,
+        publish,
+        output_path,
+    } = config;
+
+    if let Some(output_path) = &output_path {
+        if output_path.exists() {
+            return Err(format!("{:?} already exists", output_path));
+        }
+    }
+
+    let beacon_node = if let Some(bn_url) = bn_url {
+        Some(bn_http_client(bn_url, spec).await?)
+    } else {
+        None
+    };
+
+    let genesis_validators_root = match (&beacon_node, genesis_validators_root) {
+        (Some((_, genesis)), Some(root)) if genesis.genesis_validators_root != root => {
+            return Err(format!(
+                "--{} does not match the genesis validators root of the beacon node",
+                GENESIS_VALIDATORS_ROOT_FLAG
+            ))
+        }
+        (Some((_, genesis)), _) => genesis.genesis_validators_root,
+        (None, Some(root)) => root,
+        (None, None) => {
+            return Err(format!(
+                "Must supply either --{BEACON_NODE_FLAG} or --{GENESIS_VALIDATORS_ROOT_FLAG}."
+            ))
+        }
+    };
+
+    if beacon_node.is_none() {
+        eprintln!(
+            "WARNING: no beacon node was supplied, the withdrawal credentials of the validators \
+            cannot be checked against the mnemonic."
+        );
+    }
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    let mut changes = Vec::with_capacity(validator_indices.len());
+    for (derivation_index, validator_index) in (first_index..).zip(validator_indices) {
+        let (secret, _) = recover_validator_secret_from_mnemonic(
+            seed.as_bytes(),
+            derivation_index,
+            KeyType::Withdrawal,
+        )
+        .map_err(|e| format!("Unable to recover withdrawal key: {:?}", e))?;
+        let keypair = keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable to recover withdrawal key: {:?}", e))?;
+
+        // Check that the derived key controls the withdrawal credentials of the validator.
+        if let Some((bn_http_client, _)) = &beacon_node {
+            let validator = bn_http_client
+                .get_beacon_states_validator_id(StateId::Head, &ValidatorId::Index(validator_index))
+                .await
+                .map_err(|e| format!("Failed to get validator {}: {:?}", validator_index, e))?
+                .ok_or_else(|| {
+                    format!(
+                        "Validator {} is not known to the beacon node",
+                        validator_index
+                    )
+                })?
+                .data
+                .validator;
+            let expected_credentials: Hash256 =
+                WithdrawalCredentials::bls(&keypair.pk, spec).into();
+            if validator.withdrawal_credentials != expected_credentials {
+                return Err(format!(
+                    "The withdrawal credentials of validator {} do not match the withdrawal key \
+                    at derivation index {}. The credentials may already have been changed, or \
+                    --{} may be incorrect.",
+                    validator_index, derivation_index, FIRST_INDEX_FLAG
+                ));
+            }
+        }
+
+        let change = BlsToExecutionChange {
+            validator_index,
+            from_bls_pubkey: keypair.pk.compress(),
+            to_execution_address: execution_address,
+        };
+        changes.push(change.sign(&keypair.sk, genesis_validators_root, spec));
+        eprintln!(
+            "Created BLS to execution change for validator {} at derivation index {}",
+            validator_index, derivation_index
+        );
+    }
+
+    if let Some(output_path) = &output_path {
+        write_to_json_file(output_path, &changes)?;
+    }
+
+    if publish {
+        let (bn_http_client, _) = beacon_node
+            .as_ref()
+            .ok_or_else(|| format!("--{} is required with --{}", BEACON_NODE_FLAG, PUBLISH_FLAG))?;
+        bn_http_client
+            .post_beacon_pool_bls_to_execution_changes(&changes)
+            .await
+            .map_err(|e| format!("Failed to publish BLS to execution changes: {:?}", e))?;
+        eprintln!("Published {} BLS to execution changes", changes.len());
+    }
+
+    Ok(())
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth2_wallet::bip39::{Language, Mnemonic};
+    use std::fs;
+    use tempfile::{tempdir, TempDir};
+
+    type E = MainnetEthSpec;
+
+    const MNEMONIC: &str = "test test test test test test test test test test test waste";
+    const CHANGES_FILE_NAME: &str = "bls_changes.json";
+    const EXECUTION_ADDRESS: Address = Address::repeat_byte(7);
+    const GENESIS_VALIDATORS_ROOT: Hash256 = Hash256::repeat_byte(42);
+
+    fn bls_change_config(dir: &TempDir) -> BlsChangeConfig {
+        let mnemonic_path = dir.path().join("mnemonic");
+        fs::write(&mnemonic_path, MNEMONIC).unwrap();
+
+        BlsChangeConfig {
+            mnemonic_path: Some(mnemonic_path),
+            stdin_inputs: false,
+            first_index: 0,
+            validator_indices: vec![],
+            execution_address: EXECUTION_ADDRESS,
+            bn_url: None,
+            genesis_validators_root: Some(GENESIS_VALIDATORS_ROOT),
+            publish: false,
+            output_path: Some(dir.path().join(CHANGES_FILE_NAME)),
+        }
+    }
+
+    fn withdrawal_pubkey(derivation_index: u32) -> PublicKeyBytes {
+        let mnemonic = Mnemonic::from_phrase(MNEMONIC, Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "");
+        let (secret, _) = recover_validator_secret_from_mnemonic(
+            seed.as_bytes(),
+            derivation_index,
+            KeyType::Withdrawal,
+        )
+        .unwrap();
+        keypair_from_secret(secret.as_bytes())
+            .unwrap()
+            .pk
+            .compress()
+    }
+
+    #[tokio::test]
+    async fn sign_changes_offline() {
+        let dir = tempdir().unwrap();
+        let mut config = bls_change_config(&dir);
+        config.first_index = 3;
+        config.validator_indices = vec![11, 7];
+
+        let spec = E::default_spec();
+        run(config.clone(), &spec).await.unwrap();
+
+        let changes: Vec<SignedBlsToExecutionChange> =
+            serde_json::from_str(&fs::read_to_string(config.output_path.unwrap()).unwrap())
+                .unwrap();
+        assert_eq!(changes.len(), 2);
+
+        let domain = spec.compute_domain(
+            Domain::BlsToExecutionChange,
+            spec.genesis_fork_version,
+            GENESIS_VALIDATORS_ROOT,
+        );
+        for (change, (validator_index, derivation_index)) in changes.iter().zip([(11, 3), (7, 4)]) {
+            let expected_pubkey = withdrawal_pubkey(derivation_index);
+            assert_eq!(change.message.validator_index, validator_index);
+            assert_eq!(change.message.from_bls_pubkey, expected_pubkey);
+            assert_eq!(change.message.to_execution_address, EXECUTION_ADDRESS);
+            assert!(change.signature.verify(
+                &expected_pubkey.decompress().unwrap(),
+                change.message.signing_root(domain)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn output_path_exists() {
+        let dir = tempdir().unwrap();
+        let mut config = bls_change_config(&dir);
+        config.validator_indices = vec![0];
+        fs::write(config.output_path.as_ref().unwrap(), "[]").unwrap();
+
+        let result = run(config, &E::default_spec()).await;
+        assert!(result.unwrap_err().contains("already exists"));
+    }
+
+    #[tokio::test]
+    async fn genesis_validators_root_required_without_beacon_node() {
+        let dir = tempdir().unwrap();
+        let mut config = bls_change_config(&dir);
+        config.validator_indices = vec![0];
+        config.genesis_validators_root = None;
+
+        let result = run(config.clone(), &E::default_spec()).await;
+        assert!(result
+            .unwrap_err()
+            .contains(&format!("--{GENESIS_VALIDATORS_ROOT_FLAG}")));
+        assert!(!config.output_path.unwrap().exists());
+    }
+}
@@ -6,11 +6,14 @@ use eth2::{
         std_types::{ImportKeystoreStatus, ImportKeystoresRequest, SingleKeystoreResponse, Status},
         types::UpdateFeeRecipientRequest,
     },
-    SensitiveUrl,
+    types::GenesisData,
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
 };
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 use tree_hash::TreeHash;
 use types::*;
 
@@ -18,6 +21,8 @@ pub const IGNORE_DUPLICATES_FLAG: &str = "ignore-duplicates";
 pub const STDIN_INPUTS_FLAG: &str = "stdin-inputs";
 pub const COUNT_FLAG: &str = "count";
 
+const BEACON_NODE_HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
 /// When the `ethereum/staking-deposit-cli` tool generates deposit data JSON, it adds a
 /// `deposit_cli_version` to protect the web-based "Launchpad" tool against a breaking change that
 /// was introduced in `ethereum/staking-deposit-cli`. Lighthouse don't really have a version that it
@@ -349,6 +354,120 @@ pub async fn vc_http_client<P: AsRef<Path>>(
     Ok((http_client, remote_keystores))
 }
 
+/// The validators on a validator client which are the subject of a command.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ValidatorSelection {
+    /// All validators with a keystore on the validator client.
+    All,
+    Specific(Vec<PublicKeyBytes>),
+    /// The validators in a JSON file, as produced by the `create` command.
+    File(PathBuf),
+}
+
+impl ValidatorSelection {
+    /// Parses a selection from either a list of pubkeys (or "all") or a path to a JSON file.
+    pub fn from_cli(
+        validators: Option<&str>,
+        validators_file: Option<PathBuf>,
+        validators_flag: &str,
+        validators_file_flag: &str,
+    ) -> Result<Self, String> {
+        match (validators, validators_file) {
+            (Some("all"), None) => Ok(ValidatorSelection::All),
+            (Some(pubkeys), None) => pubkeys
+                .split(',')
+                .map(PublicKeyBytes::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map(ValidatorSelection::Specific),
+            (None, Some(path)) => Ok(ValidatorSelection::File(path)),
+            (None, None) => Err(format!(
+                "Must supply either --{validators_flag} or --{validators_file_flag}."
+            )),
+            (Some(_), Some(_)) => Err(format!(
+                "Cannot supply both --{validators_flag} and --{validators_file_flag}."
+            )),
+        }
+    }
+
+    /// Returns the pubkeys of the selected validators, ensuring that each of them is known to the
+    /// validator client.
+    pub fn pubkeys(
+        &self,
+        remote_keystores: &[SingleKeystoreResponse],
+    ) -> Result<Vec<PublicKeyBytes>, String> {
+        let pubkeys = match self {
+            ValidatorSelection::All => {
+                return Ok(remote_keystores
+                    .iter()
+                    .map(|keystore| keystore.validating_pubkey)
+                    .collect())
+            }
+            ValidatorSelection::Specific(pubkeys) => pubkeys.clone(),
+            ValidatorSelection::File(path) => read_validators_file(path)?
+                .iter()
+                .map(|validator| {
+                    validator
+                        .voting_keystore
+                        .public_key()
+                        .map(Into::into)
+                        .ok_or_else(|| format!("Validator in {:?} has an invalid public key", path))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        for pubkey in &pubkeys {
+            if !remote_keystores
+                .iter()
+                .any(|keystore| keystore.validating_pubkey == *pubkey)
+            {
+                return Err(format!(
+                    "Validator {:?} is not present on the validator client",
+                    pubkey
+                ));
+            }
+        }
+
+        Ok(pubkeys)
+    }
+}
+
+/// Read a list of validators from a JSON file, as produced by the `create` command.
+pub fn read_validators_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<ValidatorSpecification>, String> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(format!("Unable to find file at {:?}", path));
+    }
+
+    let validators_file = fs::OpenOptions::new()
+        .read(true)
+        .create(false)
+        .open(path)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+    serde_json::from_reader(&validators_file)
+        .map_err(|e| format!("Unable to parse JSON in {:?}: {:?}", path, e))
+}
+
+/// Connects to a beacon node, returning an error if it is on a different network to `spec`.
+pub async fn bn_http_client(
+    url: SensitiveUrl,
+    spec: &ChainSpec,
+) -> Result<(BeaconNodeHttpClient, GenesisData), String> {
+    let http_client = BeaconNodeHttpClient::new(url, Timeouts::set_all(BEACON_NODE_HTTP_TIMEOUT));
+
+    let genesis = http_client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Failed to get genesis from beacon node: {:?}", e))?
+        .data;
+    if genesis.genesis_fork_version != spec.genesis_fork_version {
+        return Err("Beacon node appears to be on the wrong network".to_string());
+    }
+
+    Ok((http_client, genesis))
+}
+
 /// Write some object to a file as JSON.
 ///
 /// The file must be created new, it must not already exist.
@@ -0,0 +1,632 @@
+use super::common::*;
+use crate::DumpConfig;
+use account_utils::read_input_from_user;
+use clap::{App, Arg, ArgMatches};
+use eth2::{
+    types::{GenesisData, StateId, ValidatorId},
+    BeaconNodeHttpClient, SensitiveUrl,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use types::*;
+
+pub const CMD: &str = "exit";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const VALIDATORS_FILE_FLAG: &str = "validators-file";
+pub const EXIT_EPOCH_FLAG: &str = "exit-epoch";
+pub const VALIDATOR_INDICES_FLAG: &str = "validator-indices";
+pub const GENESIS_VALIDATORS_ROOT_FLAG: &str = "genesis-validators-root";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const PUBLISH_FLAG: &str = "publish";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+pub const NO_CONFIRMATION_FLAG: &str = "no-confirmation";
+
+pub const CONFIRMATION_PHRASE: &str = "Exit my validator";
+pub const WEBSITE_URL: &str = "https://lighthouse-book.sigmaprime.io/voluntary-exit.html";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Signs voluntary exits for validators. Exits are either signed by a validator \
+                client using the HTTP API, or signed locally using the keystores in a JSON file \
+                which can be generated using the \"create-validators\" command. The signed exits \
+                can be written to a file and/or published to a beacon node.",
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a validator client using the keymanager-API. \
+                    The validator client signs the exits of the validators selected with \
+                    --validators.",
+                )
+                .requires(VC_TOKEN_FLAG)
+                .conflicts_with(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators to be exited by the validator client. Either a list of \
+                    0x-prefixed validator pubkeys or the keyword \"all\".",
+                )
+                .requires(VC_URL_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FILE_FLAG)
+                .long(VALIDATORS_FILE_FLAG)
+                .value_name("PATH_TO_JSON_FILE")
+                .help(
+                    "The path to a JSON file containing a list of validators to be exited. \
+                    This file is usually named \"validators.json\". The exits are signed \
+                    locally using the keystores in the file, without a validator client. \
+                    Requires either --beacon-node, or --validator-indices, \
+                    --genesis-validators-root and --exit-epoch to sign the exits offline.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_INDICES_FLAG)
+                .long(VALIDATOR_INDICES_FLAG)
+                .value_name("STRING")
+                .help(
+                    "A comma-separated list of the beacon chain indices of the validators in \
+                    --validators-file, in the same order as the file. If a beacon node is \
+                    provided, the indices are checked against it.",
+                )
+                .requires(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(GENESIS_VALIDATORS_ROOT_FLAG)
+                .long(GENESIS_VALIDATORS_ROOT_FLAG)
+                .value_name("HASH")
+                .help(
+                    "The genesis validators root of the network, used to sign exits without a \
+                    beacon node. The fork version is taken from the network configuration.",
+                )
+                .requires(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXIT_EPOCH_FLAG)
+                .long(EXIT_EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch of the voluntary exits. Defaults to the current epoch. \
+                    Exits signed for a future epoch cannot be published until that epoch.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a beacon node using the beacon-API.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PUBLISH_FLAG)
+                .long(PUBLISH_FLAG)
+                .help("If present, publish the signed exits to the beacon node.")
+                .requires(BEACON_NODE_FLAG)
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("PATH")
+                .help(
+                    "If present, the signed exits are written to a new JSON file at this path. \
+                    The file must not already exist.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(NO_CONFIRMATION_FLAG)
+                .long(NO_CONFIRMATION_FLAG)
+                .help(
+                    "Publishes the exits without prompting for confirmation that you understand \
+                    the implications of a voluntary exit. This should be used with caution.",
+                ),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExitConfig {
+    pub vc_url: Option<SensitiveUrl>,
+    pub vc_token_path: Option<PathBuf>,
+    pub validators: ValidatorSelection,
+    pub exit_epoch: Option<Epoch>,
+    pub validator_indices: Option<Vec<u64>>,
+    pub genesis_validators_root: Option<Hash256>,
+    pub bn_url: Option<SensitiveUrl>,
+    pub publish: bool,
+    pub output_path: Option<PathBuf>,
+    pub no_confirmation: bool,
+    pub stdin_inputs: bool,
+}
+
+impl ExitConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let publish = matches.is_present(PUBLISH_FLAG);
+        let output_path = clap_utils::parse_optional(matches, OUTPUT_PATH_FLAG)?;
+        if !publish && output_path.is_none() {
+            return Err(format!(
+                "Must supply --{PUBLISH_FLAG} and/or --{OUTPUT_PATH_FLAG}."
+            ));
+        }
+
+        let validators = ValidatorSelection::from_cli(
+            matches.value_of(VALIDATORS_FLAG),
+            clap_utils::parse_optional(matches, VALIDATORS_FILE_FLAG)?,
+            VALIDATORS_FLAG,
+            VALIDATORS_FILE_FLAG,
+        )?;
+        let exit_epoch = clap_utils::parse_optional(matches, EXIT_EPOCH_FLAG)?;
+        let validator_indices = matches
+            .value_of(VALIDATOR_INDICES_FLAG)
+            .map(|indices| {
+                indices
+                    .split(',')
+                    .map(|index| {
+                        index
+                            .trim()
+                            .parse::<u64>()
+                            .map_err(|e| format!("Invalid validator index {}: {:?}", index, e))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let genesis_validators_root =
+            clap_utils::parse_optional(matches, GENESIS_VALIDATORS_ROOT_FLAG)?;
+        let bn_url = clap_utils::parse_optional(matches, BEACON_NODE_FLAG)?;
+
+        let offline = validator_indices.is_some()
+            && genesis_validators_root.is_some()
+            && exit_epoch.is_some();
+        if matches!(validators, ValidatorSelection::File(_)) && bn_url.is_none() && !offline {
+            return Err(format!(
+                "Must supply either --{BEACON_NODE_FLAG}, or --{VALIDATOR_INDICES_FLAG}, \
+                --{GENESIS_VALIDATORS_ROOT_FLAG} and --{EXIT_EPOCH_FLAG} with \
+                --{VALIDATORS_FILE_FLAG}."
+            ));
+        }
+
+        Ok(Self {
+            vc_url: clap_utils::parse_optional(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_optional(matches, VC_TOKEN_FLAG)?,
+            validators,
+            exit_epoch,
+            validator_indices,
+            genesis_validators_root,
+            bn_url,
+            publish,
+            output_path,
+            no_confirmation: matches.is_present(NO_CONFIRMATION_FLAG),
+            stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
+        })
+    }
+}
+
+pub async fn cli_run<'a, T: EthSpec>(
+    matches: &'a ArgMatches<'a>,
+    spec: &ChainSpec,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = ExitConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run::<T>(config, spec).await
+    }
+}
+
+async fn run<T: EthSpec>(config: ExitConfig, spec: &ChainSpec) -> Result<(), String> {
+    let ExitConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        exit_epoch,
+        validator_indices,
+        genesis_validators_root,
+        bn_url,
+        publish,
+        output_path,
+        no_confirmation,
+        stdin_inputs,
+    } = config;
+
+    if let Some(output_path) = &output_path {
+        if output_path.exists() {
+            return Err(format!("{:?} already exists", output_path));
+        }
+    }
+
+    let beacon_node = if let Some(bn_url) = bn_url {
+        Some(bn_http_client(bn_url, spec).await?)
+    } else {
+        None
+    };
+
+    let signed_exits = if let ValidatorSelection::File(path) = &validators {
+        sign_exits_locally::<T>(
+            path,
+            exit_epoch,
+            validator_indices,
+            genesis_validators_root,
+            beacon_node.as_ref(),
+            spec,
+        )
+        .await?
+    } else {
+        let vc_url = vc_url
+            .ok_or_else(|| format!("--{} is required with --{}", VC_URL_FLAG, VALIDATORS_FLAG))?;
+        let vc_token_path = vc_token_path
+            .ok_or_else(|| format!("--{} is required with --{}", VC_TOKEN_FLAG, VC_URL_FLAG))?;
+        let (http_client, remote_keystores) = vc_http_client(vc_url, &vc_token_path).await?;
+        let pubkeys = validators.pubkeys(&remote_keystores)?;
+
+        eprintln!("Requesting {} exits from the VC", pubkeys.len());
+
+        let mut signed_exits = Vec::with_capacity(pubkeys.len());
+        for pubkey in pubkeys {
+            let signed_exit = http_client
+                .post_validator_voluntary_exit(&pubkey, exit_epoch)
+                .await
+                .map_err(|e| format!("Failed to sign exit for {:?}: {:?}", pubkey, e))?
+                .data;
+            signed_exits.push(signed_exit);
+        }
+        signed_exits
+    };
+
+    if let Some(output_path) = &output_path {
+        write_to_json_file(output_path, &signed_exits)?;
+    }
+
+    if publish {
+        let (bn_http_client, _) = beacon_node
+            .as_ref()
+            .ok_or_else(|| format!("--{} is required with --{}", BEACON_NODE_FLAG, PUBLISH_FLAG))?;
+
+        eprintln!(
+            "Publishing voluntary exits for {} validators\n",
+            signed_exits.len()
+        );
+        if !no_confirmation {
+            eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
+            eprintln!(
+                "PLEASE VISIT {} TO MAKE SURE YOU UNDERSTAND THE IMPLICATIONS OF A VOLUNTARY EXIT.",
+                WEBSITE_URL
+            );
+            eprintln!("Enter the exit phrase from the above URL to confirm the voluntary exits: ");
+            if read_input_from_user(stdin_inputs)? != CONFIRMATION_PHRASE {
+                return Err("Incorrect exit phrase, no exits have been published.".to_string());
+            }
+        }
+
+        let count = signed_exits.len();
+        let mut failures = 0;
+        for (i, signed_exit) in signed_exits.iter().enumerate() {
+            match bn_http_client
+                .post_beacon_pool_voluntary_exits(signed_exit)
+                .await
+            {
+                Ok(()) => eprintln!(
+                    "Published exit {} of {} for validator {}",
+                    i + 1,
+                    count,
+                    signed_exit.message.validator_index
+                ),
+                Err(e) => {
+                    failures += 1;
+                    eprintln!(
+                        "Failed to publish exit {} of {} for validator {}: {:?}",
+                        i + 1,
+                        count,
+                        signed_exit.message.validator_index,
+                        e
+                    );
+                }
+            }
+        }
+
+        if failures > 0 {
+            return Err(format!("Failed to publish {} of {} exits", failures, count));
+        }
+    }
+
+    Ok(())
+}
+
+/// Sign exits using the keystores in a `validators.json` file.
+///
+/// The indices of the validators, the genesis validators root and the exit epoch are taken from
+/// the beacon node unless they are supplied, in which case no beacon node is required.
+async fn sign_exits_locally<T: EthSpec>(
+    validators_file_path: &Path,
+    exit_epoch: Option<Epoch>,
+    validator_indices: Option<Vec<u64>>,
+    genesis_validators_root: Option<Hash256>,
+    beacon_node: Option<&(BeaconNodeHttpClient, GenesisData)>,
+    spec: &ChainSpec,
+) -> Result<Vec<SignedVoluntaryExit>, String> {
+    let validators = read_validators_file(validators_file_path)?;
+
+    if let Some(validator_indices) = &validator_indices {
+        if validator_indices.len() != validators.len() {
+            return Err(format!(
+                "--{} contains {} indices but there are {} validators in {:?}",
+                VALIDATOR_INDICES_FLAG,
+                validator_indices.len(),
+                validators.len(),
+                validators_file_path
+            ));
+        }
+    }
+
+    let genesis_validators_root = match (beacon_node, genesis_validators_root) {
+        (Some((_, genesis)), Some(root)) if genesis.genesis_validators_root != root => {
+            return Err(format!(
+                "--{} does not match the genesis validators root of the beacon node",
+                GENESIS_VALIDATORS_ROOT_FLAG
+            ))
+        }
+        (Some((_, genesis)), _) => genesis.genesis_validators_root,
+        (None, Some(root)) => root,
+        (None, None) => {
+            return Err(format!(
+                "Must supply either --{BEACON_NODE_FLAG} or --{GENESIS_VALIDATORS_ROOT_FLAG}."
+            ))
+        }
+    };
+
+    let exit_epoch = match (exit_epoch, beacon_node) {
+        (Some(epoch), _) => epoch,
+        (None, Some((bn_http_client, _))) => bn_http_client
+            .get_node_syncing()
+            .await
+            .map_err(|e| format!("Failed to get sync status from beacon node: {:?}", e))?
+            .data
+            .head_slot
+            .epoch(T::slots_per_epoch()),
+        (None, None) => {
+            return Err(format!(
+                "Must supply either --{BEACON_NODE_FLAG} or --{EXIT_EPOCH_FLAG}."
+            ))
+        }
+    };
+
+    eprintln!(
+        "Signing exits for {} validators at epoch {}. Each keystore may take several seconds \
+        to decrypt.",
+        validators.len(),
+        exit_epoch
+    );
+
+    let mut signed_exits = Vec::with_capacity(validators.len());
+    for (i, validator) in validators.iter().enumerate() {
+        let keypair = validator
+            .voting_keystore
+            .decrypt_keypair(validator.voting_keystore_password.as_ref())
+            .map_err(|e| format!("Failed to decrypt voting keystore {}: {:?}", i, e))?;
+        let pubkey = PublicKeyBytes::from(keypair.pk.clone());
+        let supplied_index = validator_indices.as_ref().map(|indices| indices[i]);
+
+        let validator_index = if let Some((bn_http_client, _)) = beacon_node {
+            let validator_data = bn_http_client
+                .get_beacon_states_validator_id(StateId::Head, &ValidatorId::PublicKey(pubkey))
+                .await
+                .map_err(|e| format!("Failed to get validator {:?}: {:?}", pubkey, e))?
+                .ok_or_else(|| format!("Validator {:?} is not known to the beacon node", pubkey))?
+                .data;
+            if validator_data.validator.exit_epoch != spec.far_future_epoch {
+                return Err(format!(
+                    "Validator {:?} has already exited at epoch {}",
+                    pubkey, validator_data.validator.exit_epoch
+                ));
+            }
+            if let Some(index) = supplied_index {
+                if index != validator_data.index {
+                    return Err(format!(
+                        "Validator {:?} has index {} on the beacon node, not {}",
+                        pubkey, validator_data.index, index
+                    ));
+                }
+            }
+            validator_data.index
+        } else {
+            supplied_index.ok_or_else(|| {
+                format!("Must supply either --{BEACON_NODE_FLAG} or --{VALIDATOR_INDICES_FLAG}.")
+            })?
+        };
+
+        let voluntary_exit = VoluntaryExit {
+            epoch: exit_epoch,
+            validator_index,
+        };
+        signed_exits.push(voluntary_exit.sign(&keypair.sk, genesis_validators_root, spec));
+    }
+
+    Ok(signed_exits)
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::create_validators::tests::TestBuilder as CreateTestBuilder;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+    use std::fs;
+    use tempfile::{tempdir, TempDir};
+
+    type E = MainnetEthSpec;
+
+    const EXITS_FILE_NAME: &str = "exits.json";
+    /// An epoch prior to Altair, so that exits are signed with the genesis fork version.
+    const EXIT_EPOCH: u64 = 42;
+    /// The genesis validators root used by the `ApiTester` VC.
+    const VC_GENESIS_VALIDATORS_ROOT: Hash256 = Hash256::repeat_byte(42);
+
+    fn exit_config(dir: &TempDir) -> ExitConfig {
+        ExitConfig {
+            vc_url: None,
+            vc_token_path: None,
+            validators: ValidatorSelection::All,
+            exit_epoch: Some(Epoch::new(EXIT_EPOCH)),
+            validator_indices: None,
+            genesis_validators_root: None,
+            bn_url: None,
+            publish: false,
+            output_path: Some(dir.path().join(EXITS_FILE_NAME)),
+            no_confirmation: false,
+            stdin_inputs: false,
+        }
+    }
+
+    /// Checks that the exits written to `path` are signed by the `expected` validators.
+    fn assert_exits(
+        path: &Path,
+        expected: &[(PublicKeyBytes, u64)],
+        genesis_validators_root: Hash256,
+        spec: &ChainSpec,
+    ) {
+        let exits: Vec<SignedVoluntaryExit> =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(exits.len(), expected.len());
+
+        let domain = spec.compute_domain(
+            Domain::VoluntaryExit,
+            spec.genesis_fork_version,
+            genesis_validators_root,
+        );
+        for (exit, (pubkey, index)) in exits.iter().zip(expected) {
+            assert_eq!(exit.message.validator_index, *index);
+            assert_eq!(exit.message.epoch, Epoch::new(EXIT_EPOCH));
+            assert!(exit.signature.verify(
+                &pubkey.decompress().unwrap(),
+                exit.message.signing_root(domain)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn exit_via_vc() {
+        let (import_builder, pubkeys) = ImportTestBuilder::new_with_validators(3).await;
+        // The VC learns the indices of its validators from a beacon node, so set them manually.
+        for (pubkey, index) in pubkeys.iter().zip(100..) {
+            import_builder
+                .vc
+                .initialized_validators
+                .write()
+                .set_index(pubkey, index);
+        }
+
+        let dir = tempdir().unwrap();
+        let mut config = exit_config(&dir);
+        config.vc_url = Some(import_builder.vc.url.clone());
+        config.vc_token_path = Some(import_builder.vc_token_path().into());
+        config.validators = ValidatorSelection::Specific(vec![pubkeys[0], pubkeys[2]]);
+
+        let spec = E::default_spec();
+        run::<E>(config.clone(), &spec).await.unwrap();
+        assert_exits(
+            &config.output_path.unwrap(),
+            &[(pubkeys[0], 100), (pubkeys[2], 102)],
+            VC_GENESIS_VALIDATORS_ROOT,
+            &spec,
+        );
+    }
+
+    #[tokio::test]
+    async fn exit_via_vc_without_validator_index() {
+        let (import_builder, _) = ImportTestBuilder::new_with_validators(1).await;
+
+        let dir = tempdir().unwrap();
+        let mut config = exit_config(&dir);
+        config.vc_url = Some(import_builder.vc.url.clone());
+        config.vc_token_path = Some(import_builder.vc_token_path().into());
+
+        let result = run::<E>(config.clone(), &E::default_spec()).await;
+        assert!(result.unwrap_err().contains("Failed to sign exit"));
+        assert!(!config.output_path.unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn exit_offline() {
+        let create_result = CreateTestBuilder::default()
+            .mutate_config(|config| config.count = 2)
+            .run_test()
+            .await;
+        assert!(
+            create_result.result.is_ok(),
+            "precondition: validators are created"
+        );
+        let pubkeys: Vec<PublicKeyBytes> = create_result
+            .validators()
+            .iter()
+            .map(|validator| validator.voting_keystore.public_key().unwrap().into())
+            .collect();
+
+        let dir = tempdir().unwrap();
+        let genesis_validators_root = Hash256::repeat_byte(7);
+        let mut config = exit_config(&dir);
+        config.validators = ValidatorSelection::File(create_result.validators_file_path());
+        config.validator_indices = Some(vec![7, 3]);
+        config.genesis_validators_root = Some(genesis_validators_root);
+
+        let spec = E::default_spec();
+        run::<E>(config.clone(), &spec).await.unwrap();
+        assert_exits(
+            &config.output_path.unwrap(),
+            &[(pubkeys[0], 7), (pubkeys[1], 3)],
+            genesis_validators_root,
+            &spec,
+        );
+    }
+
+    #[tokio::test]
+    async fn exit_offline_with_wrong_number_of_indices() {
+        let create_result = CreateTestBuilder::default()
+            .mutate_config(|config| config.count = 2)
+            .run_test()
+            .await;
+        assert!(
+            create_result.result.is_ok(),
+            "precondition: validators are created"
+        );
+
+        let dir = tempdir().unwrap();
+        let mut config = exit_config(&dir);
+        config.validators = ValidatorSelection::File(create_result.validators_file_path());
+        config.validator_indices = Some(vec![7]);
+        config.genesis_validators_root = Some(Hash256::repeat_byte(7));
+
+        let result = run::<E>(config, &E::default_spec()).await;
+        assert!(result
+            .unwrap_err()
+            .contains("--validator-indices contains 1 indices but there are 2 validators"));
+    }
+}
@@ -3,7 +3,6 @@ use crate::DumpConfig;
 use clap::{App, Arg, ArgMatches};
 use eth2::{lighthouse_vc::std_types::ImportKeystoreStatus, SensitiveUrl};
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
 
 pub const CMD: &str = "import";
@@ -106,22 +105,7 @@ async fn run<'a>(config: ImportConfig) -> Result<(), String> {
         ignore_duplicates,
     } = config;
 
-    if !validators_file_path.exists() {
-        return Err(format!("Unable to find file at {:?}", validators_file_path));
-    }
-
-    let validators_file = fs::OpenOptions::new()
-        .read(true)
-        .create(false)
-        .open(&validators_file_path)
-        .map_err(|e| format!("Unable to open {:?}: {:?}", validators_file_path, e))?;
-    let validators: Vec<ValidatorSpecification> = serde_json::from_reader(&validators_file)
-        .map_err(|e| {
-            format!(
-                "Unable to parse JSON in {:?}: {:?}",
-                validators_file_path, e
-            )
-        })?;
+    let validators = read_validators_file(&validators_file_path)?;
 
     let count = validators.len();
 
@@ -239,6 +223,7 @@ pub mod tests {
     use super::*;
     use crate::create_validators::tests::TestBuilder as CreateTestBuilder;
     use std::fs;
+    use std::path::Path;
     use tempfile::{tempdir, TempDir};
     use validator_client::http_api::{test_utils::ApiTester, Config as HttpConfig};
 
@@ -278,6 +263,40 @@ pub mod tests {
             }
         }
 
+        pub fn validators_file_path(&self) -> &Path {
+            &self.import_config.validators_file_path
+        }
+
+        pub fn vc_token_path(&self) -> &Path {
+            &self.import_config.vc_token_path
+        }
+
+        /// The pubkeys of the validators on the VC, in the order returned by the keymanager API.
+        pub async fn vc_pubkeys(&self) -> Vec<types::PublicKeyBytes> {
+            self.vc
+                .client
+                .get_keystores()
+                .await
+                .unwrap()
+                .data
+                .into_iter()
+                .map(|keystore| keystore.validating_pubkey)
+                .collect()
+        }
+
+        /// Returns a builder whose VC has imported `count` new validators, along with their public
+        /// keys.
+        pub async fn new_with_validators(count: u32) -> (Self, Vec<types::PublicKeyBytes>) {
+            let builder = Self::new()
+                .await
+                .create_validators(count, 0)
+                .await
+                .import_validators_without_checks()
+                .await;
+            let pubkeys = builder.vc_pubkeys().await;
+            (builder, pubkeys)
+        }
+
         pub fn mutate_import_config<F: Fn(&mut ImportConfig)>(mut self, func: F) -> Self {
             func(&mut self.import_config);
             self
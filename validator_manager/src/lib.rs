@@ -6,10 +6,14 @@ use serde::Serialize;
 use std::path::PathBuf;
 use types::EthSpec;
 
+pub mod bls_change;
 pub mod common;
 pub mod create_validators;
+pub mod exit_validators;
 pub mod import_validators;
 pub mod move_validators;
+pub mod set_fee_recipient;
+pub mod set_gas_limit;
 
 pub const CMD: &str = "validator_manager";
 
@@ -45,6 +49,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(create_validators::cli_app())
         .subcommand(import_validators::cli_app())
         .subcommand(move_validators::cli_app())
+        .subcommand(exit_validators::cli_app())
+        .subcommand(bls_change::cli_app())
+        .subcommand(set_fee_recipient::cli_app())
+        .subcommand(set_gas_limit::cli_app())
 }
 
 /// Run the account manager, returning an error if the operation did not succeed.
@@ -72,6 +80,18 @@ pub fn run<'a, T: EthSpec>(matches: &'a ArgMatches<'a>, env: Environment<T>) ->
                     (move_validators::CMD, Some(matches)) => {
                         move_validators::cli_run(matches, dump_config).await
                     }
+                    (exit_validators::CMD, Some(matches)) => {
+                        exit_validators::cli_run::<T>(matches, &spec, dump_config).await
+                    }
+                    (bls_change::CMD, Some(matches)) => {
+                        bls_change::cli_run(matches, &spec, dump_config).await
+                    }
+                    (set_fee_recipient::CMD, Some(matches)) => {
+                        set_fee_recipient::cli_run(matches, dump_config).await
+                    }
+                    (set_gas_limit::CMD, Some(matches)) => {
+                        set_gas_limit::cli_run(matches, dump_config).await
+                    }
                     ("", _) => Err("No command supplied. See --help.".to_string()),
                     (unknown, _) => Err(format!(
                         "{} is not a valid {} command. See --help.",
@@ -0,0 +1,256 @@
+use super::common::*;
+use crate::DumpConfig;
+use clap::{App, Arg, ArgMatches};
+use eth2::{lighthouse_vc::types::UpdateFeeRecipientRequest, SensitiveUrl};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use types::{Address, PublicKeyBytes};
+
+pub const CMD: &str = "set-fee-recipient";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const VALIDATORS_FILE_FLAG: &str = "validators-file";
+pub const FEE_RECIPIENT_FLAG: &str = "suggested-fee-recipient";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Sets the suggested fee recipient of validators on a validator client using the \
+                keymanager-API.",
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators to be updated. Either a list of 0x-prefixed \
+                    validator pubkeys or the keyword \"all\".",
+                )
+                .conflicts_with(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FILE_FLAG)
+                .long(VALIDATORS_FILE_FLAG)
+                .value_name("PATH_TO_JSON_FILE")
+                .help(
+                    "The path to a JSON file containing a list of validators to be \
+                    updated. This file is usually named \"validators.json\". When \
+                    --suggested-fee-recipient is omitted, the fee recipient of each \
+                    validator in the file is used.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FEE_RECIPIENT_FLAG)
+                .long(FEE_RECIPIENT_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help(
+                    "All selected validators will use this value for the suggested fee recipient.",
+                )
+                .required_unless(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SetFeeRecipientConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub validators: ValidatorSelection,
+    pub fee_recipient: Option<Address>,
+}
+
+impl SetFeeRecipientConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            validators: ValidatorSelection::from_cli(
+                matches.value_of(VALIDATORS_FLAG),
+                clap_utils::parse_optional(matches, VALIDATORS_FILE_FLAG)?,
+                VALIDATORS_FLAG,
+                VALIDATORS_FILE_FLAG,
+            )?,
+            fee_recipient: clap_utils::parse_optional(matches, FEE_RECIPIENT_FLAG)?,
+        })
+    }
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = SetFeeRecipientConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: SetFeeRecipientConfig) -> Result<(), String> {
+    let SetFeeRecipientConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        fee_recipient,
+    } = config;
+
+    let (http_client, remote_keystores) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    let updates: Vec<(PublicKeyBytes, Address)> = match (&validators, fee_recipient) {
+        (_, Some(fee_recipient)) => validators
+            .pubkeys(&remote_keystores)?
+            .into_iter()
+            .map(|pubkey| (pubkey, fee_recipient))
+            .collect(),
+        (ValidatorSelection::File(path), None) => {
+            let pubkeys = validators.pubkeys(&remote_keystores)?;
+            read_validators_file(path)?
+                .into_iter()
+                .zip(pubkeys)
+                .map(|(validator, pubkey)| {
+                    validator
+                        .fee_recipient
+                        .map(|fee_recipient| (pubkey, fee_recipient))
+                        .ok_or_else(|| {
+                            format!(
+                                "Validator {:?} has no fee recipient in {:?}, use --{}",
+                                pubkey, path, FEE_RECIPIENT_FLAG
+                            )
+                        })
+                })
+                .collect::<Result<_, _>>()?
+        }
+        (_, None) => return Err(format!("--{} is required", FEE_RECIPIENT_FLAG)),
+    };
+
+    let count = updates.len();
+    let mut failures = 0;
+    for (i, (pubkey, fee_recipient)) in updates.into_iter().enumerate() {
+        let request = UpdateFeeRecipientRequest {
+            ethaddress: fee_recipient,
+        };
+        match http_client.post_fee_recipient(&pubkey, &request).await {
+            Ok(_) => eprintln!(
+                "Set fee recipient of validator {} of {} to {:?}",
+                i + 1,
+                count,
+                fee_recipient
+            ),
+            Err(e) => {
+                failures += 1;
+                eprintln!(
+                    "Failed to set fee recipient of validator {:?}: {:?}",
+                    pubkey, e
+                );
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!(
+            "Failed to set the fee recipient of {} of {} validators",
+            failures, count
+        ));
+    }
+
+    Ok(())
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+
+    const FEE_RECIPIENT: Address = Address::repeat_byte(7);
+
+    fn config(
+        import_builder: &ImportTestBuilder,
+        validators: ValidatorSelection,
+        fee_recipient: Option<Address>,
+    ) -> SetFeeRecipientConfig {
+        SetFeeRecipientConfig {
+            vc_url: import_builder.vc.url.clone(),
+            vc_token_path: import_builder.vc_token_path().into(),
+            validators,
+            fee_recipient,
+        }
+    }
+
+    async fn fee_recipient(import_builder: &ImportTestBuilder, pubkey: &PublicKeyBytes) -> Address {
+        import_builder
+            .vc
+            .client
+            .get_fee_recipient(pubkey)
+            .await
+            .unwrap()
+            .ethaddress
+    }
+
+    #[tokio::test]
+    async fn set_specific_validator() {
+        let (import_builder, pubkeys) = ImportTestBuilder::new_with_validators(2).await;
+        let original = fee_recipient(&import_builder, &pubkeys[1]).await;
+        assert_ne!(
+            original, FEE_RECIPIENT,
+            "precondition: fee recipient differs"
+        );
+
+        let validators = ValidatorSelection::Specific(vec![pubkeys[0]]);
+        run(config(&import_builder, validators, Some(FEE_RECIPIENT)))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            fee_recipient(&import_builder, &pubkeys[0]).await,
+            FEE_RECIPIENT
+        );
+        assert_eq!(fee_recipient(&import_builder, &pubkeys[1]).await, original);
+    }
+
+    #[tokio::test]
+    async fn set_validators_in_file() {
+        let (import_builder, pubkeys) = ImportTestBuilder::new_with_validators(2).await;
+
+        let validators =
+            ValidatorSelection::File(import_builder.validators_file_path().to_path_buf());
+        run(config(&import_builder, validators, Some(FEE_RECIPIENT)))
+            .await
+            .unwrap();
+
+        for pubkey in &pubkeys {
+            assert_eq!(fee_recipient(&import_builder, pubkey).await, FEE_RECIPIENT);
+        }
+    }
+
+    #[tokio::test]
+    async fn validators_in_file_without_fee_recipient() {
+        let (import_builder, _) = ImportTestBuilder::new_with_validators(1).await;
+
+        let validators =
+            ValidatorSelection::File(import_builder.validators_file_path().to_path_buf());
+        let result = run(config(&import_builder, validators, None)).await;
+        assert!(result.unwrap_err().contains("has no fee recipient"));
+    }
+}
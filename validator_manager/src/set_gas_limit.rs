@@ -0,0 +1,240 @@
+use super::common::*;
+use crate::DumpConfig;
+use clap::{App, Arg, ArgMatches};
+use eth2::{lighthouse_vc::types::UpdateGasLimitRequest, SensitiveUrl};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use types::PublicKeyBytes;
+
+pub const CMD: &str = "set-gas-limit";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const VALIDATORS_FILE_FLAG: &str = "validators-file";
+pub const GAS_LIMIT_FLAG: &str = "gas-limit";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about("Sets the gas limit of validators on a validator client using the keymanager-API.")
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators to be updated. Either a list of 0x-prefixed \
+                    validator pubkeys or the keyword \"all\".",
+                )
+                .conflicts_with(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FILE_FLAG)
+                .long(VALIDATORS_FILE_FLAG)
+                .value_name("PATH_TO_JSON_FILE")
+                .help(
+                    "The path to a JSON file containing a list of validators to be \
+                    updated. This file is usually named \"validators.json\". When \
+                    --gas-limit is omitted, the gas limit of each validator in the file \
+                    is used.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(GAS_LIMIT_FLAG)
+                .long(GAS_LIMIT_FLAG)
+                .value_name("UINT64")
+                .help("All selected validators will use this gas limit.")
+                .required_unless(VALIDATORS_FILE_FLAG)
+                .takes_value(true),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SetGasLimitConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub validators: ValidatorSelection,
+    pub gas_limit: Option<u64>,
+}
+
+impl SetGasLimitConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            validators: ValidatorSelection::from_cli(
+                matches.value_of(VALIDATORS_FLAG),
+                clap_utils::parse_optional(matches, VALIDATORS_FILE_FLAG)?,
+                VALIDATORS_FLAG,
+                VALIDATORS_FILE_FLAG,
+            )?,
+            gas_limit: clap_utils::parse_optional(matches, GAS_LIMIT_FLAG)?,
+        })
+    }
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = SetGasLimitConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: SetGasLimitConfig) -> Result<(), String> {
+    let SetGasLimitConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        gas_limit,
+    } = config;
+
+    let (http_client, remote_keystores) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    let updates: Vec<(PublicKeyBytes, u64)> = match (&validators, gas_limit) {
+        (_, Some(gas_limit)) => validators
+            .pubkeys(&remote_keystores)?
+            .into_iter()
+            .map(|pubkey| (pubkey, gas_limit))
+            .collect(),
+        (ValidatorSelection::File(path), None) => {
+            let pubkeys = validators.pubkeys(&remote_keystores)?;
+            read_validators_file(path)?
+                .into_iter()
+                .zip(pubkeys)
+                .map(|(validator, pubkey)| {
+                    validator
+                        .gas_limit
+                        .map(|gas_limit| (pubkey, gas_limit))
+                        .ok_or_else(|| {
+                            format!(
+                                "Validator {:?} has no gas limit in {:?}, use --{}",
+                                pubkey, path, GAS_LIMIT_FLAG
+                            )
+                        })
+                })
+                .collect::<Result<_, _>>()?
+        }
+        (_, None) => return Err(format!("--{} is required", GAS_LIMIT_FLAG)),
+    };
+
+    let count = updates.len();
+    let mut failures = 0;
+    for (i, (pubkey, gas_limit)) in updates.into_iter().enumerate() {
+        let request = UpdateGasLimitRequest { gas_limit };
+        match http_client.post_gas_limit(&pubkey, &request).await {
+            Ok(_) => eprintln!(
+                "Set gas limit of validator {} of {} to {}",
+                i + 1,
+                count,
+                gas_limit
+            ),
+            Err(e) => {
+                failures += 1;
+                eprintln!("Failed to set gas limit of validator {:?}: {:?}", pubkey, e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!(
+            "Failed to set the gas limit of {} of {} validators",
+            failures, count
+        ));
+    }
+
+    Ok(())
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+
+    const GAS_LIMIT: u64 = 42_000_000;
+
+    fn config(
+        import_builder: &ImportTestBuilder,
+        validators: ValidatorSelection,
+        gas_limit: Option<u64>,
+    ) -> SetGasLimitConfig {
+        SetGasLimitConfig {
+            vc_url: import_builder.vc.url.clone(),
+            vc_token_path: import_builder.vc_token_path().into(),
+            validators,
+            gas_limit,
+        }
+    }
+
+    async fn gas_limit(import_builder: &ImportTestBuilder, pubkey: &PublicKeyBytes) -> u64 {
+        import_builder
+            .vc
+            .client
+            .get_gas_limit(pubkey)
+            .await
+            .unwrap()
+            .gas_limit
+    }
+
+    #[tokio::test]
+    async fn set_specific_validator() {
+        let (import_builder, pubkeys) = ImportTestBuilder::new_with_validators(2).await;
+        let original = gas_limit(&import_builder, &pubkeys[1]).await;
+        assert_ne!(original, GAS_LIMIT, "precondition: gas limit differs");
+
+        let validators = ValidatorSelection::Specific(vec![pubkeys[0]]);
+        run(config(&import_builder, validators, Some(GAS_LIMIT)))
+            .await
+            .unwrap();
+
+        assert_eq!(gas_limit(&import_builder, &pubkeys[0]).await, GAS_LIMIT);
+        assert_eq!(gas_limit(&import_builder, &pubkeys[1]).await, original);
+    }
+
+    #[tokio::test]
+    async fn set_validators_in_file() {
+        let (import_builder, pubkeys) = ImportTestBuilder::new_with_validators(2).await;
+
+        let validators =
+            ValidatorSelection::File(import_builder.validators_file_path().to_path_buf());
+        run(config(&import_builder, validators, Some(GAS_LIMIT)))
+            .await
+            .unwrap();
+
+        for pubkey in &pubkeys {
+            assert_eq!(gas_limit(&import_builder, pubkey).await, GAS_LIMIT);
+        }
+    }
+
+    #[tokio::test]
+    async fn validators_in_file_without_gas_limit() {
+        let (import_builder, _) = ImportTestBuilder::new_with_validators(1).await;
+
+        let validators =
+            ValidatorSelection::File(import_builder.validators_file_path().to_path_buf());
+        let result = run(config(&import_builder, validators, None)).await;
+        assert!(result.unwrap_err().contains("has no gas limit"));
+    }
+}